@@ -0,0 +1,55 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use similarity_core::{calculate_tsed, parse_and_convert_to_tree, TSEDOptions, TsedQuery};
+
+const QUERY: &str = r#"
+function sumPositive(values: number[]): number {
+    let total = 0;
+    for (const value of values) {
+        if (value > 0) {
+            total += value;
+        }
+    }
+    return total;
+}
+"#;
+
+/// Functions of growing size, so that most of them differ from the query in size
+fn candidate_source(index: usize) -> String {
+    let statements: String = (0..index % 40)
+        .map(|i| format!("    const v{i} = input * {i} + {index};\n    console.log(v{i});\n"))
+        .collect();
+    format!("function candidate{index}(input: number): void {{\n{statements}}}\n")
+}
+
+fn benchmark_query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Query Against Many Functions");
+
+    let query = parse_and_convert_to_tree("query.ts", QUERY).unwrap();
+    let candidates: Vec<_> = (0..500)
+        .map(|i| parse_and_convert_to_tree("candidate.ts", &candidate_source(i)).unwrap())
+        .collect();
+    let options = TSEDOptions::default();
+    let threshold = 0.85;
+
+    group.bench_function("naive calculate_tsed per candidate", |b| {
+        b.iter(|| {
+            candidates
+                .iter()
+                .filter(|candidate| calculate_tsed(&query, candidate, &options) >= threshold)
+                .count()
+        });
+    });
+
+    group.bench_function("TsedQuery::find_similar", |b| {
+        b.iter(|| {
+            TsedQuery::new(black_box(&query), &options)
+                .find_similar(black_box(&candidates), threshold)
+                .len()
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_query);
+criterion_main!(benches);
@@ -1,14 +1,161 @@
 use crate::tree::TreeNode;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct APTEDOptions {
     pub rename_cost: f64,
     pub delete_cost: f64,
     pub insert_cost: f64,
     /// Whether to compare node values in addition to labels
     pub compare_values: bool,
+    /// Rename costs for specific categories of leaves, overriding `rename_cost`
+    pub leaf_rename_costs: LeafRenameCosts,
+    /// Rename costs for specific pairs of labels, overriding both of the above
+    pub label_rename_costs: LabelRenameCosts,
+}
+
+/// Category of a value-bearing node, derived from its label
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeafCategory {
+    Identifier,
+    String,
+    Number,
+    Keyword,
+    Other,
+}
+
+/// Keyword tokens of the supported languages, as they appear as node labels
+const KEYWORDS: &[&str] = &[
+    "as",
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "def",
+    "default",
+    "del",
+    "do",
+    "elif",
+    "else",
+    "enum",
+    "except",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "fn",
+    "for",
+    "from",
+    "function",
+    "if",
+    "impl",
+    "import",
+    "in",
+    "interface",
+    "let",
+    "loop",
+    "match",
+    "mod",
+    "mut",
+    "new",
+    "null",
+    "pub",
+    "raise",
+    "return",
+    "static",
+    "struct",
+    "switch",
+    "throw",
+    "trait",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "use",
+    "var",
+    "where",
+    "while",
+    "with",
+    "yield",
+];
+
+impl LeafCategory {
+    /// Categorize a node label across the tree-sitter grammars and the oxc tree
+    pub fn of(label: &str) -> Self {
+        let lower = label.to_ascii_lowercase();
+        if lower.contains("identifier") {
+            LeafCategory::Identifier
+        } else if lower.contains("string") || lower == "char_literal" {
+            LeafCategory::String
+        } else if ["integer", "float", "number", "numeric"].iter().any(|n| lower.contains(n)) {
+            LeafCategory::Number
+        } else if KEYWORDS.contains(&label) {
+            LeafCategory::Keyword
+        } else {
+            LeafCategory::Other
+        }
+    }
+
+    /// Categorize a node that carries a value or is a leaf. Structural nodes
+    /// (`block`, `arguments`, ...) are `Other` whatever their label.
+    pub fn of_node(node: &TreeNode) -> Self {
+        if !node.children.is_empty() && node.value.is_empty() {
+            return LeafCategory::Other;
+        }
+        Self::of(&node.label)
+    }
+}
+
+/// Per-category rename costs; `None` falls back to `APTEDOptions::rename_cost`
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeafRenameCosts {
+    pub identifier: Option<f64>,
+    pub string: Option<f64>,
+    pub number: Option<f64>,
+    pub keyword: Option<f64>,
+}
+
+impl LeafRenameCosts {
+    fn cost(&self, category: LeafCategory) -> Option<f64> {
+        match category {
+            LeafCategory::Identifier => self.identifier,
+            LeafCategory::String => self.string,
+            LeafCategory::Number => self.number,
+            LeafCategory::Keyword => self.keyword,
+            LeafCategory::Other => None,
+        }
+    }
+}
+
+/// Rename costs between pairs of node labels, so that turning a
+/// `for_statement` into a `while_statement` can cost more than renaming an
+/// identifier. Pairs are unordered.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LabelRenameCosts {
+    costs: BTreeMap<String, BTreeMap<String, f64>>,
+}
+
+impl LabelRenameCosts {
+    /// Set the cost of renaming `label1` into `label2` and back
+    pub fn insert(&mut self, label1: &str, label2: &str, cost: f64) {
+        for (from, to) in [(label1, label2), (label2, label1)] {
+            self.costs.entry(from.to_string()).or_default().insert(to.to_string(), cost);
+        }
+    }
+
+    /// The cost set for renaming between `label1` and `label2`, if any
+    #[must_use]
+    pub fn get(&self, label1: &str, label2: &str) -> Option<f64> {
+        self.costs.get(label1)?.get(label2).copied()
+    }
 }
 
 impl Default for APTEDOptions {
@@ -18,6 +165,8 @@ impl Default for APTEDOptions {
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: true, // Default: compare both structure and values
+            leaf_rename_costs: LeafRenameCosts::default(),
+            label_rename_costs: LabelRenameCosts::default(),
         }
     }
 }
@@ -29,42 +178,182 @@ pub fn compute_edit_distance(
     tree2: &Rc<TreeNode>,
     options: &APTEDOptions,
 ) -> f64 {
-    let mut memo: HashMap<(usize, usize), f64> = HashMap::new();
-    compute_edit_distance_recursive(tree1, tree2, options, &mut memo)
+    edit_distance_with_matches(tree1, tree2, options).0
 }
 
+/// The edit distance between two trees together with the number of nodes the
+/// optimal edit script keeps unchanged (matched with equal label, and value
+/// when values are compared), for callers normalizing the distance themselves.
+#[must_use]
+pub fn edit_distance_with_matches(
+    tree1: &Rc<TreeNode>,
+    tree2: &Rc<TreeNode>,
+    options: &APTEDOptions,
+) -> (f64, usize) {
+    let mut memo = HashMap::new();
+    compute_edit_distance_recursive(tree1, tree2, options, f64::INFINITY, &mut memo)
+        .expect("no edit distance exceeds an infinite budget")
+}
+
+/// The edit distance between two trees, or `None` once it is known to exceed
+/// `max_cost`. Subtree pairs that cannot fit in what is left of the budget
+/// are abandoned instead of being aligned exactly, so callers that only need
+/// to know whether two trees are close skip most of the work on distant ones.
+#[must_use]
+pub fn compute_edit_distance_bounded(
+    tree1: &Rc<TreeNode>,
+    tree2: &Rc<TreeNode>,
+    options: &APTEDOptions,
+    max_cost: f64,
+) -> Option<f64> {
+    let mut memo = HashMap::new();
+    compute_edit_distance_recursive(tree1, tree2, options, max_cost, &mut memo)
+        .map(|(cost, _)| cost)
+}
+
+/// One operation of an edit script, naming nodes by their `id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditOp {
+    /// Rename node `from_id` of the first tree into node `to_id` of the second
+    Rename { from_id: usize, to_id: usize },
+    /// Delete node `id` of the first tree
+    Delete { id: usize },
+    /// Insert node `id` of the second tree
+    Insert { id: usize },
+}
+
+/// The operations behind [`compute_edit_distance`], whose costs sum to the
+/// distance. Nodes kept unchanged have no operation. Like the distance, a
+/// pair of subtrees cheapest to drop entirely only counts the deletions of
+/// the first or the insertions of the second, whichever cost less.
+#[must_use]
+pub fn compute_edit_script(
+    tree1: &Rc<TreeNode>,
+    tree2: &Rc<TreeNode>,
+    options: &APTEDOptions,
+) -> Vec<EditOp> {
+    let mut memo = HashMap::new();
+    let mut script = Vec::new();
+    push_edit_script(tree1, tree2, options, &mut memo, &mut script);
+    script
+}
+
+/// Replay the choices of `edit_distance_unmemoized` for two subtrees,
+/// appending their operations to `script`
+#[allow(clippy::cast_precision_loss)]
+fn push_edit_script(
+    node1: &Rc<TreeNode>,
+    node2: &Rc<TreeNode>,
+    options: &APTEDOptions,
+    memo: &mut HashMap<(usize, usize), Memo>,
+    script: &mut Vec<EditOp>,
+) {
+    let rename = (!same_node(node1, node2, options))
+        .then_some(EditOp::Rename { from_id: node1.id, to_id: node2.id });
+
+    if node1.children.is_empty() && node2.children.is_empty() {
+        script.extend(rename);
+        return;
+    }
+
+    let delete_all_cost = options.delete_cost * node1.get_subtree_size() as f64;
+    let insert_all_cost = options.insert_cost * node2.get_subtree_size() as f64;
+
+    let mut rename_plus_cost = rename_cost(node1, node2, options);
+    let mut alignment = HashMap::new();
+    if delete_all_cost.min(insert_all_cost) < rename_plus_cost {
+        rename_plus_cost = f64::INFINITY;
+    } else {
+        let mut child_cost_matrix = HashMap::new();
+        for child1 in &node1.children {
+            for child2 in &node2.children {
+                let (cost, _) =
+                    compute_edit_distance_recursive(child1, child2, options, f64::INFINITY, memo)
+                        .expect("no edit distance exceeds an infinite budget");
+                child_cost_matrix.insert((child1.id, child2.id), cost);
+            }
+        }
+
+        let (alignment_cost, child_alignment) = compute_children_alignment(
+            &node1.children,
+            &node2.children,
+            &child_cost_matrix,
+            options,
+        );
+        rename_plus_cost += alignment_cost;
+        alignment = child_alignment;
+    }
+
+    if rename_plus_cost <= delete_all_cost.min(insert_all_cost) {
+        script.extend(rename);
+        for child1 in &node1.children {
+            match alignment[&child1.id].and_then(|id| node2.children.iter().find(|c| c.id == id)) {
+                Some(child2) => push_edit_script(child1, child2, options, memo, script),
+                None => script.extend(child1.iter().map(|node| EditOp::Delete { id: node.id })),
+            }
+        }
+        let aligned: Vec<usize> = alignment.values().flatten().copied().collect();
+        for child2 in node2.children.iter().filter(|child| !aligned.contains(&child.id)) {
+            script.extend(child2.iter().map(|node| EditOp::Insert { id: node.id }));
+        }
+    } else if delete_all_cost <= insert_all_cost {
+        script.extend(node1.iter().map(|node| EditOp::Delete { id: node.id }));
+    } else {
+        script.extend(node2.iter().map(|node| EditOp::Insert { id: node.id }));
+    }
+}
+
+/// What is known of the edit distance of a pair of subtrees
+#[derive(Clone, Copy)]
+enum Memo {
+    /// The distance and the number of nodes kept
+    Exact(f64, usize),
+    /// The distance exceeds this budget
+    Exceeds(f64),
+}
+
+/// Edit distance and kept nodes of two subtrees, or `None` when the distance
+/// exceeds `budget`. Results within the budget are exact.
 fn compute_edit_distance_recursive(
     node1: &Rc<TreeNode>,
     node2: &Rc<TreeNode>,
     options: &APTEDOptions,
-    memo: &mut HashMap<(usize, usize), f64>,
-) -> f64 {
+    budget: f64,
+    memo: &mut HashMap<(usize, usize), Memo>,
+) -> Option<(f64, usize)> {
     let key = (node1.id, node2.id);
 
-    if let Some(&cost) = memo.get(&key) {
-        return cost;
+    match memo.get(&key) {
+        Some(&Memo::Exact(cost, matches)) => return (cost <= budget).then_some((cost, matches)),
+        Some(&Memo::Exceeds(exceeded)) if budget <= exceeded => return None,
+        _ => {}
+    }
+
+    let (cost, matches) = edit_distance_unmemoized(node1, node2, options, budget, memo);
+    if cost > budget {
+        memo.insert(key, Memo::Exceeds(budget));
+        return None;
     }
+    memo.insert(key, Memo::Exact(cost, matches));
+    Some((cost, matches))
+}
+
+/// Edit distance and kept nodes of two subtrees, exact when the distance is
+/// within `budget` and otherwise only known to be above it
+#[allow(clippy::cast_precision_loss)]
+fn edit_distance_unmemoized(
+    node1: &Rc<TreeNode>,
+    node2: &Rc<TreeNode>,
+    options: &APTEDOptions,
+    budget: f64,
+    memo: &mut HashMap<(usize, usize), Memo>,
+) -> (f64, usize) {
+    let kept = usize::from(same_node(node1, node2, options));
 
     // Base cases
     if node1.children.is_empty() && node2.children.is_empty() {
         // Both are leaves
-        let cost = if options.compare_values {
-            // Compare both label and value
-            if node1.label == node2.label && node1.value == node2.value {
-                0.0
-            } else {
-                options.rename_cost
-            }
-        } else {
-            // Compare only label (structural comparison)
-            if node1.label == node2.label {
-                0.0
-            } else {
-                options.rename_cost
-            }
-        };
-        memo.insert(key, cost);
-        return cost;
+        return (rename_cost(node1, node2, options), kept);
     }
 
     // Calculate costs for all three operations
@@ -72,35 +361,32 @@ fn compute_edit_distance_recursive(
     let insert_all_cost = options.insert_cost * node2.get_subtree_size() as f64;
 
     // Calculate rename + optimal children alignment
-    let mut rename_plus_cost = if options.compare_values {
-        // Compare both label and value
-        if node1.label == node2.label && node1.value == node2.value {
-            0.0
-        } else {
-            options.rename_cost
-        }
-    } else {
-        // Compare only label (structural comparison)
-        if node1.label == node2.label {
-            0.0
-        } else {
-            options.rename_cost
-        }
-    };
+    let mut rename_plus_cost = rename_cost(node1, node2, options);
+    let mut rename_plus_matches = kept;
+    // Aligning the children only pays off if it can beat the other operations
+    // and stay within the budget
+    let children_budget = budget.min(delete_all_cost).min(insert_all_cost) - rename_plus_cost;
 
-    if !node1.children.is_empty() || !node2.children.is_empty() {
-        // Compute all pairwise costs between children
+    if children_budget < 0.0 {
+        rename_plus_cost = f64::INFINITY;
+    } else if !node1.children.is_empty() || !node2.children.is_empty() {
+        // Compute all pairwise costs between children; pairs that alone
+        // exceed the budget can be on no alignment within it
         let mut child_cost_matrix: HashMap<(usize, usize), f64> = HashMap::new();
+        let mut child_matches: HashMap<(usize, usize), usize> = HashMap::new();
 
         for child1 in &node1.children {
             for child2 in &node2.children {
-                let cost = compute_edit_distance_recursive(child1, child2, options, memo);
+                let (cost, matches) =
+                    compute_edit_distance_recursive(child1, child2, options, children_budget, memo)
+                        .unwrap_or((f64::INFINITY, 0));
                 child_cost_matrix.insert((child1.id, child2.id), cost);
+                child_matches.insert((child1.id, child2.id), matches);
             }
         }
 
         // Find optimal alignment
-        let (alignment_cost, _) = compute_children_alignment(
+        let (alignment_cost, alignment) = compute_children_alignment(
             &node1.children,
             &node2.children,
             &child_cost_matrix,
@@ -108,11 +394,44 @@ fn compute_edit_distance_recursive(
         );
 
         rename_plus_cost += alignment_cost;
+        rename_plus_matches += alignment
+            .iter()
+            .filter_map(|(&child1, child2)| child2.map(|child2| child_matches[&(child1, child2)]))
+            .sum::<usize>();
     }
 
     let min_cost = delete_all_cost.min(insert_all_cost).min(rename_plus_cost);
-    memo.insert(key, min_cost);
-    min_cost
+    // Deleting or inserting everything keeps no node
+    let matches = if rename_plus_cost <= min_cost { rename_plus_matches } else { 0 };
+    (min_cost, matches)
+}
+
+/// Whether renaming `node1` into `node2` is free
+fn same_node(node1: &TreeNode, node2: &TreeNode, options: &APTEDOptions) -> bool {
+    if options.compare_values {
+        // Compare both label and value
+        node1.label == node2.label && node1.value == node2.value
+    } else {
+        // Compare only label (structural comparison)
+        node1.label == node2.label
+    }
+}
+
+/// Cost of renaming `node1` into `node2`. A cost configured for their pair of
+/// labels comes first, then the cost of their leaf category when they share one.
+fn rename_cost(node1: &TreeNode, node2: &TreeNode, options: &APTEDOptions) -> f64 {
+    if same_node(node1, node2, options) {
+        return 0.0;
+    }
+    if let Some(cost) = options.label_rename_costs.get(&node1.label, &node2.label) {
+        return cost;
+    }
+
+    let category = LeafCategory::of_node(node1);
+    if category != LeafCategory::of_node(node2) {
+        return options.rename_cost;
+    }
+    options.leaf_rename_costs.cost(category).unwrap_or(options.rename_cost)
 }
 
 fn compute_children_alignment(
@@ -183,3 +502,241 @@ fn compute_children_alignment(
 
     (dp[m][n], alignment)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(label: &str, value: &str, id: usize, children: Vec<TreeNode>) -> TreeNode {
+        let mut n = TreeNode::new(label.to_string(), value.to_string(), id);
+        for child in children {
+            n.add_child(Rc::new(child));
+        }
+        n
+    }
+
+    fn statement(keyword: &str, message: &str) -> Rc<TreeNode> {
+        Rc::new(node(
+            "expression_statement",
+            "",
+            0,
+            vec![node(keyword, keyword, 1, vec![]), node("string_literal", message, 2, vec![])],
+        ))
+    }
+
+    #[test]
+    fn test_leaf_categories() {
+        assert_eq!(LeafCategory::of("field_identifier"), LeafCategory::Identifier);
+        assert_eq!(LeafCategory::of("StringLiteral"), LeafCategory::String);
+        assert_eq!(LeafCategory::of("integer_literal"), LeafCategory::Number);
+        assert_eq!(LeafCategory::of("return"), LeafCategory::Keyword);
+        assert_eq!(LeafCategory::of("=="), LeafCategory::Other);
+        assert_eq!(LeafCategory::of("block"), LeafCategory::Other);
+        assert_eq!(LeafCategory::of("arguments"), LeafCategory::Other);
+    }
+
+    #[test]
+    fn test_structural_nodes_have_no_leaf_category() {
+        let string =
+            node("string_literal", "\"ready\"", 0, vec![node("string_content", "", 1, vec![])]);
+        assert_eq!(LeafCategory::of_node(&string), LeafCategory::String);
+
+        let pattern = node("identifier_pattern", "", 0, vec![node("identifier", "x", 1, vec![])]);
+        assert_eq!(LeafCategory::of_node(&pattern), LeafCategory::Other);
+        assert_eq!(LeafCategory::of_node(&pattern.children[0]), LeafCategory::Identifier);
+    }
+
+    #[test]
+    fn test_keyword_changes_cost_more_than_string_changes() {
+        let options = APTEDOptions {
+            leaf_rename_costs: LeafRenameCosts {
+                keyword: Some(1.5),
+                string: Some(0.25),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let original = statement("if", "\"ready\"");
+        let keyword_changed = statement("while", "\"ready\"");
+        let string_changed = statement("if", "\"waiting\"");
+
+        let keyword_distance = compute_edit_distance(&original, &keyword_changed, &options);
+        let string_distance = compute_edit_distance(&original, &string_changed, &options);
+        assert_eq!(keyword_distance, 1.5);
+        assert_eq!(string_distance, 0.25);
+
+        // With uniform costs both edits weigh the same
+        let uniform = APTEDOptions::default();
+        assert_eq!(
+            compute_edit_distance(&original, &keyword_changed, &uniform),
+            compute_edit_distance(&original, &string_changed, &uniform)
+        );
+    }
+
+    #[test]
+    fn test_label_pair_costs_override_the_other_rename_costs() {
+        let mut options = APTEDOptions {
+            leaf_rename_costs: LeafRenameCosts { keyword: Some(0.5), ..Default::default() },
+            ..Default::default()
+        };
+        options.label_rename_costs.insert("while", "if", 1.75);
+
+        // Still below deleting and inserting the keyword, which costs 2.0
+        let original = statement("if", "\"ready\"");
+        assert_eq!(
+            compute_edit_distance(&original, &statement("while", "\"ready\""), &options),
+            1.75
+        );
+        assert_eq!(
+            compute_edit_distance(&statement("while", "\"ready\""), &original, &options),
+            1.75
+        );
+        // Other keywords keep the category cost
+        assert_eq!(
+            compute_edit_distance(&original, &statement("loop", "\"ready\""), &options),
+            0.5
+        );
+    }
+
+    #[test]
+    fn test_matched_nodes_of_identical_and_edited_trees() {
+        let options = APTEDOptions::default();
+        let original = statement("if", "\"ready\"");
+
+        let (distance, matches) = edit_distance_with_matches(&original, &original, &options);
+        assert_eq!(distance, 0.0);
+        assert_eq!(matches, original.get_subtree_size());
+
+        // Renaming the string leaves the statement and the keyword matched
+        let (distance, matches) =
+            edit_distance_with_matches(&original, &statement("if", "\"waiting\""), &options);
+        assert_eq!(distance, options.rename_cost);
+        assert_eq!(matches, 2);
+    }
+
+    #[test]
+    fn test_bounded_distance_matches_unbounded_within_the_bound() {
+        let options = APTEDOptions::default();
+        let original = statement("if", "\"ready\"");
+        let edited = Rc::new(node(
+            "block",
+            "",
+            0,
+            vec![
+                node("expression_statement", "", 1, vec![node("while", "while", 2, vec![])]),
+                node("string_literal", "\"waiting\"", 3, vec![]),
+            ],
+        ));
+
+        for (tree1, tree2) in [(&original, &original), (&original, &edited), (&edited, &original)] {
+            let distance = compute_edit_distance(tree1, tree2, &options);
+            assert_eq!(
+                compute_edit_distance_bounded(tree1, tree2, &options, distance),
+                Some(distance)
+            );
+            assert_eq!(
+                compute_edit_distance_bounded(tree1, tree2, &options, distance + 10.0),
+                Some(distance)
+            );
+            if distance > 0.0 {
+                assert_eq!(
+                    compute_edit_distance_bounded(tree1, tree2, &options, distance - 0.1),
+                    None
+                );
+            }
+        }
+    }
+
+    /// Summed cost of the operations of `script`
+    fn script_cost(
+        script: &[EditOp],
+        tree1: &TreeNode,
+        tree2: &TreeNode,
+        options: &APTEDOptions,
+    ) -> f64 {
+        let nodes1: HashMap<usize, &TreeNode> = tree1.iter().map(|n| (n.id, n)).collect();
+        let nodes2: HashMap<usize, &TreeNode> = tree2.iter().map(|n| (n.id, n)).collect();
+        script
+            .iter()
+            .map(|op| match *op {
+                EditOp::Rename { from_id, to_id } => {
+                    rename_cost(nodes1[&from_id], nodes2[&to_id], options)
+                }
+                EditOp::Delete { .. } => options.delete_cost,
+                EditOp::Insert { .. } => options.insert_cost,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_edit_script_costs_sum_to_the_distance() {
+        let original = statement("if", "\"ready\"");
+        let edited = Rc::new(node(
+            "expression_statement",
+            "",
+            0,
+            vec![
+                node("while", "while", 1, vec![]),
+                node("string_literal", "\"ready\"", 2, vec![]),
+                node("identifier", "retries", 3, vec![]),
+            ],
+        ));
+        let replaced = Rc::new(node(
+            "block",
+            "",
+            0,
+            vec![node(
+                "call_expression",
+                "",
+                1,
+                vec![node("identifier", "run", 2, vec![]), node("arguments", "", 3, vec![])],
+            )],
+        ));
+        let costs = [
+            APTEDOptions::default(),
+            APTEDOptions { compare_values: false, ..Default::default() },
+            APTEDOptions {
+                rename_cost: 0.3,
+                leaf_rename_costs: LeafRenameCosts { keyword: Some(1.5), ..Default::default() },
+                ..Default::default()
+            },
+            APTEDOptions { delete_cost: 0.5, insert_cost: 2.0, ..Default::default() },
+        ];
+
+        for options in &costs {
+            for tree1 in [&original, &edited, &replaced] {
+                for tree2 in [&original, &edited, &replaced] {
+                    let script = compute_edit_script(tree1, tree2, options);
+                    let distance = compute_edit_distance(tree1, tree2, options);
+                    assert!(
+                        (script_cost(&script, tree1, tree2, options) - distance).abs() < 1e-9,
+                        "{script:?} for a distance of {distance}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_edit_script_of_a_renamed_keyword_and_an_added_leaf() {
+        let original = statement("if", "\"ready\"");
+        let edited = Rc::new(node(
+            "expression_statement",
+            "",
+            0,
+            vec![
+                node("while", "while", 1, vec![]),
+                node("string_literal", "\"ready\"", 2, vec![]),
+                node("identifier", "retries", 3, vec![]),
+            ],
+        ));
+
+        let options = APTEDOptions::default();
+        assert_eq!(
+            compute_edit_script(&original, &edited, &options),
+            vec![EditOp::Rename { from_id: 1, to_id: 1 }, EditOp::Insert { id: 3 }]
+        );
+        assert!(compute_edit_script(&original, &original, &options).is_empty());
+    }
+}
@@ -32,6 +32,21 @@ impl From<SerializableTreeNode> for TreeNode {
     }
 }
 
+/// `TreeNode` serializes as the nested [`SerializableTreeNode`]
+#[cfg(feature = "serde")]
+impl Serialize for TreeNode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializableTreeNode::from(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for TreeNode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SerializableTreeNode::deserialize(deserializer).map(TreeNode::from)
+    }
+}
+
 /// Function definition for external exchange
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExchangeFunctionDef {
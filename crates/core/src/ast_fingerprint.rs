@@ -23,6 +23,7 @@ impl AstFingerprint {
 
     /// Create fingerprint from source code
     pub fn from_source(source: &str) -> Result<Self, String> {
+        crate::parser::check_source_nesting(source)?;
         let allocator = Allocator::default();
         let source_type = SourceType::tsx(); // Default to TSX for maximum compatibility
         let ret = Parser::new(&allocator, source, source_type).parse();
@@ -0,0 +1,326 @@
+use oxc_ast::ast::*;
+use std::collections::BTreeSet;
+
+/// Names an arrow function reads from its environment: the identifiers it
+/// references without binding them as a parameter or a local, sorted.
+///
+/// Scopes inside the closure are not told apart, so a name bound anywhere in
+/// it is never a capture. Every statement is visited; expressions outside the
+/// common ones (JSX, tagged templates, ...) contribute no references.
+pub fn arrow_captures(arrow: &ArrowFunctionExpression) -> Vec<String> {
+    let mut names = Names::default();
+    names.bind_parameters(&arrow.params);
+    for stmt in &arrow.body.statements {
+        names.visit_statement(stmt);
+    }
+    names.referenced.difference(&names.bound).cloned().collect()
+}
+
+#[derive(Default)]
+struct Names {
+    referenced: BTreeSet<String>,
+    bound: BTreeSet<String>,
+}
+
+impl Names {
+    fn bind_pattern(&mut self, pattern: &BindingPattern) {
+        for ident in pattern.get_binding_identifiers() {
+            self.bound.insert(ident.name.to_string());
+        }
+    }
+
+    fn bind_parameters(&mut self, params: &FormalParameters) {
+        for param in &params.items {
+            self.bind_pattern(&param.pattern);
+        }
+        if let Some(rest) = &params.rest {
+            self.bind_pattern(&rest.argument);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement) {
+        match stmt {
+            Statement::ExpressionStatement(expr_stmt) => {
+                self.visit_expression(&expr_stmt.expression);
+            }
+            Statement::ReturnStatement(ret) => {
+                if let Some(argument) = &ret.argument {
+                    self.visit_expression(argument);
+                }
+            }
+            Statement::ThrowStatement(throw) => self.visit_expression(&throw.argument),
+            Statement::VariableDeclaration(var_decl) => self.visit_declaration(var_decl),
+            Statement::BlockStatement(block) => self.visit_block(block),
+            Statement::IfStatement(if_stmt) => {
+                self.visit_expression(&if_stmt.test);
+                self.visit_statement(&if_stmt.consequent);
+                if let Some(alternate) = &if_stmt.alternate {
+                    self.visit_statement(alternate);
+                }
+            }
+            Statement::ForStatement(for_stmt) => {
+                match &for_stmt.init {
+                    Some(ForStatementInit::VariableDeclaration(var_decl)) => {
+                        self.visit_declaration(var_decl);
+                    }
+                    Some(init) => {
+                        if let Some(expr) = init.as_expression() {
+                            self.visit_expression(expr);
+                        }
+                    }
+                    None => {}
+                }
+                for expr in [&for_stmt.test, &for_stmt.update].into_iter().flatten() {
+                    self.visit_expression(expr);
+                }
+                self.visit_statement(&for_stmt.body);
+            }
+            Statement::ForInStatement(for_in) => {
+                self.visit_for_left(&for_in.left);
+                self.visit_expression(&for_in.right);
+                self.visit_statement(&for_in.body);
+            }
+            Statement::ForOfStatement(for_of) => {
+                self.visit_for_left(&for_of.left);
+                self.visit_expression(&for_of.right);
+                self.visit_statement(&for_of.body);
+            }
+            Statement::WhileStatement(while_stmt) => {
+                self.visit_expression(&while_stmt.test);
+                self.visit_statement(&while_stmt.body);
+            }
+            Statement::DoWhileStatement(do_while) => {
+                self.visit_statement(&do_while.body);
+                self.visit_expression(&do_while.test);
+            }
+            Statement::TryStatement(try_stmt) => {
+                self.visit_block(&try_stmt.block);
+                if let Some(handler) = &try_stmt.handler {
+                    if let Some(param) = &handler.param {
+                        self.bind_pattern(&param.pattern);
+                    }
+                    self.visit_block(&handler.body);
+                }
+                if let Some(finalizer) = &try_stmt.finalizer {
+                    self.visit_block(finalizer);
+                }
+            }
+            Statement::SwitchStatement(switch) => {
+                self.visit_expression(&switch.discriminant);
+                for case in &switch.cases {
+                    if let Some(test) = &case.test {
+                        self.visit_expression(test);
+                    }
+                    for stmt in &case.consequent {
+                        self.visit_statement(stmt);
+                    }
+                }
+            }
+            Statement::LabeledStatement(labeled) => self.visit_statement(&labeled.body),
+            Statement::WithStatement(with) => {
+                self.visit_expression(&with.object);
+                self.visit_statement(&with.body);
+            }
+            Statement::FunctionDeclaration(func) => {
+                if let Some(id) = &func.id {
+                    self.bound.insert(id.name.to_string());
+                }
+                self.visit_function(&func.params, func.body.as_deref());
+            }
+            Statement::ClassDeclaration(class) => {
+                if let Some(id) = &class.id {
+                    self.bound.insert(id.name.to_string());
+                }
+                self.visit_class(class);
+            }
+            // Nothing else refers to a variable: jumps, empty statements and
+            // type declarations
+            _ => {}
+        }
+    }
+
+    fn visit_block(&mut self, block: &BlockStatement) {
+        for stmt in &block.body {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_declaration(&mut self, var_decl: &VariableDeclaration) {
+        for decl in &var_decl.declarations {
+            self.bind_pattern(&decl.id);
+            if let Some(init) = &decl.init {
+                self.visit_expression(init);
+            }
+        }
+    }
+
+    fn visit_for_left(&mut self, left: &ForStatementLeft) {
+        match left {
+            ForStatementLeft::VariableDeclaration(var_decl) => self.visit_declaration(var_decl),
+            _ => {
+                if let Some(target) =
+                    left.as_assignment_target().and_then(|t| t.as_simple_assignment_target())
+                {
+                    self.visit_simple_target(target);
+                }
+            }
+        }
+    }
+
+    fn visit_class(&mut self, class: &Class) {
+        if let Some(super_class) = &class.super_class {
+            self.visit_expression(super_class);
+        }
+        for element in &class.body.body {
+            match element {
+                ClassElement::MethodDefinition(method) => {
+                    self.visit_function(&method.value.params, method.value.body.as_deref());
+                }
+                ClassElement::PropertyDefinition(property) => {
+                    if let Some(value) = &property.value {
+                        self.visit_expression(value);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn visit_function(&mut self, params: &FormalParameters, body: Option<&FunctionBody>) {
+        self.bind_parameters(params);
+        for stmt in body.iter().flat_map(|body| &body.statements) {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_arguments(&mut self, arguments: &[Argument]) {
+        for arg in arguments {
+            match arg {
+                Argument::SpreadElement(spread) => self.visit_expression(&spread.argument),
+                _ => {
+                    if let Some(expr) = arg.as_expression() {
+                        self.visit_expression(expr);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_simple_target(&mut self, target: &SimpleAssignmentTarget) {
+        if let SimpleAssignmentTarget::AssignmentTargetIdentifier(ident) = target {
+            self.referenced.insert(ident.name.to_string());
+        } else if let Some(member) = target.as_member_expression() {
+            self.visit_member(member);
+        }
+    }
+
+    fn visit_member(&mut self, member: &MemberExpression) {
+        self.visit_expression(member.object());
+        if let MemberExpression::ComputedMemberExpression(computed) = member {
+            self.visit_expression(&computed.expression);
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression) {
+        if let Some(member) = expr.as_member_expression() {
+            self.visit_member(member);
+            return;
+        }
+
+        match expr {
+            Expression::Identifier(ident) => {
+                self.referenced.insert(ident.name.to_string());
+            }
+            Expression::CallExpression(call) => {
+                self.visit_expression(&call.callee);
+                self.visit_arguments(&call.arguments);
+            }
+            Expression::NewExpression(new) => {
+                self.visit_expression(&new.callee);
+                self.visit_arguments(&new.arguments);
+            }
+            Expression::BinaryExpression(bin) => {
+                self.visit_expression(&bin.left);
+                self.visit_expression(&bin.right);
+            }
+            Expression::LogicalExpression(logical) => {
+                self.visit_expression(&logical.left);
+                self.visit_expression(&logical.right);
+            }
+            Expression::UnaryExpression(unary) => self.visit_expression(&unary.argument),
+            Expression::UpdateExpression(update) => self.visit_simple_target(&update.argument),
+            Expression::AssignmentExpression(assign) => {
+                if let Some(target) = assign.left.as_simple_assignment_target() {
+                    self.visit_simple_target(target);
+                }
+                self.visit_expression(&assign.right);
+            }
+            Expression::ConditionalExpression(cond) => {
+                self.visit_expression(&cond.test);
+                self.visit_expression(&cond.consequent);
+                self.visit_expression(&cond.alternate);
+            }
+            Expression::TemplateLiteral(template) => {
+                for expr in &template.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::ArrayExpression(array) => {
+                for element in &array.elements {
+                    match element {
+                        ArrayExpressionElement::SpreadElement(spread) => {
+                            self.visit_expression(&spread.argument);
+                        }
+                        _ => {
+                            if let Some(expr) = element.as_expression() {
+                                self.visit_expression(expr);
+                            }
+                        }
+                    }
+                }
+            }
+            Expression::ObjectExpression(object) => {
+                for property in &object.properties {
+                    match property {
+                        ObjectPropertyKind::ObjectProperty(property) => {
+                            if property.computed {
+                                if let Some(key) = property.key.as_expression() {
+                                    self.visit_expression(key);
+                                }
+                            }
+                            self.visit_expression(&property.value);
+                        }
+                        ObjectPropertyKind::SpreadProperty(spread) => {
+                            self.visit_expression(&spread.argument);
+                        }
+                    }
+                }
+            }
+            Expression::SequenceExpression(sequence) => {
+                for expr in &sequence.expressions {
+                    self.visit_expression(expr);
+                }
+            }
+            Expression::ParenthesizedExpression(paren) => self.visit_expression(&paren.expression),
+            Expression::AwaitExpression(await_expr) => self.visit_expression(&await_expr.argument),
+            Expression::ArrowFunctionExpression(arrow) => {
+                self.visit_function(&arrow.params, Some(&arrow.body));
+            }
+            Expression::FunctionExpression(func) => {
+                self.visit_function(&func.params, func.body.as_deref());
+            }
+            Expression::ClassExpression(class) => self.visit_class(class),
+            _ => {}
+        }
+    }
+}
+
+/// Jaccard similarity of two capture lists, 1.0 when both are empty
+pub fn capture_similarity(captures1: &[String], captures2: &[String]) -> f64 {
+    if captures1.is_empty() && captures2.is_empty() {
+        return 1.0;
+    }
+    let shared = captures1.iter().filter(|name| captures2.contains(name)).count();
+    let total = captures1.len() + captures2.len() - shared;
+    shared as f64 / total as f64
+}
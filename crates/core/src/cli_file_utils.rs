@@ -1,9 +1,50 @@
-use ignore::WalkBuilder;
+use ignore::{Walk, WalkBuilder};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
+/// Which files a directory walk skips
+#[derive(Debug, Clone, Copy)]
+pub struct WalkOptions {
+    /// Skip files matched by `.gitignore`, `.ignore` and the global gitignore,
+    /// in or out of a git repository
+    pub respect_ignore: bool,
+    /// Walk hidden files and directories too
+    pub hidden: bool,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        WalkOptions { respect_ignore: true, hidden: false }
+    }
+}
+
+impl WalkOptions {
+    /// Walk the directory `path` with these filters, not following links
+    pub fn walk(&self, path: &Path) -> Walk {
+        WalkBuilder::new(path)
+            .follow_links(false)
+            .hidden(!self.hidden)
+            .parents(self.respect_ignore)
+            .ignore(self.respect_ignore)
+            .git_ignore(self.respect_ignore)
+            .git_global(self.respect_ignore)
+            .git_exclude(self.respect_ignore)
+            .require_git(false)
+            .build()
+    }
+}
+
 /// Collect files from paths with given extensions
 pub fn collect_files(paths: &[String], extensions: &[&str]) -> anyhow::Result<Vec<PathBuf>> {
+    collect_files_with(paths, extensions, &WalkOptions::default())
+}
+
+/// [`collect_files`] with the given directory walk filters
+pub fn collect_files_with(
+    paths: &[String],
+    extensions: &[&str],
+    walk_options: &WalkOptions,
+) -> anyhow::Result<Vec<PathBuf>> {
     let mut files = Vec::new();
     let mut visited = HashSet::new();
 
@@ -25,10 +66,8 @@ pub fn collect_files(paths: &[String], extensions: &[&str]) -> anyhow::Result<Ve
                 }
             }
         } else if path.is_dir() {
-            // If it's a directory, walk it respecting .gitignore
-            let walker = WalkBuilder::new(path).follow_links(false).build();
-
-            for entry in walker {
+            // If it's a directory, walk it respecting .gitignore unless disabled
+            for entry in walk_options.walk(path) {
                 let entry = entry?;
                 let entry_path = entry.path();
 
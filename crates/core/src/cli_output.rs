@@ -60,3 +60,99 @@ impl<T> DuplicateResult<T> {
         self.similarity * avg_size
     }
 }
+
+/// Sort the members of each group by `location` (typically `file:line`) so
+/// the output is stable across runs. The order of the groups is left as is.
+pub fn sort_groups<T, K: Ord>(groups: &mut [Vec<T>], location: impl Fn(&T) -> K) {
+    for group in groups.iter_mut() {
+        group.sort_by_key(|member| location(member));
+    }
+}
+
+/// Sort the members of each group like [`sort_groups`], then order the groups
+/// largest first, with ties broken by the location of their representative,
+/// i.e. their first member.
+pub fn sort_groups_by_size<T, K: Ord>(groups: &mut [Vec<T>], location: impl Fn(&T) -> K) {
+    sort_groups(groups, &location);
+    groups.sort_by(|a, b| {
+        b.len().cmp(&a.len()).then_with(|| a.first().map(&location).cmp(&b.first().map(&location)))
+    });
+}
+
+/// Spell the non-ASCII characters of an identifier as Rust escapes
+/// (`é` as `\u{e9}`), for output read by tools that cannot handle UTF-8.
+/// Identifiers contain no backslash, so [`unescape_identifier`] restores the
+/// original.
+pub fn escape_identifier(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii() {
+            escaped.push(c);
+        } else {
+            escaped.push_str(&format!("\\u{{{:x}}}", c as u32));
+        }
+    }
+    escaped
+}
+
+/// Reverse [`escape_identifier`]; `None` when an escape is malformed
+pub fn unescape_identifier(escaped: &str) -> Option<String> {
+    let mut name = String::with_capacity(escaped.len());
+    let mut rest = escaped;
+    while let Some(start) = rest.find("\\u{") {
+        name.push_str(&rest[..start]);
+        let after = &rest[start + 3..];
+        let end = after.find('}')?;
+        name.push(char::from_u32(u32::from_str_radix(&after[..end], 16).ok()?)?);
+        rest = &after[end + 1..];
+    }
+    name.push_str(rest);
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifier_escaping_round_trips() {
+        for name in ["total", "données", "合計_値", "naïve_𝛼"] {
+            let escaped = escape_identifier(name);
+            assert!(escaped.is_ascii(), "{escaped}");
+            assert_eq!(unescape_identifier(&escaped).as_deref(), Some(name));
+        }
+        assert_eq!(escape_identifier("données"), "donn\\u{e9}es");
+        assert_eq!(unescape_identifier("bad\\u{zz}"), None);
+    }
+
+    #[test]
+    fn test_sort_groups_keeps_the_group_order() {
+        let mut groups = vec![vec![("b.rs", 9), ("b.rs", 3)], vec![("a.rs", 20), ("a.rs", 5)]];
+
+        sort_groups(&mut groups, |&(file, line)| (file, line));
+
+        assert_eq!(groups, vec![vec![("b.rs", 3), ("b.rs", 9)], vec![("a.rs", 5), ("a.rs", 20)]]);
+    }
+
+    #[test]
+    fn test_sort_groups_by_size_then_representative() {
+        let mut groups = vec![
+            vec![("b.rs", 9), ("b.rs", 3)],
+            vec![("c.rs", 1)],
+            vec![("a.rs", 20), ("a.rs", 5)],
+            vec![("d.rs", 7), ("d.rs", 2), ("d.rs", 4)],
+        ];
+
+        sort_groups_by_size(&mut groups, |&(file, line)| (file, line));
+
+        assert_eq!(
+            groups,
+            vec![
+                vec![("d.rs", 2), ("d.rs", 4), ("d.rs", 7)],
+                vec![("a.rs", 5), ("a.rs", 20)],
+                vec![("b.rs", 3), ("b.rs", 9)],
+                vec![("c.rs", 1)],
+            ]
+        );
+    }
+}
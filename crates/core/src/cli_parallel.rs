@@ -1,7 +1,10 @@
+use crate::clustering::connected_components;
+use crate::language_parser::GenericFunctionDef;
 use crate::TSEDOptions;
 use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Generic file data structure for any language
 #[derive(Debug)]
@@ -24,6 +27,7 @@ pub trait FunctionExtractor {
 
 /// Generic similarity result
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimilarityResult<F> {
     pub func1: F,
     pub func2: F,
@@ -36,6 +40,87 @@ impl<F> SimilarityResult<F> {
     }
 }
 
+/// A reported pair of functions together with the files they are in
+pub trait LocatedPair {
+    /// The two functions, each with its file
+    fn ends(&self) -> [(&Path, &GenericFunctionDef); 2];
+
+    /// Exchange the two functions
+    fn swap_ends(&mut self);
+}
+
+/// Identifies a function across the pairs it is reported in
+type PairEnd = (PathBuf, u32, u32, String);
+
+fn pair_end((file, func): (&Path, &GenericFunctionDef)) -> PairEnd {
+    (file.to_path_buf(), func.start_line, func.end_line, func.name.clone())
+}
+
+/// Put the two functions of a pair in location order (file path, then line)
+pub fn canonical_order<P: LocatedPair>(pair: &mut P) {
+    let [first, second] = pair.ends().map(pair_end);
+    if second < first {
+        pair.swap_ends();
+    }
+}
+
+/// Report every unordered pair once: the two functions of a pair are put in
+/// location order (file path, then line), and later repeats of a pair are
+/// dropped.
+pub fn canonicalize_pairs<P: LocatedPair>(pairs: &mut Vec<P>) {
+    pairs.iter_mut().for_each(canonical_order);
+
+    let mut seen = HashSet::new();
+    pairs.retain(|pair| seen.insert(pair.ends().map(pair_end)));
+}
+
+/// Collapse each group of three or more functions that are all similar to
+/// each other into the pairs of its representative, the longest function,
+/// with every other member. Groups missing some pair are kept whole, since
+/// no single function stands for the others.
+///
+/// Expects pairs without repeats, as left by [`canonicalize_pairs`].
+pub fn transitive_dedup<P: LocatedPair>(pairs: Vec<P>) -> Vec<P> {
+    let edges: Vec<[PairEnd; 2]> = pairs.iter().map(|pair| pair.ends().map(pair_end)).collect();
+    let edge_tuples: Vec<(PairEnd, PairEnd)> =
+        edges.iter().map(|[a, b]| (a.clone(), b.clone())).collect();
+
+    let mut component_of: HashMap<&PairEnd, usize> = HashMap::new();
+    let components = connected_components(&edge_tuples);
+    for (index, members) in components.iter().enumerate() {
+        for member in members {
+            component_of.insert(member, index);
+        }
+    }
+    let mut edge_counts = vec![0; components.len()];
+    for [a, _] in &edges {
+        edge_counts[component_of[a]] += 1;
+    }
+
+    let representatives: Vec<Option<&PairEnd>> = components
+        .iter()
+        .zip(&edge_counts)
+        .map(|(members, &edge_count)| {
+            let complete =
+                members.len() >= 3 && edge_count == members.len() * (members.len() - 1) / 2;
+            // `max_by_key` keeps the last maximum, so search from the end
+            complete
+                .then(|| members.iter().rev().max_by_key(|(_, start, end, _)| end - start))
+                .flatten()
+        })
+        .collect();
+
+    pairs
+        .into_iter()
+        .zip(&edges)
+        .filter(|(_, [a, b])| match representatives[component_of[a]] {
+            Some(representative) => a == representative || b == representative,
+            None => true,
+        })
+        .map(|(pair, _)| pair)
+        .collect()
+}
+
 /// Trait for finding similar functions
 pub trait SimilarityChecker {
     type Function: Clone + Send + Sync;
@@ -59,6 +144,25 @@ pub trait SimilarityChecker {
     ) -> Result<f64, Box<dyn std::error::Error>>;
 }
 
+/// Run `f` on a dedicated rayon pool with `threads` worker threads, or on the
+/// global pool when `threads` is `None`.
+///
+/// Lets the IO-bound loading phase and the CPU-bound comparison phase be tuned
+/// independently.
+pub fn run_with_threads<T, F>(threads: Option<usize>, f: F) -> anyhow::Result<T>
+where
+    T: Send,
+    F: FnOnce() -> T + Send,
+{
+    match threads {
+        Some(threads) => {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build()?;
+            Ok(pool.install(f))
+        }
+        None => Ok(f()),
+    }
+}
+
 /// Load and parse files in parallel using a generic extractor
 pub fn load_files_parallel<E>(files: &[PathBuf], extractor: &E) -> Vec<FileData<E::Function>>
 where
@@ -173,3 +277,83 @@ where
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Pair(PathBuf, PathBuf, SimilarityResult<GenericFunctionDef>);
+
+    impl LocatedPair for Pair {
+        fn ends(&self) -> [(&Path, &GenericFunctionDef); 2] {
+            [(&self.0, &self.2.func1), (&self.1, &self.2.func2)]
+        }
+
+        fn swap_ends(&mut self) {
+            std::mem::swap(&mut self.0, &mut self.1);
+            std::mem::swap(&mut self.2.func1, &mut self.2.func2);
+        }
+    }
+
+    fn function(name: &str, start_line: u32, end_line: u32) -> GenericFunctionDef {
+        GenericFunctionDef {
+            name: name.to_string(),
+            start_line,
+            end_line,
+            body_start_line: start_line,
+            body_end_line: end_line,
+            parameters: Vec::new(),
+            is_method: false,
+            kind: crate::function_extractor::FunctionType::Function,
+            class_name: None,
+            is_async: false,
+            is_generator: false,
+            decorators: Vec::new(),
+        }
+    }
+
+    fn pair(first: (&str, &GenericFunctionDef), second: (&str, &GenericFunctionDef)) -> Pair {
+        Pair(
+            PathBuf::from(first.0),
+            PathBuf::from(second.0),
+            SimilarityResult::new(first.1.clone(), second.1.clone(), 0.9),
+        )
+    }
+
+    fn names(pairs: &[Pair]) -> Vec<(&str, &str)> {
+        pairs.iter().map(|p| (p.2.func1.name.as_str(), p.2.func2.name.as_str())).collect()
+    }
+
+    #[test]
+    fn test_symmetric_pairs_are_reported_once_in_location_order() {
+        let (a, b, c) = (function("a", 1, 5), function("b", 10, 20), function("c", 1, 5));
+        let mut pairs = vec![
+            pair(("x.rs", &b), ("x.rs", &a)),
+            pair(("x.rs", &a), ("x.rs", &b)),
+            pair(("y.rs", &c), ("x.rs", &b)),
+            pair(("x.rs", &a), ("y.rs", &c)),
+        ];
+
+        canonicalize_pairs(&mut pairs);
+        assert_eq!(names(&pairs), vec![("a", "b"), ("b", "c"), ("a", "c")]);
+        assert_eq!(pairs[1].0, PathBuf::from("x.rs"));
+    }
+
+    #[test]
+    fn test_complete_groups_collapse_to_their_representative() {
+        let (a, b, c) = (function("a", 1, 5), function("b", 10, 20), function("c", 30, 34));
+        let (x, y, z) = (function("x", 40, 44), function("y", 50, 54), function("z", 60, 64));
+        let pairs = vec![
+            // All three of a, b and c are similar to each other
+            pair(("f.rs", &a), ("f.rs", &b)),
+            pair(("f.rs", &b), ("f.rs", &c)),
+            pair(("f.rs", &a), ("f.rs", &c)),
+            // x and z are only linked through y
+            pair(("f.rs", &x), ("f.rs", &y)),
+            pair(("f.rs", &y), ("f.rs", &z)),
+        ];
+
+        let pairs = transitive_dedup(pairs);
+        assert_eq!(names(&pairs), vec![("a", "b"), ("b", "c"), ("x", "y"), ("y", "z")]);
+    }
+}
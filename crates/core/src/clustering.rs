@@ -0,0 +1,67 @@
+use std::collections::BTreeMap;
+
+/// Group the nodes of an undirected graph, given as its edges, into connected
+/// components with a union-find.
+///
+/// Members of a component are sorted, and components are ordered largest
+/// first with ties broken by their smallest member, so the result is
+/// deterministic.
+pub fn connected_components<N: Ord + Clone>(edges: &[(N, N)]) -> Vec<Vec<N>> {
+    let mut indices: BTreeMap<&N, usize> = BTreeMap::new();
+    for (a, b) in edges {
+        for node in [a, b] {
+            let next = indices.len();
+            indices.entry(node).or_insert(next);
+        }
+    }
+
+    let mut parent: Vec<usize> = (0..indices.len()).collect();
+    for (a, b) in edges {
+        let (root_a, root_b) =
+            (find_root(&mut parent, indices[a]), find_root(&mut parent, indices[b]));
+        parent[root_a] = root_b;
+    }
+
+    // Nodes are visited in order, so members come out sorted
+    let mut components: BTreeMap<usize, Vec<N>> = BTreeMap::new();
+    for (node, &index) in &indices {
+        components.entry(find_root(&mut parent, index)).or_default().push((*node).clone());
+    }
+    let mut components: Vec<Vec<N>> = components.into_values().collect();
+    components.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a[0].cmp(&b[0])));
+    components
+}
+
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_components_of_pairs() {
+        // A chain of three, a pair, and a clique of four given in any order
+        let edges = vec![
+            ("b", "a"),
+            ("c", "b"),
+            ("x", "y"),
+            ("p", "q"),
+            ("q", "r"),
+            ("r", "s"),
+            ("s", "p"),
+            ("p", "r"),
+        ];
+
+        assert_eq!(
+            connected_components(&edges),
+            vec![vec!["p", "q", "r", "s"], vec!["a", "b", "c"], vec!["x", "y"]]
+        );
+        assert!(connected_components::<&str>(&[]).is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+//! Control-flow skeletons for cheap, order-sensitive prefiltering.
+//!
+//! A skeleton is the sequence of branch, loop and jump constructs of a function
+//! in pre-order, with grammar-specific node kinds mapped to a shared vocabulary.
+//! Functions are bucketed by the hash of their skeleton so that only functions
+//! with the same (or an almost identical) control flow are sent to APTED.
+
+use crate::tree::TreeNode;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Map a tree-sitter node kind to its control-flow token, if it is one
+fn control_flow_token(label: &str) -> Option<&'static str> {
+    let token = match label {
+        "if_expression" | "if_statement" | "conditional_expression" | "ternary_expression" => "if",
+        "elif_clause" => "elif",
+        "else_clause" => "else",
+        "while_expression" | "while_statement" | "do_statement" => "while",
+        "for_expression" | "for_statement" | "for_in_statement" => "for",
+        "loop_expression" => "loop",
+        "match_expression" | "switch_statement" | "match_statement" => "match",
+        "match_arm" | "switch_case" | "case_clause" => "case",
+        "try_expression" | "try_statement" => "try",
+        "catch_clause" | "except_clause" => "catch",
+        "return_expression" | "return_statement" => "return",
+        "break_expression" | "break_statement" => "break",
+        "continue_expression" | "continue_statement" => "continue",
+        _ => return None,
+    };
+    Some(token)
+}
+
+/// Collect the control-flow skeleton of a tree in pre-order
+pub fn control_flow_skeleton(tree: &TreeNode) -> Vec<&'static str> {
+    fn visit(node: &TreeNode, skeleton: &mut Vec<&'static str>) {
+        if let Some(token) = control_flow_token(&node.label) {
+            skeleton.push(token);
+        }
+        for child in &node.children {
+            visit(child, skeleton);
+        }
+    }
+
+    let mut skeleton = Vec::new();
+    visit(tree, &mut skeleton);
+    skeleton
+}
+
+/// Hash a control-flow skeleton
+pub fn skeleton_hash(skeleton: &[&str]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    skeleton.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Two skeletons are compatible when they are equal or differ by a single
+/// inserted, deleted or replaced construct
+fn skeletons_compatible(a: &[&str], b: &[&str]) -> bool {
+    let (shorter, longer) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if longer.len() - shorter.len() > 1 {
+        return false;
+    }
+
+    let prefix = shorter.iter().zip(longer).take_while(|(x, y)| x == y).count();
+    let suffix = shorter[prefix..]
+        .iter()
+        .rev()
+        .zip(longer[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    longer.len() - prefix - suffix <= 1
+}
+
+/// Bucket trees by control-flow skeleton and return the index pairs `(i, j)`
+/// with `i < j` worth comparing: pairs within a bucket and pairs across
+/// compatible buckets. Pairs are returned in ascending order.
+pub fn cfg_candidate_pairs(trees: &[&TreeNode]) -> Vec<(usize, usize)> {
+    let mut buckets: HashMap<u64, (Vec<&'static str>, Vec<usize>)> = HashMap::new();
+    for (index, tree) in trees.iter().enumerate() {
+        let skeleton = control_flow_skeleton(tree);
+        buckets
+            .entry(skeleton_hash(&skeleton))
+            .or_insert_with(|| (skeleton, Vec::new()))
+            .1
+            .push(index);
+    }
+
+    let buckets: Vec<_> = buckets.into_values().collect();
+    let mut pairs = Vec::new();
+
+    for (b1, (skeleton1, members1)) in buckets.iter().enumerate() {
+        for (offset, &i) in members1.iter().enumerate() {
+            for &j in &members1[offset + 1..] {
+                pairs.push((i.min(j), i.max(j)));
+            }
+        }
+
+        for (skeleton2, members2) in &buckets[b1 + 1..] {
+            if !skeletons_compatible(skeleton1, skeleton2) {
+                continue;
+            }
+            for &i in members1 {
+                for &j in members2 {
+                    pairs.push((i.min(j), i.max(j)));
+                }
+            }
+        }
+    }
+
+    pairs.sort_unstable();
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn node(label: &str, children: Vec<TreeNode>) -> TreeNode {
+        let mut n = TreeNode::new(label.to_string(), String::new(), 0);
+        for child in children {
+            n.add_child(Rc::new(child));
+        }
+        n
+    }
+
+    #[test]
+    fn test_skeleton_is_grammar_neutral_and_ordered() {
+        let rust = node(
+            "block",
+            vec![
+                node("for_expression", vec![node("if_expression", vec![])]),
+                node("return_expression", vec![]),
+            ],
+        );
+        let python = node(
+            "block",
+            vec![
+                node("for_statement", vec![node("if_statement", vec![])]),
+                node("return_statement", vec![]),
+            ],
+        );
+
+        assert_eq!(control_flow_skeleton(&rust), vec!["for", "if", "return"]);
+        assert_eq!(
+            skeleton_hash(&control_flow_skeleton(&rust)),
+            skeleton_hash(&control_flow_skeleton(&python))
+        );
+    }
+
+    #[test]
+    fn test_candidate_pairs_follow_compatible_buckets() {
+        let loop_with_branch =
+            node("block", vec![node("for_expression", vec![node("if_expression", vec![])])]);
+        let same = loop_with_branch.clone();
+        let one_more = node(
+            "block",
+            vec![
+                node("for_expression", vec![node("if_expression", vec![])]),
+                node("return_expression", vec![]),
+            ],
+        );
+        let unrelated = node(
+            "block",
+            vec![
+                node(
+                    "match_expression",
+                    vec![node("match_arm", vec![]), node("match_arm", vec![])],
+                ),
+                node("while_expression", vec![]),
+            ],
+        );
+
+        let trees = [&loop_with_branch, &same, &one_more, &unrelated];
+        assert_eq!(cfg_candidate_pairs(&trees), vec![(0, 1), (0, 2), (1, 2)]);
+    }
+}
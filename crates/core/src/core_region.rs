@@ -0,0 +1,208 @@
+//! Similarity of the shared core of two functions.
+//!
+//! Functions that run the same logic but differ in leading setup or trailing
+//! cleanup, logging or return wrapping score low as a whole. The body
+//! statements of both functions are aligned in order and the longest run of
+//! matching statements is compared on its own, ignoring the non-matching
+//! prefix and suffix around it.
+
+use crate::tree::TreeNode;
+use crate::tsed::{calculate_tsed, calculate_tsed_bounded, TSEDOptions};
+use std::rc::Rc;
+
+/// Minimum similarity for two statements to be aligned with each other
+const STATEMENT_MATCH_THRESHOLD: f64 = 0.7;
+
+/// The best-matching contiguous statement region of two functions
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreSimilarity {
+    /// TSED similarity of the two core regions
+    pub similarity: f64,
+    /// Statement range of the core in the first function (0-based, end exclusive)
+    pub range1: (usize, usize),
+    /// Statement range of the core in the second function (0-based, end exclusive)
+    pub range2: (usize, usize),
+    /// Number of body statements of the first function
+    pub statements1: usize,
+    /// Number of body statements of the second function
+    pub statements2: usize,
+}
+
+impl CoreSimilarity {
+    /// Number of statements in the core
+    pub fn len(&self) -> usize {
+        self.range1.1 - self.range1.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Share of the shorter function's statements covered by the core
+    pub fn coverage(&self) -> f64 {
+        let shorter = self.statements1.min(self.statements2);
+        if shorter == 0 {
+            0.0
+        } else {
+            self.len() as f64 / shorter as f64
+        }
+    }
+}
+
+/// Find the longest run of statements that match in order in both functions
+/// and compute the similarity of that region alone.
+///
+/// Statements are the children of the function body, found by descending
+/// through single-child wrappers to the function node and taking its last
+/// child; punctuation tokens are skipped. Returns `None` when no pair of
+/// statements matches.
+pub fn directional_core_similarity(
+    tree1: &Rc<TreeNode>,
+    tree2: &Rc<TreeNode>,
+    options: &TSEDOptions,
+) -> Option<CoreSimilarity> {
+    let statements1 = body_statements(tree1);
+    let statements2 = body_statements(tree2);
+
+    // Single statements are short trees; the short-function penalty would keep
+    // identical statements from matching
+    let statement_options = TSEDOptions { size_penalty: false, ..options.clone() };
+    let matches: Vec<Vec<bool>> = statements1
+        .iter()
+        .map(|s1| {
+            statements2
+                .iter()
+                .map(|s2| {
+                    comparable_size(s1, s2)
+                        && calculate_tsed_bounded(
+                            s1,
+                            s2,
+                            &statement_options,
+                            STATEMENT_MATCH_THRESHOLD,
+                        )
+                        .is_some()
+                })
+                .collect()
+        })
+        .collect();
+
+    // Longest diagonal run of matches, keeping the earliest on ties
+    let mut run = vec![vec![0usize; statements2.len() + 1]; statements1.len() + 1];
+    let mut best = (0, 0, 0);
+    for i in 0..statements1.len() {
+        for j in 0..statements2.len() {
+            if matches[i][j] {
+                run[i + 1][j + 1] = run[i][j] + 1;
+                if run[i + 1][j + 1] > best.0 {
+                    best = (run[i + 1][j + 1], i + 1, j + 1);
+                }
+            }
+        }
+    }
+
+    let (len, end1, end2) = best;
+    if len == 0 {
+        return None;
+    }
+    let range1 = (end1 - len, end1);
+    let range2 = (end2 - len, end2);
+
+    let core1 = region_tree(&statements1[range1.0..range1.1]);
+    let core2 = region_tree(&statements2[range2.0..range2.1]);
+
+    Some(CoreSimilarity {
+        similarity: calculate_tsed(&core1, &core2, options),
+        range1,
+        range2,
+        statements1: statements1.len(),
+        statements2: statements2.len(),
+    })
+}
+
+fn body_statements(tree: &TreeNode) -> Vec<Rc<TreeNode>> {
+    let mut node = tree;
+    while node.children.len() == 1 {
+        node = &node.children[0];
+    }
+    let body = match node.children.last() {
+        Some(body) if !body.children.is_empty() => body,
+        _ => node,
+    };
+    body.children.iter().filter(|child| !is_punctuation(child)).cloned().collect()
+}
+
+/// Statements less than half the size of each other are never aligned, so that
+/// a bare trailing expression cannot stand in for a whole statement
+fn comparable_size(s1: &TreeNode, s2: &TreeNode) -> bool {
+    let (size1, size2) = (s1.get_subtree_size(), s2.get_subtree_size());
+    size1.min(size2) * 2 >= size1.max(size2)
+}
+
+fn is_punctuation(node: &TreeNode) -> bool {
+    node.children.is_empty() && !node.label.chars().any(char::is_alphanumeric)
+}
+
+fn region_tree(statements: &[Rc<TreeNode>]) -> Rc<TreeNode> {
+    let mut region = TreeNode::new("core".to_string(), String::new(), 0);
+    for statement in statements {
+        region.add_child(statement.clone());
+    }
+    Rc::new(region)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(label: &str, children: Vec<Rc<TreeNode>>) -> Rc<TreeNode> {
+        let mut node = TreeNode::new(label.to_string(), String::new(), 0);
+        for child in children {
+            node.add_child(child);
+        }
+        Rc::new(node)
+    }
+
+    /// A statement with `width` leaves, so that statements of different width do not match
+    fn statement(label: &str, width: usize) -> Rc<TreeNode> {
+        node(label, (0..width).map(|_| node("identifier", vec![])).collect())
+    }
+
+    fn function(statements: Vec<Rc<TreeNode>>) -> Rc<TreeNode> {
+        let mut children = vec![node("{", vec![])];
+        children.extend(statements);
+        children.push(node("}", vec![]));
+        node(
+            "source_file",
+            vec![node("function", vec![node("name", vec![]), node("block", children)])],
+        )
+    }
+
+    #[test]
+    fn test_core_ignores_differing_prefix_and_suffix() {
+        let shared = || vec![statement("loop", 1), statement("assign", 2), statement("call", 3)];
+        let mut first = vec![statement("setup", 6)];
+        first.extend(shared());
+        first.push(statement("log", 5));
+        let mut second = shared();
+        second.push(node("return", vec![node("wrap", vec![node("ok", vec![])])]));
+
+        let options = TSEDOptions { size_penalty: false, ..Default::default() };
+        let core = directional_core_similarity(&function(first), &function(second), &options)
+            .expect("shared statements form a core");
+
+        assert_eq!(core.range1, (1, 4));
+        assert_eq!(core.range2, (0, 3));
+        assert_eq!(core.similarity, 1.0);
+        assert_eq!(core.coverage(), 0.75);
+    }
+
+    #[test]
+    fn test_no_core_without_matching_statements() {
+        let first = function(vec![statement("loop", 4)]);
+        let call = node("call", vec![node("path", vec![]), node("arguments", vec![])]);
+        let second = function(vec![node("return", vec![call])]);
+        let options = TSEDOptions::default();
+
+        assert_eq!(directional_core_similarity(&first, &second, &options), None);
+    }
+}
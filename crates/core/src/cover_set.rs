@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+
+/// Approximate a minimum vertex cover of an undirected graph given by its
+/// edges: a small set of nodes such that every edge has at least one end in it.
+///
+/// Greedily picks the node covering the most remaining edges until none are
+/// left; ties go to the smallest node, so the result is deterministic. Nodes
+/// are returned in the order they were picked, most connected first.
+pub fn approximate_vertex_cover<N: Ord + Clone>(edges: &[(N, N)]) -> Vec<N> {
+    let mut remaining: Vec<&(N, N)> = edges.iter().filter(|(a, b)| a != b).collect();
+    let mut cover = Vec::new();
+
+    while !remaining.is_empty() {
+        let mut degrees: BTreeMap<&N, usize> = BTreeMap::new();
+        for (a, b) in &remaining {
+            *degrees.entry(a).or_default() += 1;
+            *degrees.entry(b).or_default() += 1;
+        }
+        // `max_by_key` keeps the last maximum, so iterate from the largest node
+        let Some((&node, _)) = degrees.iter().rev().max_by_key(|(_, &degree)| degree) else {
+            break;
+        };
+        let node = node.clone();
+        remaining.retain(|(a, b)| *a != node && *b != node);
+        cover.push(node);
+    }
+
+    cover
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cover_touches_every_edge() {
+        // A star around `hub` plus a separate triangle
+        let edges =
+            vec![("hub", "a"), ("hub", "b"), ("hub", "c"), ("x", "y"), ("y", "z"), ("z", "x")];
+
+        let cover = approximate_vertex_cover(&edges);
+
+        for (a, b) in &edges {
+            assert!(cover.contains(a) || cover.contains(b), "edge {a}-{b} is not covered");
+        }
+        assert_eq!(cover[0], "hub");
+        assert_eq!(cover.len(), 3);
+    }
+
+    #[test]
+    fn test_empty_graph_needs_no_cover() {
+        assert!(approximate_vertex_cover::<&str>(&[]).is_empty());
+    }
+}
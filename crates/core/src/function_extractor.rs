@@ -1,12 +1,20 @@
 use oxc_ast::ast::*;
 use oxc_span::Span;
+use std::collections::BTreeMap;
 
+use crate::captures::{arrow_captures, capture_similarity};
+use crate::clustering::connected_components;
 use crate::parser::parse_and_convert_to_tree;
-use crate::tsed::{calculate_tsed, TSEDOptions};
+use crate::tree::TreeNode;
+use crate::tsed::{
+    calculate_tsed_bounded, calculate_tsed_keyed, exact_match_key, KeyedTrees, TSEDOptions,
+};
+use std::rc::Rc;
 
 type CrossFileSimilarityResult = Vec<(String, SimilarityResult, String)>;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimilarityResult {
     pub func1: FunctionDefinition,
     pub func2: FunctionDefinition,
@@ -23,16 +31,21 @@ impl SimilarityResult {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FunctionDefinition {
     pub name: String,
     pub function_type: FunctionType,
     pub parameters: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(with = "span_serde"))]
     pub body_span: Span,
     pub start_line: u32,
     pub end_line: u32,
     pub class_name: Option<String>,
     pub parent_function: Option<String>,
     pub node_count: Option<u32>,
+    /// Variables an arrow function reads from its environment; empty for the
+    /// other kinds of functions
+    pub captures: Vec<String>,
 }
 
 impl FunctionDefinition {
@@ -58,12 +71,38 @@ impl FunctionDefinition {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FunctionType {
+    #[default]
     Function,
     Method,
     Arrow,
     Constructor,
+    /// A closure or lambda expression, such as Rust's `|x| x + 1`
+    Closure,
+}
+
+/// `Span` as `{ "start": .., "end": .. }`; oxc only implements `Serialize` for it
+#[cfg(feature = "serde")]
+mod span_serde {
+    use oxc_span::Span;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Offsets {
+        start: u32,
+        end: u32,
+    }
+
+    pub fn serialize<S: Serializer>(span: &Span, serializer: S) -> Result<S::Ok, S::Error> {
+        Offsets { start: span.start, end: span.end }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Span, D::Error> {
+        let offsets = Offsets::deserialize(deserializer)?;
+        Ok(Span::new(offsets.start, offsets.end))
+    }
 }
 
 /// Extract all functions from TypeScript/JavaScript code
@@ -75,6 +114,7 @@ pub fn extract_functions(
     use oxc_parser::Parser;
     use oxc_span::SourceType;
 
+    crate::parser::check_source_nesting(source_text)?;
     let allocator = Allocator::default();
     let source_type = SourceType::from_path(filename).unwrap_or(SourceType::tsx());
     let ret = Parser::new(&allocator, source_text, source_type).parse();
@@ -95,6 +135,60 @@ pub fn extract_functions(
     Ok(functions)
 }
 
+/// A class declaration with the class it extends
+#[derive(Debug, Clone)]
+pub struct ClassDefinition {
+    pub name: String,
+    /// Superclass by simple name, e.g. `React.Component` -> `Component`
+    pub extends: Option<String>,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+/// Extract the classes declared at the top level of TypeScript/JavaScript
+/// code, the same classes whose methods `extract_functions` reports
+pub fn extract_classes(filename: &str, source_text: &str) -> Result<Vec<ClassDefinition>, String> {
+    use oxc_allocator::Allocator;
+    use oxc_parser::Parser;
+    use oxc_span::SourceType;
+
+    crate::parser::check_source_nesting(source_text)?;
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path(filename).unwrap_or(SourceType::tsx());
+    let ret = Parser::new(&allocator, source_text, source_type).parse();
+
+    if !ret.errors.is_empty() {
+        return Err(format!("Parse errors: {:?}", ret.errors));
+    }
+
+    let mut classes = Vec::new();
+    for stmt in &ret.program.body {
+        let class = match stmt {
+            Statement::ClassDeclaration(class) => class,
+            Statement::ExportNamedDeclaration(export) => match &export.declaration {
+                Some(Declaration::ClassDeclaration(class)) => class,
+                _ => continue,
+            },
+            _ => continue,
+        };
+        let Some(id) = &class.id else {
+            continue;
+        };
+        let extends = class.super_class.as_ref().and_then(|super_class| match super_class {
+            Expression::Identifier(ident) => Some(ident.name.to_string()),
+            Expression::StaticMemberExpression(member) => Some(member.property.name.to_string()),
+            _ => None,
+        });
+        classes.push(ClassDefinition {
+            name: id.name.to_string(),
+            extends,
+            start_line: get_line_number(class.span.start, source_text),
+            end_line: get_line_number(class.span.end, source_text),
+        });
+    }
+    Ok(classes)
+}
+
 struct ExtractionContext<'a> {
     functions: &'a mut Vec<FunctionDefinition>,
     source_text: &'a str,
@@ -124,6 +218,7 @@ fn extract_from_statement(stmt: &Statement, ctx: &mut ExtractionContext) {
                     class_name: None,
                     parent_function: ctx.parent_function.clone(),
                     node_count: count_function_nodes(func.span, ctx.source_text),
+                    captures: Vec::new(),
                 });
 
                 // Extract nested functions within the function body
@@ -171,6 +266,7 @@ fn extract_from_statement(stmt: &Statement, ctx: &mut ExtractionContext) {
                         class_name: class_name.clone(),
                         parent_function: ctx.parent_function.clone(),
                         node_count: count_function_nodes(method.span, ctx.source_text),
+                        captures: Vec::new(),
                     });
 
                     // Extract nested functions within method body
@@ -201,6 +297,7 @@ fn extract_from_statement(stmt: &Statement, ctx: &mut ExtractionContext) {
                             class_name: None,
                             parent_function: ctx.parent_function.clone(),
                             node_count: count_function_nodes(arrow.span, ctx.source_text),
+                            captures: arrow_captures(arrow),
                         });
 
                         // Extract nested functions within arrow function body
@@ -238,6 +335,7 @@ fn extract_from_statement(stmt: &Statement, ctx: &mut ExtractionContext) {
                     class_name: None,
                     parent_function: ctx.parent_function.clone(),
                     node_count: count_function_nodes(func.span, ctx.source_text),
+                    captures: Vec::new(),
                 });
 
                 // Extract nested functions within the function body
@@ -269,6 +367,7 @@ fn extract_from_declaration(decl: &Declaration, ctx: &mut ExtractionContext) {
                     class_name: None,
                     parent_function: ctx.parent_function.clone(),
                     node_count: count_function_nodes(func.span, ctx.source_text),
+                    captures: Vec::new(),
                 });
 
                 // Extract nested functions within the function body
@@ -316,6 +415,7 @@ fn extract_from_declaration(decl: &Declaration, ctx: &mut ExtractionContext) {
                         class_name: class_name.clone(),
                         parent_function: ctx.parent_function.clone(),
                         node_count: count_function_nodes(method.span, ctx.source_text),
+                        captures: Vec::new(),
                     });
 
                     // Extract nested functions within method body
@@ -346,6 +446,7 @@ fn extract_from_declaration(decl: &Declaration, ctx: &mut ExtractionContext) {
                             class_name: None,
                             parent_function: ctx.parent_function.clone(),
                             node_count: count_function_nodes(arrow.span, ctx.source_text),
+                            captures: arrow_captures(arrow),
                         });
 
                         // Extract nested functions within arrow function body
@@ -405,16 +506,78 @@ pub fn compare_functions(
     source2: &str,
     options: &TSEDOptions,
 ) -> Result<f64, String> {
-    // Extract function body text
-    let body1 = extract_body_text(func1, source1);
-    let body2 = extract_body_text(func2, source2);
+    // Every similarity reaches 0.0, so this computes it in full
+    Ok(compare_functions_bounded(func1, func2, source1, source2, options, 0.0)?.unwrap_or(0.0))
+}
 
-    // Parse and compare
-    let tree1 = parse_and_convert_to_tree("func1.ts", &body1)?;
-    let tree2 = parse_and_convert_to_tree("func2.ts", &body2)?;
+/// Similarity between two functions when it reaches `threshold`
+fn compare_functions_bounded(
+    func1: &FunctionDefinition,
+    func2: &FunctionDefinition,
+    source1: &str,
+    source2: &str,
+    options: &TSEDOptions,
+    threshold: f64,
+) -> Result<Option<f64>, String> {
+    let tree1 = body_tree(func1, source1)?;
+    let tree2 = body_tree(func2, source2)?;
+
+    let Some(similarity) = calculate_tsed_bounded(&tree1, &tree2, options, threshold) else {
+        return Ok(None);
+    };
+    Ok(adjust_function_similarity(func1, func2, similarity, options, threshold))
+}
+
+/// Hash under which functions whose bodies are exact duplicates collide (see
+/// [`exact_match_key`]), to compare them with [`compare_functions_keyed`]
+pub fn function_match_key(
+    func: &FunctionDefinition,
+    source: &str,
+    options: &TSEDOptions,
+) -> Result<u64, String> {
+    Ok(exact_match_key(&body_tree(func, source)?, options))
+}
+
+/// Similarity between two functions when it reaches `threshold`, scoring
+/// exact duplicates, which share their [`function_match_key`], without
+/// running APTED
+pub fn compare_functions_keyed(
+    (func1, source1, key1): (&FunctionDefinition, &str, u64),
+    (func2, source2, key2): (&FunctionDefinition, &str, u64),
+    options: &TSEDOptions,
+    threshold: f64,
+) -> Result<Option<f64>, String> {
+    let tree1 = body_tree(func1, source1)?;
+    let tree2 = body_tree(func2, source2)?;
+
+    let Some(similarity) = calculate_tsed_keyed((&tree1, key1), (&tree2, key2), options, threshold)
+    else {
+        return Ok(None);
+    };
+    Ok(adjust_function_similarity(func1, func2, similarity, options, threshold))
+}
+
+fn body_tree(func: &FunctionDefinition, source: &str) -> Result<Rc<TreeNode>, String> {
+    parse_and_convert_to_tree("func.ts", &extract_body_text(func, source))
+}
+
+/// Share of the score of two nested closures that depends on how many of
+/// their captures they share
+const CAPTURE_WEIGHT: f64 = 0.25;
 
-    let mut similarity = calculate_tsed(&tree1, &tree2, options);
+fn is_nested_closure(func: &FunctionDefinition) -> bool {
+    func.function_type == FunctionType::Arrow && func.parent_function.is_some()
+}
 
+/// Similarity of two functions from the similarity of their bodies, when it
+/// reaches `threshold`
+fn adjust_function_similarity(
+    func1: &FunctionDefinition,
+    func2: &FunctionDefinition,
+    mut similarity: f64,
+    options: &TSEDOptions,
+    threshold: f64,
+) -> Option<f64> {
     // Apply size penalty for short functions if enabled
     if options.size_penalty {
         let avg_lines = (func1.line_count() + func2.line_count()) as f64 / 2.0;
@@ -425,7 +588,24 @@ pub fn compare_functions(
         }
     }
 
-    Ok(similarity)
+    // Closures reading different variables of their enclosing function behave
+    // differently even when their bodies match. Top-level arrow functions
+    // only read module names, which tell nothing more than their bodies.
+    if is_nested_closure(func1) && is_nested_closure(func2) {
+        let capture_similarity = capture_similarity(&func1.captures, &func2.captures);
+        similarity *= 1.0 - CAPTURE_WEIGHT * (1.0 - capture_similarity);
+    }
+
+    (similarity >= threshold).then_some(similarity)
+}
+
+/// Whether a function is long enough to be compared
+fn meets_minimum_size(func: &FunctionDefinition, options: &TSEDOptions) -> bool {
+    match options.min_tokens {
+        // If min_tokens is specified, use token count instead of line count
+        Some(min_tokens) => func.node_count.unwrap_or(0) >= min_tokens,
+        None => func.line_count() >= options.min_lines,
+    }
 }
 
 fn extract_body_text(func: &FunctionDefinition, source: &str) -> String {
@@ -500,38 +680,39 @@ pub fn find_similar_functions_in_file(
     threshold: f64,
     options: &TSEDOptions,
 ) -> Result<Vec<SimilarityResult>, String> {
-    let functions = extract_functions(filename, source_text)?;
+    let functions: Vec<FunctionDefinition> = extract_functions(filename, source_text)?
+        .into_iter()
+        .filter(|func| meets_minimum_size(func, options))
+        .collect();
     let mut similar_pairs = Vec::new();
+    if functions.len() < 2 {
+        return Ok(similar_pairs);
+    }
+
+    // Parse each body once; exact duplicates skip APTED
+    let trees =
+        functions.iter().map(|func| body_tree(func, source_text)).collect::<Result<Vec<_>, _>>()?;
+    let keyed = KeyedTrees::new(&trees, options);
 
     // Compare all pairs
     for i in 0..functions.len() {
         for j in (i + 1)..functions.len() {
-            // Skip if either function is too short
-            if let Some(min_tokens) = options.min_tokens {
-                // If min_tokens is specified, use token count instead of line count
-                let tokens_i = functions[i].node_count.unwrap_or(0);
-                let tokens_j = functions[j].node_count.unwrap_or(0);
-                if tokens_i < min_tokens || tokens_j < min_tokens {
-                    continue;
-                }
-            } else {
-                // Otherwise use line count
-                if functions[i].line_count() < options.min_lines
-                    || functions[j].line_count() < options.min_lines
-                {
-                    continue;
-                }
-            }
-
             // Skip if functions have parent-child relationship
             if functions[i].is_parent_child_relationship(&functions[j]) {
                 continue;
             }
 
-            let similarity =
-                compare_functions(&functions[i], &functions[j], source_text, source_text, options)?;
+            let similarity = keyed.similarity(i, j, threshold).and_then(|similarity| {
+                adjust_function_similarity(
+                    &functions[i],
+                    &functions[j],
+                    similarity,
+                    options,
+                    threshold,
+                )
+            });
 
-            if similarity >= threshold {
+            if let Some(similarity) = similarity {
                 similar_pairs.push(SimilarityResult::new(
                     functions[i].clone(),
                     functions[j].clone(),
@@ -558,56 +739,46 @@ pub fn find_similar_functions_across_files(
     options: &TSEDOptions,
 ) -> Result<CrossFileSimilarityResult, String> {
     let mut all_functions = Vec::new();
+    let mut trees = Vec::new();
 
-    // Extract functions from all files
+    // Extract functions from all files, parsing each body once
     for (filename, source) in files {
         let functions = extract_functions(filename, source)?;
-        for func in functions {
-            all_functions.push((filename.clone(), source.clone(), func));
+        for func in functions.into_iter().filter(|func| meets_minimum_size(func, options)) {
+            trees.push(body_tree(&func, source)?);
+            all_functions.push((filename, func));
         }
     }
+    // Exact duplicates skip APTED
+    let keyed = KeyedTrees::new(&trees, options);
 
     let mut similar_pairs = Vec::new();
 
     // Compare all pairs across files
     for i in 0..all_functions.len() {
         for j in (i + 1)..all_functions.len() {
-            let (first_file, source1, func1) = &all_functions[i];
-            let (second_file, source2, func2) = &all_functions[j];
+            let (first_file, func1) = &all_functions[i];
+            let (second_file, func2) = &all_functions[j];
 
             // Skip if same file (already handled by find_similar_functions_in_file)
             if first_file == second_file {
                 continue;
             }
 
-            // Skip if either function is too short
-            if let Some(min_tokens) = options.min_tokens {
-                // If min_tokens is specified, use token count instead of line count
-                let tokens1 = func1.node_count.unwrap_or(0);
-                let tokens2 = func2.node_count.unwrap_or(0);
-                if tokens1 < min_tokens || tokens2 < min_tokens {
-                    continue;
-                }
-            } else {
-                // Otherwise use line count
-                if func1.line_count() < options.min_lines || func2.line_count() < options.min_lines
-                {
-                    continue;
-                }
-            }
-
             // Skip if functions have parent-child relationship (across files)
             if func1.is_parent_child_relationship(func2) {
                 continue;
             }
 
-            let similarity = compare_functions(func1, func2, source1, source2, options)?;
+            let similarity = keyed.similarity(i, j, threshold).and_then(|similarity| {
+                adjust_function_similarity(func1, func2, similarity, options, threshold)
+            });
 
-            if similarity >= threshold {
+            if let Some(similarity) = similarity {
                 similar_pairs.push((
-                    first_file.clone(),
+                    (*first_file).clone(),
                     SimilarityResult::new(func1.clone(), func2.clone(), similarity),
-                    second_file.clone(),
+                    (*second_file).clone(),
                 ));
             }
         }
@@ -623,6 +794,73 @@ pub fn find_similar_functions_across_files(
     Ok(similar_pairs)
 }
 
+/// Group the functions of the pairs into clusters of transitively similar
+/// functions, largest cluster first.
+///
+/// Functions are identified by their span, so the results should come from
+/// one file; use [`cluster_similar_functions_across_files`] for the results of
+/// [`find_similar_functions_across_files`]. Each cluster starts with its
+/// representative, the longest function, followed by the other members in
+/// source order.
+pub fn cluster_similar_functions(results: &[SimilarityResult]) -> Vec<Vec<FunctionDefinition>> {
+    let pairs: Vec<_> =
+        results.iter().map(|result| (("", &result.func1), ("", &result.func2))).collect();
+    cluster_functions(&pairs)
+        .into_iter()
+        .map(|cluster| cluster.into_iter().map(|(_, func)| func.clone()).collect())
+        .collect()
+}
+
+/// Like [`cluster_similar_functions`] for pairs of functions from several
+/// files, identifying each function by its file and span. Members are paired
+/// with their file.
+pub fn cluster_similar_functions_across_files(
+    results: &[(String, SimilarityResult, String)],
+) -> Vec<Vec<(String, FunctionDefinition)>> {
+    let pairs: Vec<_> = results
+        .iter()
+        .map(|(file1, result, file2)| {
+            ((file1.as_str(), &result.func1), (file2.as_str(), &result.func2))
+        })
+        .collect();
+    cluster_functions(&pairs)
+        .into_iter()
+        .map(|cluster| {
+            cluster.into_iter().map(|(file, func)| (file.to_string(), func.clone())).collect()
+        })
+        .collect()
+}
+
+type Located<'a> = (&'a str, &'a FunctionDefinition);
+
+fn cluster_functions<'a>(pairs: &[(Located<'a>, Located<'a>)]) -> Vec<Vec<Located<'a>>> {
+    type Key<'a> = (&'a str, u32, u32, &'a str);
+    let key =
+        |(file, func): Located<'a>| (file, func.start_line, func.end_line, func.name.as_str());
+
+    let mut functions: BTreeMap<Key<'a>, Located<'a>> = BTreeMap::new();
+    let mut edges = Vec::with_capacity(pairs.len());
+    for &(a, b) in pairs {
+        functions.insert(key(a), a);
+        functions.insert(key(b), b);
+        edges.push((key(a), key(b)));
+    }
+
+    connected_components(&edges)
+        .into_iter()
+        .map(|members| {
+            let mut cluster: Vec<Located<'a>> =
+                members.iter().map(|member| functions[member]).collect();
+            // `max_by_key` keeps the last maximum, so search from the end
+            let longest =
+                (0..cluster.len()).rev().max_by_key(|&i| cluster[i].1.line_count()).unwrap_or(0);
+            let representative = cluster.remove(longest);
+            cluster.insert(0, representative);
+            cluster
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -694,6 +932,167 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_extract_classes_with_superclass() {
+        let code = r"
+            class Base {}
+            export class Widget extends React.Component {}
+            class Button extends Base {
+                render() {}
+            }
+        ";
+
+        let classes = extract_classes("test.ts", code).unwrap();
+        let summary: Vec<_> =
+            classes.iter().map(|class| (class.name.as_str(), class.extends.as_deref())).collect();
+        assert_eq!(
+            summary,
+            vec![("Base", None), ("Widget", Some("Component")), ("Button", Some("Base"))]
+        );
+        assert_eq!((classes[2].start_line, classes[2].end_line), (4, 6));
+    }
+
+    #[test]
+    fn test_closures_capturing_different_variables_score_lower() {
+        let code = "
+            function counters(count: number, step: number, total: number) {
+                const next = (value: number) => {
+                    const scaled = value * step;
+                    return count + scaled;
+                };
+                const again = (value: number) => {
+                    const scaled = value * step;
+                    return count + scaled;
+                };
+                const other = (value: number) => {
+                    const scaled = value * step;
+                    return total + scaled;
+                };
+            }
+        ";
+        let functions = extract_functions("counters.ts", code).unwrap();
+        let closure = |name: &str| functions.iter().find(|f| f.name == name).unwrap();
+        assert_eq!(closure("next").captures, vec!["count", "step"]);
+        assert_eq!(closure("other").captures, vec!["step", "total"]);
+        assert!(closure("counters").captures.is_empty());
+
+        let options = TSEDOptions { size_penalty: false, ..TSEDOptions::default() };
+        let compare = |a: &str, b: &str| {
+            compare_functions(closure(a), closure(b), code, code, &options).unwrap()
+        };
+        assert_eq!(compare("next", "again"), 1.0);
+        // One shared capture out of three takes two thirds of the capture weight
+        assert!(compare("next", "other") <= 1.0 - CAPTURE_WEIGHT * 2.0 / 3.0);
+        assert!(compare("next", "other") >= 1.0 - CAPTURE_WEIGHT);
+    }
+
+    #[test]
+    fn test_captures_of_top_level_arrow_functions_do_not_change_their_score() {
+        let code = "
+            const formatPrice = (item: Item) => {
+                const value = item.amount * 100;
+                return formatPrice(value);
+            };
+            const formatWeight = (item: Item) => {
+                const value = item.amount * 100;
+                return formatWeight(value);
+            };
+        ";
+        let mut functions = extract_functions("format.ts", code).unwrap();
+        assert_eq!(functions[0].captures, vec!["formatPrice"]);
+        assert_eq!(functions[1].captures, vec!["formatWeight"]);
+
+        let options = TSEDOptions { size_penalty: false, ..TSEDOptions::default() };
+        let score = compare_functions(&functions[0], &functions[1], code, code, &options).unwrap();
+        functions.iter_mut().for_each(|func| func.captures.clear());
+        let without_captures =
+            compare_functions(&functions[0], &functions[1], code, code, &options).unwrap();
+        assert_eq!(score, without_captures);
+    }
+
+    #[test]
+    fn test_captures_are_found_in_loops_try_and_switch() {
+        let code = "
+            function outer(items: number[], limit: number, fallback: number, mode: string) {
+                const inner = () => {
+                    for (let i = 0; i < limit; i++) {}
+                    for (const item of items) {}
+                    while (limit > 0) {}
+                    try {} catch (error) { return fallback; }
+                    switch (mode) { case 'a': return error; }
+                };
+            }
+        ";
+        let functions = extract_functions("outer.ts", code).unwrap();
+        let inner = functions.iter().find(|f| f.name == "inner").unwrap();
+        assert_eq!(inner.captures, vec!["fallback", "items", "limit", "mode"]);
+    }
+
+    #[test]
+    fn test_cluster_similar_functions() {
+        let function = |name: &str, start_line: u32, end_line: u32| FunctionDefinition {
+            name: name.to_string(),
+            function_type: FunctionType::Function,
+            parameters: Vec::new(),
+            body_span: Span::new(0, 0),
+            start_line,
+            end_line,
+            class_name: None,
+            parent_function: None,
+            node_count: None,
+            captures: Vec::new(),
+        };
+        let pair = |a: &FunctionDefinition, b: &FunctionDefinition| {
+            SimilarityResult::new(a.clone(), b.clone(), 0.9)
+        };
+        let (a, b, c) = (function("a", 1, 5), function("b", 7, 14), function("c", 16, 20));
+        let (x, y) = (function("x", 30, 34), function("y", 40, 44));
+
+        // Five mutually similar functions would be ten pairs; a chain is enough
+        let results = vec![pair(&a, &b), pair(&x, &y), pair(&b, &c)];
+        let clusters = cluster_similar_functions(&results);
+
+        let names: Vec<Vec<&str>> = clusters
+            .iter()
+            .map(|cluster| cluster.iter().map(|f| f.name.as_str()).collect())
+            .collect();
+        // The longest function represents its cluster
+        assert_eq!(names, vec![vec!["b", "a", "c"], vec!["x", "y"]]);
+    }
+
+    #[test]
+    fn test_cluster_similar_functions_across_files() {
+        let function = |name: &str| FunctionDefinition {
+            name: name.to_string(),
+            function_type: FunctionType::Function,
+            parameters: Vec::new(),
+            body_span: Span::new(0, 0),
+            start_line: 1,
+            end_line: 5,
+            class_name: None,
+            parent_function: None,
+            node_count: None,
+            captures: Vec::new(),
+        };
+        let pair = |file1: &str, file2: &str| {
+            (
+                file1.to_string(),
+                SimilarityResult::new(function("new"), function("new"), 0.9),
+                file2.to_string(),
+            )
+        };
+
+        // The same function at the same lines of four files is four functions
+        let results = vec![pair("a.ts", "b.ts"), pair("c.ts", "d.ts")];
+        let clusters = cluster_similar_functions_across_files(&results);
+
+        let files: Vec<Vec<&str>> = clusters
+            .iter()
+            .map(|cluster| cluster.iter().map(|(file, _)| file.as_str()).collect())
+            .collect();
+        assert_eq!(files, vec![vec!["a.ts", "b.ts"], vec!["c.ts", "d.ts"]]);
+    }
+
     #[test]
     fn test_node_count_calculation() {
         let code = r#"
@@ -771,6 +1170,37 @@ mod tests {
         assert!(sum_pairs >= 3, "Expected at least 3 pairs involving sum functions");
     }
 
+    #[test]
+    fn test_size_prefilter_keeps_the_same_pairs() {
+        // Functions of growing size, so that many pairs differ a lot in size
+        let code: String = (0..8)
+            .map(|i| {
+                let statements: String = (0..i * 2)
+                    .map(|j| format!("    const v{j} = items[{j}] * {i} + offset;\n    total += v{j};\n"))
+                    .collect();
+                format!(
+                    "function compute{i}(items: number[], offset: number): number {{\n    let total = 0;\n{statements}    return total;\n}}\n\n"
+                )
+            })
+            .collect();
+
+        let pairs = |size_prefilter: bool| {
+            let options = TSEDOptions { min_lines: 1, size_prefilter, ..TSEDOptions::default() };
+            let mut pairs: Vec<(String, String, f64)> =
+                find_similar_functions_in_file("corpus.ts", &code, 0.6, &options)
+                    .unwrap()
+                    .into_iter()
+                    .map(|result| (result.func1.name, result.func2.name, result.similarity))
+                    .collect();
+            pairs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            pairs
+        };
+
+        let filtered = pairs(true);
+        assert!(!filtered.is_empty());
+        assert_eq!(filtered, pairs(false));
+    }
+
     #[test]
     fn test_find_similar_functions_across_files() {
         let file1 = (
@@ -829,4 +1259,31 @@ mod tests {
         });
         assert!(validate_check.is_some());
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let function = |name: &str, body_span: Span| FunctionDefinition {
+            name: name.to_string(),
+            function_type: FunctionType::Method,
+            parameters: vec!["a".to_string()],
+            body_span,
+            start_line: 3,
+            end_line: 9,
+            class_name: Some("Cart".to_string()),
+            parent_function: None,
+            node_count: Some(12),
+            captures: vec!["total".to_string()],
+        };
+        let result = SimilarityResult::new(
+            function("add", Span::new(10, 42)),
+            function("sum", Span::new(50, 80)),
+            0.9,
+        );
+
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains(r#""body_span":{"start":10,"end":42}"#), "{json}");
+        let restored: SimilarityResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{restored:?}"), format!("{result:?}"));
+    }
 }
@@ -0,0 +1,120 @@
+use crate::language_parser::GenericFunctionDef;
+use crate::tree::TreeNode;
+
+/// Node labels that open an additional execution path, across the
+/// tree-sitter grammars used by the language CLIs
+const DECISION_LABELS: &[&str] = &[
+    // Rust
+    "if_expression",
+    "while_expression",
+    "for_expression",
+    "loop_expression",
+    // Python / JavaScript / others
+    "if_statement",
+    "elif_clause",
+    "while_statement",
+    "for_statement",
+    "for_in_statement",
+    "do_statement",
+    "switch_case",
+    "case_clause",
+    "catch_clause",
+    "except_clause",
+    "conditional_expression",
+    "ternary_expression",
+    // Short-circuit operators
+    "&&",
+    "||",
+    "and",
+    "or",
+];
+
+/// Size and complexity figures for a single function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionMetrics {
+    /// Number of source lines, including the signature
+    pub lines: u32,
+    /// Number of nodes in the function's tree
+    pub subtree_size: usize,
+    /// Cyclomatic complexity (1 + number of decision points)
+    pub complexity: u32,
+    /// Number of declared parameters
+    pub parameters: usize,
+}
+
+impl FunctionMetrics {
+    /// Compute metrics for `func` from the tree of its source
+    pub fn new(func: &GenericFunctionDef, tree: &TreeNode) -> Self {
+        FunctionMetrics {
+            lines: func.end_line - func.start_line + 1,
+            subtree_size: tree.get_subtree_size(),
+            complexity: cyclomatic_complexity(tree),
+            parameters: func.parameters.len(),
+        }
+    }
+}
+
+/// Calculate the cyclomatic complexity of a tree: one plus the number of
+/// branching constructs and short-circuit operators it contains. A `match`
+/// with n arms adds n - 1 paths; `?` is an early return, not a branch.
+pub fn cyclomatic_complexity(tree: &TreeNode) -> u32 {
+    fn count_decisions(node: &TreeNode) -> u32 {
+        let own = u32::from(DECISION_LABELS.contains(&node.label.as_str()));
+        let arms = if node.label == "match_block" {
+            let arms = node.children.iter().filter(|child| child.label == "match_arm").count();
+            u32::try_from(arms.saturating_sub(1)).unwrap_or(u32::MAX)
+        } else {
+            0
+        };
+        own + arms + node.children.iter().map(|child| count_decisions(child)).sum::<u32>()
+    }
+
+    1 + count_decisions(tree)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn node(label: &str, children: Vec<TreeNode>) -> TreeNode {
+        let mut n = TreeNode::new(label.to_string(), String::new(), 0);
+        for child in children {
+            n.add_child(Rc::new(child));
+        }
+        n
+    }
+
+    #[test]
+    fn test_straight_line_code_has_complexity_one() {
+        let tree = node("block", vec![node("let_declaration", vec![]), node("identifier", vec![])]);
+        assert_eq!(cyclomatic_complexity(&tree), 1);
+    }
+
+    #[test]
+    fn test_branches_and_operators_are_counted() {
+        let condition =
+            node("binary_expression", vec![node("identifier", vec![]), node("&&", vec![])]);
+        let tree = node(
+            "block",
+            vec![
+                node("if_expression", vec![condition]),
+                node("for_expression", vec![node("block", vec![])]),
+            ],
+        );
+        assert_eq!(cyclomatic_complexity(&tree), 4);
+    }
+
+    #[test]
+    fn test_match_arms_add_one_path_less_than_their_count() {
+        let arm = || node("match_arm", vec![node("match_pattern", vec![])]);
+        let tree = node(
+            "block",
+            vec![
+                node("match_expression", vec![node("match_block", vec![arm(), arm(), arm()])]),
+                node("try_expression", vec![node("call_expression", vec![])]),
+            ],
+        );
+        assert_eq!(cyclomatic_complexity(&tree), 3);
+    }
+}
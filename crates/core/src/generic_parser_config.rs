@@ -1,10 +1,12 @@
-use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+#[cfg(feature = "serde")]
 use std::fs;
+#[cfg(feature = "serde")]
 use std::path::Path;
 
 /// Configuration for a generic tree-sitter based parser
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GenericParserConfig {
     /// Language name (e.g., "python", "rust", "javascript")
     pub language: String,
@@ -26,9 +28,17 @@ pub struct GenericParserConfig {
 
     /// Optional: Custom node type mappings
     pub custom_mappings: Option<HashMap<String, String>>,
+
+    /// Optional: Deepest syntax tree level converted; deeper subtrees are
+    /// replaced by a `truncated` node (defaults to [`DEFAULT_MAX_TREE_DEPTH`])
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_depth: Option<usize>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub use crate::tree::DEFAULT_MAX_TREE_DEPTH;
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FieldMappings {
     /// Field name for function/method name
     pub name_field: String,
@@ -46,7 +56,8 @@ pub struct FieldMappings {
     pub class_field: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TestPatterns {
     /// Attribute patterns that indicate test functions
     pub attribute_patterns: Vec<String>,
@@ -60,6 +71,7 @@ pub struct TestPatterns {
 
 impl GenericParserConfig {
     /// Load configuration from a JSON file
+    #[cfg(feature = "serde")]
     pub fn from_file<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
         let content = fs::read_to_string(path)?;
         let config = serde_json::from_str(&content)
@@ -68,6 +80,7 @@ impl GenericParserConfig {
     }
 
     /// Save configuration to a JSON file
+    #[cfg(feature = "serde")]
     pub fn to_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
         let content = serde_json::to_string_pretty(self)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
@@ -92,6 +105,7 @@ impl Default for GenericParserConfig {
             value_nodes: vec!["identifier".to_string(), "string".to_string()],
             test_patterns: None,
             custom_mappings: None,
+            max_depth: None,
         }
     }
 }
@@ -125,6 +139,7 @@ impl GenericParserConfig {
                 name_suffixes: vec!["_test".to_string()],
             }),
             custom_mappings: None,
+            max_depth: None,
         }
     }
 
@@ -153,6 +168,7 @@ impl GenericParserConfig {
                 name_suffixes: vec![],
             }),
             custom_mappings: None,
+            max_depth: None,
         }
     }
 
@@ -188,6 +204,7 @@ impl GenericParserConfig {
                 name_suffixes: vec![".test".to_string(), ".spec".to_string()],
             }),
             custom_mappings: None,
+            max_depth: None,
         }
     }
 
@@ -226,6 +243,7 @@ impl GenericParserConfig {
                 name_suffixes: vec!["_test".to_string()],
             }),
             custom_mappings: None,
+            max_depth: None,
         }
     }
 
@@ -264,6 +282,7 @@ impl GenericParserConfig {
                 name_suffixes: vec!["Test".to_string()],
             }),
             custom_mappings: None,
+            max_depth: None,
         }
     }
 
@@ -298,6 +317,7 @@ impl GenericParserConfig {
                 name_suffixes: vec!["_test".to_string()],
             }),
             custom_mappings: None,
+            max_depth: None,
         }
     }
 
@@ -335,6 +355,7 @@ impl GenericParserConfig {
                 name_suffixes: vec!["_test".to_string(), "Test".to_string()],
             }),
             custom_mappings: None,
+            max_depth: None,
         }
     }
 
@@ -381,6 +402,7 @@ impl GenericParserConfig {
                 name_suffixes: vec!["Test".to_string(), "Tests".to_string()],
             }),
             custom_mappings: None,
+            max_depth: None,
         }
     }
 
@@ -411,6 +433,7 @@ impl GenericParserConfig {
                 name_suffixes: vec!["_test".to_string(), "_spec".to_string()],
             }),
             custom_mappings: None,
+            max_depth: None,
         }
     }
 
@@ -448,6 +471,7 @@ impl GenericParserConfig {
                 name_suffixes: vec!["Test".to_string()],
             }),
             custom_mappings: None,
+            max_depth: None,
         }
     }
 }
@@ -456,6 +480,7 @@ impl GenericParserConfig {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_config_serialization() {
         let config = GenericParserConfig::go();
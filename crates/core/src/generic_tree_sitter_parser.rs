@@ -1,6 +1,7 @@
 #![allow(clippy::io_other_error)]
 
-use crate::generic_parser_config::GenericParserConfig;
+use crate::function_extractor::FunctionType;
+use crate::generic_parser_config::{GenericParserConfig, DEFAULT_MAX_TREE_DEPTH};
 use crate::language_parser::{GenericFunctionDef, GenericTypeDef, Language, LanguageParser};
 use crate::tree::TreeNode;
 use std::error::Error;
@@ -19,16 +20,25 @@ impl GenericTreeSitterParser {
         config: GenericParserConfig,
     ) -> Result<Self, Box<dyn Error + Send + Sync>> {
         let mut parser = Parser::new();
-        parser.set_language(&language).map_err(|e| {
+        parser.set_language(&language).map_err(|_| {
             Box::new(std::io::Error::new(
                 std::io::ErrorKind::Other,
-                format!("Failed to set language: {:?}", e),
+                grammar_version_mismatch(&config.language, &language),
             )) as Box<dyn Error + Send + Sync>
         })?;
 
         Ok(Self { parser, config })
     }
 
+    /// Create parsers for several languages at once.
+    ///
+    /// A grammar that fails to load (typically because its ABI version does not
+    /// match the linked tree-sitter runtime) does not abort the others: it is
+    /// reported in the returned diagnostics and the remaining languages are kept.
+    pub fn for_languages(language_names: &[&str]) -> (Vec<(String, Self)>, Vec<String>) {
+        load_available_parsers(language_names, Self::from_language_name)
+    }
+
     /// Create from a pre-configured language
     pub fn from_language_name(language_name: &str) -> Result<Self, Box<dyn Error + Send + Sync>> {
         let (language, config) = match language_name {
@@ -40,6 +50,9 @@ impl GenericTreeSitterParser {
                 (tree_sitter_c_sharp::LANGUAGE.into(), GenericParserConfig::csharp())
             }
             "ruby" | "rb" => (tree_sitter_ruby::LANGUAGE.into(), GenericParserConfig::ruby()),
+            "php" => (tree_sitter_php::LANGUAGE_PHP.into(), GenericParserConfig::php()),
+            "python" | "py" => (tree_sitter_python::LANGUAGE.into(), GenericParserConfig::python()),
+            "rust" | "rs" => (tree_sitter_rust::LANGUAGE.into(), GenericParserConfig::rust()),
             _ => {
                 return Err(Box::new(std::io::Error::new(
                     std::io::ErrorKind::InvalidInput,
@@ -51,10 +64,21 @@ impl GenericTreeSitterParser {
         Self::new(language, config)
     }
 
-    fn convert_node(&self, node: Node, source: &str, id_counter: &mut usize) -> TreeNode {
+    fn convert_node(
+        &self,
+        node: Node,
+        source: &str,
+        id_counter: &mut usize,
+        depth: usize,
+    ) -> TreeNode {
         let current_id = *id_counter;
         *id_counter += 1;
 
+        // Stop descending into pathologically deep input rather than overflow the stack
+        if depth >= self.config.max_depth.unwrap_or(DEFAULT_MAX_TREE_DEPTH) {
+            return TreeNode::truncated(current_id);
+        }
+
         let label = node.kind().to_string();
         let value = if self.config.value_nodes.contains(&node.kind().to_string()) {
             node.utf8_text(source.as_bytes()).unwrap_or("").to_string()
@@ -65,7 +89,7 @@ impl GenericTreeSitterParser {
         let mut tree_node = TreeNode::new(label, value, current_id);
 
         for child in node.children(&mut node.walk()) {
-            let child_node = self.convert_node(child, source, id_counter);
+            let child_node = self.convert_node(child, source, id_counter, depth + 1);
             tree_node.add_child(Rc::new(child_node));
         }
 
@@ -133,8 +157,8 @@ impl GenericTreeSitterParser {
                     .child_by_field_name("declarator")
                     .and_then(|n| n.utf8_text(source.as_bytes()).ok())
                     .map(String::from)?,
-                "pointer_declarator" => {
-                    // Handle functions returning pointers
+                "pointer_declarator" | "reference_declarator" => {
+                    // Handle functions returning pointers or references
                     let func_decl = declarator
                         .children(&mut declarator.walk())
                         .find(|n| n.kind() == "function_declarator")?;
@@ -204,7 +228,7 @@ impl GenericTreeSitterParser {
             let declarator = node.child_by_field_name("declarator")?;
             match declarator.kind() {
                 "function_declarator" => declarator.child_by_field_name("parameters"),
-                "pointer_declarator" => declarator
+                "pointer_declarator" | "reference_declarator" => declarator
                     .children(&mut declarator.walk())
                     .find(|n| n.kind() == "function_declarator")
                     .and_then(|n| n.child_by_field_name("parameters")),
@@ -215,6 +239,18 @@ impl GenericTreeSitterParser {
         };
 
         let body_node = node.child_by_field_name(&self.config.field_mappings.body_field);
+        // Abstract and interface methods in PHP have no body to compare
+        if self.config.language == "php" && body_node.is_none() {
+            return None;
+        }
+
+        // Out-of-line C++ definitions such as `Foo::bar` belong to `Foo`
+        let (name_string, class_name) = match name_string.rsplit_once("::") {
+            Some((owner, name)) if self.config.language == "cpp" && class_name.is_none() => {
+                (name.to_string(), Some(owner.to_string()))
+            }
+            _ => (name_string, class_name.map(String::from)),
+        };
 
         let params = self.extract_parameters(params_node, source);
         let decorators = self.extract_decorators(node, source);
@@ -229,7 +265,8 @@ impl GenericTreeSitterParser {
             body_end_line: body_node.map(|n| n.end_position().row as u32 + 1).unwrap_or(0),
             parameters: params,
             is_method: class_name.is_some(),
-            class_name: class_name.map(String::from),
+            kind: if class_name.is_some() { FunctionType::Method } else { FunctionType::Function },
+            class_name,
             is_async,
             is_generator,
             decorators,
@@ -364,6 +401,40 @@ impl GenericTreeSitterParser {
     }
 }
 
+/// Describe a grammar whose ABI version the linked tree-sitter runtime cannot load
+pub fn grammar_version_mismatch(language_name: &str, language: &tree_sitter::Language) -> String {
+    format!(
+        "Failed to load the {} grammar: grammar ABI version {} is not supported by the \
+         tree-sitter runtime (supported versions {}-{})",
+        language_name,
+        language.abi_version(),
+        tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION,
+        tree_sitter::LANGUAGE_VERSION
+    )
+}
+
+/// Build a parser for each language with `build`, collecting construction
+/// failures as diagnostics instead of returning early
+pub fn load_available_parsers<P, F>(
+    language_names: &[&str],
+    mut build: F,
+) -> (Vec<(String, P)>, Vec<String>)
+where
+    F: FnMut(&str) -> Result<P, Box<dyn Error + Send + Sync>>,
+{
+    let mut parsers = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    for name in language_names {
+        match build(name) {
+            Ok(parser) => parsers.push((name.to_string(), parser)),
+            Err(e) => diagnostics.push(format!("Skipping {} files: {}", name, e)),
+        }
+    }
+
+    (parsers, diagnostics)
+}
+
 impl LanguageParser for GenericTreeSitterParser {
     fn parse(
         &mut self,
@@ -377,7 +448,7 @@ impl LanguageParser for GenericTreeSitterParser {
 
         let root_node = tree.root_node();
         let mut id_counter = 0;
-        Ok(Rc::new(self.convert_node(root_node, source, &mut id_counter)))
+        Ok(Rc::new(self.convert_node(root_node, source, &mut id_counter, 0)))
     }
 
     fn extract_functions(
@@ -478,4 +549,64 @@ public class Calculator {
         assert_eq!(functions[0].name, "add");
         assert_eq!(functions[1].name, "multiply");
     }
+
+    #[test]
+    fn test_failing_grammar_does_not_block_other_languages() {
+        let language: tree_sitter::Language = tree_sitter_java::LANGUAGE.into();
+        let (parsers, diagnostics) = load_available_parsers(&["go", "java", "ruby"], |name| {
+            if name == "java" {
+                // Simulate a grammar built against an incompatible runtime
+                Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    grammar_version_mismatch(name, &language),
+                )) as Box<dyn Error + Send + Sync>)
+            } else {
+                GenericTreeSitterParser::from_language_name(name)
+            }
+        });
+
+        let loaded: Vec<&str> = parsers.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(loaded, vec!["go", "ruby"]);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].contains("java grammar"));
+        assert!(diagnostics[0].contains("ABI version"));
+
+        // The surviving parsers are still usable
+        let (_, go_parser) = &mut parsers.into_iter().next().unwrap();
+        let functions = go_parser
+            .extract_functions("package main\nfunc f() int { return 1 }\n", "a.go")
+            .unwrap();
+        assert_eq!(functions.len(), 1);
+    }
+
+    #[test]
+    fn test_for_languages_loads_bundled_grammars() {
+        let (parsers, diagnostics) = GenericTreeSitterParser::for_languages(&["go", "ruby"]);
+        assert_eq!(parsers.len(), 2);
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_deeply_nested_source_is_truncated_instead_of_overflowing() {
+        let mut parser = GenericTreeSitterParser::from_language_name("go").unwrap();
+        let nesting = 20_000;
+        let source = format!(
+            "package main\nfunc f() int {{ return {}1{} }}\n",
+            "(".repeat(nesting),
+            ")".repeat(nesting)
+        );
+
+        let tree = parser.parse(&source, "deep.go").unwrap();
+
+        let mut stack = vec![Rc::clone(&tree)];
+        let mut truncated = 0;
+        while let Some(node) = stack.pop() {
+            if node.label == "truncated" {
+                truncated += 1;
+            }
+            stack.extend(node.children.iter().cloned());
+        }
+        assert!(truncated > 0);
+        assert!(tree.get_subtree_size() < nesting);
+    }
 }
@@ -0,0 +1,165 @@
+//! Identifier-frequency weighting of similarity scores.
+//!
+//! Ubiquitous identifiers (`i`, `result`, `value`) say little about whether
+//! two functions implement the same thing, while a shared domain name such as
+//! `invoice_total` says a lot. Each identifier gets an inverse document
+//! frequency over a corpus of functions, and a TSED score is adjusted by how
+//! much the IDF-weighted identifier overlap of a pair differs from its plain
+//! overlap.
+
+use crate::apted::LeafCategory;
+use crate::tree::TreeNode;
+use std::collections::{BTreeSet, HashMap};
+
+/// How strongly the weighted overlap moves a TSED score
+const IDF_ADJUSTMENT_WEIGHT: f64 = 0.5;
+
+/// Distinct identifier names of a tree (leaves whose label is an identifier kind)
+pub fn collect_identifiers(tree: &TreeNode) -> BTreeSet<String> {
+    let mut identifiers = BTreeSet::new();
+    collect_into(tree, &mut identifiers);
+    identifiers
+}
+
+fn collect_into(node: &TreeNode, identifiers: &mut BTreeSet<String>) {
+    if node.children.is_empty()
+        && !node.value.is_empty()
+        && LeafCategory::of(&node.label) == LeafCategory::Identifier
+    {
+        identifiers.insert(node.value.clone());
+    }
+    for child in &node.children {
+        collect_into(child, identifiers);
+    }
+}
+
+/// In how many documents (functions) of a corpus each identifier occurs
+#[derive(Debug, Clone, Default)]
+pub struct IdentifierFrequencies {
+    documents: usize,
+    document_counts: HashMap<String, usize>,
+}
+
+impl IdentifierFrequencies {
+    /// Count identifiers over `documents`, each given as its set of identifiers
+    pub fn from_documents<'a>(documents: impl IntoIterator<Item = &'a BTreeSet<String>>) -> Self {
+        let mut frequencies = Self::default();
+        for identifiers in documents {
+            frequencies.documents += 1;
+            for identifier in identifiers {
+                *frequencies.document_counts.entry(identifier.clone()).or_default() += 1;
+            }
+        }
+        frequencies
+    }
+
+    /// Smoothed inverse document frequency; identifiers unseen in the corpus
+    /// are treated as the rarest
+    #[allow(clippy::cast_precision_loss)]
+    pub fn idf(&self, identifier: &str) -> f64 {
+        let count = self.document_counts.get(identifier).copied().unwrap_or(0);
+        ((1 + self.documents) as f64 / (1 + count) as f64).ln() + 1.0
+    }
+
+    /// IDF-weighted Jaccard overlap of two identifier sets
+    pub fn weighted_overlap(&self, ids1: &BTreeSet<String>, ids2: &BTreeSet<String>) -> f64 {
+        let shared: f64 = ids1.intersection(ids2).map(|id| self.idf(id)).sum();
+        let union: f64 = ids1.union(ids2).map(|id| self.idf(id)).sum();
+        if union == 0.0 {
+            1.0
+        } else {
+            shared / union
+        }
+    }
+}
+
+/// Adjust a TSED score by the identifiers both functions share.
+///
+/// Pairs whose shared identifiers are rarer than their differing ones score
+/// higher than TSED alone, pairs that only share common identifiers lower.
+/// Pairs without identifiers, or whose identifiers are all equally frequent,
+/// keep their TSED score.
+#[allow(clippy::cast_precision_loss)]
+pub fn idf_weighted_similarity(
+    tsed: f64,
+    ids1: &BTreeSet<String>,
+    ids2: &BTreeSet<String>,
+    frequencies: &IdentifierFrequencies,
+) -> f64 {
+    let union = ids1.union(ids2).count();
+    if union == 0 {
+        return tsed;
+    }
+    let plain = ids1.intersection(ids2).count() as f64 / union as f64;
+    let weighted = frequencies.weighted_overlap(ids1, ids2);
+    (tsed * (1.0 + IDF_ADJUSTMENT_WEIGHT * (weighted - plain))).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|name| (*name).to_string()).collect()
+    }
+
+    #[test]
+    fn test_collect_identifiers_from_leaves() {
+        let mut call = TreeNode::new("call_expression".to_string(), String::new(), 0);
+        call.add_child(std::rc::Rc::new(TreeNode::new(
+            "identifier".to_string(),
+            "apply_discount".to_string(),
+            1,
+        )));
+        call.add_child(std::rc::Rc::new(TreeNode::new(
+            "field_identifier".to_string(),
+            "total".to_string(),
+            2,
+        )));
+        call.add_child(std::rc::Rc::new(TreeNode::new(
+            "integer_literal".to_string(),
+            "10".to_string(),
+            3,
+        )));
+
+        assert_eq!(collect_identifiers(&call), ids(&["apply_discount", "total"]));
+    }
+
+    #[test]
+    fn test_rare_shared_identifier_scores_higher_than_common_ones() {
+        // `i` and `result` occur in every function, `invoice_total` in two
+        let corpus = [
+            ids(&["i", "result", "invoice_total", "tax"]),
+            ids(&["i", "result", "invoice_total", "fee"]),
+            ids(&["i", "result", "width"]),
+            ids(&["i", "result", "height"]),
+            ids(&["i", "result", "depth"]),
+        ];
+        let frequencies = IdentifierFrequencies::from_documents(&corpus);
+        assert!(frequencies.idf("invoice_total") > frequencies.idf("i"));
+
+        // Same structure, same TSED, same number of shared identifiers
+        let tsed = 0.8;
+        let rare_pair = idf_weighted_similarity(
+            tsed,
+            &ids(&["i", "invoice_total", "tax"]),
+            &ids(&["result", "invoice_total", "fee"]),
+            &frequencies,
+        );
+        let common_pair = idf_weighted_similarity(
+            tsed,
+            &ids(&["i", "width", "tax"]),
+            &ids(&["i", "height", "fee"]),
+            &frequencies,
+        );
+
+        assert!(rare_pair > tsed, "rare: {rare_pair}");
+        assert!(common_pair < tsed, "common: {common_pair}");
+    }
+
+    #[test]
+    fn test_no_identifiers_keeps_tsed() {
+        let frequencies = IdentifierFrequencies::default();
+        assert_eq!(idf_weighted_similarity(0.75, &ids(&[]), &ids(&[]), &frequencies), 0.75);
+    }
+}
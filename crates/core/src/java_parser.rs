@@ -0,0 +1,404 @@
+#![allow(clippy::io_other_error)]
+
+use crate::function_extractor::FunctionType;
+use crate::language_parser::{
+    GenericFunctionDef, GenericTypeDef, Language, LanguageParser, TypeDefKind,
+};
+use crate::tree::{TreeNode, DEFAULT_MAX_TREE_DEPTH};
+use std::error::Error;
+use std::rc::Rc;
+use tree_sitter::{Node, Parser};
+
+/// Declarations whose name becomes the `class_name` of the methods inside
+const TYPE_DECLARATIONS: &[&str] =
+    &["class_declaration", "interface_declaration", "enum_declaration", "record_declaration"];
+
+/// Node kinds whose source text is kept as the node value
+const VALUE_NODES: &[&str] = &[
+    "identifier",
+    "type_identifier",
+    "string_literal",
+    "character_literal",
+    "decimal_integer_literal",
+    "hex_integer_literal",
+    "decimal_floating_point_literal",
+    "true",
+    "false",
+    "null_literal",
+];
+
+/// Parser for Java source using `tree-sitter-java`.
+///
+/// Methods and constructors are extracted as functions; overloads each get
+/// their own `GenericFunctionDef`. Methods of anonymous classes are skipped,
+/// as they belong to the expression that creates the class.
+pub struct JavaParser {
+    parser: Parser,
+}
+
+impl JavaParser {
+    pub fn new() -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut parser = Parser::new();
+        parser.set_language(&tree_sitter_java::LANGUAGE.into()).map_err(|e| {
+            Box::new(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+                as Box<dyn Error + Send + Sync>
+        })?;
+        Ok(Self { parser })
+    }
+
+    fn parse_tree(
+        &mut self,
+        source: &str,
+    ) -> Result<tree_sitter::Tree, Box<dyn Error + Send + Sync>> {
+        self.parser.parse(source, None).ok_or_else(|| {
+            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse source"))
+                as Box<dyn Error + Send + Sync>
+        })
+    }
+
+    fn convert_node(
+        node: Node,
+        source: &str,
+        id_counter: &mut usize,
+        depth: usize,
+    ) -> Rc<TreeNode> {
+        let current_id = *id_counter;
+        *id_counter += 1;
+
+        // Stop descending into pathologically deep input rather than overflow the stack
+        if depth >= DEFAULT_MAX_TREE_DEPTH {
+            return Rc::new(TreeNode::truncated(current_id));
+        }
+
+        let value = if VALUE_NODES.contains(&node.kind()) {
+            source[node.byte_range()].to_string()
+        } else {
+            String::new()
+        };
+        let mut tree_node = TreeNode::new(node.kind().to_string(), value, current_id);
+
+        for child in node.children(&mut node.walk()) {
+            if !child.is_extra() {
+                tree_node.add_child(Self::convert_node(child, source, id_counter, depth + 1));
+            }
+        }
+
+        Rc::new(tree_node)
+    }
+
+    fn extract_functions_from_node(
+        node: Node,
+        source: &str,
+        class_name: Option<&str>,
+        functions: &mut Vec<GenericFunctionDef>,
+    ) {
+        match node.kind() {
+            "object_creation_expression" => return,
+            "method_declaration" | "constructor_declaration" => {
+                if let Some(func) = Self::function_definition(node, source, class_name) {
+                    functions.push(func);
+                }
+            }
+            kind if TYPE_DECLARATIONS.contains(&kind) => {
+                let name = node.child_by_field_name("name").map(|n| &source[n.byte_range()]);
+                for child in node.children(&mut node.walk()) {
+                    Self::extract_functions_from_node(child, source, name, functions);
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        for child in node.children(&mut node.walk()) {
+            Self::extract_functions_from_node(child, source, class_name, functions);
+        }
+    }
+
+    fn function_definition(
+        node: Node,
+        source: &str,
+        class_name: Option<&str>,
+    ) -> Option<GenericFunctionDef> {
+        let name = node.child_by_field_name("name")?;
+        let body = node.child_by_field_name("body");
+
+        // `formal_parameter` names its identifier; varargs keep it in a declarator
+        let parameters = node
+            .child_by_field_name("parameters")
+            .map(|params| {
+                params
+                    .named_children(&mut params.walk())
+                    .filter_map(|param| match param.kind() {
+                        "formal_parameter" => param.child_by_field_name("name"),
+                        "spread_parameter" => param
+                            .named_children(&mut param.walk())
+                            .find(|child| child.kind() == "variable_declarator")
+                            .and_then(|declarator| declarator.child_by_field_name("name")),
+                        _ => None,
+                    })
+                    .map(|name| source[name.byte_range()].to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let decorators = node
+            .child(0)
+            .filter(|first| first.kind() == "modifiers")
+            .map(|modifiers| {
+                modifiers
+                    .named_children(&mut modifiers.walk())
+                    .filter(|m| matches!(m.kind(), "marker_annotation" | "annotation"))
+                    .map(|m| source[m.byte_range()].trim_start_matches('@').to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(GenericFunctionDef {
+            name: source[name.byte_range()].to_string(),
+            start_line: node.start_position().row as u32 + 1,
+            end_line: node.end_position().row as u32 + 1,
+            body_start_line: body.map_or(0, |b| b.start_position().row as u32 + 1),
+            body_end_line: body.map_or(0, |b| b.end_position().row as u32 + 1),
+            parameters,
+            is_method: class_name.is_some(),
+            kind: if class_name.is_some() { FunctionType::Method } else { FunctionType::Function },
+            class_name: class_name.map(String::from),
+            is_async: false,
+            is_generator: false,
+            decorators,
+        })
+    }
+
+    fn extract_types_from_node(node: Node, source: &str, types: &mut Vec<GenericTypeDef>) {
+        let kind = match node.kind() {
+            "class_declaration" => Some(TypeDefKind::Class),
+            "interface_declaration" => Some(TypeDefKind::Interface),
+            "enum_declaration" => Some(TypeDefKind::Enum),
+            _ => None,
+        };
+
+        if let (Some(kind), Some(name)) = (kind, node.child_by_field_name("name")) {
+            let fields = node.child_by_field_name("body").map_or_else(Vec::new, |body| {
+                let mut fields = Vec::new();
+                collect_members(body, source, &kind, &mut fields);
+                fields
+            });
+            types.push(GenericTypeDef {
+                name: source[name.byte_range()].to_string(),
+                kind: kind.as_str().to_string(),
+                start_line: node.start_position().row as u32 + 1,
+                end_line: node.end_position().row as u32 + 1,
+                fields,
+            });
+        }
+
+        for child in node.children(&mut node.walk()) {
+            Self::extract_types_from_node(child, source, types);
+        }
+    }
+}
+
+/// Field names of a class, constants of an enum or method names of an interface
+fn collect_members(body: Node, source: &str, kind: &TypeDefKind, fields: &mut Vec<String>) {
+    for member in body.named_children(&mut body.walk()) {
+        match (kind, member.kind()) {
+            (TypeDefKind::Class | TypeDefKind::Enum, "field_declaration") => {
+                for declarator in member.children_by_field_name("declarator", &mut member.walk()) {
+                    if let Some(name) = declarator.child_by_field_name("name") {
+                        fields.push(source[name.byte_range()].to_string());
+                    }
+                }
+            }
+            (TypeDefKind::Enum, "enum_constant")
+            | (TypeDefKind::Interface, "method_declaration") => {
+                if let Some(name) = member.child_by_field_name("name") {
+                    fields.push(source[name.byte_range()].to_string());
+                }
+            }
+            // Fields of an enum follow its constants in the body declarations
+            (TypeDefKind::Enum, "enum_body_declarations") => {
+                collect_members(member, source, kind, fields);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl LanguageParser for JavaParser {
+    /// Parse a compilation unit, or a bare member as extracted from a class.
+    /// Members the grammar rejects at the top level are parsed inside a class.
+    fn parse(
+        &mut self,
+        source: &str,
+        _filename: &str,
+    ) -> Result<Rc<TreeNode>, Box<dyn Error + Send + Sync>> {
+        let tree = self.parse_tree(source)?;
+        let mut id_counter = 0;
+        if !tree.root_node().has_error() {
+            return Ok(Self::convert_node(tree.root_node(), source, &mut id_counter, 0));
+        }
+
+        let wrapped = format!("class Snippet {{\n{}\n}}", source);
+        let wrapped_tree = self.parse_tree(&wrapped)?;
+        let member = wrapped_tree
+            .root_node()
+            .child(0)
+            .and_then(|class| class.child_by_field_name("body"))
+            .and_then(|body| body.named_child(0));
+        match member {
+            // Same shape as a member that parses on its own: a program holding it
+            Some(member) if !wrapped_tree.root_node().has_error() => {
+                let mut program = TreeNode::new("program".to_string(), String::new(), 0);
+                id_counter += 1;
+                program.add_child(Self::convert_node(member, &wrapped, &mut id_counter, 1));
+                Ok(Rc::new(program))
+            }
+            _ => Ok(Self::convert_node(tree.root_node(), source, &mut id_counter, 0)),
+        }
+    }
+
+    fn extract_functions(
+        &mut self,
+        source: &str,
+        _filename: &str,
+    ) -> Result<Vec<GenericFunctionDef>, Box<dyn Error + Send + Sync>> {
+        let tree = self.parse_tree(source)?;
+        let mut functions = Vec::new();
+        Self::extract_functions_from_node(tree.root_node(), source, None, &mut functions);
+        Ok(functions)
+    }
+
+    fn extract_types(
+        &mut self,
+        source: &str,
+        _filename: &str,
+    ) -> Result<Vec<GenericTypeDef>, Box<dyn Error + Send + Sync>> {
+        let tree = self.parse_tree(source)?;
+        let mut types = Vec::new();
+        Self::extract_types_from_node(tree.root_node(), source, &mut types);
+        Ok(types)
+    }
+
+    fn language(&self) -> Language {
+        Language::Java
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tsed::{calculate_tsed, TSEDOptions};
+
+    const SOURCE: &str = r#"
+package shop;
+
+public class Cart {
+    private int total;
+    private String owner, currency;
+
+    public Cart(String owner) {
+        this.owner = owner;
+    }
+
+    @Override
+    public int add(int price) {
+        total += price;
+        return total;
+    }
+
+    public int add(int price, int quantity) {
+        total += price * quantity;
+        return total;
+    }
+
+    public void log(String format, Object... args) {
+        Runnable task = new Runnable() {
+            public void run() { System.out.println(format); }
+        };
+        task.run();
+    }
+}
+
+interface Priced {
+    int price();
+}
+
+enum Currency {
+    EUR, USD;
+
+    private final int scale = 2;
+}
+"#;
+
+    #[test]
+    fn test_methods_constructors_and_overloads() {
+        let mut parser = JavaParser::new().unwrap();
+        let functions = parser.extract_functions(SOURCE, "Cart.java").unwrap();
+
+        let summary: Vec<(&str, Option<&str>, Vec<&str>)> = functions
+            .iter()
+            .map(|f| {
+                (
+                    f.name.as_str(),
+                    f.class_name.as_deref(),
+                    f.parameters.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Cart", Some("Cart"), vec!["owner"]),
+                ("add", Some("Cart"), vec!["price"]),
+                ("add", Some("Cart"), vec!["price", "quantity"]),
+                ("log", Some("Cart"), vec!["format", "args"]),
+                ("price", Some("Priced"), vec![]),
+            ]
+        );
+        assert_eq!(functions[1].decorators, vec!["Override"]);
+        assert_eq!((functions[2].start_line, functions[2].end_line), (18, 21));
+    }
+
+    #[test]
+    fn test_types_have_distinct_kinds() {
+        let mut parser = JavaParser::new().unwrap();
+        let types = parser.extract_types(SOURCE, "Cart.java").unwrap();
+
+        let summary: Vec<(&str, &str, Vec<&str>)> = types
+            .iter()
+            .map(|t| {
+                (t.name.as_str(), t.kind.as_str(), t.fields.iter().map(String::as_str).collect())
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Cart", "class", vec!["total", "owner", "currency"]),
+                ("Priced", "interface", vec!["price"]),
+                ("Currency", "enum", vec!["EUR", "USD", "scale"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_overloads_are_compared_independently() {
+        let mut parser = JavaParser::new().unwrap();
+        let functions = parser.extract_functions(SOURCE, "Cart.java").unwrap();
+        let lines: Vec<&str> = SOURCE.lines().collect();
+        let tree_of = |parser: &mut JavaParser, func: &GenericFunctionDef| {
+            let code = lines[func.start_line as usize - 1..func.end_line as usize].join("\n");
+            parser.parse(&code, "Cart.java").unwrap()
+        };
+
+        let add = tree_of(&mut parser, &functions[1]);
+        let add_quantity = tree_of(&mut parser, &functions[2]);
+        assert_eq!(add.children[0].label, "method_declaration");
+
+        let similarity = calculate_tsed(&add, &add_quantity, &TSEDOptions::default());
+        assert!(similarity > 0.5 && similarity < 1.0, "similarity {similarity}");
+
+        let constructor = tree_of(&mut parser, &functions[0]);
+        assert_eq!(constructor.label, "program");
+        assert_eq!(constructor.children[0].label, "constructor_declaration");
+    }
+}
@@ -1,9 +1,13 @@
+use crate::function_extractor::FunctionType;
+use crate::generic_tree_sitter_parser::GenericTreeSitterParser;
 use crate::tree::TreeNode;
 use std::error::Error;
+use std::fs;
+use std::path::Path;
 use std::rc::Rc;
 
 /// Supported programming languages
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Language {
     JavaScript,
     TypeScript,
@@ -42,8 +46,52 @@ impl Language {
     }
 }
 
+/// Language of a file, from its extension
+pub fn detect_language(path: &Path) -> Option<Language> {
+    path.extension()?.to_str().and_then(Language::from_extension)
+}
+
+/// Read a file and parse it into a tree with the parser for its language.
+///
+/// JavaScript and TypeScript go through oxc, the other languages through
+/// [`GenericTreeSitterParser`]. The trees of Python and Rust files follow the
+/// generic configs of those languages, not the dedicated parsers of the
+/// `similarity-py` and `similarity-rs` crates.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read, its language is unknown, or
+/// parsing fails
+pub fn parse_file(path: &Path) -> Result<Rc<TreeNode>, String> {
+    let language =
+        detect_language(path).ok_or_else(|| format!("Unknown language for {}", path.display()))?;
+    let source = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let filename = path.to_string_lossy();
+
+    let language_name = match language {
+        Language::JavaScript | Language::TypeScript => {
+            return crate::parser::parse_and_convert_to_tree(&filename, &source);
+        }
+        Language::Go => "go",
+        Language::Java => "java",
+        Language::C => "c",
+        Language::Cpp => "cpp",
+        Language::CSharp => "csharp",
+        Language::Ruby => "ruby",
+        Language::Php => "php",
+        Language::Python => "python",
+        Language::Rust => "rust",
+        Language::Unknown => return Err(format!("Unknown language for {}", path.display())),
+    };
+    let mut parser =
+        GenericTreeSitterParser::from_language_name(language_name).map_err(|e| e.to_string())?;
+    parser.parse(&source, &filename).map_err(|e| e.to_string())
+}
+
 /// Generic function definition that works across languages
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GenericFunctionDef {
     pub name: String,
     pub start_line: u32,
@@ -52,12 +100,34 @@ pub struct GenericFunctionDef {
     pub body_end_line: u32,
     pub parameters: Vec<String>,
     pub is_method: bool,
+    /// What kind of function this is; closures and lambdas are `Closure`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub kind: FunctionType,
     pub class_name: Option<String>,
     pub is_async: bool,
     pub is_generator: bool,
     pub decorators: Vec<String>,
 }
 
+impl GenericFunctionDef {
+    /// Whether the function spans the given 1-based line
+    pub fn contains_line(&self, line: u32) -> bool {
+        self.start_line <= line && line <= self.end_line
+    }
+}
+
+/// Find the innermost function spanning the given 1-based line, e.g. the
+/// function under the cursor in an editor
+pub fn function_at_line(
+    functions: &[GenericFunctionDef],
+    line: u32,
+) -> Option<&GenericFunctionDef> {
+    functions
+        .iter()
+        .filter(|func| func.contains_line(line))
+        .min_by_key(|func| func.end_line - func.start_line)
+}
+
 /// Generic type definition that works across languages
 #[derive(Debug, Clone)]
 pub struct GenericTypeDef {
@@ -75,6 +145,28 @@ pub enum TypeDefKind {
     TypeAlias,
     Enum,
     Struct,
+    /// A Python `@dataclass` class
+    DataClass,
+    /// A Python `TypedDict` subclass
+    TypedDict,
+    /// A Python `NamedTuple` subclass
+    NamedTuple,
+}
+
+impl TypeDefKind {
+    /// The `kind` string of a `GenericTypeDef` of this kind
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TypeDefKind::Class => "class",
+            TypeDefKind::Interface => "interface",
+            TypeDefKind::TypeAlias => "type_alias",
+            TypeDefKind::Enum => "enum",
+            TypeDefKind::Struct => "struct",
+            TypeDefKind::DataClass => "dataclass",
+            TypeDefKind::TypedDict => "typed_dict",
+            TypeDefKind::NamedTuple => "named_tuple",
+        }
+    }
 }
 
 /// Trait for language-specific parsers
@@ -120,9 +212,54 @@ mod tests {
         assert_eq!(Language::from_filename("test.txt"), None);
     }
 
+    #[test]
+    fn test_detect_language_from_path() {
+        assert_eq!(detect_language(Path::new("src/app.ts")), Some(Language::TypeScript));
+        assert_eq!(detect_language(Path::new("pkg/module.py")), Some(Language::Python));
+        assert_eq!(detect_language(Path::new("src/lib.rs")), Some(Language::Rust));
+        assert_eq!(detect_language(Path::new("Makefile")), None);
+        assert_eq!(detect_language(Path::new("notes.txt")), None);
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+
+        let tree = parse_file(&fixtures.join("sample.go")).unwrap();
+        assert!(tree.get_subtree_size() > 1);
+
+        let error = parse_file(&fixtures.join("missing.go")).unwrap_err();
+        assert!(error.contains("Failed to read"), "{error}");
+        let error = parse_file(&fixtures.join("sample1.unknown")).unwrap_err();
+        assert!(error.contains("Unknown language"), "{error}");
+    }
+
     #[test]
     fn test_case_insensitive_extension() {
         assert_eq!(Language::from_extension("JS"), Some(Language::JavaScript));
         assert_eq!(Language::from_extension("Py"), Some(Language::Python));
     }
+
+    #[test]
+    fn test_function_at_line_prefers_innermost() {
+        let function = |name: &str, start_line, end_line| GenericFunctionDef {
+            name: name.to_string(),
+            start_line,
+            end_line,
+            body_start_line: start_line,
+            body_end_line: end_line,
+            parameters: Vec::new(),
+            is_method: false,
+            kind: FunctionType::Function,
+            class_name: None,
+            is_async: false,
+            is_generator: false,
+            decorators: Vec::new(),
+        };
+        let functions = vec![function("outer", 1, 20), function("inner", 5, 8)];
+
+        assert_eq!(function_at_line(&functions, 6).map(|f| f.name.as_str()), Some("inner"));
+        assert_eq!(function_at_line(&functions, 12).map(|f| f.name.as_str()), Some("outer"));
+        assert!(function_at_line(&functions, 21).is_none());
+    }
 }
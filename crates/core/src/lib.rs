@@ -1,19 +1,30 @@
 #![allow(clippy::uninlined_format_args)]
 
 pub mod apted;
+#[cfg(feature = "serde")]
 pub mod ast_exchange;
 pub mod ast_fingerprint;
+pub mod captures;
+pub mod clustering;
+pub mod control_flow;
+pub mod core_region;
+pub mod cover_set;
 pub mod enhanced_similarity;
 pub mod fast_similarity;
 pub mod function_extractor;
+pub mod function_metrics;
 pub mod generic_overlap_detector;
 pub mod generic_parser_config;
 pub mod generic_tree_sitter_parser;
+pub mod identifier_weighting;
+pub mod java_parser;
 pub mod language_parser;
 pub mod overlap_detector;
 pub mod parser;
+pub mod parser_pool;
 pub mod subtree_fingerprint;
 pub mod tree;
+pub mod tree_normalizer;
 pub mod tsed;
 pub mod type_comparator;
 pub mod type_extractor;
@@ -23,18 +34,37 @@ pub mod type_normalizer;
 pub mod cli_file_utils;
 pub mod cli_output;
 pub mod cli_parallel;
+#[cfg(feature = "serde")]
+pub mod sarif;
 
-pub use apted::{compute_edit_distance, APTEDOptions};
+pub use apted::{
+    compute_edit_distance, compute_edit_distance_bounded, compute_edit_script,
+    edit_distance_with_matches, APTEDOptions, EditOp, LabelRenameCosts, LeafCategory,
+    LeafRenameCosts,
+};
+pub use clustering::connected_components;
+pub use core_region::{directional_core_similarity, CoreSimilarity};
+pub use cover_set::approximate_vertex_cover;
 pub use enhanced_similarity::{
     calculate_enhanced_similarity, calculate_semantic_similarity, EnhancedSimilarityOptions,
 };
 pub use function_extractor::{
-    compare_functions, extract_functions, find_similar_functions_across_files,
-    find_similar_functions_in_file, FunctionDefinition, FunctionType, SimilarityResult,
+    cluster_similar_functions, cluster_similar_functions_across_files, compare_functions,
+    compare_functions_keyed, extract_classes, extract_functions,
+    find_similar_functions_across_files, find_similar_functions_in_file, function_match_key,
+    ClassDefinition, FunctionDefinition, FunctionType, SimilarityResult,
+};
+pub use identifier_weighting::{
+    collect_identifiers, idf_weighted_similarity, IdentifierFrequencies,
 };
+pub use java_parser::JavaParser;
+pub use language_parser::{detect_language, parse_file};
 pub use parser::{ast_to_tree_node, parse_and_convert_to_tree};
-pub use tree::TreeNode;
-pub use tsed::{calculate_tsed, calculate_tsed_from_code, TSEDOptions};
+pub use tree::{TreeNode, DEFAULT_MAX_TREE_DEPTH};
+pub use tsed::{
+    calculate_tsed, calculate_tsed_bounded, calculate_tsed_from_code, calculate_tsed_keyed,
+    exact_match_key, find_similar_to, max_similarity_of_sizes, KeyedTrees, TSEDOptions, TsedQuery,
+};
 
 // Type-related exports
 pub use type_comparator::{
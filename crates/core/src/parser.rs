@@ -1,13 +1,41 @@
 use oxc_allocator::Allocator;
 use oxc_ast::ast::{
     BindingPatternKind, BlockStatement, ClassElement, Expression, FormalParameter, FunctionBody,
-    Program, PropertyKey, Statement, VariableDeclarator,
+    Program, PropertyKey, Statement, TSType, TSTypeName, VariableDeclarator,
 };
 use oxc_parser::Parser;
 use oxc_span::SourceType;
 use std::rc::Rc;
 
-use crate::tree::TreeNode;
+use crate::tree::{TreeNode, DEFAULT_MAX_TREE_DEPTH};
+
+/// Deepest bracket nesting handed to the oxc parser. oxc parses nested
+/// expressions recursively and overflows the stack of a rayon worker well
+/// before `DEFAULT_MAX_TREE_DEPTH` levels in debug builds, so deeper sources are
+/// rejected before parsing.
+pub const MAX_SOURCE_NESTING: usize = 500;
+
+/// Reject sources whose `(`, `[` and `{` nest deeper than `MAX_SOURCE_NESTING`
+///
+/// # Errors
+///
+/// Returns an error naming the limit if the nesting is deeper
+pub fn check_source_nesting(source_text: &str) -> Result<(), String> {
+    let mut depth = 0usize;
+    for byte in source_text.bytes() {
+        match byte {
+            b'(' | b'[' | b'{' => {
+                depth += 1;
+                if depth > MAX_SOURCE_NESTING {
+                    return Err(format!("Nesting deeper than {MAX_SOURCE_NESTING} levels"));
+                }
+            }
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    Ok(())
+}
 
 /// Parse TypeScript code and convert to `TreeNode` structure
 ///
@@ -18,6 +46,7 @@ pub fn parse_and_convert_to_tree(
     filename: &str,
     source_text: &str,
 ) -> Result<Rc<TreeNode>, String> {
+    check_source_nesting(source_text)?;
     let allocator = Allocator::default();
     let source_type = SourceType::from_path(filename).unwrap_or(SourceType::tsx());
     let ret = Parser::new(&allocator, source_text, source_type).parse();
@@ -35,7 +64,7 @@ pub fn ast_to_tree_node(program: &Program, id_counter: &mut usize) -> Rc<TreeNod
     *id_counter += 1;
 
     for stmt in &program.body {
-        if let Some(child) = statement_to_tree_node(stmt, id_counter) {
+        if let Some(child) = statement_to_tree_node(stmt, id_counter, 1) {
             root.add_child(child);
         }
     }
@@ -43,7 +72,18 @@ pub fn ast_to_tree_node(program: &Program, id_counter: &mut usize) -> Rc<TreeNod
     Rc::new(root)
 }
 
-fn statement_to_tree_node(stmt: &Statement, id_counter: &mut usize) -> Option<Rc<TreeNode>> {
+fn statement_to_tree_node(
+    stmt: &Statement,
+    id_counter: &mut usize,
+    depth: usize,
+) -> Option<Rc<TreeNode>> {
+    // Stop descending into pathologically deep input rather than overflow the stack
+    if depth >= DEFAULT_MAX_TREE_DEPTH {
+        let node = TreeNode::truncated(*id_counter);
+        *id_counter += 1;
+        return Some(Rc::new(node));
+    }
+
     match stmt {
         Statement::FunctionDeclaration(func) => {
             let label = func.id.as_ref().map_or("Function", |id| id.name.as_str()).to_string();
@@ -57,9 +97,14 @@ fn statement_to_tree_node(stmt: &Statement, id_counter: &mut usize) -> Option<Rc
                 }
             }
 
+            // Add the return type
+            if let Some(return_type) = &func.return_type {
+                node.add_child(type_to_tree_node(&return_type.type_annotation, id_counter));
+            }
+
             // Add body
             if let Some(body) = &func.body {
-                if let Some(body_node) = function_body_to_tree_node(body, id_counter) {
+                if let Some(body_node) = function_body_to_tree_node(body, id_counter, depth + 1) {
                     node.add_child(body_node);
                 }
             }
@@ -73,7 +118,8 @@ fn statement_to_tree_node(stmt: &Statement, id_counter: &mut usize) -> Option<Rc
 
             // Add class body elements
             for element in &class.body.body {
-                if let Some(elem_node) = class_element_to_tree_node(element, id_counter) {
+                if let Some(elem_node) = class_element_to_tree_node(element, id_counter, depth + 1)
+                {
                     node.add_child(elem_node);
                 }
             }
@@ -89,7 +135,9 @@ fn statement_to_tree_node(stmt: &Statement, id_counter: &mut usize) -> Option<Rc
             *id_counter += 1;
 
             for decl in &var_decl.declarations {
-                if let Some(decl_node) = variable_declarator_to_tree_node(decl, id_counter) {
+                if let Some(decl_node) =
+                    variable_declarator_to_tree_node(decl, id_counter, depth + 1)
+                {
                     node.add_child(decl_node);
                 }
             }
@@ -97,27 +145,29 @@ fn statement_to_tree_node(stmt: &Statement, id_counter: &mut usize) -> Option<Rc
             Some(Rc::new(node))
         }
         Statement::ExpressionStatement(expr_stmt) => {
-            expression_to_tree_node(&expr_stmt.expression, id_counter)
+            expression_to_tree_node(&expr_stmt.expression, id_counter, depth)
         }
-        Statement::BlockStatement(block) => block_statement_to_tree_node(block, id_counter),
+        Statement::BlockStatement(block) => block_statement_to_tree_node(block, id_counter, depth),
         Statement::IfStatement(if_stmt) => {
             let mut node =
                 TreeNode::new("IfStatement".to_string(), "IfStatement".to_string(), *id_counter);
             *id_counter += 1;
 
             // Add test expression
-            if let Some(test_node) = expression_to_tree_node(&if_stmt.test, id_counter) {
+            if let Some(test_node) = expression_to_tree_node(&if_stmt.test, id_counter, depth + 1) {
                 node.add_child(test_node);
             }
 
             // Add consequent
-            if let Some(cons_node) = statement_to_tree_node(&if_stmt.consequent, id_counter) {
+            if let Some(cons_node) =
+                statement_to_tree_node(&if_stmt.consequent, id_counter, depth + 1)
+            {
                 node.add_child(cons_node);
             }
 
             // Add alternate if exists
             if let Some(alt) = &if_stmt.alternate {
-                if let Some(alt_node) = statement_to_tree_node(alt, id_counter) {
+                if let Some(alt_node) = statement_to_tree_node(alt, id_counter, depth + 1) {
                     node.add_child(alt_node);
                 }
             }
@@ -133,7 +183,7 @@ fn statement_to_tree_node(stmt: &Statement, id_counter: &mut usize) -> Option<Rc
             *id_counter += 1;
 
             if let Some(arg) = &ret_stmt.argument {
-                if let Some(arg_node) = expression_to_tree_node(arg, id_counter) {
+                if let Some(arg_node) = expression_to_tree_node(arg, id_counter, depth + 1) {
                     node.add_child(arg_node);
                 }
             }
@@ -149,7 +199,18 @@ fn statement_to_tree_node(stmt: &Statement, id_counter: &mut usize) -> Option<Rc
     }
 }
 
-fn expression_to_tree_node(expr: &Expression, id_counter: &mut usize) -> Option<Rc<TreeNode>> {
+fn expression_to_tree_node(
+    expr: &Expression,
+    id_counter: &mut usize,
+    depth: usize,
+) -> Option<Rc<TreeNode>> {
+    // Stop descending into pathologically deep input rather than overflow the stack
+    if depth >= DEFAULT_MAX_TREE_DEPTH {
+        let node = TreeNode::truncated(*id_counter);
+        *id_counter += 1;
+        return Some(Rc::new(node));
+    }
+
     match expr {
         Expression::Identifier(ident) => {
             let node = TreeNode::new(
@@ -186,11 +247,14 @@ fn expression_to_tree_node(expr: &Expression, id_counter: &mut usize) -> Option<
             );
             *id_counter += 1;
 
-            if let Some(left_node) = expression_to_tree_node(&bin_expr.left, id_counter) {
+            if let Some(left_node) = expression_to_tree_node(&bin_expr.left, id_counter, depth + 1)
+            {
                 node.add_child(left_node);
             }
 
-            if let Some(right_node) = expression_to_tree_node(&bin_expr.right, id_counter) {
+            if let Some(right_node) =
+                expression_to_tree_node(&bin_expr.right, id_counter, depth + 1)
+            {
                 node.add_child(right_node);
             }
 
@@ -204,13 +268,15 @@ fn expression_to_tree_node(expr: &Expression, id_counter: &mut usize) -> Option<
             );
             *id_counter += 1;
 
-            if let Some(callee_node) = expression_to_tree_node(&call_expr.callee, id_counter) {
+            if let Some(callee_node) =
+                expression_to_tree_node(&call_expr.callee, id_counter, depth + 1)
+            {
                 node.add_child(callee_node);
             }
 
             for arg in &call_expr.arguments {
                 if let Some(expr) = arg.as_expression() {
-                    if let Some(arg_node) = expression_to_tree_node(expr, id_counter) {
+                    if let Some(arg_node) = expression_to_tree_node(expr, id_counter, depth + 1) {
                         node.add_child(arg_node);
                     }
                 }
@@ -233,6 +299,11 @@ fn expression_to_tree_node(expr: &Expression, id_counter: &mut usize) -> Option<
                 }
             }
 
+            // Add the return type
+            if let Some(return_type) = &arrow.return_type {
+                node.add_child(type_to_tree_node(&return_type.type_annotation, id_counter));
+            }
+
             // Add body
             if arrow.expression {
                 // Expression body (e.g., => x + 1)
@@ -240,14 +311,16 @@ fn expression_to_tree_node(expr: &Expression, id_counter: &mut usize) -> Option<
                     arrow.body.statements.first()
                 {
                     if let Some(expr_node) =
-                        expression_to_tree_node(&expr_stmt.expression, id_counter)
+                        expression_to_tree_node(&expr_stmt.expression, id_counter, depth + 1)
                     {
                         node.add_child(expr_node);
                     }
                 }
             } else {
                 // Block body (e.g., => { return x + 1; })
-                if let Some(body_node) = function_body_to_tree_node(&arrow.body, id_counter) {
+                if let Some(body_node) =
+                    function_body_to_tree_node(&arrow.body, id_counter, depth + 1)
+                {
                     node.add_child(body_node);
                 }
             }
@@ -272,18 +345,47 @@ fn formal_parameter_to_tree_node(
         BindingPatternKind::BindingIdentifier(ident) => ident.name.as_str().to_string(),
         _ => "Parameter".to_string(),
     };
-    let node = TreeNode::new(label, "Parameter".to_string(), *id_counter);
+    let mut node = TreeNode::new(label, "Parameter".to_string(), *id_counter);
     *id_counter += 1;
+
+    // Add the type annotation
+    if let Some(annotation) = &param.pattern.type_annotation {
+        node.add_child(type_to_tree_node(&annotation.type_annotation, id_counter));
+    }
+
     Some(Rc::new(node))
 }
 
-fn function_body_to_tree_node(body: &FunctionBody, id_counter: &mut usize) -> Option<Rc<TreeNode>> {
+/// A type annotation as one `TSType` leaf, labeled with the keyword or the
+/// referenced type name (`string`, `number`, `User`) and `Type` otherwise
+fn type_to_tree_node(ts_type: &TSType, id_counter: &mut usize) -> Rc<TreeNode> {
+    let label = match ts_type {
+        TSType::TSStringKeyword(_) => "string",
+        TSType::TSNumberKeyword(_) => "number",
+        TSType::TSBooleanKeyword(_) => "boolean",
+        TSType::TSBigIntKeyword(_) => "bigint",
+        TSType::TSTypeReference(reference) => match &reference.type_name {
+            TSTypeName::IdentifierReference(ident) => ident.name.as_str(),
+            TSTypeName::QualifiedName(_) => "Type",
+        },
+        _ => "Type",
+    };
+    let node = TreeNode::new(label.to_string(), "TSType".to_string(), *id_counter);
+    *id_counter += 1;
+    Rc::new(node)
+}
+
+fn function_body_to_tree_node(
+    body: &FunctionBody,
+    id_counter: &mut usize,
+    depth: usize,
+) -> Option<Rc<TreeNode>> {
     let mut node =
         TreeNode::new("BlockStatement".to_string(), "BlockStatement".to_string(), *id_counter);
     *id_counter += 1;
 
     for stmt in &body.statements {
-        if let Some(stmt_node) = statement_to_tree_node(stmt, id_counter) {
+        if let Some(stmt_node) = statement_to_tree_node(stmt, id_counter, depth + 1) {
             node.add_child(stmt_node);
         }
     }
@@ -294,13 +396,14 @@ fn function_body_to_tree_node(body: &FunctionBody, id_counter: &mut usize) -> Op
 fn block_statement_to_tree_node(
     block: &BlockStatement,
     id_counter: &mut usize,
+    depth: usize,
 ) -> Option<Rc<TreeNode>> {
     let mut node =
         TreeNode::new("BlockStatement".to_string(), "BlockStatement".to_string(), *id_counter);
     *id_counter += 1;
 
     for stmt in &block.body {
-        if let Some(stmt_node) = statement_to_tree_node(stmt, id_counter) {
+        if let Some(stmt_node) = statement_to_tree_node(stmt, id_counter, depth + 1) {
             node.add_child(stmt_node);
         }
     }
@@ -311,6 +414,7 @@ fn block_statement_to_tree_node(
 fn variable_declarator_to_tree_node(
     decl: &VariableDeclarator,
     id_counter: &mut usize,
+    depth: usize,
 ) -> Option<Rc<TreeNode>> {
     let label = match &decl.id.kind {
         BindingPatternKind::BindingIdentifier(ident) => ident.name.as_str().to_string(),
@@ -320,7 +424,7 @@ fn variable_declarator_to_tree_node(
     *id_counter += 1;
 
     if let Some(init) = &decl.init {
-        if let Some(init_node) = expression_to_tree_node(init, id_counter) {
+        if let Some(init_node) = expression_to_tree_node(init, id_counter, depth + 1) {
             node.add_child(init_node);
         }
     }
@@ -331,6 +435,7 @@ fn variable_declarator_to_tree_node(
 fn class_element_to_tree_node(
     element: &ClassElement,
     id_counter: &mut usize,
+    depth: usize,
 ) -> Option<Rc<TreeNode>> {
     match element {
         ClassElement::MethodDefinition(method) => {
@@ -344,7 +449,7 @@ fn class_element_to_tree_node(
 
             // Add method body
             if let Some(body) = &method.value.body {
-                if let Some(body_node) = function_body_to_tree_node(body, id_counter) {
+                if let Some(body_node) = function_body_to_tree_node(body, id_counter, depth + 1) {
                     node.add_child(body_node);
                 }
             }
@@ -0,0 +1,208 @@
+//! Incremental SARIF 2.1.0 output.
+//!
+//! The document header is written up front and every result as soon as it is
+//! known, so the memory used does not grow with the number of results.
+
+use serde::Serialize;
+use serde_json::json;
+use std::io::{self, Write};
+
+/// A source span a result points at
+#[derive(Debug, Clone)]
+pub struct SarifLocation {
+    /// Path of the file, written out as a percent-encoded URI reference
+    pub uri: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    /// Shown next to related locations, e.g. the name of the function
+    pub message: Option<String>,
+}
+
+/// Severity of a result, as shown by code scanning tools
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SarifLevel {
+    Note,
+    Warning,
+    Error,
+}
+
+/// One reported finding: its primary location and the locations it relates to
+#[derive(Debug, Clone)]
+pub struct SarifResult {
+    pub level: SarifLevel,
+    pub message: String,
+    pub location: SarifLocation,
+    pub related_locations: Vec<SarifLocation>,
+    /// Written as the result's property bag when not empty, e.g. the score
+    pub properties: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Writes a SARIF document with a single run and a single rule
+pub struct SarifWriter<W: Write> {
+    writer: W,
+    rule_id: String,
+    results: usize,
+}
+
+impl<W: Write> SarifWriter<W> {
+    /// Start the document: everything up to the opening of the results array
+    pub fn new(
+        mut writer: W,
+        tool_name: &str,
+        tool_version: &str,
+        rule_id: &str,
+        rule_description: &str,
+    ) -> io::Result<Self> {
+        let driver = json!({
+            "name": tool_name,
+            "version": tool_version,
+            "rules": [{ "id": rule_id, "shortDescription": { "text": rule_description } }],
+        });
+        write!(
+            writer,
+            r#"{{"$schema":"https://json.schemastore.org/sarif-2.1.0.json","version":"2.1.0","runs":[{{"tool":{{"driver":{}}},"results":["#,
+            driver
+        )?;
+        Ok(SarifWriter { writer, rule_id: rule_id.to_string(), results: 0 })
+    }
+
+    /// Append one result to the document
+    pub fn write_result(&mut self, result: &SarifResult) -> io::Result<()> {
+        let related: Vec<_> = result
+            .related_locations
+            .iter()
+            .enumerate()
+            .map(|(id, location)| {
+                let mut related = location_json(location);
+                related["id"] = json!(id);
+                if let Some(message) = &location.message {
+                    related["message"] = json!({ "text": message });
+                }
+                related
+            })
+            .collect();
+        let mut entry = json!({
+            "ruleId": self.rule_id,
+            "level": result.level,
+            "message": { "text": result.message },
+            "locations": [location_json(&result.location)],
+            "relatedLocations": related,
+        });
+        if !result.properties.is_empty() {
+            entry["properties"] = json!(result.properties);
+        }
+
+        if self.results > 0 {
+            self.writer.write_all(b",")?;
+        }
+        write_json(&mut self.writer, &entry)?;
+        self.results += 1;
+        Ok(())
+    }
+
+    /// Number of results written so far
+    pub fn result_count(&self) -> usize {
+        self.results
+    }
+
+    /// Close the results array and the document, returning the writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.write_all(b"]}]}\n")?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+fn location_json(location: &SarifLocation) -> serde_json::Value {
+    json!({
+        "physicalLocation": {
+            "artifactLocation": { "uri": uri_reference(&location.uri) },
+            "region": { "startLine": location.start_line, "endLine": location.end_line },
+        }
+    })
+}
+
+/// A path as a URI reference: `/`-separated, with the UTF-8 bytes of
+/// non-ASCII characters and of characters URIs reserve percent-encoded
+fn uri_reference(path: &str) -> String {
+    let path = if cfg!(windows) { path.replace('\\', "/") } else { path.to_string() };
+    let mut uri = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/:".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+fn write_json<W: Write, T: Serialize>(writer: &mut W, value: &T) -> io::Result<()> {
+    serde_json::to_writer(writer, value).map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(uri: &str, start_line: u32) -> SarifLocation {
+        SarifLocation {
+            uri: uri.to_string(),
+            start_line,
+            end_line: start_line + 5,
+            message: Some("other".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_streamed_document_is_valid_json() {
+        let mut writer =
+            SarifWriter::new(Vec::new(), "tool", "1.0.0", "duplicate", "Duplicate code").unwrap();
+        for line in [1, 10] {
+            let mut properties = serde_json::Map::new();
+            if line == 10 {
+                properties.insert("similarity".to_string(), json!(0.9));
+            }
+            let result = SarifResult {
+                level: SarifLevel::Note,
+                message: "Similar \"functions\"".to_string(),
+                location: location("src/a.rs", line),
+                related_locations: vec![location("src/b.rs", line + 20)],
+                properties,
+            };
+            writer.write_result(&result).unwrap();
+        }
+        assert_eq!(writer.result_count(), 2);
+        let output = writer.finish().unwrap();
+
+        let document: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(document["version"], "2.1.0");
+        let run = &document["runs"][0];
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "duplicate");
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1]["message"]["text"], "Similar \"functions\"");
+        let region = &results[1]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 10);
+        assert_eq!(results[0]["relatedLocations"][0]["message"]["text"], "other");
+        assert_eq!(results[0]["level"], "note");
+        assert!(results[0].get("properties").is_none());
+        assert_eq!(results[1]["properties"]["similarity"], 0.9);
+
+        // A document without results is valid too
+        let empty = SarifWriter::new(Vec::new(), "tool", "1.0.0", "duplicate", "Duplicate code")
+            .unwrap()
+            .finish()
+            .unwrap();
+        let document: serde_json::Value = serde_json::from_slice(&empty).unwrap();
+        assert!(document["runs"][0]["results"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_uris_are_percent_encoded_utf8() {
+        assert_eq!(uri_reference("src/a.rs"), "src/a.rs");
+        assert_eq!(uri_reference("src/données/a b.rs"), "src/donn%C3%A9es/a%20b.rs");
+        assert_eq!(uri_reference("100%.rs"), "100%25.rs");
+    }
+}
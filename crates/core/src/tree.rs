@@ -1,32 +1,194 @@
 use std::rc::Rc;
 
+/// Nesting depth past which tree conversion stops descending, so that
+/// pathologically deep input cannot overflow the stack
+pub const DEFAULT_MAX_TREE_DEPTH: usize = 1000;
+
 #[derive(Debug, Clone)]
 pub struct TreeNode {
     pub label: String,
     pub value: String,
     pub children: Vec<Rc<TreeNode>>,
     pub id: usize,
+    /// Number of nodes of the subtree, kept up to date by `add_child`; code
+    /// that edits `children` directly must reset it to `None`
     pub subtree_size: Option<usize>,
 }
 
 impl TreeNode {
     #[must_use]
     pub fn new(label: String, value: String, id: usize) -> Self {
-        TreeNode { label, value, children: Vec::new(), id, subtree_size: None }
+        TreeNode { label, value, children: Vec::new(), id, subtree_size: Some(1) }
+    }
+
+    /// The leaf standing in for a subtree nested deeper than the converter
+    /// descends (see [`DEFAULT_MAX_TREE_DEPTH`])
+    #[must_use]
+    pub fn truncated(id: usize) -> Self {
+        TreeNode::new("truncated".to_string(), String::new(), id)
     }
 
     pub fn add_child(&mut self, child: Rc<TreeNode>) {
+        // Children are shared and immutable, so their size can no longer change
+        self.subtree_size = self.subtree_size.map(|size| size + child.get_subtree_size());
         self.children.push(child);
     }
 
+    /// Number of nodes of the subtree, read from the memoized size when set
     #[must_use]
     pub fn get_subtree_size(&self) -> usize {
-        // Since we can't mutate through Rc, we'll calculate it each time
-        // In a real implementation, you might want to use RefCell for interior mutability
-        let mut size = 1;
-        for child in &self.children {
-            size += child.get_subtree_size();
+        self.subtree_size.unwrap_or_else(|| {
+            1 + self.children.iter().map(|child| child.get_subtree_size()).sum::<usize>()
+        })
+    }
+
+    /// The nodes of the subtree in pre-order, starting with this one
+    pub fn iter(&self) -> impl Iterator<Item = &TreeNode> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev().map(Rc::as_ref));
+            Some(node)
+        })
+    }
+
+    /// The nodes below this one with the given label, in pre-order
+    pub fn descendants_matching<'a>(
+        &'a self,
+        label: &'a str,
+    ) -> impl Iterator<Item = &'a TreeNode> {
+        self.iter().skip(1).filter(move |node| node.label == label)
+    }
+
+    /// Hash of the labels and values of the subtree, independent of node
+    /// ids and of where the code sits in its file. FNV-1a, so the hash is
+    /// the same across builds and can be stored.
+    #[must_use]
+    pub fn structural_hash(&self) -> u64 {
+        self.hash_with(true)
+    }
+
+    /// [`Self::structural_hash`] of the labels alone, shared by subtrees that
+    /// only differ in names and literals
+    #[must_use]
+    pub fn shape_hash(&self) -> u64 {
+        self.hash_with(false)
+    }
+
+    fn hash_with(&self, include_values: bool) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+        fn feed(hash: &mut u64, bytes: &[u8]) {
+            for byte in bytes {
+                *hash ^= u64::from(*byte);
+                *hash = hash.wrapping_mul(FNV_PRIME);
+            }
         }
-        size
+
+        fn visit(node: &TreeNode, include_values: bool, hash: &mut u64) {
+            feed(hash, node.label.as_bytes());
+            feed(hash, &[0]);
+            if include_values {
+                feed(hash, node.value.as_bytes());
+                feed(hash, &[0]);
+            }
+            // The child count keeps the preorder unambiguous
+            feed(hash, &(node.children.len() as u64).to_le_bytes());
+            for child in &node.children {
+                visit(child, include_values, hash);
+            }
+        }
+
+        let mut hash = FNV_OFFSET_BASIS;
+        visit(self, include_values, &mut hash);
+        hash
+    }
+
+    /// Whether both subtrees have the same labels in the same shape, and the
+    /// same values too when `include_values` is set. Node ids are ignored.
+    #[must_use]
+    pub fn same_structure(&self, other: &TreeNode, include_values: bool) -> bool {
+        self.get_subtree_size() == other.get_subtree_size()
+            && self.iter().zip(other.iter()).all(|(node1, node2)| {
+                node1.label == node2.label
+                    && node1.children.len() == node2.children.len()
+                    && (!include_values || node1.value == node2.value)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generic_tree_sitter_parser::GenericTreeSitterParser;
+    use crate::language_parser::LanguageParser;
+
+    #[test]
+    fn test_iter_is_pre_order() {
+        let leaf = |label: &str, id| Rc::new(TreeNode::new(label.to_string(), String::new(), id));
+        let mut left = TreeNode::new("left".to_string(), String::new(), 1);
+        left.add_child(leaf("left_child", 2));
+        let mut root = TreeNode::new("root".to_string(), String::new(), 0);
+        root.add_child(Rc::new(left));
+        root.add_child(leaf("right", 3));
+
+        let labels: Vec<&str> = root.iter().map(|node| node.label.as_str()).collect();
+        assert_eq!(labels, vec!["root", "left", "left_child", "right"]);
+        assert_eq!(root.descendants_matching("root").count(), 0);
+    }
+
+    #[test]
+    fn test_count_identifiers_in_parsed_tree() {
+        let mut parser = GenericTreeSitterParser::from_language_name("go").unwrap();
+        let tree =
+            parser.parse("func add(a int, b int) int {\n\treturn a + b\n}\n", "add.go").unwrap();
+
+        // The function name, both parameters and both operands
+        let identifiers: Vec<&str> =
+            tree.descendants_matching("identifier").map(|node| node.value.as_str()).collect();
+        assert_eq!(identifiers.len(), 5, "{identifiers:?}");
+        assert_eq!(tree.iter().count(), tree.get_subtree_size());
+    }
+
+    #[test]
+    fn test_memoized_sizes_survive_normalization() {
+        let mut parser = GenericTreeSitterParser::from_language_name("go").unwrap();
+        let tree = parser
+            .parse(
+                "func total(xs []int) int {\n\tsum := 0\n\tfor _, x := range xs {\n\t\tif x > 10 {\n\t\t\tsum += x\n\t\t}\n\t}\n\treturn sum\n}\n",
+                "total.go",
+            )
+            .unwrap();
+        let options = crate::tsed::TSEDOptions {
+            ignore_literals: true,
+            normalize_boolean: true,
+            normalize_identifier_case: true,
+            ..Default::default()
+        };
+        let normalized = crate::tree_normalizer::normalize_tree(&tree, &options);
+
+        for root in [&tree, &normalized] {
+            for node in root.iter() {
+                assert_eq!(node.subtree_size, Some(node.iter().count()), "{}", node.label);
+            }
+        }
+    }
+
+    #[test]
+    fn test_renamed_functions_share_a_shape_hash() {
+        let mut parser = GenericTreeSitterParser::from_language_name("go").unwrap();
+        let mut parse = |code: &str| parser.parse(code, "sum.go").unwrap();
+        let sum = parse("func sum(xs []int) int {\n\ttotal := 0\n\tfor _, x := range xs {\n\t\ttotal += x\n\t}\n\treturn total\n}\n");
+        let renamed = parse("func add(ys []int) int {\n\tacc := 0\n\tfor _, y := range ys {\n\t\tacc += y\n\t}\n\treturn acc\n}\n");
+        let changed = parse("func add(ys []int) int {\n\tacc := 0\n\tfor _, y := range ys {\n\t\tacc -= y\n\t}\n\treturn acc\n}\n");
+
+        assert_eq!(sum.shape_hash(), renamed.shape_hash());
+        assert!(sum.same_structure(&renamed, false));
+        assert_ne!(sum.structural_hash(), renamed.structural_hash());
+        assert!(!sum.same_structure(&renamed, true));
+
+        assert_ne!(sum.shape_hash(), changed.shape_hash());
+        assert!(!sum.same_structure(&changed, false));
     }
 }
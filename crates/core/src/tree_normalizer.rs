@@ -0,0 +1,1407 @@
+//! Normalization passes that rewrite a `TreeNode` into a canonical shape
+//! before TSED comparison.
+//!
+//! The passes work on the tree-sitter based trees (Rust, Python, JavaScript
+//! grammars) and are intentionally conservative: they only rewrite shapes whose
+//! equivalence does not depend on what the code does at runtime.
+
+use crate::apted::LeafCategory;
+use crate::tree::TreeNode;
+use crate::tsed::TSEDOptions;
+use std::rc::Rc;
+
+/// Call node labels of the supported tree-sitter grammars
+const CALL_LABELS: &[&str] = &["call_expression", "call"];
+
+/// Member access labels whose last child names the accessed field or method
+const MEMBER_LABELS: &[&str] = &["field_expression", "member_expression", "attribute"];
+
+/// Method names that mark a call chain as an iterator/stream pipeline
+const PIPELINE_STAGES: &[&str] = &[
+    "map",
+    "filter",
+    "filter_map",
+    "flat_map",
+    "flatMap",
+    "fold",
+    "reduce",
+    "for_each",
+    "forEach",
+    "collect",
+    "sum",
+    "count",
+    "any",
+    "all",
+    "some",
+    "every",
+    "find",
+    "take",
+    "skip",
+    "zip",
+    "enumerate",
+    "rev",
+    "chain",
+];
+
+/// Labels of negation nodes (`!x`, `not x`)
+const NEGATION_LABELS: &[&str] = &["unary_expression", "not_operator"];
+
+/// Labels of binary nodes laid out as `[left, operator, right]`
+const BINARY_LABELS: &[&str] = &["binary_expression", "boolean_operator", "comparison_operator"];
+
+/// Commutative boolean operators whose operands may be reordered when pure
+const COMMUTATIVE_BOOLEAN_OPERATORS: &[&str] = &["&&", "||", "and", "or"];
+
+/// Labels that can appear in a side-effect-free operand, besides bare tokens
+const PURE_LABELS: &[&str] = &[
+    "identifier",
+    "field_identifier",
+    "property_identifier",
+    "self",
+    "this",
+    "integer_literal",
+    "float_literal",
+    "string_literal",
+    "char_literal",
+    "boolean_literal",
+    "integer",
+    "float",
+    "number",
+    "string",
+    "string_start",
+    "string_content",
+    "string_end",
+    "true",
+    "false",
+    "none",
+    "null",
+    "binary_expression",
+    "boolean_operator",
+    "comparison_operator",
+    "unary_expression",
+    "not_operator",
+    "parenthesized_expression",
+    "and",
+    "or",
+    "not",
+];
+
+/// Punctuation around and between the elements of a container construction
+const CONTAINER_PUNCTUATION: &[&str] = &["[", "]", "(", ")", ","];
+
+/// Spread/splat nodes whose last child is the spread operand (`...xs`, `*xs`)
+const SPREAD_LABELS: &[&str] = &["spread_element", "list_splat"];
+
+/// String-like literals, including raw strings, f-strings and template literals
+const STRING_LITERAL_LABELS: &[&str] =
+    &["string_literal", "raw_string_literal", "char_literal", "string", "template_string"];
+
+/// Code embedded in a string literal (`{x}` in f-strings, `${x}` in templates)
+const INTERPOLATION_LABELS: &[&str] = &["interpolation", "template_substitution"];
+
+/// Leaves naming a type (`i32`, `String`, `number`, ...)
+pub const TYPE_NAME_LABELS: &[&str] = &["type_identifier", "primitive_type", "predefined_type"];
+
+/// Spellings of the primitive types of the supported languages and the
+/// language-neutral token each of them is compared as
+const PRIMITIVE_TYPE_TOKENS: &[(&str, &str)] = &[
+    ("i8", "number"),
+    ("i16", "number"),
+    ("i32", "number"),
+    ("i64", "number"),
+    ("i128", "number"),
+    ("isize", "number"),
+    ("u8", "number"),
+    ("u16", "number"),
+    ("u32", "number"),
+    ("u64", "number"),
+    ("u128", "number"),
+    ("usize", "number"),
+    ("f32", "number"),
+    ("f64", "number"),
+    ("number", "number"),
+    ("bigint", "number"),
+    ("int", "number"),
+    ("float", "number"),
+    ("String", "string"),
+    ("str", "string"),
+    ("string", "string"),
+    ("bool", "boolean"),
+    ("boolean", "boolean"),
+];
+
+/// Python annotations wrap the type name in a `type` node
+const TYPE_ANNOTATION_LABELS: &[&str] = &["type"];
+
+/// Numeric literals
+const NUMBER_LITERAL_LABELS: &[&str] =
+    &["integer_literal", "float_literal", "integer", "float", "number"];
+
+/// Parameter lists a receiver is stripped from
+const PARAMETER_LIST_LABELS: &[&str] = &["parameters"];
+
+/// Receiver nodes: Rust's `&self`/`&mut self` parameter and bare `self`
+const RECEIVER_LABELS: &[&str] = &["self_parameter", "self"];
+
+/// Parameters with a type annotation whose first child is the receiver name
+const TYPED_PARAMETER_LABELS: &[&str] = &["parameter", "typed_parameter"];
+
+/// Conventional names of the receiver in languages where it is a plain identifier
+const RECEIVER_NAMES: &[&str] = &["self", "cls"];
+
+/// Await nodes: Rust/JavaScript `await_expression`, Python `await`
+const AWAIT_LABELS: &[&str] = &["await_expression", "await"];
+
+/// Closures that can be passed as a `.then()` callback
+const CALLBACK_LABELS: &[&str] =
+    &["closure_expression", "arrow_function", "function_expression", "function"];
+
+/// Parameter lists of callbacks
+const CALLBACK_PARAMETER_LABELS: &[&str] = &["closure_parameters", "formal_parameters"];
+
+/// Statement blocks whose statements can be folded into a continuation
+const BLOCK_LABELS: &[&str] = &["block", "statement_block"];
+
+/// Values of the oxc tree nodes labeled with a name from the source
+const OXC_NAMED_KINDS: &[&str] = &[
+    "Identifier",
+    "Parameter",
+    "VariableDeclarator",
+    "FunctionDeclaration",
+    "ClassDeclaration",
+    "MethodDefinition",
+    "PropertyDefinition",
+];
+
+/// Apply every normalization pass enabled in `options`.
+///
+/// Returns the input tree unchanged (a cheap `Rc` clone) when no pass is enabled.
+#[must_use]
+pub fn normalize_tree(tree: &Rc<TreeNode>, options: &TSEDOptions) -> Rc<TreeNode> {
+    let mut normalized = Rc::clone(tree);
+    let mut changed = false;
+
+    if options.normalize_async {
+        normalized = normalize_async(&normalized);
+        changed = true;
+    }
+
+    if options.normalize_pipelines {
+        normalized = normalize_pipelines(&normalized);
+        changed = true;
+    }
+
+    if options.normalize_boolean {
+        normalized = normalize_boolean(&normalized);
+        changed = true;
+    }
+
+    if options.normalize_containers {
+        normalized = normalize_containers(&normalized);
+        changed = true;
+    }
+
+    if options.ignore_literals {
+        normalized = normalize_literals(&normalized);
+        changed = true;
+    }
+
+    if options.normalize_primitive_types {
+        normalized = normalize_primitive_types(&normalized);
+        changed = true;
+    }
+
+    if options.normalize_identifier_case {
+        normalized = normalize_identifier_case(&normalized);
+        changed = true;
+    }
+
+    if !options.include_receiver_in_tree {
+        normalized = strip_receiver(&normalized);
+        changed = true;
+    }
+
+    if changed {
+        renumber(&normalized)
+    } else {
+        normalized
+    }
+}
+
+/// Flatten method-call chains such as `xs.iter().map(f).filter(g).collect()`
+/// into a `pipeline` node holding the chain's source followed by one
+/// `pipeline_stage` node per call, in execution order.
+///
+/// The parser produces such chains as deeply left-nested call/member nodes, so
+/// two equivalent pipelines only align after APTED matches every nesting level.
+/// The flat form lets stages be matched (or inserted/deleted) one by one.
+#[must_use]
+pub fn normalize_pipelines(tree: &Rc<TreeNode>) -> Rc<TreeNode> {
+    if let Some((source, stages)) = collect_chain(tree) {
+        if stages.len() >= 2
+            && stages.iter().any(|(method, _)| PIPELINE_STAGES.contains(&method.as_str()))
+        {
+            let mut pipeline = TreeNode::new("pipeline".to_string(), String::new(), tree.id);
+            pipeline.add_child(normalize_pipelines(&source));
+
+            for (method, arguments) in stages {
+                let mut stage = TreeNode::new("pipeline_stage".to_string(), method, tree.id);
+                if let Some(arguments) = arguments {
+                    stage.add_child(normalize_pipelines(&arguments));
+                }
+                pipeline.add_child(Rc::new(stage));
+            }
+
+            return Rc::new(pipeline);
+        }
+    }
+
+    map_children(tree, normalize_pipelines)
+}
+
+/// Desugar awaiting into one canonical shape, so that `let x = f().await; rest`
+/// and `f().then(|x| { rest })` (also JavaScript's `await`/`.then(x => ...)`
+/// and Python's `x = await f()`) compare equal:
+///
+/// - an awaited expression becomes an `awaited` node holding the operand
+/// - a binding of an awaited value to a plain name followed by more statements
+///   of the same block becomes an `async_continuation` node: an
+///   `await_binding` of the name and the awaited value, then the statements
+/// - `.then()` with a single callback taking a single plain parameter becomes
+///   the same `async_continuation`, with the callback body as statements
+///
+/// The scope is deliberately narrow: destructuring, `let mut`, typed
+/// bindings, `.then()` with a rejection handler and `async` blocks are left
+/// alone, and the `async` modifier and return type of the function are still
+/// compared. The rewrite ignores that a callback's `return` leaves only the
+/// callback while an early return after `await` leaves the whole function.
+#[must_use]
+pub fn normalize_async(tree: &Rc<TreeNode>) -> Rc<TreeNode> {
+    if let Some(operand) = await_operand(tree) {
+        return awaited(&normalize_async(&operand), tree.id);
+    }
+
+    if let Some((receiver, parameter, body)) = then_callback(tree) {
+        let body = normalize_async(&body);
+        let statements = if BLOCK_LABELS.contains(&body.label.as_str()) {
+            body.children
+                .iter()
+                .filter(|s| !matches!(s.label.as_str(), "{" | "}"))
+                .cloned()
+                .collect()
+        } else {
+            vec![body]
+        };
+        let awaited = awaited(&normalize_async(&receiver), tree.id);
+        return continuation(parameter, awaited, statements, tree.id);
+    }
+
+    let node = map_children(tree, normalize_async);
+    if BLOCK_LABELS.contains(&node.label.as_str()) {
+        let mut block = TreeNode::new(node.label.clone(), node.value.clone(), node.id);
+        for statement in fold_awaited_bindings(&node.children) {
+            block.add_child(statement);
+        }
+        Rc::new(block)
+    } else {
+        node
+    }
+}
+
+fn awaited(operand: &Rc<TreeNode>, id: usize) -> Rc<TreeNode> {
+    let mut node = TreeNode::new("awaited".to_string(), String::new(), id);
+    node.add_child(Rc::clone(operand));
+    Rc::new(node)
+}
+
+fn continuation(
+    name: Rc<TreeNode>,
+    awaited: Rc<TreeNode>,
+    statements: Vec<Rc<TreeNode>>,
+    id: usize,
+) -> Rc<TreeNode> {
+    let mut binding = TreeNode::new("await_binding".to_string(), String::new(), id);
+    binding.add_child(name);
+    binding.add_child(awaited);
+
+    let mut continuation = TreeNode::new("async_continuation".to_string(), String::new(), id);
+    continuation.add_child(Rc::new(binding));
+    for statement in statements {
+        continuation.add_child(statement);
+    }
+    Rc::new(continuation)
+}
+
+/// The operand of `x.await` (Rust) or `await x` (JavaScript, Python)
+fn await_operand(node: &TreeNode) -> Option<Rc<TreeNode>> {
+    if !AWAIT_LABELS.contains(&node.label.as_str()) || node.children.is_empty() {
+        return None;
+    }
+    let mut operands = node.children.iter().filter(|child| {
+        !(child.children.is_empty() && matches!(child.label.as_str(), "await" | "."))
+    });
+    match (operands.next(), operands.next()) {
+        (Some(operand), None) => Some(Rc::clone(operand)),
+        _ => None,
+    }
+}
+
+/// Receiver, parameter and body of `receiver.then(|x| body)`
+fn then_callback(node: &TreeNode) -> Option<(Rc<TreeNode>, Rc<TreeNode>, Rc<TreeNode>)> {
+    if !CALL_LABELS.contains(&node.label.as_str()) || node.children.len() != 2 {
+        return None;
+    }
+    let (member, arguments) = (&node.children[0], &node.children[1]);
+    if !MEMBER_LABELS.contains(&member.label.as_str())
+        || member.children.len() < 2
+        || member.children.last()?.value != "then"
+    {
+        return None;
+    }
+
+    let mut callbacks =
+        arguments.children.iter().filter(|a| !matches!(a.label.as_str(), "(" | ")"));
+    let callback = match (callbacks.next(), callbacks.next()) {
+        (Some(callback), None) if CALLBACK_LABELS.contains(&callback.label.as_str()) => callback,
+        _ => return None,
+    };
+
+    // `|x| ...`, `(x) => ...` or `x => ...`
+    let parameter = match callback.children.first() {
+        Some(first) if first.label == "identifier" => Rc::clone(first),
+        _ => {
+            let parameters = callback
+                .children
+                .iter()
+                .find(|c| CALLBACK_PARAMETER_LABELS.contains(&c.label.as_str()))?;
+            let mut names =
+                parameters.children.iter().filter(|p| !matches!(p.label.as_str(), "|" | "(" | ")"));
+            match (names.next(), names.next()) {
+                (Some(name), None) if name.label == "identifier" => Rc::clone(name),
+                _ => return None,
+            }
+        }
+    };
+
+    Some((Rc::clone(&member.children[0]), parameter, Rc::clone(callback.children.last()?)))
+}
+
+/// Replace the first binding of an awaited value that is followed by more
+/// statements with a continuation holding those statements
+fn fold_awaited_bindings(statements: &[Rc<TreeNode>]) -> Vec<Rc<TreeNode>> {
+    let closing = statements.last().filter(|s| s.label == "}");
+    let body = &statements[..statements.len() - usize::from(closing.is_some())];
+
+    for (index, statement) in body.iter().enumerate() {
+        let rest = &body[index + 1..];
+        if rest.is_empty() {
+            break;
+        }
+        if let Some((name, awaited)) = awaited_binding(statement) {
+            let mut folded = body[..index].to_vec();
+            folded.push(continuation(name, awaited, fold_awaited_bindings(rest), statement.id));
+            folded.extend(closing.cloned());
+            return folded;
+        }
+    }
+
+    statements.to_vec()
+}
+
+/// Name and awaited value of `let x = f().await;`, `const x = await f();` or
+/// Python's `x = await f()`, after the await was normalized
+fn awaited_binding(statement: &TreeNode) -> Option<(Rc<TreeNode>, Rc<TreeNode>)> {
+    let labels: Vec<&str> = statement.children.iter().map(|c| c.label.as_str()).collect();
+    let assignment = match (statement.label.as_str(), labels.as_slice()) {
+        ("let_declaration", ["let", "identifier", "=", "awaited", ";"]) => {
+            return Some((Rc::clone(&statement.children[1]), Rc::clone(&statement.children[3])));
+        }
+        ("lexical_declaration", ["const" | "let", "variable_declarator", ..]) => {
+            &statement.children[1]
+        }
+        ("expression_statement", ["assignment"]) => &statement.children[0],
+        _ => return None,
+    };
+
+    match assignment.children.iter().map(|c| c.label.as_str()).collect::<Vec<_>>().as_slice() {
+        ["identifier", "=", "awaited"] => {
+            Some((Rc::clone(&assignment.children[0]), Rc::clone(&assignment.children[2])))
+        }
+        _ => None,
+    }
+}
+
+/// Canonicalize a conservative set of equivalent boolean forms:
+///
+/// - `!(a == b)` becomes `a != b` and `!(a != b)` becomes `a == b`
+///   (also `not (a == b)` in Python and `===`/`!==` in JavaScript)
+/// - the operands of `&&`/`||` (`and`/`or`) are put in a stable order
+///
+/// Operands are only reordered when both sides are side-effect free
+/// (identifiers, literals and operators over them), since swapping calls or
+/// assignments changes evaluation order and short-circuiting. Member accesses
+/// count as impure too: a getter, `__getattr__` or `Deref` may run code.
+/// Ordering comparisons are left alone: `!(a < b)` is not `a >= b` for NaN.
+#[must_use]
+pub fn normalize_boolean(tree: &Rc<TreeNode>) -> Rc<TreeNode> {
+    if let Some(comparison) = negated_equality(tree) {
+        return normalize_boolean(&comparison);
+    }
+
+    let node = map_children(tree, normalize_boolean);
+
+    if BINARY_LABELS.contains(&node.label.as_str()) && node.children.len() == 3 {
+        let (left, operator, right) = (&node.children[0], &node.children[1], &node.children[2]);
+        if COMMUTATIVE_BOOLEAN_OPERATORS.contains(&operator.label.as_str())
+            && is_pure(left)
+            && is_pure(right)
+            && canonical_key(right) < canonical_key(left)
+        {
+            let mut swapped = TreeNode::new(node.label.clone(), node.value.clone(), node.id);
+            swapped.add_child(Rc::clone(right));
+            swapped.add_child(Rc::clone(operator));
+            swapped.add_child(Rc::clone(left));
+            return Rc::new(swapped);
+        }
+    }
+
+    node
+}
+
+/// Rewrite equivalent container constructions into a `container_literal` node
+/// holding the elements:
+///
+/// - Rust: `vec![a, b]`, `Vec::from([a, b])` and `[a, b].to_vec()`
+/// - Python: `[a, b]` and `list([a, b])`
+/// - JavaScript: `[a, b]`, `Array.of(a, b)` and `[...[a, b]]`
+///
+/// Rust arrays are left alone since they are not `Vec`s, and `vec!` is only
+/// rewritten when every element is a single token (its arguments are a raw
+/// token tree rather than parsed expressions).
+#[must_use]
+pub fn normalize_containers(tree: &Rc<TreeNode>) -> Rc<TreeNode> {
+    let node = map_children(tree, normalize_containers);
+
+    match container_elements(&node) {
+        Some(elements) => {
+            let mut container =
+                TreeNode::new("container_literal".to_string(), String::new(), node.id);
+            for element in elements {
+                container.add_child(element);
+            }
+            Rc::new(container)
+        }
+        None => node,
+    }
+}
+
+/// The elements of a recognized container construction
+fn container_elements(node: &TreeNode) -> Option<Vec<Rc<TreeNode>>> {
+    match node.label.as_str() {
+        "macro_invocation" => {
+            let name = node.children.first()?;
+            if name.label != "identifier" || name.value != "vec" {
+                return None;
+            }
+            let tokens = node.children.iter().find(|c| c.label == "token_tree")?;
+            single_token_elements(tokens)
+        }
+        "call_expression" | "call" => {
+            let (callee, arguments) = (node.children.first()?, node.children.get(1)?);
+            let arguments = elements_between_punctuation(arguments);
+            let path: Vec<&str> = callee
+                .children
+                .iter()
+                .filter(|c| !matches!(c.label.as_str(), "::" | "."))
+                .map(|c| c.value.as_str())
+                .collect();
+
+            match callee.label.as_str() {
+                // Vec::from([a, b])
+                "scoped_identifier" if path == ["Vec", "from"] => match arguments.as_slice() {
+                    [array] if array.label == "array_expression" => {
+                        Some(elements_between_punctuation(array))
+                    }
+                    _ => None,
+                },
+                // [a, b].to_vec()
+                "field_expression"
+                    if arguments.is_empty()
+                        && callee.children.first()?.label == "array_expression"
+                        && callee.children.last()?.value == "to_vec" =>
+                {
+                    Some(elements_between_punctuation(callee.children.first()?))
+                }
+                // Array.of(a, b)
+                "member_expression" if path == ["Array", "of"] => Some(arguments),
+                // list([a, b])
+                "identifier" if callee.value == "list" => match arguments.as_slice() {
+                    [container] if container.label == "container_literal" => {
+                        Some(container.children.clone())
+                    }
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        "list" | "array" => {
+            let mut elements = Vec::new();
+            for element in elements_between_punctuation(node) {
+                match element.children.last() {
+                    // [...[a, b]] and [*[a, b]] spread a literal in place
+                    Some(operand)
+                        if SPREAD_LABELS.contains(&element.label.as_str())
+                            && operand.label == "container_literal" =>
+                    {
+                        elements.extend(operand.children.iter().cloned());
+                    }
+                    _ => elements.push(element),
+                }
+            }
+            Some(elements)
+        }
+        _ => None,
+    }
+}
+
+/// Children of a bracketed node, without brackets and separators
+fn elements_between_punctuation(node: &TreeNode) -> Vec<Rc<TreeNode>> {
+    node.children
+        .iter()
+        .filter(|c| !CONTAINER_PUNCTUATION.contains(&c.label.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// Elements of a token tree, if each element is a single token
+fn single_token_elements(tokens: &TreeNode) -> Option<Vec<Rc<TreeNode>>> {
+    let inner = tokens.children.get(1..tokens.children.len().checked_sub(1)?)?;
+    let segments: Vec<&[Rc<TreeNode>]> = inner.split(|c| c.label == ",").collect();
+    let mut elements = Vec::new();
+    for (index, segment) in segments.iter().enumerate() {
+        match segment {
+            [element] if element.children.is_empty() => elements.push(Rc::clone(element)),
+            // Empty macro or trailing comma
+            [] if index + 1 == segments.len() => {}
+            _ => return None,
+        }
+    }
+    Some(elements)
+}
+
+/// Rewrite `!(a == b)` into `a != b` (and the reverse), if `node` has that shape
+fn negated_equality(node: &TreeNode) -> Option<Rc<TreeNode>> {
+    if !NEGATION_LABELS.contains(&node.label.as_str()) || node.children.len() != 2 {
+        return None;
+    }
+    if !matches!(node.children[0].label.as_str(), "!" | "not") {
+        return None;
+    }
+
+    let mut operand = &node.children[1];
+    while operand.label == "parenthesized_expression" {
+        operand = operand.children.iter().find(|c| !matches!(c.label.as_str(), "(" | ")"))?;
+    }
+    if !BINARY_LABELS.contains(&operand.label.as_str()) || operand.children.len() != 3 {
+        return None;
+    }
+
+    let operator = &operand.children[1];
+    let flipped = match operator.label.as_str() {
+        "==" => "!=",
+        "!=" => "==",
+        "===" => "!==",
+        "!==" => "===",
+        _ => return None,
+    };
+    let value = if operator.value.is_empty() { String::new() } else { flipped.to_string() };
+
+    let mut rewritten = TreeNode::new(operand.label.clone(), operand.value.clone(), operand.id);
+    rewritten.add_child(Rc::clone(&operand.children[0]));
+    rewritten.add_child(Rc::new(TreeNode::new(flipped.to_string(), value, operator.id)));
+    rewritten.add_child(Rc::clone(&operand.children[2]));
+    Some(Rc::new(rewritten))
+}
+
+/// Whether evaluating `node` cannot have side effects
+fn is_pure(node: &TreeNode) -> bool {
+    let label = node.label.as_str();
+    let is_token = node.children.is_empty() && !label.chars().any(char::is_alphabetic);
+    (is_token || PURE_LABELS.contains(&label)) && node.children.iter().all(|c| is_pure(c))
+}
+
+/// Stable textual key of a subtree used to order commutative operands
+fn canonical_key(node: &TreeNode) -> String {
+    let mut key = format!("({}:{}", node.label, node.value);
+    for child in &node.children {
+        key.push_str(&canonical_key(child));
+    }
+    key.push(')');
+    key
+}
+
+/// Unwind a chain of method calls, returning the receiver the chain starts
+/// from and the `(method, arguments)` stages in execution order
+#[allow(clippy::type_complexity)]
+fn collect_chain(
+    node: &Rc<TreeNode>,
+) -> Option<(Rc<TreeNode>, Vec<(String, Option<Rc<TreeNode>>)>)> {
+    let mut stages = Vec::new();
+    let mut current = Rc::clone(node);
+
+    while CALL_LABELS.contains(&current.label.as_str()) {
+        let Some(callee) = current.children.first() else {
+            break;
+        };
+
+        // `iter.collect::<Vec<_>>()` wraps the member access in a generic_function
+        let member = if callee.label == "generic_function" {
+            match callee.children.first() {
+                Some(inner) => inner,
+                None => break,
+            }
+        } else {
+            callee
+        };
+
+        if !MEMBER_LABELS.contains(&member.label.as_str()) || member.children.len() < 2 {
+            break;
+        }
+
+        let method = member.children.last().map(|n| n.value.clone()).unwrap_or_default();
+        let arguments = current.children.get(1).cloned();
+        stages.push((method, arguments));
+
+        let receiver = Rc::clone(&member.children[0]);
+        current = receiver;
+    }
+
+    if stages.is_empty() {
+        return None;
+    }
+
+    stages.reverse();
+    Some((current, stages))
+}
+
+/// Drop the static text of literals so that only their kind and the code
+/// embedded in them are compared.
+///
+/// Numbers and the text of strings (quotes, raw-string prefixes, escapes and
+/// content) are discarded, but the interpolated expressions of f-strings and
+/// template literals are kept as children of the literal: `f"Hi {user.name}"`
+/// and `f"Bye {user.name}!"` become equal, `f"Hi {user.name}"` and
+/// `f"Hi {user.id}"` do not.
+#[must_use]
+pub fn normalize_literals(tree: &Rc<TreeNode>) -> Rc<TreeNode> {
+    let label = tree.label.as_str();
+    if STRING_LITERAL_LABELS.contains(&label) {
+        let mut literal = TreeNode::new(tree.label.clone(), String::new(), tree.id);
+        for child in &tree.children {
+            if INTERPOLATION_LABELS.contains(&child.label.as_str()) {
+                literal.add_child(map_children(child, normalize_literals));
+            }
+        }
+        return Rc::new(literal);
+    }
+    if NUMBER_LITERAL_LABELS.contains(&label) {
+        return Rc::new(TreeNode::new(tree.label.clone(), String::new(), tree.id));
+    }
+
+    map_children(tree, normalize_literals)
+}
+
+/// Replace every type name leaf with a `type_placeholder` node, so that
+/// functions differing only in the concrete types they work on compare equal.
+///
+/// Unlike identifier normalization, only type positions are affected: variable,
+/// field and function names are kept.
+#[must_use]
+pub fn normalize_type_names(tree: &Rc<TreeNode>) -> Rc<TreeNode> {
+    if tree.children.is_empty() && TYPE_NAME_LABELS.contains(&tree.label.as_str()) {
+        return Rc::new(TreeNode::new("type_placeholder".to_string(), String::new(), tree.id));
+    }
+
+    map_children(tree, normalize_type_names)
+}
+
+/// Rewrite the spellings of primitive types (`i32`, `number`, `int`,
+/// `String`, `string`, `str`, ...) to one `primitive_type` leaf per kind of
+/// value, so that a Rust and a TypeScript or Python function differing only in
+/// how their languages name numbers and strings compare equal.
+///
+/// Other type names are left alone.
+#[must_use]
+pub fn normalize_primitive_types(tree: &Rc<TreeNode>) -> Rc<TreeNode> {
+    fn visit(node: &Rc<TreeNode>, in_annotation: bool) -> Rc<TreeNode> {
+        let label = node.label.as_str();
+        // Plain identifiers are only type names directly inside an annotation
+        let is_type_name =
+            TYPE_NAME_LABELS.contains(&label) || (in_annotation && label == "identifier");
+        if node.children.is_empty() {
+            // TypeScript trees carry the spelling in the label of a `TSType` leaf
+            let token = if node.value == "TSType" {
+                primitive_type_token(&node.label)
+            } else {
+                primitive_type_token(&node.value).filter(|_| is_type_name)
+            };
+            return match token {
+                Some(token) => {
+                    Rc::new(TreeNode::new("primitive_type".to_string(), token.to_string(), node.id))
+                }
+                None => Rc::clone(node),
+            };
+        }
+
+        let annotation = TYPE_ANNOTATION_LABELS.contains(&label);
+        let mut rebuilt = TreeNode::new(node.label.clone(), node.value.clone(), node.id);
+        for child in &node.children {
+            rebuilt.add_child(visit(child, annotation));
+        }
+        Rc::new(rebuilt)
+    }
+
+    visit(tree, false)
+}
+
+fn primitive_type_token(spelling: &str) -> Option<&'static str> {
+    PRIMITIVE_TYPE_TOKENS.iter().find(|(name, _)| *name == spelling).map(|(_, token)| *token)
+}
+
+/// Clear the names of identifier leaves (variables, fields, functions and
+/// types), so that trees compare on their structure alone even when values are
+/// compared.
+#[must_use]
+pub fn normalize_identifiers(tree: &Rc<TreeNode>) -> Rc<TreeNode> {
+    if tree.children.is_empty() && LeafCategory::of(&tree.label) == LeafCategory::Identifier {
+        return Rc::new(TreeNode::new(tree.label.clone(), String::new(), tree.id));
+    }
+
+    map_children(tree, normalize_identifiers)
+}
+
+/// Rewrite identifier names in `snake_case`, so that the `camelCase`,
+/// `PascalCase` and `kebab-case` spellings of a name compare equal.
+///
+/// Tree-sitter trees carry names in the value of identifier leaves; the oxc
+/// tree carries them in the label of the named node.
+#[must_use]
+pub fn normalize_identifier_case(tree: &Rc<TreeNode>) -> Rc<TreeNode> {
+    if OXC_NAMED_KINDS.contains(&tree.value.as_str()) {
+        let mut rebuilt = TreeNode::new(snake_case(&tree.label), tree.value.clone(), tree.id);
+        for child in &tree.children {
+            rebuilt.add_child(normalize_identifier_case(child));
+        }
+        return Rc::new(rebuilt);
+    }
+    if tree.children.is_empty() && LeafCategory::of(&tree.label) == LeafCategory::Identifier {
+        return Rc::new(TreeNode::new(tree.label.clone(), snake_case(&tree.value), tree.id));
+    }
+
+    map_children(tree, normalize_identifier_case)
+}
+
+/// Lowercase words joined by `_`. A word starts at an uppercase letter after
+/// a lowercase letter or digit, or at the last capital of an acronym
+/// (`HTTPServer` is `http_server`).
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = String::with_capacity(name.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if !words.is_empty() && !words.ends_with('_') {
+                words.push('_');
+            }
+            continue;
+        }
+        if c.is_uppercase() && i > 0 {
+            let previous = chars[i - 1];
+            let next_is_lowercase = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            let word_start = previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lowercase);
+            if word_start && !words.is_empty() && !words.ends_with('_') {
+                words.push('_');
+            }
+        }
+        words.extend(c.to_lowercase());
+    }
+    words.trim_end_matches('_').to_string()
+}
+
+/// Drop the receiver from the parameter list of methods: Rust's `&self`,
+/// `&mut self`, `self` and `self: Box<Self>`, or a leading Python `self`/`cls`,
+/// together with the comma after it.
+///
+/// Only the first parameter is considered, so a later parameter that happens to
+/// be named `cls` is kept.
+#[must_use]
+pub fn strip_receiver(tree: &Rc<TreeNode>) -> Rc<TreeNode> {
+    if !PARAMETER_LIST_LABELS.contains(&tree.label.as_str()) {
+        return map_children(tree, strip_receiver);
+    }
+
+    let mut parameters = TreeNode::new(tree.label.clone(), tree.value.clone(), tree.id);
+    let first_parameter = tree.children.iter().position(|child| child.label != "(");
+    let mut after_receiver = false;
+    for (index, child) in tree.children.iter().enumerate() {
+        if Some(index) == first_parameter && is_receiver(child) {
+            after_receiver = true;
+            continue;
+        }
+        if std::mem::take(&mut after_receiver) && child.label == "," {
+            continue;
+        }
+        parameters.add_child(map_children(child, strip_receiver));
+    }
+    Rc::new(parameters)
+}
+
+fn is_receiver(node: &TreeNode) -> bool {
+    RECEIVER_LABELS.contains(&node.label.as_str())
+        || (node.label == "identifier" && RECEIVER_NAMES.contains(&node.value.as_str()))
+        || (TYPED_PARAMETER_LABELS.contains(&node.label.as_str())
+            && node.children.first().is_some_and(|first| is_receiver(first)))
+}
+
+/// Rebuild `node` with each child passed through `f`
+fn map_children(node: &Rc<TreeNode>, f: fn(&Rc<TreeNode>) -> Rc<TreeNode>) -> Rc<TreeNode> {
+    if node.children.is_empty() {
+        return Rc::clone(node);
+    }
+
+    let mut rebuilt = TreeNode::new(node.label.clone(), node.value.clone(), node.id);
+    for child in &node.children {
+        rebuilt.add_child(f(child));
+    }
+    Rc::new(rebuilt)
+}
+
+/// Assign fresh pre-order ids so that nodes created by the passes stay unique,
+/// which APTED relies on for memoization
+fn renumber(tree: &Rc<TreeNode>) -> Rc<TreeNode> {
+    fn visit(node: &TreeNode, id_counter: &mut usize) -> Rc<TreeNode> {
+        let mut rebuilt = TreeNode::new(node.label.clone(), node.value.clone(), *id_counter);
+        *id_counter += 1;
+        for child in &node.children {
+            rebuilt.add_child(visit(child, id_counter));
+        }
+        Rc::new(rebuilt)
+    }
+
+    let mut id_counter = 0;
+    visit(tree, &mut id_counter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(label: &str, value: &str, children: Vec<Rc<TreeNode>>) -> Rc<TreeNode> {
+        let mut n = TreeNode::new(label.to_string(), value.to_string(), 0);
+        for child in children {
+            n.add_child(child);
+        }
+        Rc::new(n)
+    }
+
+    /// Build `receiver.method(args)` the way the Rust grammar does
+    fn method_call(receiver: Rc<TreeNode>, method: &str) -> Rc<TreeNode> {
+        let member = node(
+            "field_expression",
+            "",
+            vec![receiver, node(".", "", vec![]), node("field_identifier", method, vec![])],
+        );
+        node("call_expression", "", vec![member, node("arguments", "", vec![])])
+    }
+
+    fn labels(tree: &TreeNode) -> Vec<String> {
+        tree.children.iter().map(|c| format!("{}:{}", c.label, c.value)).collect()
+    }
+
+    #[test]
+    fn test_chain_is_flattened_in_execution_order() {
+        let chain = method_call(
+            method_call(method_call(node("identifier", "xs", vec![]), "iter"), "map"),
+            "collect",
+        );
+
+        let normalized = normalize_pipelines(&chain);
+        assert_eq!(normalized.label, "pipeline");
+        assert_eq!(
+            labels(&normalized),
+            vec![
+                "identifier:xs",
+                "pipeline_stage:iter",
+                "pipeline_stage:map",
+                "pipeline_stage:collect"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_non_pipeline_chain_is_kept() {
+        let chain = method_call(method_call(node("identifier", "s", vec![]), "trim"), "len");
+        let normalized = normalize_pipelines(&chain);
+        assert_eq!(normalized.label, "call_expression");
+    }
+
+    fn binary(left: Rc<TreeNode>, operator: &str, right: Rc<TreeNode>) -> Rc<TreeNode> {
+        node("binary_expression", "", vec![left, node(operator, operator, vec![]), right])
+    }
+
+    fn ident(name: &str) -> Rc<TreeNode> {
+        node("identifier", name, vec![])
+    }
+
+    #[test]
+    fn test_negated_equality_becomes_inequality() {
+        let negated = node(
+            "unary_expression",
+            "",
+            vec![
+                node("!", "!", vec![]),
+                node(
+                    "parenthesized_expression",
+                    "",
+                    vec![
+                        node("(", "", vec![]),
+                        binary(ident("a"), "==", ident("b")),
+                        node(")", "", vec![]),
+                    ],
+                ),
+            ],
+        );
+
+        let normalized = normalize_boolean(&negated);
+        assert_eq!(normalized.label, "binary_expression");
+        assert_eq!(labels(&normalized), vec!["identifier:a", "!=:!=", "identifier:b"]);
+    }
+
+    #[test]
+    fn test_pure_operands_are_ordered() {
+        let ab = normalize_boolean(&binary(ident("a"), "&&", ident("b")));
+        let ba = normalize_boolean(&binary(ident("b"), "&&", ident("a")));
+        assert_eq!(canonical_key(&ab), canonical_key(&ba));
+    }
+
+    #[test]
+    fn test_operands_with_calls_keep_their_order() {
+        let call = node("call_expression", "", vec![ident("check"), node("arguments", "", vec![])]);
+        let expr = binary(ident("b"), "&&", call);
+        let normalized = normalize_boolean(&expr);
+        assert_eq!(normalized.children[0].value, "b");
+    }
+
+    #[test]
+    fn test_operands_with_member_access_keep_their_order() {
+        let member = |label: &str| {
+            node(label, "", vec![ident("user"), node(".", "", vec![]), ident("active")])
+        };
+        for label in ["member_expression", "field_expression", "attribute"] {
+            let normalized = normalize_boolean(&binary(member(label), "&&", ident("a")));
+            assert_eq!(normalized.children[0].label, label, "{label} operand was reordered");
+        }
+    }
+
+    #[test]
+    fn test_vec_macro_and_vec_from_become_the_same_container() {
+        let token = |label: &str, value: &str| node(label, value, vec![]);
+        let vec_macro = node(
+            "macro_invocation",
+            "",
+            vec![
+                token("identifier", "vec"),
+                token("!", ""),
+                node(
+                    "token_tree",
+                    "",
+                    vec![
+                        token("[", ""),
+                        token("integer_literal", "1"),
+                        token(",", ""),
+                        token("integer_literal", "2"),
+                        token("]", ""),
+                    ],
+                ),
+            ],
+        );
+        let vec_from = node(
+            "call_expression",
+            "",
+            vec![
+                node(
+                    "scoped_identifier",
+                    "",
+                    vec![token("identifier", "Vec"), token("::", ""), token("identifier", "from")],
+                ),
+                node(
+                    "arguments",
+                    "",
+                    vec![
+                        token("(", ""),
+                        node(
+                            "array_expression",
+                            "",
+                            vec![
+                                token("[", ""),
+                                token("integer_literal", "1"),
+                                token(",", ""),
+                                token("integer_literal", "2"),
+                                token("]", ""),
+                            ],
+                        ),
+                        token(")", ""),
+                    ],
+                ),
+            ],
+        );
+
+        let from_macro = normalize_containers(&vec_macro);
+        assert_eq!(from_macro.label, "container_literal");
+        assert_eq!(labels(&from_macro), vec!["integer_literal:1", "integer_literal:2"]);
+        assert_eq!(canonical_key(&from_macro), canonical_key(&normalize_containers(&vec_from)));
+    }
+
+    #[test]
+    fn test_vec_macro_with_expressions_is_kept() {
+        let token = |label: &str, value: &str| node(label, value, vec![]);
+        let vec_macro = node(
+            "macro_invocation",
+            "",
+            vec![
+                token("identifier", "vec"),
+                token("!", ""),
+                node(
+                    "token_tree",
+                    "",
+                    vec![
+                        token("[", ""),
+                        token("identifier", "a"),
+                        token("+", "+"),
+                        token("integer_literal", "1"),
+                        token("]", ""),
+                    ],
+                ),
+            ],
+        );
+        assert_eq!(normalize_containers(&vec_macro).label, "macro_invocation");
+    }
+
+    #[test]
+    fn test_normalized_ids_are_unique() {
+        let chain = method_call(method_call(node("identifier", "xs", vec![]), "iter"), "map");
+        let options = TSEDOptions { normalize_pipelines: true, ..Default::default() };
+        let normalized = normalize_tree(&chain, &options);
+
+        let mut ids = Vec::new();
+        let mut stack = vec![normalized];
+        while let Some(n) = stack.pop() {
+            ids.push(n.id);
+            stack.extend(n.children.iter().cloned());
+        }
+        let count = ids.len();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), count);
+    }
+
+    #[test]
+    fn test_literal_text_is_dropped_but_interpolations_kept() {
+        let interpolation = |name: &str| {
+            node(
+                "interpolation",
+                "",
+                vec![
+                    node("{", "", vec![]),
+                    node("identifier", name, vec![]),
+                    node("}", "", vec![]),
+                ],
+            )
+        };
+        let fstring = node(
+            "string",
+            "f\"Hi {user}!\"",
+            vec![
+                node("string_start", "", vec![]),
+                node("string_content", "", vec![]),
+                interpolation("user"),
+                node("string_content", "", vec![]),
+                node("string_end", "", vec![]),
+            ],
+        );
+
+        let normalized = normalize_literals(&fstring);
+        assert_eq!(normalized.value, "");
+        assert_eq!(labels(&normalized), vec!["interpolation:"]);
+        assert_eq!(labels(&normalized.children[0]), vec!["{:", "identifier:user", "}:"]);
+
+        let number = normalize_literals(&node("integer_literal", "42", vec![]));
+        assert_eq!(number.value, "");
+    }
+
+    #[test]
+    fn test_type_names_become_placeholders() {
+        let declaration = node(
+            "let_declaration",
+            "",
+            vec![
+                node("identifier", "total", vec![]),
+                node("primitive_type", "i32", vec![]),
+                node(
+                    "generic_type",
+                    "",
+                    vec![
+                        node("type_identifier", "Vec", vec![]),
+                        node("type_arguments", "", vec![node("type_identifier", "Item", vec![])]),
+                    ],
+                ),
+            ],
+        );
+
+        let normalized = normalize_type_names(&declaration);
+        assert_eq!(
+            labels(&normalized),
+            vec!["identifier:total", "type_placeholder:", "generic_type:"]
+        );
+        assert_eq!(labels(&normalized.children[2]), vec!["type_placeholder:", "type_arguments:"]);
+    }
+
+    #[test]
+    fn test_receiver_and_its_comma_are_stripped() {
+        let parameters = |first: Rc<TreeNode>| {
+            node(
+                "parameters",
+                "",
+                vec![
+                    node("(", "", vec![]),
+                    first,
+                    node(",", "", vec![]),
+                    node("identifier", "cls", vec![]),
+                    node(")", "", vec![]),
+                ],
+            )
+        };
+        let ref_self =
+            node("self_parameter", "", vec![node("&", "", vec![]), node("self", "", vec![])]);
+        let python_self = node("identifier", "self", vec![]);
+        let plain = node("identifier", "value", vec![]);
+
+        let expected = vec!["(:", "identifier:cls", "):"];
+        assert_eq!(labels(&strip_receiver(&parameters(ref_self))), expected);
+        assert_eq!(labels(&strip_receiver(&parameters(python_self))), expected);
+        // Not a receiver: a later `cls` parameter is kept too
+        assert_eq!(
+            labels(&strip_receiver(&parameters(plain))),
+            vec!["(:", "identifier:value", ",:", "identifier:cls", "):"]
+        );
+    }
+
+    #[test]
+    fn test_awaited_binding_folds_the_rest_of_the_block() {
+        let token = |label: &str| node(label, "", vec![]);
+        let call = node("call", "", vec![ident("fetch"), node("argument_list", "", vec![])]);
+        // Python: `user = await fetch()` followed by `return user`
+        let binding = node(
+            "expression_statement",
+            "",
+            vec![node(
+                "assignment",
+                "",
+                vec![ident("user"), token("="), node("await", "", vec![token("await"), call])],
+            )],
+        );
+        let ret = node("return_statement", "", vec![token("return"), ident("user")]);
+        let block = node("block", "", vec![binding.clone(), ret.clone()]);
+
+        let normalized = normalize_async(&block);
+        assert_eq!(labels(&normalized), vec!["async_continuation:"]);
+        let continuation = &normalized.children[0];
+        assert_eq!(labels(continuation), vec!["await_binding:", "return_statement:"]);
+        assert_eq!(labels(&continuation.children[0]), vec!["identifier:user", "awaited:"]);
+
+        // The last statement has nothing to continue with
+        let normalized = normalize_async(&node("block", "", vec![ret, binding]));
+        assert_eq!(labels(&normalized), vec!["return_statement:", "expression_statement:"]);
+    }
+
+    #[test]
+    fn test_primitive_type_spellings_become_one_token() {
+        let annotation = node("type", "", vec![ident("int")]);
+        let parameter = node("typed_parameter", "", vec![ident("str"), annotation]);
+        let tree = node(
+            "parameters",
+            "",
+            vec![
+                parameter,
+                node("primitive_type", "i32", vec![]),
+                node("predefined_type", "string", vec![]),
+                node("type_identifier", "User", vec![]),
+            ],
+        );
+
+        let normalized = normalize_primitive_types(&tree);
+        assert_eq!(
+            labels(&normalized),
+            vec![
+                "typed_parameter:",
+                "primitive_type:number",
+                "primitive_type:string",
+                "type_identifier:User"
+            ]
+        );
+        // A variable named like a type is kept; only the annotation changes
+        assert_eq!(labels(&normalized.children[0]), vec!["identifier:str", "type:"]);
+        assert_eq!(labels(&normalized.children[0].children[1]), vec!["primitive_type:number"]);
+    }
+
+    #[test]
+    fn test_identifier_case_is_normalized_to_snake_case() {
+        for (name, expected) in [
+            ("getUser", "get_user"),
+            ("GetUser", "get_user"),
+            ("get_user", "get_user"),
+            ("get-user", "get_user"),
+            ("HTTPServer", "http_server"),
+            ("parseJSON", "parse_json"),
+            ("utf8Decode", "utf8_decode"),
+            ("MAX_SIZE", "max_size"),
+            ("_unused", "unused"),
+        ] {
+            assert_eq!(snake_case(name), expected, "{name}");
+        }
+
+        // Only identifier leaves are rewritten
+        let tree =
+            node("call_expression", "", vec![ident("getUser"), node("string", "getUser", vec![])]);
+        assert_eq!(
+            labels(&normalize_identifier_case(&tree)),
+            vec!["identifier:get_user", "string:getUser"]
+        );
+    }
+
+    #[test]
+    fn test_cross_language_functions_differing_in_identifier_case_match() {
+        // `fn get_user(user_id: u64) { find_user_by_id(user_id) }` against
+        // `function getUser(userId: number) { findUserById(userId) }`
+        let function = |name: &str, parameter: &str, callee: &str| {
+            node(
+                "function",
+                "",
+                vec![
+                    ident(name),
+                    node("parameters", "", vec![ident(parameter)]),
+                    node(
+                        "block",
+                        "",
+                        vec![node(
+                            "call_expression",
+                            "",
+                            vec![ident(callee), node("arguments", "", vec![ident(parameter)])],
+                        )],
+                    ),
+                ],
+            )
+        };
+        let rust = renumber(&function("get_user", "user_id", "find_user_by_id"));
+        let typescript = renumber(&function("getUser", "userId", "findUserById"));
+
+        let mut options = TSEDOptions { size_penalty: false, ..TSEDOptions::default() };
+        options.apted_options.compare_values = true;
+        assert!(crate::tsed::calculate_tsed(&rust, &typescript, &options) < 0.9);
+        options.normalize_identifier_case = true;
+        let similarity = crate::tsed::calculate_tsed(&rust, &typescript, &options);
+        assert!((similarity - 1.0).abs() < f64::EPSILON, "got {similarity}");
+    }
+
+    #[test]
+    fn test_typescript_names_are_normalized_to_snake_case() {
+        let parse = |source: &str| {
+            crate::parser::parse_and_convert_to_tree("test.ts", source).expect("valid TypeScript")
+        };
+        let camel = parse("function getUser(userId: number) { return findUserById(userId); }");
+        let snake =
+            parse("function get_user(user_id: number) { return find_user_by_id(user_id); }");
+
+        let mut options = TSEDOptions { size_penalty: false, ..TSEDOptions::default() };
+        assert!(crate::tsed::calculate_tsed(&camel, &snake, &options) < 1.0);
+
+        options.normalize_identifier_case = true;
+        let similarity = crate::tsed::calculate_tsed(&camel, &snake, &options);
+        assert!((similarity - 1.0).abs() < f64::EPSILON, "got {similarity}");
+        let function = &normalize_identifier_case(&camel).children[0];
+        assert_eq!(function.label, "get_user");
+        assert_eq!(labels(function)[0], "user_id:Parameter");
+    }
+
+    #[test]
+    fn test_cross_language_functions_differing_in_type_spellings_match() {
+        let token = |label: &str| node(label, "", vec![]);
+        // `fn greet(name: String, times: i32) -> String` against
+        // `function greet(name: string, times: number): string`
+        let function = |type_label: &str, string: &str, number: &str| {
+            let parameter = |name: &str, spelling: &str| {
+                node(
+                    "parameter",
+                    "",
+                    vec![ident(name), token(":"), node(type_label, spelling, vec![])],
+                )
+            };
+            let call = node(
+                "call_expression",
+                "",
+                vec![ident("repeat"), node("arguments", "", vec![ident("name"), ident("times")])],
+            );
+            node(
+                "function",
+                "",
+                vec![
+                    ident("greet"),
+                    node(
+                        "parameters",
+                        "",
+                        vec![parameter("name", string), parameter("times", number)],
+                    ),
+                    node(type_label, string, vec![]),
+                    node(
+                        "block",
+                        "",
+                        vec![node("return_statement", "", vec![token("return"), call])],
+                    ),
+                ],
+            )
+        };
+        // APTED memoizes by node id
+        let rust = renumber(&function("primitive_type", "String", "i32"));
+        let typescript = renumber(&function("predefined_type", "string", "number"));
+
+        let mut options = TSEDOptions { size_penalty: false, ..TSEDOptions::default() };
+        options.apted_options.compare_values = true;
+        // Without the mapping every type leaf is a rename
+        let spelled = crate::tsed::calculate_tsed(&rust, &typescript, &options);
+        options.normalize_primitive_types = true;
+        let similarity = crate::tsed::calculate_tsed(&rust, &typescript, &options);
+        assert!((similarity - 1.0).abs() < f64::EPSILON, "got {similarity}");
+        assert!(spelled < similarity);
+    }
+
+    #[test]
+    fn test_typescript_primitive_annotations_are_normalized() {
+        let parse = |source: &str| {
+            crate::parser::parse_and_convert_to_tree("test.ts", source).expect("valid TypeScript")
+        };
+        let numbers = parse("function scale(x: number): number { return x * 2; }");
+        let bigints = parse("function scale(x: bigint): bigint { return x * 2; }");
+        let strings = parse("function scale(x: string): string { return x * 2; }");
+
+        let mut options = TSEDOptions { size_penalty: false, ..TSEDOptions::default() };
+        options.apted_options.compare_values = true;
+        assert!(crate::tsed::calculate_tsed(&numbers, &bigints, &options) < 1.0);
+
+        options.normalize_primitive_types = true;
+        let same_kind = crate::tsed::calculate_tsed(&numbers, &bigints, &options);
+        assert!((same_kind - 1.0).abs() < f64::EPSILON, "got {same_kind}");
+        // Different kinds of value still differ
+        assert!(crate::tsed::calculate_tsed(&numbers, &strings, &options) < 1.0);
+    }
+}
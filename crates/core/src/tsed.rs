@@ -1,14 +1,54 @@
-use crate::apted::{compute_edit_distance, APTEDOptions};
+use crate::apted::{
+    compute_edit_distance, compute_edit_distance_bounded, APTEDOptions, LabelRenameCosts,
+    LeafRenameCosts,
+};
+use crate::cli_parallel::SimilarityResult;
 use crate::tree::TreeNode;
+use crate::tree_normalizer::{normalize_identifiers, normalize_tree};
+use std::collections::HashMap;
 use std::rc::Rc;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TSEDOptions {
     pub apted_options: APTEDOptions,
     pub min_lines: u32, // Minimum number of lines for a function to be considered
     pub min_tokens: Option<u32>, // Minimum number of tokens (AST nodes) for a function to be considered
     pub size_penalty: bool,      // Apply penalty for short functions
     pub skip_test: bool,         // Skip test functions (language-specific)
+    /// Flatten iterator/stream method chains into ordered pipeline stages before comparing
+    pub normalize_pipelines: bool,
+    /// Experimental: canonicalize equivalent boolean forms (`!(a == b)` vs `a != b`,
+    /// operand order of `&&`/`||`). Operands are only reordered when side-effect free.
+    pub normalize_boolean: bool,
+    /// Treat equivalent container constructions (`vec![..]`, `Vec::from([..])`, ...) as equal
+    pub normalize_containers: bool,
+    /// Ignore the text of literals while still comparing code interpolated into strings
+    pub ignore_literals: bool,
+    /// Keep the receiver (`&self`, `&mut self`, Python's `self`) in method trees.
+    /// When off, it is dropped so that methods with different receivers, and
+    /// methods and free functions, are compared on their remaining parameters.
+    pub include_receiver_in_tree: bool,
+    /// Compare `await` and equivalent single-callback `.then()` chains in one
+    /// desugared form (see [`crate::tree_normalizer::normalize_async`])
+    pub normalize_async: bool,
+    /// Compare the spellings of primitive types across languages (`i32`,
+    /// `number`, `int`; `String`, `string`, `str`) as one token per kind
+    pub normalize_primitive_types: bool,
+    /// Compare identifier names in one casing, lowercase words joined by `_`,
+    /// so that `getUser` and `get_user` match
+    pub normalize_identifier_case: bool,
+    /// Blend factor in [0, 1] between a pure-structure score, computed with
+    /// identifier names ignored, and the name-sensitive score:
+    /// `w * structure + (1 - w) * named`. Only matters when values are compared.
+    pub structure_weight: f64,
+    /// Skip pairs whose tree sizes alone rule out reaching the threshold
+    /// before running APTED (see [`max_similarity_of_sizes`]). The results are
+    /// the same either way; turning it off always computes the full distance.
+    pub size_prefilter: bool,
+    /// Leave out functions whose parse needed error recovery (tree-sitter
+    /// `ERROR` or `MISSING` nodes), as their comparisons are unreliable
+    pub skip_parse_errors: bool,
 }
 
 impl Default for TSEDOptions {
@@ -19,11 +59,24 @@ impl Default for TSEDOptions {
                 delete_cost: 1.0,
                 insert_cost: 1.0,
                 compare_values: false, // TypeScript default: structural comparison only
+                leaf_rename_costs: LeafRenameCosts::default(),
+                label_rename_costs: LabelRenameCosts::default(),
             },
             min_lines: 5,       // Increased default to better filter trivial matches
             min_tokens: None,   // No token limit by default
             size_penalty: true, // Enable size penalty by default
             skip_test: false,   // Don't skip test functions by default
+            normalize_pipelines: false,
+            normalize_boolean: false,
+            normalize_containers: false,
+            ignore_literals: false,
+            include_receiver_in_tree: true,
+            normalize_async: false,
+            normalize_primitive_types: false,
+            normalize_identifier_case: false,
+            structure_weight: 0.0,
+            size_prefilter: true,
+            skip_parse_errors: false,
         }
     }
 }
@@ -31,13 +84,216 @@ impl Default for TSEDOptions {
 /// Calculate TSED (Tree Structure Edit Distance) similarity between two trees
 /// Returns a value between 0.0 and 1.0, where 1.0 means identical
 #[must_use]
-#[allow(clippy::cast_precision_loss)]
 pub fn calculate_tsed(tree1: &Rc<TreeNode>, tree2: &Rc<TreeNode>, options: &TSEDOptions) -> f64 {
+    let tree1 = &normalize_tree(tree1, options);
+    let tree2 = &normalize_tree(tree2, options);
+    tsed_of_normalized(tree1, tree2, options)
+}
+
+/// TSED similarity of two trees, or `None` when it is below `threshold`.
+///
+/// Equal to [`calculate_tsed`] whenever it reaches the threshold, but the
+/// threshold caps the edit distance worth computing, so distant pairs are
+/// given up on early.
+#[must_use]
+pub fn calculate_tsed_bounded(
+    tree1: &Rc<TreeNode>,
+    tree2: &Rc<TreeNode>,
+    options: &TSEDOptions,
+    threshold: f64,
+) -> Option<f64> {
+    let tree1 = &normalize_tree(tree1, options);
+    let tree2 = &normalize_tree(tree2, options);
+    tsed_of_normalized_bounded(tree1, tree2, options, threshold)
+}
+
+/// Hash under which trees that are exact duplicates for `options` collide:
+/// the structural hash of the normalized tree, with the values only when
+/// they are compared. Trees with different keys are never exact duplicates.
+#[must_use]
+pub fn exact_match_key(tree: &Rc<TreeNode>, options: &TSEDOptions) -> u64 {
+    key_of_normalized(&normalize_tree(tree, options), options)
+}
+
+fn key_of_normalized(tree: &TreeNode, options: &TSEDOptions) -> u64 {
+    if options.apted_options.compare_values {
+        tree.structural_hash()
+    } else {
+        tree.shape_hash()
+    }
+}
+
+/// Score of a tree against an exact duplicate of itself
+#[allow(clippy::cast_precision_loss)]
+fn exact_duplicate_score(tree: &TreeNode, options: &TSEDOptions) -> f64 {
+    let size = tree.get_subtree_size() as f64;
+    // Identifiers of equal trees are equal too, so the blend is the same
+    score_of_distance(0.0, size, size, options)
+}
+
+/// [`calculate_tsed_bounded`] for trees keyed by [`exact_match_key`].
+///
+/// Exact duplicates, which share a key, are at distance 0 and are scored
+/// without running APTED; this is where large clone clusters spend most of
+/// their time otherwise.
+#[must_use]
+pub fn calculate_tsed_keyed(
+    (tree1, key1): (&Rc<TreeNode>, u64),
+    (tree2, key2): (&Rc<TreeNode>, u64),
+    options: &TSEDOptions,
+    threshold: f64,
+) -> Option<f64> {
+    let tree1 = &normalize_tree(tree1, options);
+    let tree2 = &normalize_tree(tree2, options);
+    // Equal keys may still collide, so the trees are compared node by node
+    if key1 == key2 && tree1.same_structure(tree2, options.apted_options.compare_values) {
+        let similarity = exact_duplicate_score(tree1, options);
+        return (similarity >= threshold).then_some(similarity);
+    }
+    tsed_of_normalized_bounded(tree1, tree2, options, threshold)
+}
+
+/// Trees compared pairwise, such as the functions of a file.
+///
+/// Each tree is normalized once and bucketed by its [`exact_match_key`], and
+/// the trees of a bucket are confirmed equal node by node once, so pairs of
+/// exact duplicates are scored without normalizing again or running APTED.
+/// The similarities are the same as those of [`calculate_tsed_bounded`].
+pub struct KeyedTrees<'a> {
+    trees: Vec<Rc<TreeNode>>,
+    /// Index of the first tree each tree is an exact duplicate of
+    duplicate_of: Vec<usize>,
+    options: &'a TSEDOptions,
+}
+
+impl<'a> KeyedTrees<'a> {
+    #[must_use]
+    pub fn new(trees: &[Rc<TreeNode>], options: &'a TSEDOptions) -> Self {
+        let trees: Vec<Rc<TreeNode>> =
+            trees.iter().map(|tree| normalize_tree(tree, options)).collect();
+        let compare_values = options.apted_options.compare_values;
+
+        let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+        let mut duplicate_of = Vec::with_capacity(trees.len());
+        for (i, tree) in trees.iter().enumerate() {
+            let bucket = buckets.entry(key_of_normalized(tree, options)).or_default();
+            // Equal keys may still collide, so each bucket keeps one tree per structure
+            let first = bucket
+                .iter()
+                .copied()
+                .find(|&first| trees[first].same_structure(tree, compare_values));
+            duplicate_of.push(first.unwrap_or_else(|| {
+                bucket.push(i);
+                i
+            }));
+        }
+
+        Self { trees, duplicate_of, options }
+    }
+
+    /// Whether trees `i` and `j` are exact duplicates of each other
+    #[must_use]
+    pub fn are_duplicates(&self, i: usize, j: usize) -> bool {
+        self.duplicate_of[i] == self.duplicate_of[j]
+    }
+
+    /// Similarity of trees `i` and `j` when it reaches `threshold`
+    #[must_use]
+    pub fn similarity(&self, i: usize, j: usize, threshold: f64) -> Option<f64> {
+        if self.are_duplicates(i, j) {
+            let similarity = exact_duplicate_score(&self.trees[i], self.options);
+            return (similarity >= threshold).then_some(similarity);
+        }
+        tsed_of_normalized_bounded(&self.trees[i], &self.trees[j], self.options, threshold)
+    }
+}
+
+/// [`tsed_of_normalized`] when it reaches `threshold`. Each blended score is
+/// at most 1.0, which bounds how low the other may go.
+fn tsed_of_normalized_bounded(
+    tree1: &Rc<TreeNode>,
+    tree2: &Rc<TreeNode>,
+    options: &TSEDOptions,
+    threshold: f64,
+) -> Option<f64> {
+    if options.size_prefilter {
+        let bound =
+            max_similarity_of_sizes(tree1.get_subtree_size(), tree2.get_subtree_size(), options);
+        if bound < threshold {
+            return None;
+        }
+    }
+
+    let weight = options.structure_weight.clamp(0.0, 1.0);
+    let named = if weight < 1.0 {
+        tsed_score_bounded(tree1, tree2, options, (threshold - weight) / (1.0 - weight))?
+    } else {
+        0.0
+    };
+    let similarity = if weight <= 0.0 {
+        named
+    } else {
+        let structure = tsed_score_bounded(
+            &normalize_identifiers(tree1),
+            &normalize_identifiers(tree2),
+            options,
+            (threshold - (1.0 - weight)) / weight,
+        )?;
+        weight * structure + (1.0 - weight) * named
+    };
+    (similarity >= threshold).then_some(similarity)
+}
+
+/// TSED similarity of two trees the normalization passes were already applied
+/// to, blending in the structure-only score when `structure_weight` asks for it
+fn tsed_of_normalized(tree1: &Rc<TreeNode>, tree2: &Rc<TreeNode>, options: &TSEDOptions) -> f64 {
+    let weight = options.structure_weight.clamp(0.0, 1.0);
+    let named = if weight < 1.0 { tsed_score(tree1, tree2, options) } else { 0.0 };
+    if weight <= 0.0 {
+        return named;
+    }
+
+    let structure =
+        tsed_score(&normalize_identifiers(tree1), &normalize_identifiers(tree2), options);
+    weight * structure + (1.0 - weight) * named
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn tsed_score(tree1: &Rc<TreeNode>, tree2: &Rc<TreeNode>, options: &TSEDOptions) -> f64 {
     let distance = compute_edit_distance(tree1, tree2, &options.apted_options);
+    score_of_distance(
+        distance,
+        tree1.get_subtree_size() as f64,
+        tree2.get_subtree_size() as f64,
+        options,
+    )
+}
+
+/// [`tsed_score`] when it reaches `min_similarity`, or `None`. The penalties
+/// only lower the score, so the base score `1 - distance / max_size` has to
+/// reach it already, which caps the distance.
+#[allow(clippy::cast_precision_loss)]
+fn tsed_score_bounded(
+    tree1: &Rc<TreeNode>,
+    tree2: &Rc<TreeNode>,
+    options: &TSEDOptions,
+    min_similarity: f64,
+) -> Option<f64> {
+    if min_similarity <= 0.0 {
+        return Some(tsed_score(tree1, tree2, options));
+    }
 
     let size1 = tree1.get_subtree_size() as f64;
     let size2 = tree2.get_subtree_size() as f64;
+    // The slack keeps distances exactly at the bound despite rounding
+    let max_cost = (1.0 - min_similarity) * size1.max(size2) + 1e-9;
+    let distance = compute_edit_distance_bounded(tree1, tree2, &options.apted_options, max_cost)?;
+    let similarity = score_of_distance(distance, size1, size2, options);
+    (similarity >= min_similarity).then_some(similarity)
+}
 
+/// TSED similarity of trees of `size1` and `size2` nodes at edit `distance`
+fn score_of_distance(distance: f64, size1: f64, size2: f64, options: &TSEDOptions) -> f64 {
     // TSED normalization: Use the larger tree size
     // This ensures that when comparing trees of different sizes,
     // the similarity reflects how much of the larger tree matches
@@ -81,39 +337,163 @@ pub fn calculate_tsed(tree1: &Rc<TreeNode>, tree2: &Rc<TreeNode>, options: &TSED
     };
 
     // Apply additional penalties for structural differences
-    let mut similarity = tsed_similarity;
+    if options.size_penalty {
+        apply_size_penalty(tsed_similarity, size1, size2)
+    } else {
+        tsed_similarity
+    }
+}
+
+/// Upper bound of the similarity of two normalized trees of `size1` and
+/// `size2` nodes, whatever their shape.
+///
+/// [`score_of_distance`] starts from a base score `1 - distance / max_size`
+/// of at most 1.0, only multiplies it by factors of at most 1.0, and ends with
+/// [`apply_size_penalty`], which multiplies it by a factor depending on the
+/// sizes alone. So every score is at most `apply_size_penalty(1.0, ..)`.
+/// Identifiers are renamed rather than removed by the structure-only pass,
+/// which leaves the sizes and so the bound of the blended score unchanged.
+///
+/// The difference in size does not bound the distance: [`compute_edit_distance`]
+/// replaces a subtree at the cost of deleting it or of inserting the other,
+/// whichever is cheaper, so a small tree can be close to a much larger one.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn max_similarity_of_sizes(size1: usize, size2: usize, options: &TSEDOptions) -> f64 {
+    if options.size_penalty {
+        apply_size_penalty(1.0, size1 as f64, size2 as f64)
+    } else {
+        1.0
+    }
+}
 
+/// Penalties depending only on the tree sizes: short functions and trees of
+/// very different sizes score lower
+fn apply_size_penalty(mut similarity: f64, size1: f64, size2: f64) -> f64 {
     // Size ratio penalty: penalize when trees have very different sizes
     let size_ratio = size1.min(size2) / size1.max(size2);
 
-    if options.size_penalty {
-        // For short functions, make differences more pronounced
-        let min_size = size1.min(size2);
-
-        if min_size < 30.0 {
-            // Short function penalty: the shorter, the more sensitive to differences
-            let short_function_factor = (min_size / 30.0).powf(0.5);
-            similarity *= short_function_factor;
-
-            // Additional penalty for very short functions
-            if min_size < 10.0 {
-                similarity *= 0.5; // Strong penalty for very short functions
-            } else if min_size < 20.0 {
-                similarity *= 0.7; // Moderate penalty for short functions
-            }
-        }
+    // For short functions, make differences more pronounced
+    let min_size = size1.min(size2);
+
+    if min_size < 30.0 {
+        // Short function penalty: the shorter, the more sensitive to differences
+        let short_function_factor = (min_size / 30.0).powf(0.5);
+        similarity *= short_function_factor;
 
-        // Size difference penalty
-        if size_ratio < 0.5 {
-            // If one tree is less than half the size of the other,
-            // they're likely fundamentally different
-            similarity *= size_ratio.powf(0.5);
+        // Additional penalty for very short functions
+        if min_size < 10.0 {
+            similarity *= 0.5; // Strong penalty for very short functions
+        } else if min_size < 20.0 {
+            similarity *= 0.7; // Moderate penalty for short functions
         }
     }
 
+    // Size difference penalty
+    if size_ratio < 0.5 {
+        // If one tree is less than half the size of the other,
+        // they're likely fundamentally different
+        similarity *= size_ratio.powf(0.5);
+    }
+
     similarity
 }
 
+/// One tree compared against many candidates, e.g. a query function against
+/// every function of a codebase.
+///
+/// The query is normalized and measured once, and when the size penalty is
+/// enabled, candidates whose size alone rules out reaching the threshold are
+/// skipped before running APTED. The similarities are the same as those of
+/// [`calculate_tsed`].
+pub struct TsedQuery<'a> {
+    tree: Rc<TreeNode>,
+    size: usize,
+    options: &'a TSEDOptions,
+}
+
+impl<'a> TsedQuery<'a> {
+    #[must_use]
+    pub fn new(tree: &Rc<TreeNode>, options: &'a TSEDOptions) -> Self {
+        let tree = normalize_tree(tree, options);
+        let size = tree.get_subtree_size();
+        Self { tree, size, options }
+    }
+
+    /// Upper bound of the similarity to a normalized tree of `candidate_size`
+    /// nodes: the base score is at most 1.0, and the size penalty only depends
+    /// on the sizes of both trees.
+    #[must_use]
+    pub fn max_similarity(&self, candidate_size: usize) -> f64 {
+        max_similarity_of_sizes(self.size, candidate_size, self.options)
+    }
+
+    /// Similarity of the query to one candidate
+    #[must_use]
+    pub fn similarity(&self, candidate: &Rc<TreeNode>) -> f64 {
+        tsed_of_normalized(&self.tree, &normalize_tree(candidate, self.options), self.options)
+    }
+
+    /// Indices and similarities of the candidates reaching `threshold`, most
+    /// similar first
+    #[must_use]
+    pub fn find_similar(&self, candidates: &[Rc<TreeNode>], threshold: f64) -> Vec<(usize, f64)> {
+        let mut similar: Vec<(usize, f64)> = candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(index, candidate)| {
+                let normalized = normalize_tree(candidate, self.options);
+                if self.max_similarity(normalized.get_subtree_size()) < threshold {
+                    return None;
+                }
+                tsed_of_normalized_bounded(&self.tree, &normalized, self.options, threshold)
+                    .map(|similarity| (index, similarity))
+            })
+            .collect();
+        similar.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0))
+        });
+        similar
+    }
+
+    /// The `k` most similar candidates reaching `threshold`, most similar
+    /// first; ties keep the candidate order
+    #[must_use]
+    pub fn best_matches(
+        &self,
+        candidates: &[Rc<TreeNode>],
+        threshold: f64,
+        k: usize,
+    ) -> Vec<(usize, f64)> {
+        let mut matches = self.find_similar(candidates, threshold);
+        matches.truncate(k);
+        matches
+    }
+}
+
+/// The functions of `corpus` similar to `target`, most similar first.
+///
+/// A query for one function, much cheaper than comparing all pairs; see
+/// [`TsedQuery`]. Leave `target` itself out of the corpus, or it is reported
+/// as its own perfect match.
+#[must_use]
+pub fn find_similar_to<F: Clone>(
+    target: &F,
+    target_tree: &Rc<TreeNode>,
+    corpus: &[(F, Rc<TreeNode>)],
+    threshold: f64,
+    options: &TSEDOptions,
+) -> Vec<SimilarityResult<F>> {
+    let trees: Vec<Rc<TreeNode>> = corpus.iter().map(|(_, tree)| tree.clone()).collect();
+    TsedQuery::new(target_tree, options)
+        .find_similar(&trees, threshold)
+        .into_iter()
+        .map(|(index, similarity)| {
+            SimilarityResult::new(target.clone(), corpus[index].0.clone(), similarity)
+        })
+        .collect()
+}
+
 /// Calculate TSED from TypeScript code strings
 ///
 /// # Errors
@@ -177,4 +557,54 @@ mod tests {
         // Should have lower similarity due to structural differences
         assert!(similarity < 0.7);
     }
+
+    #[test]
+    fn test_size_bound_keeps_trees_closer_than_their_size_difference() {
+        let node = |label: &str, id: usize, children: Vec<Rc<TreeNode>>| {
+            let mut node = TreeNode::new(label.to_string(), String::new(), id);
+            for child in children {
+                node.add_child(child);
+            }
+            Rc::new(node)
+        };
+        let small = node("block", 0, vec![node("call", 1, vec![])]);
+        let arguments = (0..19).map(|id| node("argument", id + 2, vec![])).collect();
+        let large = node("block", 0, vec![node("call", 1, arguments)]);
+        let options = TSEDOptions { size_penalty: false, ..Default::default() };
+
+        // Replacing the leaf costs one deletion, far less than the 19 nodes of difference
+        let similarity = calculate_tsed(&small, &large, &options);
+        assert!(similarity > 0.9, "got {similarity}");
+        assert!(max_similarity_of_sizes(2, 21, &options) >= similarity);
+        assert_eq!(calculate_tsed_bounded(&small, &large, &options, 0.9), Some(similarity));
+    }
+
+    #[test]
+    fn test_find_similar_to_ranks_the_corpus() {
+        let block = |statements: &[&str]| {
+            let mut root = TreeNode::new("block".to_string(), String::new(), 0);
+            for (id, statement) in statements.iter().enumerate() {
+                root.add_child(Rc::new(TreeNode::new(
+                    statement.to_string(),
+                    String::new(),
+                    id + 1,
+                )));
+            }
+            Rc::new(root)
+        };
+        let options = TSEDOptions { size_penalty: false, ..Default::default() };
+        let corpus = vec![
+            ("far", block(&["if", "match"])),
+            ("close", block(&["let", "for", "assign", "call"])),
+            ("same", block(&["let", "for", "assign", "return"])),
+        ];
+
+        let target = block(&["let", "for", "assign", "return"]);
+        let results = find_similar_to(&"target", &target, &corpus, 0.9, &options);
+
+        let names: Vec<&str> = results.iter().map(|result| result.func2).collect();
+        assert_eq!(names, vec!["same", "close"]);
+        assert!(results.iter().all(|result| result.func1 == "target"));
+        assert!(results[0].similarity > results[1].similarity);
+    }
 }
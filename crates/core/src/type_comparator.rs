@@ -1,3 +1,4 @@
+use crate::cli_output::sort_groups_by_size;
 use crate::type_extractor::{TypeDefinition, TypeLiteralDefinition};
 use crate::type_normalizer::{
     calculate_property_similarity, find_property_matches, normalize_type, NormalizationOptions,
@@ -6,6 +7,7 @@ use crate::type_normalizer::{
 use std::collections::HashSet;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TypeComparisonResult {
     pub similarity: f64,
     pub structural_similarity: f64,
@@ -15,6 +17,7 @@ pub struct TypeComparisonResult {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TypeDifferences {
     pub missing_properties: Vec<String>,
     pub extra_properties: Vec<String>,
@@ -23,6 +26,7 @@ pub struct TypeDifferences {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TypeMismatch {
     pub property: String,
     pub type1: String,
@@ -30,6 +34,7 @@ pub struct TypeMismatch {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MatchedProperty {
     pub prop1: String,
     pub prop2: String,
@@ -37,6 +42,7 @@ pub struct MatchedProperty {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TypeComparisonOptions {
     pub structural_weight: f64, // Weight for structural similarity (default: 0.6)
     pub naming_weight: f64,     // Weight for naming similarity (default: 0.4)
@@ -72,9 +78,12 @@ pub fn compare_types(
     let normalized1 = normalize_type(type1, &options.normalization_options);
     let normalized2 = normalize_type(type2, &options.normalization_options);
 
-    // Find property matches
-    let property_matches =
-        find_property_matches(&normalized1, &normalized2, options.property_match_threshold);
+    // Find property matches, pairing each property at most once
+    let property_matches = select_one_to_one_matches(find_property_matches(
+        &normalized1,
+        &normalized2,
+        options.property_match_threshold,
+    ));
 
     // Calculate structural similarity
     let structural_similarity =
@@ -110,6 +119,35 @@ pub fn compare_types(
     }
 }
 
+/// Reduce candidate matches to a one-to-one pairing, best matches first.
+///
+/// Ties are broken by property name so that the pairing depends only on the
+/// property sets, not on declaration order or hash map iteration order.
+fn select_one_to_one_matches(mut matches: Vec<PropertyMatch>) -> Vec<PropertyMatch> {
+    matches.sort_by(|a, b| {
+        b.overall_similarity
+            .partial_cmp(&a.overall_similarity)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.prop1.cmp(&b.prop1))
+            .then_with(|| a.prop2.cmp(&b.prop2))
+    });
+
+    let mut used_props1 = HashSet::new();
+    let mut used_props2 = HashSet::new();
+    matches.retain(|property_match| {
+        if used_props1.contains(&property_match.prop1)
+            || used_props2.contains(&property_match.prop2)
+        {
+            return false;
+        }
+        used_props1.insert(property_match.prop1.clone());
+        used_props2.insert(property_match.prop2.clone());
+        true
+    });
+
+    matches
+}
+
 /// Calculate structural similarity between two normalized types
 fn calculate_structural_similarity(
     type1: &NormalizedType,
@@ -127,22 +165,9 @@ fn calculate_structural_similarity(
         return 0.0; // One empty, one not
     }
 
-    // Use the best matches (avoid double counting)
-    let mut used_props1 = HashSet::new();
-    let mut used_props2 = HashSet::new();
-    let mut matched_count = 0;
-    let mut total_match_score = 0.0;
-
-    for property_match in matches {
-        if !used_props1.contains(&property_match.prop1)
-            && !used_props2.contains(&property_match.prop2)
-        {
-            used_props1.insert(property_match.prop1.clone());
-            used_props2.insert(property_match.prop2.clone());
-            matched_count += 1;
-            total_match_score += property_match.overall_similarity;
-        }
-    }
+    // Matches are one-to-one, so every match counts once
+    let matched_count = matches.len();
+    let total_match_score: f64 = matches.iter().map(|m| m.overall_similarity).sum();
 
     if matched_count == 0 {
         return 0.0;
@@ -359,7 +384,9 @@ pub fn group_similar_types(
     }
 
     // Filter out groups with only one type
-    groups.into_iter().filter(|group| group.len() > 1).collect()
+    let mut groups: Vec<_> = groups.into_iter().filter(|group| group.len() > 1).collect();
+    sort_groups_by_size(&mut groups, |t| (t.file_path.clone(), t.start_line));
+    groups
 }
 
 /// Compare type literal with type definition
@@ -552,6 +579,54 @@ mod tests {
         assert!(result.similarity < 0.5);
     }
 
+    #[test]
+    fn test_field_order_does_not_affect_similarity() {
+        let type1 = create_test_type(
+            "Config",
+            vec![
+                ("host", "string", false, false),
+                ("port", "number", false, false),
+                ("secure", "boolean", true, false),
+            ],
+        );
+        let type2 = create_test_type(
+            "Config",
+            vec![
+                ("secure", "boolean", true, false),
+                ("port", "number", false, false),
+                ("host", "string", false, false),
+            ],
+        );
+
+        let options = TypeComparisonOptions::default();
+        let result = compare_types(&type1, &type2, &options);
+
+        assert_eq!(result.similarity, 1.0);
+        assert_eq!(result.matched_properties.len(), 3);
+        assert!(result.differences.missing_properties.is_empty());
+        assert!(result.differences.optionality_differences.is_empty());
+    }
+
+    #[test]
+    fn test_similar_field_names_are_paired_once() {
+        let type1 = create_test_type(
+            "Range",
+            vec![("start1", "number", false, false), ("start2", "number", false, false)],
+        );
+        let type2 = create_test_type(
+            "Range",
+            vec![("start2", "number", false, false), ("start1", "number", false, false)],
+        );
+
+        let options = TypeComparisonOptions::default();
+        let result = compare_types(&type1, &type2, &options);
+
+        assert_eq!(result.similarity, 1.0);
+        for matched in &result.matched_properties {
+            assert_eq!(matched.prop1, matched.prop2);
+        }
+    }
+
     #[test]
     fn test_find_similar_types() {
         let types = vec![
@@ -1,7 +1,9 @@
+use crate::language_parser::GenericTypeDef;
 use oxc_allocator::Allocator;
 use oxc_ast::ast::{
-    Expression, PropertyKey, Statement, TSInterfaceDeclaration, TSPropertySignature, TSType,
-    TSTypeAliasDeclaration, VariableDeclarator,
+    Declaration, ExportDefaultDeclarationKind, Expression, FormalParameters, Function, PropertyKey,
+    Statement, TSInterfaceDeclaration, TSPropertySignature, TSType, TSTypeAliasDeclaration,
+    VariableDeclaration, VariableDeclarator,
 };
 use oxc_parser::Parser;
 use oxc_span::SourceType;
@@ -33,6 +35,44 @@ pub struct PropertyDefinition {
     pub readonly: bool,
 }
 
+impl TypeDefinition {
+    /// Build a type definition from a tree-sitter extracted type, so that
+    /// types of other languages can be compared with `find_duplicate_types`.
+    ///
+    /// Fields written as `name: annotation` become typed properties; bare
+    /// field names get an empty annotation.
+    pub fn from_generic(def: &GenericTypeDef, file_path: &str) -> Self {
+        let kind = match def.kind.as_str() {
+            "type_alias" => TypeKind::TypeAlias,
+            _ => TypeKind::Interface,
+        };
+        let properties = def
+            .fields
+            .iter()
+            .map(|field| {
+                let (name, annotation) = field.split_once(':').unwrap_or((field, ""));
+                PropertyDefinition {
+                    name: name.trim().to_string(),
+                    type_annotation: annotation.trim().to_string(),
+                    optional: false,
+                    readonly: false,
+                }
+            })
+            .collect();
+
+        TypeDefinition {
+            name: def.name.clone(),
+            kind,
+            properties,
+            generics: Vec::new(),
+            extends: Vec::new(),
+            start_line: def.start_line as usize,
+            end_line: def.end_line as usize,
+            file_path: file_path.to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TypeLiteralDefinition {
     pub name: String, // Function name, variable name, etc.
@@ -81,6 +121,7 @@ impl TypeExtractor {
     }
 
     pub fn extract_types(&self) -> Result<Vec<TypeDefinition>, String> {
+        crate::parser::check_source_nesting(&self.source_text)?;
         let allocator = Allocator::default();
         let source_type = SourceType::from_path(&self.file_path).unwrap_or(SourceType::tsx());
         let ret = Parser::new(&allocator, &self.source_text, source_type).parse();
@@ -105,12 +146,93 @@ impl TypeExtractor {
                 }
                 _ => {}
             }
+
+            if source_type.is_jsx() {
+                self.extract_component_props(stmt, &mut types);
+            }
         }
 
         Ok(types)
     }
 
+    /// Extract the prop types written inline on function components, e.g.
+    /// `function Button({ label }: { label: string })`, so that components
+    /// re-declaring the same props are found like duplicate interfaces.
+    ///
+    /// A component is a function or a `const` arrow/function expression with a
+    /// capitalized name; its props are the type literal of its first parameter.
+    fn extract_component_props(&self, stmt: &Statement, types: &mut Vec<TypeDefinition>) {
+        match stmt {
+            Statement::FunctionDeclaration(func) => {
+                types.extend(self.function_component_props(func))
+            }
+            Statement::ExportNamedDeclaration(export) => match &export.declaration {
+                Some(Declaration::FunctionDeclaration(func)) => {
+                    types.extend(self.function_component_props(func))
+                }
+                Some(Declaration::VariableDeclaration(var_decl)) => {
+                    self.extract_component_props_from_variables(var_decl, types);
+                }
+                _ => {}
+            },
+            Statement::ExportDefaultDeclaration(export) => {
+                if let ExportDefaultDeclarationKind::FunctionDeclaration(func) = &export.declaration
+                {
+                    types.extend(self.function_component_props(func));
+                }
+            }
+            Statement::VariableDeclaration(var_decl) => {
+                self.extract_component_props_from_variables(var_decl, types);
+            }
+            _ => {}
+        }
+    }
+
+    fn function_component_props(&self, func: &Function) -> Option<TypeDefinition> {
+        self.component_props(func.id.as_ref()?.name.as_str(), &func.params)
+    }
+
+    fn extract_component_props_from_variables(
+        &self,
+        var_decl: &VariableDeclaration,
+        types: &mut Vec<TypeDefinition>,
+    ) {
+        for declarator in &var_decl.declarations {
+            let Some(name) = self.get_variable_name(declarator) else {
+                continue;
+            };
+            let params = match &declarator.init {
+                Some(Expression::ArrowFunctionExpression(arrow)) => &arrow.params,
+                Some(Expression::FunctionExpression(func)) => &func.params,
+                _ => continue,
+            };
+            types.extend(self.component_props(&name, params));
+        }
+    }
+
+    fn component_props(&self, name: &str, params: &FormalParameters) -> Option<TypeDefinition> {
+        if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+            return None;
+        }
+        let annotation = params.items.first()?.pattern.type_annotation.as_ref()?;
+        let TSType::TSTypeLiteral(type_literal) = &annotation.type_annotation else {
+            return None;
+        };
+
+        Some(TypeDefinition {
+            name: format!("{name} (props)"),
+            kind: TypeKind::TypeLiteral,
+            properties: self.extract_interface_properties(&type_literal.members),
+            generics: Vec::new(),
+            extends: Vec::new(),
+            start_line: self.get_line_number(type_literal.span.start as usize),
+            end_line: self.get_line_number(type_literal.span.end as usize),
+            file_path: self.file_path.clone(),
+        })
+    }
+
     pub fn extract_type_literals(&self) -> Result<Vec<TypeLiteralDefinition>, String> {
+        crate::parser::check_source_nesting(&self.source_text)?;
         let allocator = Allocator::default();
         let source_type = SourceType::from_path(&self.file_path).unwrap_or(SourceType::tsx());
         let ret = Parser::new(&allocator, &self.source_text, source_type).parse();
@@ -588,4 +710,53 @@ interface User extends BaseUser {
         assert_eq!(user_type.name, "User");
         assert_eq!(user_type.extends, vec!["BaseUser"]);
     }
+
+    #[test]
+    fn test_component_prop_types_are_extracted_and_compared() {
+        let user_card = r#"
+export function UserCard({ name, avatarUrl, onSelect }: {
+    name: string;
+    avatarUrl?: string;
+    onSelect: () => void;
+}) {
+    return <div onClick={onSelect}>{name}</div>;
+}
+
+function formatName(user: { first: string; last: string }) {
+    return user.first + " " + user.last;
+}
+"#;
+        let team_card = r#"
+export const TeamCard = ({ name, avatarUrl, onSelect }: {
+    name: string;
+    avatarUrl?: string;
+    onSelect: () => void;
+}) => <div onClick={onSelect}>{name}</div>;
+"#;
+
+        let mut types = extract_types_from_code(user_card, "UserCard.tsx").unwrap();
+        types.extend(extract_types_from_code(team_card, "TeamCard.tsx").unwrap());
+
+        // `formatName` is not a component
+        let names: Vec<&str> = types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["UserCard (props)", "TeamCard (props)"]);
+        assert_eq!(types[0].kind, TypeKind::TypeLiteral);
+        assert_eq!((types[0].start_line, types[0].end_line), (2, 6));
+        assert!(types[0].properties.iter().any(|p| p.name == "avatarUrl" && p.optional));
+
+        let duplicates = crate::type_comparator::find_duplicate_types(
+            &types,
+            0.9,
+            &crate::type_comparator::TypeComparisonOptions::default(),
+        );
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].type1.name, "UserCard (props)");
+        assert_eq!(duplicates[0].type2.name, "TeamCard (props)");
+    }
+
+    #[test]
+    fn test_component_props_are_only_extracted_from_jsx_files() {
+        let source = "export function Build(options: { target: string }) {}\n";
+        assert!(extract_types_from_code(source, "build.ts").unwrap().is_empty());
+    }
 }
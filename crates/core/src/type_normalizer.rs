@@ -11,7 +11,87 @@ pub struct NormalizedType {
     pub kind: TypeKind,
 }
 
+impl NormalizedType {
+    /// Render the type as a stable string: properties sorted by name,
+    /// whitespace collapsed and union/intersection members sorted, so
+    /// equivalently written types produce identical strings. The type name
+    /// and kind are not part of the result.
+    pub fn to_canonical_string(&self) -> String {
+        let mut names: Vec<&String> = self.properties.keys().collect();
+        names.sort();
+
+        let entries: Vec<String> = names
+            .into_iter()
+            .map(|name| {
+                format!(
+                    "{}{}{}: {}",
+                    if self.readonly_properties.contains(name) { "readonly " } else { "" },
+                    name,
+                    if self.optional_properties.contains(name) { "?" } else { "" },
+                    canonical_annotation(&self.properties[name])
+                )
+            })
+            .collect();
+
+        if entries.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{ {} }}", entries.join("; "))
+        }
+    }
+}
+
+/// Collapse whitespace in a type annotation and sort its top-level
+/// union/intersection members
+fn canonical_annotation(annotation: &str) -> String {
+    const PUNCTUATION: &[char] =
+        &['<', '>', '(', ')', '[', ']', '{', '}', ',', ':', ';', '?', '|', '&'];
+
+    let collapsed = annotation.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut compact = String::with_capacity(collapsed.len());
+    let chars: Vec<char> = collapsed.chars().collect();
+    for (i, &ch) in chars.iter().enumerate() {
+        let next_to_punctuation = i > 0
+            && i + 1 < chars.len()
+            && (PUNCTUATION.contains(&chars[i - 1]) || PUNCTUATION.contains(&chars[i + 1]));
+        if ch == ' ' && next_to_punctuation {
+            continue;
+        }
+        compact.push(ch);
+    }
+
+    for (separator, joiner) in [('|', " | "), ('&', " & ")] {
+        let mut members = split_top_level(&compact, separator);
+        if members.len() > 1 {
+            members.sort();
+            return members.join(joiner);
+        }
+    }
+    compact
+}
+
+/// Split on a separator outside of brackets
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, ch) in text.char_indices() {
+        match ch {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' | ')' | ']' | '}' => depth -= 1,
+            c if c == separator && depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NormalizationOptions {
     pub ignore_property_order: bool,
     pub ignore_optional_modifiers: bool,
@@ -406,6 +486,36 @@ mod tests {
         assert!(normalized.readonly_properties.is_empty()); // ignore_readonly_modifiers is true by default
     }
 
+    #[test]
+    fn test_equivalent_types_share_canonical_string() {
+        let options = NormalizationOptions::default();
+        let first = create_test_type(
+            "User",
+            vec![
+                ("id", "string", false, false),
+                ("tags", "Array<string>", false, false),
+                ("status", "'active' | 'inactive'", true, false),
+                ("meta", "Map<string,  number>", false, false),
+            ],
+        );
+        let second = create_test_type(
+            "Account",
+            vec![
+                ("meta", "Map< string , number >", false, false),
+                ("status", "'inactive'|'active'", true, false),
+                ("id", "String", false, false),
+                ("tags", "string[]", false, false),
+            ],
+        );
+
+        let canonical = normalize_type(&first, &options).to_canonical_string();
+        assert_eq!(canonical, normalize_type(&second, &options).to_canonical_string());
+        assert_eq!(
+            canonical,
+            "{ id: string; meta: Map<string,number>; status?: 'active' | 'inactive'; tags: string[] }"
+        );
+    }
+
     #[test]
     fn test_normalize_type_name() {
         assert_eq!(normalize_type_name("String"), "string");
@@ -0,0 +1,60 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::{parse_and_convert_to_tree, JavaParser, TreeNode, DEFAULT_MAX_TREE_DEPTH};
+
+fn depth(tree: &TreeNode) -> usize {
+    let mut deepest = 0;
+    let mut stack = vec![(tree, 1)];
+    while let Some((node, level)) = stack.pop() {
+        deepest = deepest.max(level);
+        stack.extend(node.children.iter().map(|child| (child.as_ref(), level + 1)));
+    }
+    deepest
+}
+
+#[test]
+fn test_deep_typescript_expression_is_truncated() {
+    let terms = vec!["x"; 20_000].join(" + ");
+    let source = format!("function sum(x: number) {{ return {terms}; }}");
+
+    // The stack of a main thread; unoptimized builds need it for the frames of
+    // the oxc converter, which are several times larger than in release builds
+    let converter = std::thread::Builder::new()
+        .stack_size(8 << 20)
+        .spawn(move || {
+            let tree = parse_and_convert_to_tree("deep.ts", &source).unwrap();
+            let truncated = tree.iter().any(|node| node.label == "truncated");
+            (truncated, depth(&tree))
+        })
+        .unwrap();
+
+    let (truncated, tree_depth) = converter.join().unwrap();
+    assert!(truncated);
+    assert!(tree_depth <= DEFAULT_MAX_TREE_DEPTH + 1);
+}
+
+#[test]
+fn test_deep_java_expression_is_truncated() {
+    let nesting = 20_000;
+    let source = format!(
+        "class Deep {{ int f() {{ return {}1{}; }} }}",
+        "(".repeat(nesting),
+        ")".repeat(nesting)
+    );
+
+    let tree = JavaParser::new().unwrap().parse(&source, "Deep.java").unwrap();
+
+    assert!(tree.iter().any(|node| node.label == "truncated"));
+    assert!(depth(&tree) <= DEFAULT_MAX_TREE_DEPTH + 1);
+}
+
+#[test]
+fn test_deep_typescript_brackets_are_rejected_before_parsing() {
+    let nesting = 20_000;
+    let source =
+        format!("function f() {{ return {}1{}; }}", "(".repeat(nesting), ")".repeat(nesting));
+
+    let error = parse_and_convert_to_tree("deep.ts", &source).unwrap_err();
+
+    assert!(error.contains("Nesting deeper than"));
+    assert!(similarity_core::extract_functions("deep.ts", &source).is_err());
+}
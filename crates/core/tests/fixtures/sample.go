@@ -0,0 +1,9 @@
+package sample
+
+func Sum(values []int) int {
+	total := 0
+	for _, value := range values {
+		total += value
+	}
+	return total
+}
@@ -0,0 +1,7 @@
+pub fn total(values: &[u64]) -> u64 {
+    let mut result = 0;
+    for value in values {
+        result += value;
+    }
+    result
+}
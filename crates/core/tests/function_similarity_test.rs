@@ -1,5 +1,5 @@
 use similarity_core::{
-    find_similar_functions_across_files, find_similar_functions_in_file, TSEDOptions,
+    find_similar_functions_across_files, find_similar_functions_in_file, parse_file, TSEDOptions,
 };
 
 #[test]
@@ -412,3 +412,33 @@ const processDataArrow = (data: number[]): number => {
         assert!(pair.similarity < 0.9, "Different functions should not have very high similarity");
     }
 }
+
+#[test]
+fn test_parse_file_infers_typescript() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sample1.ts");
+    let tree = parse_file(&path).unwrap();
+    assert_eq!(tree.label, "Program");
+    assert!(!tree.children.is_empty());
+}
+
+#[test]
+fn test_parse_file_parses_python_and_rust() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let labels = |tree: &similarity_core::TreeNode| {
+        let mut labels = Vec::new();
+        let mut stack = vec![tree];
+        while let Some(node) = stack.pop() {
+            labels.push(node.label.clone());
+            stack.extend(node.children.iter().map(|child| child.as_ref()));
+        }
+        labels
+    };
+
+    let tree = parse_file(&fixtures.join("sample.py")).unwrap();
+    assert_eq!(tree.label, "module");
+    assert!(labels(&tree).contains(&"function_definition".to_string()));
+
+    let tree = parse_file(&fixtures.join("sample.rs")).unwrap();
+    assert_eq!(tree.label, "source_file");
+    assert!(labels(&tree).contains(&"function_item".to_string()));
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "serde")]
+
+use serde::{de::DeserializeOwned, Serialize};
+use similarity_core::function_extractor::FunctionType;
+use similarity_core::{
+    apted::{LabelRenameCosts, LeafRenameCosts},
+    cli_parallel::SimilarityResult,
+    language_parser::GenericFunctionDef,
+    type_comparator::{MatchedProperty, TypeDifferences, TypeMismatch},
+    APTEDOptions, NormalizationOptions, TSEDOptions, TreeNode, TypeComparisonOptions,
+    TypeComparisonResult,
+};
+use std::fmt::Debug;
+use std::rc::Rc;
+
+/// Serialize to JSON and back, comparing the debug output of both values
+fn round_trip<T: Serialize + DeserializeOwned + Debug>(value: &T) -> T {
+    let json = serde_json::to_string(value).unwrap();
+    let restored: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(format!("{restored:?}"), format!("{value:?}"), "{json}");
+    restored
+}
+
+#[test]
+fn test_options_round_trip() {
+    let mut options = TSEDOptions { min_lines: 7, ..TSEDOptions::default() };
+    options.apted_options.leaf_rename_costs.identifier = Some(0.1);
+    round_trip(&options);
+
+    let mut label_rename_costs = LabelRenameCosts::default();
+    label_rename_costs.insert("for_statement", "while_statement", 2.0);
+    round_trip(&APTEDOptions {
+        rename_cost: 0.5,
+        delete_cost: 1.0,
+        insert_cost: 2.0,
+        compare_values: true,
+        leaf_rename_costs: LeafRenameCosts { number: Some(0.2), ..LeafRenameCosts::default() },
+        label_rename_costs,
+    });
+    round_trip(&TypeComparisonOptions::default());
+    round_trip(&NormalizationOptions::default());
+}
+
+#[test]
+fn test_generic_similarity_result_round_trip() {
+    let function = |name: &str| GenericFunctionDef {
+        name: name.to_string(),
+        start_line: 1,
+        end_line: 6,
+        body_start_line: 2,
+        body_end_line: 5,
+        parameters: vec!["items".to_string()],
+        is_method: true,
+        kind: FunctionType::Method,
+        class_name: Some("Cart".to_string()),
+        is_async: false,
+        is_generator: false,
+        decorators: Vec::new(),
+    };
+    round_trip(&SimilarityResult::new(function("total"), function("sum"), 0.93));
+}
+
+#[test]
+fn test_type_comparison_result_round_trip() {
+    round_trip(&TypeComparisonResult {
+        similarity: 0.8,
+        structural_similarity: 0.9,
+        naming_similarity: 0.6,
+        differences: TypeDifferences {
+            missing_properties: vec!["email".to_string()],
+            extra_properties: Vec::new(),
+            type_mismatches: vec![TypeMismatch {
+                property: "id".to_string(),
+                type1: "string".to_string(),
+                type2: "number".to_string(),
+            }],
+            optionality_differences: vec!["name".to_string()],
+        },
+        matched_properties: vec![MatchedProperty {
+            prop1: "id".to_string(),
+            prop2: "id".to_string(),
+            similarity: 1.0,
+        }],
+    });
+}
+
+#[test]
+fn test_tree_node_serializes_as_nested_tree() {
+    let mut body = TreeNode::new("Block".to_string(), String::new(), 1);
+    body.add_child(Rc::new(TreeNode::new("Return".to_string(), "x".to_string(), 2)));
+    let mut root = TreeNode::new("Function".to_string(), "f".to_string(), 0);
+    root.add_child(Rc::new(body));
+
+    let json = serde_json::to_value(&root).unwrap();
+    assert_eq!(json["children"][0]["children"][0]["value"], "x");
+
+    let restored = round_trip(&root);
+    assert_eq!(restored.get_subtree_size(), 3);
+}
@@ -3,7 +3,7 @@
 use crate::parallel::check_within_file_duplicates_parallel;
 use similarity_core::{
     cli_file_utils::collect_files,
-    cli_output::{format_function_output, show_function_code},
+    cli_output::{format_function_output, show_function_code, sort_groups},
     cli_parallel::SimilarityResult,
     language_parser::{GenericFunctionDef, LanguageParser},
     TSEDOptions,
@@ -139,16 +139,21 @@ fn display_all_results(
         b.priority().partial_cmp(&a.priority()).unwrap_or(std::cmp::Ordering::Equal)
     });
 
-    // Group by file
-    let mut file_groups = std::collections::HashMap::new();
+    // Group by file, in an order that is stable across runs
+    let mut file_groups = std::collections::BTreeMap::new();
     for dup in all_results {
         let file_path = dup.file1.to_string_lossy().to_string();
         file_groups.entry(file_path).or_insert_with(Vec::new).push(dup);
     }
+    let mut file_groups: Vec<Vec<DuplicateResult>> = file_groups.into_values().collect();
+    sort_groups(&mut file_groups, |dup| {
+        (dup.file1.clone(), dup.result.func1.start_line, dup.result.func2.start_line)
+    });
 
     // Display results grouped by file
     let mut total_count = 0;
-    for (file_path, duplicates) in file_groups {
+    for duplicates in file_groups {
+        let file_path = duplicates[0].file1.to_string_lossy().to_string();
         println!("\nDuplicates in {}:", file_path);
         println!("{}", "-".repeat(60));
 
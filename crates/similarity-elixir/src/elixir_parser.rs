@@ -1,7 +1,8 @@
+use similarity_core::function_extractor::FunctionType;
 use similarity_core::language_parser::{
     GenericFunctionDef, GenericTypeDef, Language, LanguageParser,
 };
-use similarity_core::tree::TreeNode;
+use similarity_core::tree::{TreeNode, DEFAULT_MAX_TREE_DEPTH};
 use std::error::Error;
 use std::rc::Rc;
 use tree_sitter::{Node, Parser};
@@ -124,6 +125,7 @@ impl ElixirParser {
             body_end_line: body_node.map(|n| n.end_position().row as u32 + 1).unwrap_or(0),
             parameters: params,
             is_method: module_name.is_some(),
+            kind: if module_name.is_some() { FunctionType::Method } else { FunctionType::Function },
             class_name: module_name.map(String::from),
             is_async: false,
             is_generator: false,
@@ -147,7 +149,15 @@ impl ElixirParser {
         params
     }
 
-    fn build_tree_from_node(node: Node, source: &str, id: &mut usize) -> TreeNode {
+    fn build_tree_from_node(node: Node, source: &str, id: &mut usize, depth: usize) -> TreeNode {
+        let current_id = *id;
+        *id += 1;
+
+        // Stop descending into pathologically deep input rather than overflow the stack
+        if depth >= DEFAULT_MAX_TREE_DEPTH {
+            return TreeNode::truncated(current_id);
+        }
+
         let label = node.kind().to_string();
         let value = if node.child_count() == 0 {
             node.utf8_text(source.as_bytes()).ok().unwrap_or_default().to_string()
@@ -155,13 +165,10 @@ impl ElixirParser {
             String::new()
         };
 
-        let current_id = *id;
-        *id += 1;
-
         let mut tree_node = TreeNode::new(label, value, current_id);
 
         for child in node.children(&mut node.walk()) {
-            let child_node = Self::build_tree_from_node(child, source, id);
+            let child_node = Self::build_tree_from_node(child, source, id, depth + 1);
             tree_node.add_child(Rc::new(child_node));
         }
 
@@ -181,7 +188,7 @@ impl LanguageParser for ElixirParser {
     ) -> Result<Rc<TreeNode>, Box<dyn Error + Send + Sync>> {
         let tree = self.parser.parse(source, None).ok_or("Failed to parse Elixir code")?;
         let mut id = 0;
-        Ok(Rc::new(Self::build_tree_from_node(tree.root_node(), source, &mut id)))
+        Ok(Rc::new(Self::build_tree_from_node(tree.root_node(), source, &mut id, 0)))
     }
 
     fn extract_functions(
@@ -18,7 +18,7 @@ struct Cli {
     #[arg(short, long)]
     print: bool,
 
-    /// Similarity threshold (0.0-1.0)
+    /// Similarity threshold (0.0-1.0); 0 reports every compared pair with its score
     #[arg(short, long, default_value = "0.85")]
     threshold: f64,
 
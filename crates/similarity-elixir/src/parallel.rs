@@ -5,7 +5,7 @@ use rayon::prelude::*;
 use similarity_core::{
     cli_parallel::{FileData, SimilarityResult},
     language_parser::{GenericFunctionDef, LanguageParser},
-    tsed::{calculate_tsed, TSEDOptions},
+    tsed::{calculate_tsed_bounded, TSEDOptions},
 };
 use std::fs;
 use std::path::PathBuf;
@@ -97,13 +97,15 @@ pub fn check_within_file_duplicates_parallel(
                                             parser.parse(&body2, &format!("{}:func2", file_str)),
                                         ) {
                                             (Ok(tree1), Ok(tree2)) => {
-                                                // Use calculate_tsed to apply size_penalty and other options
-                                                calculate_tsed(&tree1, &tree2, options)
+                                                // Applies size_penalty and the other options, giving up below the threshold
+                                                calculate_tsed_bounded(
+                                                    &tree1, &tree2, options, threshold,
+                                                )
                                             }
-                                            _ => 0.0,
+                                            _ => None,
                                         };
 
-                                        if similarity >= threshold {
+                                        if let Some(similarity) = similarity {
                                             similar_pairs.push(SimilarityResult::new(
                                                 func1.clone(),
                                                 func2.clone(),
@@ -1,12 +1,16 @@
 use anyhow::Result;
 use clap::Parser;
+use similarity_core::cli_file_utils::collect_files;
 use similarity_core::generic_parser_config::GenericParserConfig;
-use similarity_core::generic_tree_sitter_parser::GenericTreeSitterParser;
+use similarity_core::generic_tree_sitter_parser::{
+    load_available_parsers, GenericTreeSitterParser,
+};
 use similarity_core::language_parser::LanguageParser;
-use similarity_core::tsed::{calculate_tsed, TSEDOptions};
+use similarity_core::tsed::{KeyedTrees, TSEDOptions};
 use similarity_core::APTEDOptions;
+use similarity_core::JavaParser;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // Include auto-generated language configs
 include!(concat!(env!("OUT_DIR"), "/language_configs.rs"));
@@ -27,7 +31,7 @@ struct Cli {
     #[arg(short, long, conflicts_with_all = ["config", "supported", "show_config"])]
     language: Option<String>,
 
-    /// Similarity threshold (0.0-1.0)
+    /// Similarity threshold (0.0-1.0); 0 reports every compared pair with its score
     #[arg(short, long, default_value = "0.85")]
     threshold: f64,
 
@@ -72,6 +76,7 @@ fn main() -> Result<()> {
         println!("  cpp        - C++ language");
         println!("  csharp     - C# language");
         println!("  ruby       - Ruby language");
+        println!("  php        - PHP language");
         println!();
         println!("Note: For Python, TypeScript, and Rust, use the dedicated implementations:");
         println!("  similarity-py  - Optimized Python analyzer");
@@ -89,6 +94,7 @@ fn main() -> Result<()> {
             "cpp" | "c++" => GenericParserConfig::cpp(),
             "csharp" | "cs" => GenericParserConfig::csharp(),
             "ruby" | "rb" => GenericParserConfig::ruby(),
+            "php" => GenericParserConfig::php(),
             _ => {
                 return Err(anyhow::anyhow!(
                     "Unknown language: {}. Use --supported to see available languages.",
@@ -105,6 +111,11 @@ fn main() -> Result<()> {
     // Normal parsing mode
     let path = cli.path.ok_or_else(|| anyhow::anyhow!("Path is required"))?;
 
+    // Without an explicit language, a directory is scanned with every built-in language
+    if path.is_dir() && cli.config.is_none() && cli.language.is_none() && !cli.overlap {
+        return check_directory(&path, cli.threshold, cli.show_functions);
+    }
+
     let config = if let Some(config_path) = &cli.config {
         GenericParserConfig::from_file(config_path)
             .map_err(|e| anyhow::anyhow!("Failed to load config: {}", e))?
@@ -132,6 +143,7 @@ fn main() -> Result<()> {
                 "cpp" | "c++" => GenericParserConfig::cpp(),
                 "csharp" | "cs" => GenericParserConfig::csharp(),
                 "ruby" | "rb" => GenericParserConfig::ruby(),
+                "php" => GenericParserConfig::php(),
                 _ => {
                     eprintln!("Error: Language '{lang}' is not supported by similarity-generic.");
                     eprintln!("Use --supported to see available languages.");
@@ -155,23 +167,18 @@ fn main() -> Result<()> {
             }
         }
     } else {
-        return Err(anyhow::anyhow!("Either --config or --language must be provided"));
+        return Err(anyhow::anyhow!(
+            "Either --config or --language must be provided when analyzing a single file"
+        ));
     };
 
-    // Create parser based on language
-    let language = match config.language.as_str() {
-        "go" => tree_sitter_go::LANGUAGE.into(),
-        "java" => tree_sitter_java::LANGUAGE.into(),
-        "c" => tree_sitter_c::LANGUAGE.into(),
-        "cpp" => tree_sitter_cpp::LANGUAGE.into(),
-        "csharp" => tree_sitter_c_sharp::LANGUAGE.into(),
-        "ruby" => tree_sitter_ruby::LANGUAGE.into(),
-        _ => return Err(anyhow::anyhow!("Unsupported language: {}", config.language)),
+    // Java has a dedicated parser, unless a custom configuration asks otherwise
+    let mut parser: Box<dyn LanguageParser> = if cli.config.is_none() && config.language == "java" {
+        Box::new(JavaParser::new().map_err(|e| anyhow::anyhow!("Failed to create parser: {}", e))?)
+    } else {
+        Box::new(tree_sitter_parser(config)?)
     };
 
-    let mut parser = GenericTreeSitterParser::new(language, config.clone())
-        .map_err(|e| anyhow::anyhow!("Failed to create parser: {}", e))?;
-
     // Read file
     let content = fs::read_to_string(&path)?;
     let filename = path.to_string_lossy();
@@ -181,7 +188,7 @@ fn main() -> Result<()> {
         // Overlap detection mode
         check_overlaps(
             path,
-            parser,
+            parser.as_mut(),
             cli.threshold,
             cli.overlap_min_window,
             cli.overlap_max_window,
@@ -189,63 +196,172 @@ fn main() -> Result<()> {
         )?;
     } else {
         // Normal similarity detection mode
-        // Extract functions
-        let functions = parser
-            .extract_functions(&content, &filename)
-            .map_err(|e| anyhow::anyhow!("Failed to extract functions: {}", e))?;
-
-        if cli.show_functions {
-            println!("Found {} functions:", functions.len());
-            for func in &functions {
-                println!("  {} {}:{}-{}", func.name, filename, func.start_line, func.end_line);
+        compare_functions(parser.as_mut(), &content, &filename, cli.threshold, cli.show_functions)?;
+    }
+
+    Ok(())
+}
+
+/// Create a tree-sitter parser driven by `config`
+fn tree_sitter_parser(config: GenericParserConfig) -> Result<GenericTreeSitterParser> {
+    let language = match config.language.as_str() {
+        "go" => tree_sitter_go::LANGUAGE.into(),
+        "java" => tree_sitter_java::LANGUAGE.into(),
+        "c" => tree_sitter_c::LANGUAGE.into(),
+        "cpp" => tree_sitter_cpp::LANGUAGE.into(),
+        "csharp" => tree_sitter_c_sharp::LANGUAGE.into(),
+        "ruby" => tree_sitter_ruby::LANGUAGE.into(),
+        "php" => tree_sitter_php::LANGUAGE_PHP.into(),
+        _ => return Err(anyhow::anyhow!("Unsupported language: {}", config.language)),
+    };
+
+    GenericTreeSitterParser::new(language, config)
+        .map_err(|e| anyhow::anyhow!("Failed to create parser: {}", e))
+}
+
+/// Parser of a built-in language: the dedicated [`JavaParser`] for Java, the
+/// configured tree-sitter parser for the others
+fn builtin_parser(
+    language: &str,
+) -> Result<Box<dyn LanguageParser>, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(match language {
+        "java" => Box::new(JavaParser::new()?),
+        _ => Box::new(GenericTreeSitterParser::from_language_name(language)?),
+    })
+}
+
+/// Map a file extension to the built-in language that handles it
+fn language_for_extension(ext: &str) -> Option<&'static str> {
+    match ext {
+        "go" => Some("go"),
+        "java" => Some("java"),
+        "c" | "h" => Some("c"),
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" => Some("cpp"),
+        "cs" => Some("csharp"),
+        "rb" => Some("ruby"),
+        "php" => Some("php"),
+        _ => None,
+    }
+}
+
+/// Scan every supported file under `dir`, picking the parser from each file's extension.
+///
+/// A language whose grammar cannot be loaded is reported and skipped; files of the
+/// other languages are still analyzed.
+fn check_directory(dir: &Path, threshold: f64, show_functions: bool) -> Result<()> {
+    const EXTENSIONS: &[&str] =
+        &["go", "java", "c", "h", "cpp", "cc", "cxx", "hpp", "hh", "cs", "rb", "php"];
+
+    let files = collect_files(&[dir.to_string_lossy().to_string()], EXTENSIONS)?;
+
+    let mut needed: Vec<&str> = files
+        .iter()
+        .filter_map(|file| file.extension().and_then(|ext| ext.to_str()))
+        .filter_map(language_for_extension)
+        .collect();
+    needed.sort_unstable();
+    needed.dedup();
+
+    let (parsers, diagnostics) = load_available_parsers(&needed, builtin_parser);
+    for diagnostic in &diagnostics {
+        eprintln!("Warning: {diagnostic}");
+    }
+    let mut parsers: HashMap<String, Box<dyn LanguageParser>> = parsers.into_iter().collect();
+
+    for file in &files {
+        let Some(language) =
+            file.extension().and_then(|ext| ext.to_str()).and_then(language_for_extension)
+        else {
+            continue;
+        };
+        let Some(parser) = parsers.get_mut(language) else {
+            continue;
+        };
+
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
             }
-            println!();
+        };
+        let filename = file.to_string_lossy();
+
+        println!("\n{filename}");
+        if let Err(e) =
+            compare_functions(parser.as_mut(), &content, &filename, threshold, show_functions)
+        {
+            eprintln!("Error analyzing {filename}: {e}");
         }
+    }
 
-        // Compare functions
-        if functions.len() >= 2 {
-            println!("Comparing functions for similarity...");
-
-            let tsed_options = TSEDOptions {
-                apted_options: APTEDOptions {
-                    rename_cost: 0.3,
-                    delete_cost: 1.0,
-                    insert_cost: 1.0,
-                    compare_values: false,
-                },
-                min_lines: 1,
-                min_tokens: None,
-                size_penalty: false,
-                skip_test: false,
-            };
-
-            for i in 0..functions.len() {
-                for j in (i + 1)..functions.len() {
-                    let func1 = &functions[i];
-                    let func2 = &functions[j];
-
-                    // Extract function bodies
-                    let lines: Vec<&str> = content.lines().collect();
-                    let body1 =
-                        extract_function_body(&lines, func1.body_start_line, func1.body_end_line);
-                    let body2 =
-                        extract_function_body(&lines, func2.body_start_line, func2.body_end_line);
-
-                    // Parse and compare
-                    let tree1 =
-                        parser.parse(&body1, &format!("{}:{}", filename, func1.name)).map_err(
-                            |e| anyhow::anyhow!("Failed to parse function {}: {}", func1.name, e),
-                        )?;
-                    let tree2 =
-                        parser.parse(&body2, &format!("{}:{}", filename, func2.name)).map_err(
-                            |e| anyhow::anyhow!("Failed to parse function {}: {}", func2.name, e),
-                        )?;
-
-                    let similarity = calculate_tsed(&tree1, &tree2, &tsed_options);
-
-                    if similarity >= cli.threshold {
-                        println!("  {} <-> {}: {:.2}%", func1.name, func2.name, similarity * 100.0);
-                    }
+    Ok(())
+}
+
+fn compare_functions(
+    parser: &mut dyn LanguageParser,
+    content: &str,
+    filename: &str,
+    threshold: f64,
+    show_functions: bool,
+) -> Result<()> {
+    // Extract functions; declarations without a body have nothing to compare
+    let functions: Vec<_> = parser
+        .extract_functions(content, filename)
+        .map_err(|e| anyhow::anyhow!("Failed to extract functions: {}", e))?
+        .into_iter()
+        .filter(|func| func.body_start_line > 0)
+        .collect();
+
+    if show_functions {
+        println!("Found {} functions:", functions.len());
+        for func in &functions {
+            println!("  {} {}:{}-{}", func.name, filename, func.start_line, func.end_line);
+        }
+        println!();
+    }
+
+    // Compare functions
+    if functions.len() >= 2 {
+        println!("Comparing functions for similarity...");
+
+        let tsed_options = TSEDOptions {
+            apted_options: APTEDOptions {
+                rename_cost: 0.3,
+                delete_cost: 1.0,
+                insert_cost: 1.0,
+                compare_values: false,
+                ..Default::default()
+            },
+            min_lines: 1,
+            min_tokens: None,
+            size_penalty: false,
+            skip_test: false,
+            ..Default::default()
+        };
+
+        // Parse each body once; exact duplicates skip APTED
+        let lines: Vec<&str> = content.lines().collect();
+        let trees = functions
+            .iter()
+            .map(|func| {
+                let body = extract_function_body(&lines, func.body_start_line, func.body_end_line);
+                parser
+                    .parse(&body, &format!("{}:{}", filename, func.name))
+                    .map_err(|e| anyhow::anyhow!("Failed to parse function {}: {}", func.name, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let keyed = KeyedTrees::new(&trees, &tsed_options);
+
+        for i in 0..functions.len() {
+            for j in (i + 1)..functions.len() {
+                if let Some(similarity) = keyed.similarity(i, j, threshold) {
+                    println!(
+                        "  {} <-> {}: {:.2}%",
+                        functions[i].name,
+                        functions[j].name,
+                        similarity * 100.0
+                    );
                 }
             }
         }
@@ -267,14 +383,13 @@ fn extract_function_body(lines: &[&str], start_line: u32, end_line: u32) -> Stri
 
 fn check_overlaps(
     path: PathBuf,
-    mut parser: GenericTreeSitterParser,
+    parser: &mut dyn LanguageParser,
     threshold: f64,
     min_window_size: u32,
     max_window_size: u32,
     size_tolerance: f64,
 ) -> anyhow::Result<()> {
     use similarity_core::{find_overlaps_across_files_generic, OverlapOptions};
-    use std::collections::HashMap;
 
     println!("Checking for overlapping code...\n");
 
@@ -290,7 +405,7 @@ fn check_overlaps(
     let options = OverlapOptions { min_window_size, max_window_size, threshold, size_tolerance };
 
     // Find overlaps
-    let overlaps = find_overlaps_across_files_generic(&mut parser, &file_contents, &options)
+    let overlaps = find_overlaps_across_files_generic(parser, &file_contents, &options)
         .map_err(|e| anyhow::anyhow!("Failed to find overlaps: {}", e))?;
 
     if overlaps.is_empty() {
@@ -278,3 +278,55 @@ void test_lambdas() {
     println!("Has deleted functions: {has_deleted}");
     println!("Has defaulted functions: {has_defaulted}");
 }
+
+#[test]
+fn test_cpp_out_of_line_methods_and_prototypes() {
+    let config = GenericParserConfig::cpp();
+    let mut parser = GenericTreeSitterParser::new(tree_sitter_cpp::LANGUAGE.into(), config)
+        .expect("Failed to create parser");
+
+    let code = r#"
+int helper(int value);
+
+class Counter {
+public:
+    void increment();
+    int value() const { return count; }
+    int& slot();
+private:
+    int count;
+};
+
+void Counter::increment() {
+    count = helper(count);
+}
+
+int& Counter::slot() {
+    return count;
+}
+
+int helper(int value) {
+    return value + 1;
+}
+"#;
+
+    let functions =
+        parser.extract_functions(code, "counter.cpp").expect("Failed to extract functions");
+
+    let summary: Vec<(&str, Option<&str>)> =
+        functions.iter().map(|f| (f.name.as_str(), f.class_name.as_deref())).collect();
+    // Prototypes have no body to compare and are not reported
+    assert_eq!(
+        summary,
+        vec![
+            ("value", Some("Counter")),
+            ("increment", Some("Counter")),
+            ("slot", Some("Counter")),
+            ("helper", None),
+        ]
+    );
+    assert!(functions.iter().filter(|f| f.class_name.is_some()).all(|f| f.is_method));
+
+    let types = parser.extract_types(code, "counter.cpp").expect("Failed to extract types");
+    assert!(types.iter().any(|t| t.name == "Counter"));
+}
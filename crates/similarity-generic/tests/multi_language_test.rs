@@ -59,6 +59,43 @@ public class Test {
     cmd.assert().success().stdout(predicate::str::contains("add <-> sum:"));
 }
 
+#[test]
+fn test_java_overloads_and_abstract_methods_in_directory_scan() {
+    let dir = TempDir::new().unwrap();
+    create_test_file(
+        &dir,
+        "Shop.java",
+        r#"
+public abstract class Shop {
+    public Shop() {
+        this.items = 0;
+    }
+
+    public int total(int a) {
+        return a + 1;
+    }
+
+    public int total(int a, int b) {
+        return a + b;
+    }
+
+    public abstract int discount(int a);
+}
+"#,
+    );
+
+    let mut cmd = Command::cargo_bin("similarity-generic").unwrap();
+    cmd.arg(dir.path()).arg("--show-functions").arg("--threshold").arg("0.8");
+
+    // Each overload is compared on its own; the abstract method has no body to compare
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Found 3 functions:"))
+        .stdout(predicate::str::contains("Shop.java:3-5"))
+        .stdout(predicate::str::contains("total <-> total:"))
+        .stdout(predicate::str::contains("discount").not());
+}
+
 #[test]
 fn test_c_similarity() {
     let dir = TempDir::new().unwrap();
@@ -160,6 +197,39 @@ end
     cmd.assert().success().stdout(predicate::str::contains("calculate_sum <-> compute_total:"));
 }
 
+#[test]
+fn test_php_similarity() {
+    let dir = TempDir::new().unwrap();
+    let file = create_test_file(
+        &dir,
+        "test.php",
+        r#"<?php
+namespace App;
+
+function calculate_sum(array $numbers) {
+    $total = 0;
+    foreach ($numbers as $n) {
+        $total += $n;
+    }
+    return $total;
+}
+
+function compute_total(array $values) {
+    $sum = 0;
+    foreach ($values as $v) {
+        $sum += $v;
+    }
+    return $sum;
+}
+"#,
+    );
+
+    let mut cmd = Command::cargo_bin("similarity-generic").unwrap();
+    cmd.arg(file).arg("--language").arg("php").arg("--threshold").arg("0.8");
+
+    cmd.assert().success().stdout(predicate::str::contains("calculate_sum <-> compute_total:"));
+}
+
 #[test]
 fn test_language_aliases() {
     let dir = TempDir::new().unwrap();
@@ -349,3 +419,47 @@ fn test_python_language_redirect() {
 
     cmd.assert().failure().stderr(predicate::str::contains("similarity-py"));
 }
+
+#[test]
+fn test_directory_scan_detects_languages_by_extension() {
+    let dir = TempDir::new().unwrap();
+    create_test_file(
+        &dir,
+        "math.go",
+        r#"
+package main
+
+func add(a, b int) int {
+    return a + b
+}
+
+func sum(x, y int) int {
+    return x + y
+}
+"#,
+    );
+    create_test_file(
+        &dir,
+        "math.rb",
+        r#"
+def add(a, b)
+  a + b
+end
+
+def sum(x, y)
+  x + y
+end
+"#,
+    );
+    create_test_file(&dir, "notes.txt", "not source code");
+
+    let mut cmd = Command::cargo_bin("similarity-generic").unwrap();
+    cmd.arg(dir.path()).arg("--threshold").arg("0.8");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("math.go"))
+        .stdout(predicate::str::contains("math.rb"))
+        .stdout(predicate::str::contains("add <-> sum:"))
+        .stdout(predicate::str::contains("notes.txt").not());
+}
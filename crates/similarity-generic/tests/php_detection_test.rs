@@ -0,0 +1,86 @@
+use similarity_core::generic_parser_config::GenericParserConfig;
+use similarity_core::generic_tree_sitter_parser::GenericTreeSitterParser;
+use similarity_core::language_parser::LanguageParser;
+
+#[test]
+fn test_php_function_detection() {
+    let config = GenericParserConfig::php();
+    let mut parser = GenericTreeSitterParser::new(tree_sitter_php::LANGUAGE_PHP.into(), config)
+        .expect("Failed to create parser");
+
+    let code = r#"<?php
+namespace App\Billing;
+
+function format_amount(int $cents, string $currency = 'EUR'): string {
+    return sprintf('%d %s', $cents / 100, $currency);
+}
+
+class Invoice {
+    public function total(array $lines): int {
+        $sum = 0;
+        foreach ($lines as $line) {
+            $sum += $line;
+        }
+        return $sum;
+    }
+
+    public static function create(): self {
+        return new self();
+    }
+}
+
+trait Loggable {
+    protected function log(string $message, ...$context): void {
+        echo $message;
+    }
+}
+
+interface Payable {
+    public function pay(int $amount): bool;
+}
+
+abstract class Gateway {
+    abstract protected function send(string $payload): void;
+}
+"#;
+
+    let functions =
+        parser.extract_functions(code, "invoice.php").expect("Failed to extract functions");
+
+    let summary: Vec<(&str, Option<&str>, bool)> =
+        functions.iter().map(|f| (f.name.as_str(), f.class_name.as_deref(), f.is_method)).collect();
+    // Interface and abstract methods have no body and are only declared
+    assert_eq!(
+        summary,
+        vec![
+            ("format_amount", None, false),
+            ("total", Some("Invoice"), true),
+            ("create", Some("Invoice"), true),
+            ("log", Some("Loggable"), true),
+        ]
+    );
+
+    let format_amount = &functions[0];
+    assert_eq!(format_amount.parameters, vec!["$cents", "$currency"]);
+    assert_eq!(format_amount.start_line, 4);
+    assert_eq!(format_amount.end_line, 6);
+}
+
+#[test]
+fn test_php_type_detection() {
+    let config = GenericParserConfig::php();
+    let mut parser = GenericTreeSitterParser::new(tree_sitter_php::LANGUAGE_PHP.into(), config)
+        .expect("Failed to create parser");
+
+    let code = r#"<?php
+namespace App {
+    class Invoice {}
+    interface Payable {}
+    trait Loggable {}
+}
+"#;
+
+    let types = parser.extract_types(code, "types.php").expect("Failed to extract types");
+    let type_names: Vec<&str> = types.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(type_names, vec!["Invoice", "Payable", "Loggable"]);
+}
@@ -350,3 +350,58 @@ end
     assert!(!function_names.contains(&"new_name"), "alias_method should not create new detection");
     assert!(!function_names.contains(&"another_name"), "alias should not create new detection");
 }
+
+#[test]
+fn test_ruby_method_details() {
+    let config = GenericParserConfig::ruby();
+    let mut parser = GenericTreeSitterParser::new(tree_sitter_ruby::LANGUAGE.into(), config)
+        .expect("Failed to create parser");
+
+    let code = r#"
+module Billing
+  class Invoice
+    def total=(value)
+      @total = value
+    end
+
+    def charge(amount, currency = "EUR", *extras, retries:, notify: true, **options, &block)
+      amount
+    end
+
+    def self.build
+      new
+    end
+  end
+
+  def self.enabled?
+    true
+  end
+end
+
+def top_level(name)
+  name
+end
+"#;
+
+    let functions =
+        parser.extract_functions(code, "billing.rb").expect("Failed to extract functions");
+
+    let summary: Vec<(&str, Option<&str>, bool)> =
+        functions.iter().map(|f| (f.name.as_str(), f.class_name.as_deref(), f.is_method)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("total=", Some("Invoice"), true),
+            ("charge", Some("Invoice"), true),
+            ("build", Some("Invoice"), true),
+            ("enabled?", Some("Billing"), true),
+            ("top_level", None, false),
+        ]
+    );
+
+    let charge = functions.iter().find(|f| f.name == "charge").unwrap();
+    assert_eq!(
+        charge.parameters,
+        vec!["amount", "currency", "extras", "retries", "notify", "options", "block"]
+    );
+}
@@ -3,7 +3,7 @@
 use crate::parallel::check_within_file_duplicates_parallel;
 use similarity_core::{
     cli_file_utils::collect_files,
-    cli_output::{format_function_output, show_function_code},
+    cli_output::{format_function_output, show_function_code, sort_groups},
     cli_parallel::SimilarityResult,
     language_parser::GenericFunctionDef,
     TSEDOptions,
@@ -42,6 +42,7 @@ pub fn check_paths(
     _fast_mode: bool, // Python doesn't support fast mode yet
     filter_function: Option<&String>,
     filter_function_body: Option<&String>,
+    ignore_literals: bool,
 ) -> anyhow::Result<()> {
     let default_extensions = vec!["py"];
     let exts: Vec<&str> =
@@ -61,6 +62,7 @@ pub fn check_paths(
     options.min_lines = min_lines;
     options.min_tokens = min_tokens;
     options.size_penalty = !no_size_penalty;
+    options.ignore_literals = ignore_literals;
 
     let mut all_results = Vec::new();
 
@@ -118,16 +120,21 @@ fn display_all_results(
         b.priority().partial_cmp(&a.priority()).unwrap_or(std::cmp::Ordering::Equal)
     });
 
-    // Group by file
-    let mut file_groups = std::collections::HashMap::new();
+    // Group by file, in an order that is stable across runs
+    let mut file_groups = std::collections::BTreeMap::new();
     for dup in all_results {
         let file_path = dup.file1.to_string_lossy().to_string();
         file_groups.entry(file_path).or_insert_with(Vec::new).push(dup);
     }
+    let mut file_groups: Vec<Vec<DuplicateResult>> = file_groups.into_values().collect();
+    sort_groups(&mut file_groups, |dup| {
+        (dup.file1.clone(), dup.result.func1.start_line, dup.result.func2.start_line)
+    });
 
     // Display results grouped by file
     let mut total_count = 0;
-    for (file_path, duplicates) in file_groups {
+    for duplicates in file_groups {
+        let file_path = duplicates[0].file1.to_string_lossy().to_string();
         println!("\nDuplicates in {}:", file_path);
         println!("{}", "-".repeat(60));
 
@@ -2,6 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 
 mod check;
+mod overrides;
 mod parallel;
 mod python_parser;
 
@@ -18,7 +19,7 @@ struct Cli {
     #[arg(short, long)]
     print: bool,
 
-    /// Similarity threshold (0.0-1.0)
+    /// Similarity threshold (0.0-1.0); 0 reports every compared pair with its score
     #[arg(short, long, default_value = "0.85")]
     threshold: f64,
 
@@ -50,6 +51,10 @@ struct Cli {
     #[arg(long)]
     filter_function_body: Option<String>,
 
+    /// Ignore the text of string and number literals; expressions inside f-strings are still compared
+    #[arg(long)]
+    ignore_literals: bool,
+
     /// Disable fast mode with bloom filter pre-filtering
     #[arg(long)]
     no_fast: bool,
@@ -69,6 +74,10 @@ struct Cli {
     /// Size tolerance for overlap detection (0.0-1.0)
     #[arg(long, default_value = "0.25")]
     overlap_size_tolerance: f64,
+
+    /// Report methods nearly identical to the parent class method they override
+    #[arg(long)]
+    overrides: bool,
 }
 
 fn main() -> Result<()> {
@@ -96,6 +105,7 @@ fn main() -> Result<()> {
             !cli.no_fast,
             cli.filter_function.as_ref(),
             cli.filter_function_body.as_ref(),
+            cli.ignore_literals,
         )?;
     }
 
@@ -107,7 +117,7 @@ fn main() -> Result<()> {
     if overlap_enabled {
         println!("=== Overlap Detection ===");
         check_overlaps(
-            cli.paths,
+            cli.paths.clone(),
             cli.threshold,
             cli.extensions.as_ref(),
             cli.print,
@@ -117,6 +127,16 @@ fn main() -> Result<()> {
         )?;
     }
 
+    if cli.overrides {
+        println!("\n{separator}\n");
+        println!("=== Redundant Overrides ===");
+        let mut options = similarity_core::TSEDOptions::default();
+        options.apted_options.rename_cost = cli.rename_cost;
+        options.size_penalty = !cli.no_size_penalty;
+        options.ignore_literals = cli.ignore_literals;
+        overrides::check_overrides(&cli.paths, cli.extensions.as_ref(), cli.threshold, &options)?;
+    }
+
     Ok(())
 }
 
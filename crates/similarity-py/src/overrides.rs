@@ -0,0 +1,172 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::parallel::extract_function_body;
+use crate::python_parser::{PythonClass, PythonParser};
+use similarity_core::{
+    cli_file_utils::collect_files,
+    cli_output::format_function_output,
+    language_parser::{GenericFunctionDef, LanguageParser},
+    tree::TreeNode,
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// A class of the scanned code with the parsed body of each of its methods
+struct ParsedClass {
+    file: PathBuf,
+    class: PythonClass,
+    methods: Vec<(GenericFunctionDef, Rc<TreeNode>)>,
+}
+
+impl ParsedClass {
+    fn method(&self, name: &str) -> Option<&(GenericFunctionDef, Rc<TreeNode>)> {
+        self.methods.iter().find(|(method, _)| method.name == name)
+    }
+}
+
+/// Report methods whose body is nearly identical to the implementation they
+/// override in an ancestor class
+pub fn check_overrides(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["py"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = collect_files(paths, &exts)?;
+
+    let mut parser = PythonParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut classes = Vec::new();
+
+    for file in &files {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        let filename = file.to_string_lossy();
+        let (file_classes, functions) =
+            match (parser.extract_classes(&content), parser.extract_functions(&content, &filename))
+            {
+                (Ok(classes), Ok(functions)) => (classes, functions),
+                (Err(e), _) | (_, Err(e)) => {
+                    eprintln!("Error parsing {}: {}", file.display(), e);
+                    continue;
+                }
+            };
+
+        let lines: Vec<&str> = content.lines().collect();
+        for class in file_classes {
+            let methods = functions
+                .iter()
+                .filter(|func| {
+                    // Methods of nested classes carry the dotted `Outer.Inner` name
+                    func.class_name.as_deref().and_then(|name| name.rsplit('.').next())
+                        == Some(class.name.as_str())
+                })
+                .filter(|func| {
+                    func.start_line >= class.start_line && func.end_line <= class.end_line
+                })
+                .filter_map(|func| {
+                    let body = extract_function_body(&lines, func);
+                    let tree = parser.parse(&body, &func.name).ok()?;
+                    Some((func.clone(), tree))
+                })
+                .collect();
+            classes.push(ParsedClass { file: file.clone(), class, methods });
+        }
+    }
+
+    let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, parsed) in classes.iter().enumerate() {
+        by_name.entry(parsed.class.name.as_str()).or_default().push(index);
+    }
+
+    let mut redundant = Vec::new();
+    for (child_index, child) in classes.iter().enumerate() {
+        for (method, tree) in &child.methods {
+            let Some(parent_index) = find_overridden(&classes, &by_name, child_index, &method.name)
+            else {
+                continue;
+            };
+            let (parent_method, parent_tree) =
+                classes[parent_index].method(&method.name).expect("ancestor defines the method");
+            let similarity = calculate_tsed(tree, parent_tree, options);
+            if similarity >= threshold {
+                redundant.push((child_index, method, parent_index, parent_method, similarity));
+            }
+        }
+    }
+    redundant.sort_by(|a, b| b.4.partial_cmp(&a.4).unwrap_or(std::cmp::Ordering::Equal));
+
+    if redundant.is_empty() {
+        println!("\nNo redundant overrides found!");
+        return Ok(());
+    }
+
+    for (child_index, method, parent_index, parent_method, similarity) in &redundant {
+        println!(
+            "\n  {} overrides {}",
+            describe(&classes[*child_index], method),
+            describe(&classes[*parent_index], parent_method)
+        );
+        println!("  Similarity: {:.2}%", similarity * 100.0);
+    }
+
+    println!("\nTotal redundant overrides: {}", redundant.len());
+
+    Ok(())
+}
+
+fn describe(parsed: &ParsedClass, method: &GenericFunctionDef) -> String {
+    format_function_output(
+        &parsed.file.to_string_lossy(),
+        &format!("{}.{}", parsed.class.name, method.name),
+        method.start_line,
+        method.end_line,
+    )
+}
+
+/// Find the nearest ancestor of a class that defines the given method.
+///
+/// Base classes are resolved by name, preferring a class defined in the same
+/// file; bases that are not part of the scanned code are ignored.
+fn find_overridden(
+    classes: &[ParsedClass],
+    by_name: &HashMap<&str, Vec<usize>>,
+    class_index: usize,
+    method_name: &str,
+) -> Option<usize> {
+    let mut visited = HashSet::from([class_index]);
+    let mut queue = VecDeque::from([class_index]);
+
+    while let Some(index) = queue.pop_front() {
+        let class = &classes[index];
+        for base in &class.class.bases {
+            let Some(candidates) = by_name.get(base.as_str()) else {
+                continue;
+            };
+            let base_index = candidates
+                .iter()
+                .copied()
+                .find(|&candidate| classes[candidate].file == class.file)
+                .unwrap_or(candidates[0]);
+            if !visited.insert(base_index) {
+                continue;
+            }
+            if classes[base_index].method(method_name).is_some() {
+                return Some(base_index);
+            }
+            queue.push_back(base_index);
+        }
+    }
+
+    None
+}
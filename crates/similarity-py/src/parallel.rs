@@ -4,8 +4,9 @@ use crate::python_parser::PythonParser;
 use rayon::prelude::*;
 use similarity_core::{
     cli_parallel::{FileData, SimilarityResult},
-    language_parser::{GenericFunctionDef, LanguageParser},
-    tsed::{calculate_tsed, TSEDOptions},
+    language_parser::{GenericFunctionDef, Language, LanguageParser},
+    parser_pool::with_pooled_parser,
+    tsed::{KeyedTrees, TSEDOptions},
 };
 use std::fs;
 use std::path::PathBuf;
@@ -23,19 +24,18 @@ pub fn load_files_parallel(files: &[PathBuf]) -> Vec<PythonFileData> {
             match fs::read_to_string(file) {
                 Ok(content) => {
                     let filename = file.to_string_lossy();
-                    // Create Python parser
-                    match PythonParser::new() {
-                        Ok(mut parser) => {
-                            // Extract functions
-                            match parser.extract_functions(&content, &filename) {
-                                Ok(functions) => {
-                                    Some(FileData { path: file.clone(), content, functions })
-                                }
-                                Err(e) => {
-                                    eprintln!("Error parsing {}: {}", file.display(), e);
-                                    None
-                                }
-                            }
+                    // Reuse this thread's Python parser across files
+                    let extracted =
+                        with_pooled_parser(Language::Python, PythonParser::new, |parser| {
+                            parser.extract_functions(&content, &filename)
+                        });
+                    match extracted {
+                        Ok(Ok(functions)) => {
+                            Some(FileData { path: file.clone(), content, functions })
+                        }
+                        Ok(Err(e)) => {
+                            eprintln!("Error parsing {}: {}", file.display(), e);
+                            None
                         }
                         Err(e) => {
                             eprintln!("Error creating parser for {}: {}", file.display(), e);
@@ -60,78 +60,59 @@ pub fn check_within_file_duplicates_parallel(
 ) -> Vec<(PathBuf, Vec<SimilarityResult<GenericFunctionDef>>)> {
     files
         .par_iter()
-        .filter_map(|file| match fs::read_to_string(file) {
-            Ok(code) => {
-                let file_str = file.to_string_lossy();
-
-                // Create Python parser
-                match PythonParser::new() {
-                    Ok(mut parser) => {
-                        // Extract functions
-                        match parser.extract_functions(&code, &file_str) {
-                            Ok(functions) => {
-                                let mut similar_pairs = Vec::new();
-
-                                // Compare all pairs within the file
-                                for i in 0..functions.len() {
-                                    for j in (i + 1)..functions.len() {
-                                        let func1 = &functions[i];
-                                        let func2 = &functions[j];
-
-                                        // Skip if functions don't meet minimum requirements
-                                        if func1.end_line - func1.start_line + 1 < options.min_lines
-                                            || func2.end_line - func2.start_line + 1
-                                                < options.min_lines
-                                        {
-                                            continue;
-                                        }
-
-                                        // Extract function bodies
-                                        let lines: Vec<&str> = code.lines().collect();
-                                        let body1 = extract_function_body(&lines, func1);
-                                        let body2 = extract_function_body(&lines, func2);
-
-                                        // Calculate similarity using Python parser
-                                        let similarity = match (
-                                            parser.parse(&body1, &format!("{}:func1", file_str)),
-                                            parser.parse(&body2, &format!("{}:func2", file_str)),
-                                        ) {
-                                            (Ok(tree1), Ok(tree2)) => {
-                                                // Use calculate_tsed to apply size_penalty and other options
-                                                calculate_tsed(&tree1, &tree2, options)
-                                            }
-                                            _ => 0.0,
-                                        };
+        .filter_map(|file| {
+            let code = fs::read_to_string(file).ok()?;
+            let file_str = file.to_string_lossy();
 
-                                        if similarity >= threshold {
-                                            similar_pairs.push(SimilarityResult::new(
-                                                func1.clone(),
-                                                func2.clone(),
-                                                similarity,
-                                            ));
-                                        }
-                                    }
-                                }
+            // Reuse this thread's Python parser across files
+            let similar_pairs = with_pooled_parser(Language::Python, PythonParser::new, |parser| {
+                // Extract the functions meeting the minimum requirements, parsing each body once
+                let lines: Vec<&str> = code.lines().collect();
+                let mut functions = Vec::new();
+                let mut trees = Vec::new();
+                for func in parser.extract_functions(&code, &file_str).ok()? {
+                    if func.end_line - func.start_line + 1 < options.min_lines {
+                        continue;
+                    }
+                    let body = extract_function_body(&lines, &func);
+                    if let Ok(tree) = parser.parse(&body, &format!("{}:{}", file_str, func.name)) {
+                        functions.push(func);
+                        trees.push(tree);
+                    }
+                }
+                // Exact duplicates share a key and skip APTED
+                let keyed = KeyedTrees::new(&trees, options);
+                let mut similar_pairs = Vec::new();
 
-                                if similar_pairs.is_empty() {
-                                    None
-                                } else {
-                                    Some((file.clone(), similar_pairs))
-                                }
-                            }
-                            Err(_) => None,
+                // Compare all pairs within the file
+                for i in 0..functions.len() {
+                    for j in (i + 1)..functions.len() {
+                        // Applies size_penalty and the other options, giving up below the threshold
+                        if let Some(similarity) = keyed.similarity(i, j, threshold) {
+                            similar_pairs.push(SimilarityResult::new(
+                                functions[i].clone(),
+                                functions[j].clone(),
+                                similarity,
+                            ));
                         }
                     }
-                    Err(_) => None,
                 }
+
+                Some(similar_pairs)
+            })
+            .ok()??;
+
+            if similar_pairs.is_empty() {
+                None
+            } else {
+                Some((file.clone(), similar_pairs))
             }
-            Err(_) => None,
         })
         .collect()
 }
 
 /// Extract function body from lines
-fn extract_function_body(lines: &[&str], func: &GenericFunctionDef) -> String {
+pub fn extract_function_body(lines: &[&str], func: &GenericFunctionDef) -> String {
     let start_idx = (func.body_start_line.saturating_sub(1)) as usize;
     let end_idx = std::cmp::min(func.body_end_line as usize, lines.len());
 
@@ -1,9 +1,10 @@
 #![allow(clippy::io_other_error)]
 
+use similarity_core::function_extractor::FunctionType;
 use similarity_core::language_parser::{
-    GenericFunctionDef, GenericTypeDef, Language, LanguageParser,
+    GenericFunctionDef, GenericTypeDef, Language, LanguageParser, TypeDefKind,
 };
-use similarity_core::tree::TreeNode;
+use similarity_core::tree::{TreeNode, DEFAULT_MAX_TREE_DEPTH};
 use std::error::Error;
 use std::rc::Rc;
 use tree_sitter::{Node, Parser};
@@ -12,6 +13,16 @@ pub struct PythonParser {
     parser: Parser,
 }
 
+/// A class definition with the names of the classes it inherits from
+#[derive(Debug, Clone)]
+pub struct PythonClass {
+    pub name: String,
+    /// Base classes by simple name, e.g. `models.Model` -> `Model`
+    pub bases: Vec<String>,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
 impl PythonParser {
     pub fn new() -> Result<Self, Box<dyn Error + Send + Sync>> {
         let mut parser = Parser::new();
@@ -25,8 +36,85 @@ impl PythonParser {
         Ok(Self { parser })
     }
 
-    #[allow(clippy::only_used_in_recursion)]
-    fn convert_node(&self, node: Node, source: &str, id_counter: &mut usize) -> TreeNode {
+    /// Extract the classes defined in a module along with their base classes.
+    /// Keyword arguments such as `metaclass=...` are not bases and are skipped.
+    pub fn extract_classes(
+        &mut self,
+        source: &str,
+    ) -> Result<Vec<PythonClass>, Box<dyn Error + Send + Sync>> {
+        let tree = self.parser.parse(source, None).ok_or_else(|| {
+            Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Failed to parse Python source",
+            )) as Box<dyn Error + Send + Sync>
+        })?;
+
+        fn visit(node: Node, source: &str, classes: &mut Vec<PythonClass>) {
+            if node.kind() == "class_definition" {
+                if let Some(name) = node.child_by_field_name("name") {
+                    let bases = node
+                        .child_by_field_name("superclasses")
+                        .map(|args| {
+                            args.named_children(&mut args.walk())
+                                .filter(|arg| matches!(arg.kind(), "identifier" | "attribute"))
+                                .filter_map(|arg| arg.utf8_text(source.as_bytes()).ok())
+                                .map(|text| text.rsplit('.').next().unwrap_or(text).to_string())
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    classes.push(PythonClass {
+                        name: name.utf8_text(source.as_bytes()).unwrap_or("").to_string(),
+                        bases,
+                        start_line: node.start_position().row as u32 + 1,
+                        end_line: node.end_position().row as u32 + 1,
+                    });
+                }
+            }
+            for child in node.children(&mut node.walk()) {
+                visit(child, source, classes);
+            }
+        }
+
+        let mut classes = Vec::new();
+        visit(tree.root_node(), source, &mut classes);
+        Ok(classes)
+    }
+
+    /// Convert the syntax tree below `node`, numbering the nodes in pre-order.
+    /// The traversal keeps its own stack, so deeply nested expressions in
+    /// generated files cannot overflow the thread's stack, and subtrees deeper
+    /// than [`DEFAULT_MAX_TREE_DEPTH`] are replaced by a `truncated` node, so
+    /// that the passes walking the tree recursively cannot either.
+    fn convert_node(node: Node, source: &str, id_counter: &mut usize) -> TreeNode {
+        // Nodes still being filled in, innermost last, with their unvisited children
+        let mut stack = vec![Self::open_node(node, source, id_counter)];
+        loop {
+            let depth = stack.len();
+            let (parent, children) = stack.last_mut().expect("the root is popped last");
+            if let Some(child) = children.next() {
+                if depth < DEFAULT_MAX_TREE_DEPTH {
+                    stack.push(Self::open_node(child, source, id_counter));
+                } else {
+                    parent.add_child(Rc::new(TreeNode::truncated(*id_counter)));
+                    *id_counter += 1;
+                }
+                continue;
+            }
+
+            let (tree_node, _) = stack.pop().expect("the root is popped last");
+            match stack.last_mut() {
+                Some((parent, _)) => parent.add_child(Rc::new(tree_node)),
+                None => return tree_node,
+            }
+        }
+    }
+
+    /// The childless `TreeNode` of `node` along with the children to convert
+    fn open_node<'tree>(
+        node: Node<'tree>,
+        source: &str,
+        id_counter: &mut usize,
+    ) -> (TreeNode, std::vec::IntoIter<Node<'tree>>) {
         let current_id = *id_counter;
         *id_counter += 1;
 
@@ -38,14 +126,8 @@ impl PythonParser {
             _ => "".to_string(),
         };
 
-        let mut tree_node = TreeNode::new(label, value, current_id);
-
-        for child in node.children(&mut node.walk()) {
-            let child_node = self.convert_node(child, source, id_counter);
-            tree_node.add_child(Rc::new(child_node));
-        }
-
-        tree_node
+        let children: Vec<Node> = node.children(&mut node.walk()).collect();
+        (TreeNode::new(label, value, current_id), children.into_iter())
     }
 
     fn extract_functions_from_node(
@@ -62,7 +144,12 @@ impl PythonParser {
             source: &str,
             functions: &mut Vec<GenericFunctionDef>,
             class_name: Option<&str>,
+            depth: usize,
         ) {
+            // Definitions are never this deep; what is are expressions outside functions
+            if depth >= DEFAULT_MAX_TREE_DEPTH {
+                return;
+            }
             match node.kind() {
                 "function_definition" => {
                     if let Some(name_node) = node.child_by_field_name("name") {
@@ -84,11 +171,19 @@ impl PythonParser {
                                     .unwrap_or(0),
                                 parameters: params,
                                 is_method: class_name.is_some(),
+                                kind: if class_name.is_some() {
+                                    FunctionType::Method
+                                } else {
+                                    FunctionType::Function
+                                },
                                 class_name: class_name.map(|s| s.to_string()),
-                                is_async: is_async_def(node, source),
-                                is_generator: is_generator_def(node, source),
+                                is_async: is_async_def(node),
+                                is_generator: is_generator_def(node),
                                 decorators: extract_decorators(node, source),
                             });
+                            if let Some(body) = body_node {
+                                collect_lambdas(body, source, functions);
+                            }
                         }
                     }
                 }
@@ -115,26 +210,44 @@ impl PythonParser {
                                             .unwrap_or(0),
                                         parameters: params,
                                         is_method: class_name.is_some(),
+                                        kind: if class_name.is_some() {
+                                            FunctionType::Method
+                                        } else {
+                                            FunctionType::Function
+                                        },
                                         class_name: class_name.map(|s| s.to_string()),
-                                        is_async: is_async_def(child, source),
-                                        is_generator: is_generator_def(child, source),
+                                        is_async: is_async_def(child),
+                                        is_generator: is_generator_def(child),
                                         decorators: extract_decorators(child, source),
                                     });
+                                    if let Some(body) = body_node {
+                                        collect_lambdas(body, source, functions);
+                                    }
                                 }
                             }
                         }
                     }
                 }
+                // The `lambda` keyword token shares the node's kind
+                "lambda" if node.is_named() => {
+                    push_lambda(node, source, functions);
+                    let mut subcursor = node.walk();
+                    for child in node.children(&mut subcursor) {
+                        visit_node(child, source, functions, class_name, depth + 1);
+                    }
+                }
                 "class_definition" => {
-                    // Don't recurse into nested classes when we're already in a class
-                    if class_name.is_none() {
-                        if let Some(name_node) = node.child_by_field_name("name") {
-                            if let Ok(name) = name_node.utf8_text(source.as_bytes()) {
-                                // Recursively extract methods from this class
-                                let mut subcursor = node.walk();
-                                for child in node.children(&mut subcursor) {
-                                    visit_node(child, source, functions, Some(name));
-                                }
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        if let Ok(name) = name_node.utf8_text(source.as_bytes()) {
+                            // Nested classes are named after their enclosing ones
+                            let qualified = match class_name {
+                                Some(outer) => format!("{outer}.{name}"),
+                                None => name.to_string(),
+                            };
+                            // Recursively extract methods from this class
+                            let mut subcursor = node.walk();
+                            for child in node.children(&mut subcursor) {
+                                visit_node(child, source, functions, Some(&qualified), depth + 1);
                             }
                         }
                     }
@@ -143,81 +256,118 @@ impl PythonParser {
                     // Continue traversing for other node types
                     let mut subcursor = node.walk();
                     for child in node.children(&mut subcursor) {
-                        visit_node(child, source, functions, class_name);
+                        visit_node(child, source, functions, class_name, depth + 1);
                     }
                 }
             }
         }
 
-        fn is_async_def(node: Node, source: &str) -> bool {
-            if let Ok(text) = node.utf8_text(source.as_bytes()) {
-                text.starts_with("async ")
-            } else {
-                false
+        /// Add the lambdas found anywhere under `node`, such as a function body
+        fn collect_lambdas(node: Node, source: &str, functions: &mut Vec<GenericFunctionDef>) {
+            // Walked with an explicit stack, since expressions may nest arbitrarily deep
+            let mut cursor = node.walk();
+            let mut stack: Vec<Node> = node.children(&mut cursor).collect();
+            stack.reverse();
+            while let Some(node) = stack.pop() {
+                if node.kind() == "lambda" && node.is_named() {
+                    push_lambda(node, source, functions);
+                }
+                let mut cursor = node.walk();
+                let children: Vec<Node> = node.children(&mut cursor).collect();
+                stack.extend(children.into_iter().rev());
             }
         }
 
-        fn is_generator_def(node: Node, source: &str) -> bool {
-            // Python generators are functions that contain yield statements
-            // For simplicity, we'll just check if the function body contains "yield"
-            if let Some(body) = node.child_by_field_name("body") {
-                if let Ok(body_text) = body.utf8_text(source.as_bytes()) {
-                    return body_text.contains("yield");
-                }
-            }
-            false
+        fn push_lambda(node: Node, source: &str, functions: &mut Vec<GenericFunctionDef>) {
+            let line = node.start_position().row as u32 + 1;
+            let body_node = node.child_by_field_name("body");
+            functions.push(GenericFunctionDef {
+                name: format!("lambda@{line}"),
+                start_line: line,
+                end_line: node.end_position().row as u32 + 1,
+                body_start_line: body_node.map(|n| n.start_position().row as u32 + 1).unwrap_or(0),
+                body_end_line: body_node.map(|n| n.end_position().row as u32 + 1).unwrap_or(0),
+                parameters: extract_params(node.child_by_field_name("parameters"), source),
+                is_method: false,
+                kind: FunctionType::Closure,
+                class_name: None,
+                is_async: false,
+                is_generator: false,
+                decorators: Vec::new(),
+            });
         }
 
-        fn extract_decorators(node: Node, source: &str) -> Vec<String> {
-            let mut decorators = Vec::new();
+        fn is_async_def(node: Node) -> bool {
             let mut cursor = node.walk();
+            let is_async = node.children(&mut cursor).any(|child| child.kind() == "async");
+            is_async
+        }
 
-            // Look for decorator nodes before the function definition
-            if let Some(parent) = node.parent() {
-                for child in parent.children(&mut cursor) {
-                    if child.kind() == "decorator"
-                        && child.end_position().row < node.start_position().row
-                    {
-                        if let Ok(decorator_text) = child.utf8_text(source.as_bytes()) {
-                            decorators.push(decorator_text.trim_start_matches('@').to_string());
-                        }
+        fn is_generator_def(node: Node) -> bool {
+            node.child_by_field_name("body").is_some_and(contains_yield)
+        }
+
+        /// Whether `node` yields, leaving out the functions and classes nested in it
+        fn contains_yield(node: Node) -> bool {
+            let mut stack = vec![node];
+            while let Some(node) = stack.pop() {
+                let mut cursor = node.walk();
+                for child in node.children(&mut cursor) {
+                    match child.kind() {
+                        "yield" if child.is_named() => return true,
+                        "function_definition" | "lambda" | "class_definition" => {}
+                        _ => stack.push(child),
                     }
                 }
             }
+            false
+        }
 
+        /// The decorator expressions of a function, without their `@`
+        fn extract_decorators(node: Node, source: &str) -> Vec<String> {
+            let Some(parent) = node.parent().filter(|p| p.kind() == "decorated_definition") else {
+                return Vec::new();
+            };
+
+            let mut cursor = parent.walk();
+            let decorators = parent
+                .named_children(&mut cursor)
+                .filter(|child| child.kind() == "decorator")
+                .filter_map(|decorator| decorator.named_child(0))
+                .filter_map(|expr| expr.utf8_text(source.as_bytes()).ok())
+                .map(str::to_string)
+                .collect();
             decorators
         }
 
         fn extract_params(params_node: Option<Node>, source: &str) -> Vec<String> {
             if let Some(node) = params_node {
-                let mut params = Vec::new();
                 let mut cursor = node.walk();
-
-                for child in node.children(&mut cursor) {
-                    match child.kind() {
-                        "identifier" => {
-                            if let Ok(param_text) = child.utf8_text(source.as_bytes()) {
-                                params.push(param_text.to_string());
-                            }
-                        }
-                        "typed_parameter" | "default_parameter" => {
-                            if let Some(ident) = child.child_by_field_name("name") {
-                                if let Ok(param_text) = ident.utf8_text(source.as_bytes()) {
-                                    params.push(param_text.to_string());
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-
-                params
+                // `/` and `*` separators have no name and are skipped
+                node.named_children(&mut cursor)
+                    .filter_map(|child| param_name(child, source))
+                    .collect()
             } else {
                 Vec::new()
             }
         }
 
-        visit_node(node, source, &mut functions, class_name);
+        fn param_name(node: Node, source: &str) -> Option<String> {
+            match node.kind() {
+                "identifier" => node.utf8_text(source.as_bytes()).ok().map(str::to_string),
+                "default_parameter" | "typed_default_parameter" => {
+                    param_name(node.child_by_field_name("name")?, source)
+                }
+                // `*args`, `**kwargs` and their typed forms keep the name in
+                // their first named child
+                "typed_parameter" | "list_splat_pattern" | "dictionary_splat_pattern" => {
+                    param_name(node.named_child(0)?, source)
+                }
+                _ => None,
+            }
+        }
+
+        visit_node(node, source, &mut functions, class_name, 0);
         functions
     }
 }
@@ -237,7 +387,7 @@ impl LanguageParser for PythonParser {
 
         let root_node = tree.root_node();
         let mut id_counter = 0;
-        Ok(Rc::new(self.convert_node(root_node, source, &mut id_counter)))
+        Ok(Rc::new(Self::convert_node(root_node, source, &mut id_counter)))
     }
 
     fn extract_functions(
@@ -272,18 +422,46 @@ impl LanguageParser for PythonParser {
         let mut types = Vec::new();
 
         fn visit_node_for_types(node: Node, source: &str, types: &mut Vec<GenericTypeDef>) {
-            if node.kind() == "class_definition" {
-                if let Some(name_node) = node.child_by_field_name("name") {
-                    if let Ok(name) = name_node.utf8_text(source.as_bytes()) {
-                        types.push(GenericTypeDef {
-                            name: name.to_string(),
-                            kind: "class".to_string(),
-                            start_line: node.start_position().row as u32 + 1,
-                            end_line: node.end_position().row as u32 + 1,
-                            fields: extract_class_fields(node, source),
-                        });
+            match node.kind() {
+                "class_definition" => {
+                    if let Some(name_node) = node.child_by_field_name("name") {
+                        if let Ok(name) = name_node.utf8_text(source.as_bytes()) {
+                            let kind = structural_base(node, source).or_else(|| {
+                                is_dataclass(node, source).then_some(TypeDefKind::DataClass)
+                            });
+                            // Structural types are described by their annotated fields
+                            let (kind, fields) = match kind {
+                                Some(kind) => (kind, extract_annotated_fields(node, source)),
+                                None => (TypeDefKind::Class, extract_class_fields(node, source)),
+                            };
+                            types.push(GenericTypeDef {
+                                name: name.to_string(),
+                                kind: kind.as_str().to_string(),
+                                start_line: node.start_position().row as u32 + 1,
+                                end_line: node.end_position().row as u32 + 1,
+                                fields,
+                            });
+                        }
+                    }
+                }
+                "type_alias_statement" => {
+                    if let Some(left) = node.child_by_field_name("left") {
+                        push_type_alias(node, left, source, types);
                     }
                 }
+                "assignment" => {
+                    // Only module-level assignments declare aliases
+                    let at_module_level = node
+                        .parent()
+                        .and_then(|statement| statement.parent())
+                        .is_some_and(|parent| parent.kind() == "module");
+                    if at_module_level && is_type_alias_assignment(node, source) {
+                        if let Some(left) = node.child_by_field_name("left") {
+                            push_type_alias(node, left, source, types);
+                        }
+                    }
+                }
+                _ => {}
             }
 
             // Continue traversing
@@ -293,6 +471,104 @@ impl LanguageParser for PythonParser {
             }
         }
 
+        fn push_type_alias(
+            node: Node,
+            name_node: Node,
+            source: &str,
+            types: &mut Vec<GenericTypeDef>,
+        ) {
+            if let Ok(name) = name_node.utf8_text(source.as_bytes()) {
+                types.push(GenericTypeDef {
+                    name: name.to_string(),
+                    kind: "type_alias".to_string(),
+                    start_line: node.start_position().row as u32 + 1,
+                    end_line: node.end_position().row as u32 + 1,
+                    fields: Vec::new(),
+                });
+            }
+        }
+
+        /// Last segment of a dotted name, e.g. `typing.TypedDict` -> `TypedDict`
+        fn short_name(text: &str) -> &str {
+            text.rsplit('.').next().unwrap_or(text)
+        }
+
+        /// `X: TypeAlias = ...` or `X = TypeVar(...)`
+        fn is_type_alias_assignment(node: Node, source: &str) -> bool {
+            if let Some(annotation) = node.child_by_field_name("type") {
+                return annotation
+                    .utf8_text(source.as_bytes())
+                    .is_ok_and(|text| short_name(text.trim()) == "TypeAlias");
+            }
+            node.child_by_field_name("right")
+                .filter(|right| right.kind() == "call")
+                .and_then(|call| call.child_by_field_name("function"))
+                .and_then(|function| function.utf8_text(source.as_bytes()).ok())
+                .is_some_and(|function| short_name(function) == "TypeVar")
+        }
+
+        /// `TypedDict` or `NamedTuple` when the class derives from one of them
+        fn structural_base(node: Node, source: &str) -> Option<TypeDefKind> {
+            let superclasses = node.child_by_field_name("superclasses")?;
+            let mut cursor = superclasses.walk();
+            let kind = superclasses.named_children(&mut cursor).find_map(|base| {
+                match short_name(base.utf8_text(source.as_bytes()).ok()?) {
+                    "TypedDict" => Some(TypeDefKind::TypedDict),
+                    "NamedTuple" => Some(TypeDefKind::NamedTuple),
+                    _ => None,
+                }
+            });
+            kind
+        }
+
+        fn is_dataclass(node: Node, source: &str) -> bool {
+            let Some(parent) = node.parent().filter(|p| p.kind() == "decorated_definition") else {
+                return false;
+            };
+            let mut cursor = parent.walk();
+            let is_dataclass = parent.children(&mut cursor).any(|child| {
+                child.kind() == "decorator"
+                    && child.utf8_text(source.as_bytes()).is_ok_and(|text| {
+                        let decorator = text.trim_start_matches('@').trim();
+                        let decorator = decorator.split('(').next().unwrap_or(decorator);
+                        short_name(decorator) == "dataclass"
+                    })
+            });
+            is_dataclass
+        }
+
+        /// Class-level annotated fields (`name: type [= default]`) as `name: type`
+        fn extract_annotated_fields(node: Node, source: &str) -> Vec<String> {
+            let mut fields = Vec::new();
+
+            if let Some(body) = node.child_by_field_name("body") {
+                let mut cursor = body.walk();
+                for statement in body.children(&mut cursor) {
+                    if statement.kind() != "expression_statement" {
+                        continue;
+                    }
+                    let Some(assignment) =
+                        statement.named_child(0).filter(|n| n.kind() == "assignment")
+                    else {
+                        continue;
+                    };
+                    let (Some(left), Some(annotation)) = (
+                        assignment.child_by_field_name("left"),
+                        assignment.child_by_field_name("type"),
+                    ) else {
+                        continue;
+                    };
+                    if let (Ok(name), Ok(annotation)) =
+                        (left.utf8_text(source.as_bytes()), annotation.utf8_text(source.as_bytes()))
+                    {
+                        fields.push(format!("{name}: {annotation}"));
+                    }
+                }
+            }
+
+            fields
+        }
+
         fn extract_class_fields(node: Node, source: &str) -> Vec<String> {
             let mut fields = Vec::new();
 
@@ -384,6 +660,128 @@ class Calculator:
         assert!(functions[3].is_method);
     }
 
+    #[test]
+    fn test_variadic_parameters() {
+        let mut parser = PythonParser::new().unwrap();
+        let source = r#"
+def f(a, *args, b=1, **kwargs):
+    return a
+
+def g(a: int, /, *, b: int = 1, *rest: str, **options: str):
+    return a
+"#;
+
+        let functions = parser.extract_functions(source, "test.py").unwrap();
+        assert_eq!(functions[0].parameters, vec!["a", "args", "b", "kwargs"]);
+        assert_eq!(functions[1].parameters, vec!["a", "b", "rest", "options"]);
+    }
+
+    #[test]
+    fn test_lambdas() {
+        let mut parser = PythonParser::new().unwrap();
+        let source = r#"
+square = lambda x: x * x
+twice = lambda x: x * x
+
+def sort_users(users):
+    return sorted(users, key=lambda user, *rest: user.name)
+"#;
+
+        let functions = parser.extract_functions(source, "test.py").unwrap();
+        let lambdas: Vec<_> = functions.iter().filter(|f| f.name.starts_with("lambda@")).collect();
+        assert_eq!(lambdas.len(), 3);
+        assert_eq!(lambdas[0].name, "lambda@2");
+        assert_eq!(lambdas[0].parameters, vec!["x"]);
+        assert_eq!(lambdas[1].name, "lambda@3");
+        assert_eq!(lambdas[1].parameters, vec!["x"]);
+        assert_eq!(lambdas[2].name, "lambda@6");
+        assert_eq!(lambdas[2].parameters, vec!["user", "rest"]);
+        assert_eq!(lambdas[2].body_start_line, 6);
+    }
+
+    #[test]
+    fn test_async_and_generator_flags() {
+        let mut parser = PythonParser::new().unwrap();
+        let source = r#"
+def load(path):
+    yielded = "yield"
+    return open(path).read()
+
+async def fetch(url):
+    return await client.get(url)
+
+def lines(path):
+    for line in open(path):
+        yield line.strip()
+
+def outer():
+    def inner():
+        yield 1
+    return inner
+"#;
+
+        let functions = parser.extract_functions(source, "test.py").unwrap();
+        let flags: Vec<_> =
+            functions.iter().map(|f| (f.name.as_str(), f.is_async, f.is_generator)).collect();
+        assert_eq!(
+            flags,
+            vec![
+                ("load", false, false),
+                ("fetch", true, false),
+                ("lines", false, true),
+                ("outer", false, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_class_methods() {
+        let mut parser = PythonParser::new().unwrap();
+        let source = r#"
+class Outer:
+    def run(self):
+        return self.Inner().value()
+
+    class Inner:
+        def value(self):
+            return 1
+"#;
+
+        let functions = parser.extract_functions(source, "test.py").unwrap();
+        assert_eq!(functions.len(), 2);
+        assert_eq!(functions[0].class_name, Some("Outer".to_string()));
+        assert_eq!(functions[1].name, "value");
+        assert!(functions[1].is_method);
+        assert_eq!(functions[1].class_name, Some("Outer.Inner".to_string()));
+    }
+
+    #[test]
+    fn test_decorators() {
+        let mut parser = PythonParser::new().unwrap();
+        let source = r#"
+class Config:
+    @staticmethod
+    def default():
+        return Config()
+
+    @property
+    @cache( maxsize=1 )
+    def path(self):
+        return self._path
+
+    def reload(self):
+        self._path = None
+"#;
+
+        let functions = parser.extract_functions(source, "test.py").unwrap();
+        assert_eq!(functions.len(), 3);
+        assert_eq!(functions[0].decorators, vec!["staticmethod"]);
+        assert!(functions[0].is_method);
+        assert_eq!(functions[1].decorators, vec!["property", "cache( maxsize=1 )"]);
+        assert!(functions[1].is_method);
+        assert!(functions[2].decorators.is_empty());
+    }
+
     #[test]
     fn test_python_classes() {
         let mut parser = PythonParser::new().unwrap();
@@ -404,4 +802,184 @@ class Admin(User):
         assert_eq!(types[0].kind, "class");
         assert_eq!(types[1].name, "Admin");
     }
+
+    #[test]
+    fn test_python_typed_dicts_dataclasses_and_aliases() {
+        let mut parser = PythonParser::new().unwrap();
+        let source = r#"
+from dataclasses import dataclass
+from typing import TypeAlias, TypedDict, TypeVar
+
+T = TypeVar("T")
+UserId: TypeAlias = int
+type Callback = Callable[[int], None]
+
+class Movie(TypedDict):
+    title: str
+    year: int
+
+@dataclass(frozen=True)
+class Point:
+    x: float
+    y: float = 0.0
+
+    def norm(self) -> float:
+        local: int = 0
+        return (self.x ** 2 + self.y ** 2) ** 0.5
+
+def helper():
+    Local = TypeVar("Local")
+"#;
+
+        let types = parser.extract_types(source, "test.py").unwrap();
+        let summary: Vec<(&str, &str)> =
+            types.iter().map(|t| (t.name.as_str(), t.kind.as_str())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("T", "type_alias"),
+                ("UserId", "type_alias"),
+                ("Callback", "type_alias"),
+                ("Movie", "typed_dict"),
+                ("Point", "dataclass"),
+            ]
+        );
+        assert_eq!(types[3].fields, vec!["title: str", "year: int"]);
+        assert_eq!(types[4].fields, vec!["x: float", "y: float"]);
+    }
+
+    #[test]
+    fn test_python_structural_type_kinds_and_fields() {
+        let mut parser = PythonParser::new().unwrap();
+        let source = r#"
+@dataclasses.dataclass
+class Order:
+    id: int
+    customer: str
+    total: float = 0.0
+    TAX_RATE = 0.2
+
+    def with_tax(self) -> float:
+        taxed: float = self.total * (1 + self.TAX_RATE)
+        return taxed
+
+class P(TypedDict, total=False):
+    name: str
+    age: int
+
+class Pair(typing.NamedTuple):
+    left: int
+    right: int
+"#;
+
+        let types = parser.extract_types(source, "models.py").unwrap();
+        let summary: Vec<(&str, &str, Vec<&str>)> = types
+            .iter()
+            .map(|t| {
+                (t.name.as_str(), t.kind.as_str(), t.fields.iter().map(String::as_str).collect())
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "Order",
+                    TypeDefKind::DataClass.as_str(),
+                    vec!["id: int", "customer: str", "total: float"]
+                ),
+                ("P", TypeDefKind::TypedDict.as_str(), vec!["name: str", "age: int"]),
+                ("Pair", TypeDefKind::NamedTuple.as_str(), vec!["left: int", "right: int"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identical_dataclasses_are_duplicate_types() {
+        use similarity_core::{find_duplicate_types, TypeComparisonOptions, TypeDefinition};
+
+        let mut parser = PythonParser::new().unwrap();
+        let source = r#"
+@dataclass
+class UserRecord:
+    id: int
+    name: str
+    email: str
+
+@dataclass
+class CustomerRecord:
+    email: str
+    id: int
+    name: str
+
+@dataclass
+class Settings:
+    theme: str
+    retries: int
+"#;
+
+        let types: Vec<TypeDefinition> = parser
+            .extract_types(source, "models.py")
+            .unwrap()
+            .iter()
+            .map(|def| TypeDefinition::from_generic(def, "models.py"))
+            .collect();
+
+        let duplicates = find_duplicate_types(&types, 0.9, &TypeComparisonOptions::default());
+        assert_eq!(duplicates.len(), 1);
+        let names = [duplicates[0].type1.name.as_str(), duplicates[0].type2.name.as_str()];
+        assert!(names.contains(&"UserRecord") && names.contains(&"CustomerRecord"));
+    }
+
+    #[test]
+    fn test_tree_matches_recursive_conversion() {
+        fn convert_recursively(node: Node, source: &str, id_counter: &mut usize) -> TreeNode {
+            let (mut tree_node, children) = PythonParser::open_node(node, source, id_counter);
+            for child in children {
+                tree_node.add_child(Rc::new(convert_recursively(child, source, id_counter)));
+            }
+            tree_node
+        }
+
+        let source = r#"
+class Stack:
+    def push(self, item):
+        self.items.append(item)
+        return len(self.items) > 10 and not self.frozen
+
+def total(values, start=0):
+    return sum(v * 2 for v in values if v is not None) + start
+"#;
+        let mut parser = PythonParser::new().unwrap();
+        let tree = parser.parse(source, "stack.py").unwrap();
+
+        let syntax_tree = parser.parser.parse(source, None).unwrap();
+        let expected = convert_recursively(syntax_tree.root_node(), source, &mut 0);
+
+        fn assert_same_nodes(node: &TreeNode, expected: &TreeNode) {
+            assert_eq!(
+                (node.id, &node.label, &node.value, node.children.len()),
+                (expected.id, &expected.label, &expected.value, expected.children.len())
+            );
+            for (child, expected) in node.children.iter().zip(&expected.children) {
+                assert_same_nodes(child, expected);
+            }
+        }
+
+        assert_eq!(tree.get_subtree_size(), expected.get_subtree_size());
+        assert_same_nodes(&tree, &expected);
+    }
+
+    #[test]
+    fn test_deeply_nested_source_does_not_overflow() {
+        let nesting = 10_000;
+        let source = format!("x = {}1{}\n", "(".repeat(nesting), ")".repeat(nesting));
+
+        let mut parser = PythonParser::new().unwrap();
+        let tree = parser.parse(&source, "deep.py").unwrap();
+
+        // The literal is nested too deep to be kept
+        assert!(tree.iter().any(|node| node.label == "truncated"));
+        assert!(!tree.iter().any(|node| node.label == "integer"));
+        assert!(tree.get_subtree_size() < nesting);
+    }
 }
@@ -163,3 +163,41 @@ def longer_func2():
         .stdout(predicate::str::contains("longer_func2"))
         .stdout(predicate::str::contains("f1").not());
 }
+
+#[test]
+fn test_deeply_nested_file_does_not_crash() {
+    let dir = tempdir().unwrap();
+    let nesting = 10_000;
+    let deep = format!("def deep():\n    return {}1{}\n", "(".repeat(nesting), ")".repeat(nesting));
+    fs::write(dir.path().join("deep.py"), format!("{deep}\n{}", deep.replace("deep", "deeper")))
+        .unwrap();
+    fs::write(
+        dir.path().join("items.py"),
+        r#"
+def process_items(items):
+    result = []
+    for item in items:
+        if item > 0:
+            result.append(item * 2)
+    return result
+
+def handle_items(data):
+    output = []
+    for d in data:
+        if d > 0:
+            output.append(d * 2)
+    return output
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("similarity-py")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--threshold")
+        .arg("0.8")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("process_items"))
+        .stdout(predicate::str::contains("handle_items"));
+}
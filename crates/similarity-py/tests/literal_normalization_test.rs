@@ -0,0 +1,41 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::{tsed::calculate_tsed, TSEDOptions};
+use similarity_py::python_parser::PythonParser;
+
+fn fstring_similarity(code1: &str, code2: &str, ignore_literals: bool) -> f64 {
+    let mut parser = PythonParser::new().unwrap();
+    let tree1 = parser.parse(code1, "a.py").unwrap();
+    let tree2 = parser.parse(code2, "b.py").unwrap();
+
+    let mut options = TSEDOptions::default();
+    options.apted_options.compare_values = true;
+    options.size_penalty = false;
+    options.ignore_literals = ignore_literals;
+    calculate_tsed(&tree1, &tree2, &options)
+}
+
+const GREETING: &str = r#"
+def greet(user):
+    return f"Hello {user.name}, you have {len(user.messages)} new messages"
+"#;
+
+const FAREWELL: &str = r#"
+def greet(user):
+    return f"Goodbye {user.name}! {len(user.messages)} unread"
+"#;
+
+const OTHER_FIELDS: &str = r#"
+def greet(user):
+    return f"Hello {user.email}, you have {user.id} new messages"
+"#;
+
+#[test]
+fn test_fstrings_with_same_interpolations_match_when_ignoring_literals() {
+    assert_eq!(fstring_similarity(GREETING, FAREWELL, true), 1.0);
+    assert!(fstring_similarity(GREETING, FAREWELL, false) < 1.0);
+}
+
+#[test]
+fn test_interpolated_expressions_are_still_compared() {
+    assert!(fstring_similarity(GREETING, OTHER_FIELDS, true) < 1.0);
+}
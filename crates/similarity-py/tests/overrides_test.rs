@@ -0,0 +1,65 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_overrides_flags_method_copied_from_parent() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("shapes.py");
+
+    let content = r#"
+class Shape:
+    def describe(self):
+        parts = []
+        for key, value in self.__dict__.items():
+            parts.append(f"{key}={value}")
+        return ", ".join(parts)
+
+    def area(self):
+        return 0
+
+
+class Square(Shape):
+    def describe(self):
+        items = []
+        for key, value in self.__dict__.items():
+            items.append(f"{key}={value}")
+        return ", ".join(items)
+
+    def area(self):
+        total = self.side * self.side
+        if total < 0:
+            raise ValueError("negative area")
+        return total
+"#;
+
+    fs::write(&file_path, content).unwrap();
+
+    Command::cargo_bin("similarity-py")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--overrides")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=== Redundant Overrides ==="))
+        .stdout(predicate::str::contains("Square.describe overrides"))
+        .stdout(predicate::str::contains("Shape.describe"))
+        .stdout(predicate::str::contains("Square.area overrides").not())
+        .stdout(predicate::str::contains("Total redundant overrides: 1"));
+}
+
+#[test]
+fn test_overrides_not_reported_without_flag() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("base.py");
+
+    fs::write(&file_path, "class A:\n    def f(self):\n        return 1\n").unwrap();
+
+    Command::cargo_bin("similarity-py")
+        .unwrap()
+        .arg(&file_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Redundant Overrides").not());
+}
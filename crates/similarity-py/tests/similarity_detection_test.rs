@@ -41,11 +41,13 @@ def compute_total(values):
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: false,
+            ..Default::default()
         },
         min_lines: 3,
         min_tokens: None,
         size_penalty: false, // Disable for this test
         skip_test: false,
+        ..Default::default()
     };
 
     let similarity = calculate_tsed(&tree1, &tree2, &tsed_options);
@@ -80,6 +82,7 @@ def multiply(x, y):
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: true,
+            ..Default::default()
         },
     };
 
@@ -115,11 +118,13 @@ def filter_positive(numbers):
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: false,
+            ..Default::default()
         },
         min_lines: 1,
         min_tokens: None,
         size_penalty: true, // Enable size penalty
         skip_test: false,
+        ..Default::default()
     };
 
     let similarity = calculate_tsed(&tree1, &tree2, &tsed_options);
@@ -161,11 +166,13 @@ class MathOperations:
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: false,
+            ..Default::default()
         },
         min_lines: 3,
         min_tokens: None,
         size_penalty: false,
         skip_test: false,
+        ..Default::default()
     };
 
     let similarity = calculate_tsed(&tree1, &tree2, &tsed_options);
@@ -198,11 +205,13 @@ def title(self):
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: false,
+            ..Default::default()
         },
         min_lines: 1,
         min_tokens: None,
         size_penalty: false,
         skip_test: false,
+        ..Default::default()
     };
 
     let similarity = calculate_tsed(&tree1, &tree2, &tsed_options);
@@ -237,6 +246,7 @@ def bar():
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: true, // Compare values to detect different function names
+            ..Default::default()
         },
     };
 
@@ -273,11 +283,13 @@ def get_numbers(n):
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: false,
+            ..Default::default()
         },
         min_lines: 1,
         min_tokens: None,
         size_penalty: true,
         skip_test: false,
+        ..Default::default()
     };
 
     let similarity = calculate_tsed(&tree1, &tree2, &tsed_options);
@@ -311,11 +323,13 @@ async def get_data(endpoint):
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: false,
+            ..Default::default()
         },
         min_lines: 1,
         min_tokens: None,
         size_penalty: false,
         skip_test: false,
+        ..Default::default()
     };
 
     let similarity = calculate_tsed(&tree1, &tree2, &tsed_options);
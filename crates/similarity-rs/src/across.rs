@@ -0,0 +1,104 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::patterns::parse_functions;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_output::format_function_output,
+    language_parser::GenericFunctionDef,
+    tree::TreeNode,
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// A parsed function and the side of the boundary its file is under
+struct BoundaryFunction {
+    file: PathBuf,
+    func: GenericFunctionDef,
+    tree: Rc<TreeNode>,
+    in_a: bool,
+    in_b: bool,
+}
+
+/// Report duplicates where one function is under `dir_a` and the other under
+/// `dir_b`, e.g. code copied from `frontend/` that belongs in `shared/`.
+/// Pairs are kept by path prefix; duplicates within one directory are not reported.
+pub fn check_across(
+    dir_a: &Path,
+    dir_b: &Path,
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    for dir in [dir_a, dir_b] {
+        if !dir.is_dir() {
+            anyhow::bail!("{} is not a directory", dir.display());
+        }
+    }
+
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let mut files = file_filter.collect(
+        &[dir_a.to_string_lossy().to_string(), dir_b.to_string_lossy().to_string()],
+        &exts,
+    )?;
+    // Nested directories would otherwise yield the same file twice
+    files.sort();
+    files.dedup();
+
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut functions = Vec::new();
+    for file in &files {
+        let in_a = file.starts_with(dir_a);
+        let in_b = file.starts_with(dir_b);
+        for (func, tree) in parse_functions(&mut parser, file, options.skip_parse_errors)? {
+            if func.end_line - func.start_line + 1 < options.min_lines {
+                continue;
+            }
+            functions.push(BoundaryFunction { file: file.clone(), func, tree, in_a, in_b });
+        }
+    }
+
+    println!("Comparing functions across {} and {}", dir_a.display(), dir_b.display());
+
+    let mut similar = Vec::new();
+    for i in 0..functions.len() {
+        for j in (i + 1)..functions.len() {
+            let (f1, f2) = (&functions[i], &functions[j]);
+            if !((f1.in_a && f2.in_b) || (f1.in_b && f2.in_a)) {
+                continue;
+            }
+            let similarity = calculate_tsed(&f1.tree, &f2.tree, options);
+            if similarity >= threshold {
+                similar.push((i, j, similarity));
+            }
+        }
+    }
+    similar.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+
+    if similar.is_empty() {
+        println!("\nNo duplicates across the two directories found!");
+        return Ok(());
+    }
+
+    for (i, j, similarity) in &similar {
+        println!("\n  {} <-> {}", describe(&functions[*i]), describe(&functions[*j]));
+        println!("  Similarity: {:.2}%", similarity * 100.0);
+    }
+
+    println!("\nTotal duplicates across directories: {}", similar.len());
+
+    Ok(())
+}
+
+fn describe(function: &BoundaryFunction) -> String {
+    format_function_output(
+        &function.file.to_string_lossy(),
+        &function.func.name,
+        function.func.start_line,
+        function.func.end_line,
+    )
+}
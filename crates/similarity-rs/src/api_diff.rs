@@ -0,0 +1,143 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::rust_parser::RustParser;
+use similarity_core::language_parser::LanguageParser;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// A public function or type declaration
+struct ApiItem {
+    kind: String,
+    signature: String,
+}
+
+/// Compare the public surface of two versions of a crate and print the
+/// added, removed and signature-changed items.
+///
+/// Only declarations written with a `pub` visibility are considered. Items
+/// are keyed by their file (relative to the given root) and qualified name,
+/// so a moved item shows up as removed and added.
+pub fn api_diff(old_root: &Path, new_root: &Path, file_filter: &FileFilter) -> anyhow::Result<()> {
+    let old_api = collect_api(old_root, file_filter)?;
+    let new_api = collect_api(new_root, file_filter)?;
+
+    let added: Vec<_> = new_api.iter().filter(|(key, _)| !old_api.contains_key(*key)).collect();
+    let removed: Vec<_> = old_api.iter().filter(|(key, _)| !new_api.contains_key(*key)).collect();
+    let changed: Vec<_> = old_api
+        .iter()
+        .filter_map(|(key, old)| {
+            new_api.get(key).filter(|new| new.signature != old.signature).map(|new| (key, old, new))
+        })
+        .collect();
+
+    println!("API diff: {} -> {}", old_root.display(), new_root.display());
+
+    if added.is_empty() && removed.is_empty() && changed.is_empty() {
+        println!("\nNo API changes found.");
+        return Ok(());
+    }
+
+    if !added.is_empty() {
+        println!("\nAdded ({}):", added.len());
+        for (key, item) in &added {
+            println!("  + {} {}", item.kind, key);
+            println!("      {}", item.signature);
+        }
+    }
+
+    if !removed.is_empty() {
+        println!("\nRemoved ({}):", removed.len());
+        for (key, item) in &removed {
+            println!("  - {} {}", item.kind, key);
+            println!("      {}", item.signature);
+        }
+    }
+
+    if !changed.is_empty() {
+        println!("\nSignature changed ({}):", changed.len());
+        for (key, old, new) in &changed {
+            println!("  ~ {} {}", new.kind, key);
+            println!("      old: {}", old.signature);
+            println!("      new: {}", new.signature);
+        }
+    }
+
+    Ok(())
+}
+
+/// Extract the public functions and types of every Rust file under `root`
+fn collect_api(root: &Path, file_filter: &FileFilter) -> anyhow::Result<BTreeMap<String, ApiItem>> {
+    if !root.is_dir() {
+        anyhow::bail!("{} is not a directory", root.display());
+    }
+
+    let files = file_filter.collect(&[root.to_string_lossy().to_string()], &["rs"])?;
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut api = BTreeMap::new();
+
+    for file in files {
+        let content = fs::read_to_string(&file)?;
+        let relative =
+            file.strip_prefix(root).unwrap_or(&file).to_string_lossy().replace('\\', "/");
+
+        let public = parser
+            .public_items(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", file.display(), e))?;
+
+        let functions = parser
+            .extract_functions(&content, &relative)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", file.display(), e))?;
+        for func in functions {
+            if !public.contains(&func.start_line) {
+                continue;
+            }
+            let signature = declaration_text(&content, func.start_line, func.end_line);
+            let name = match &func.class_name {
+                Some(class_name) => format!("{}::{}", class_name, func.name),
+                None => func.name.clone(),
+            };
+            api.insert(
+                format!("{}::{}", relative, name),
+                ApiItem { kind: "fn".to_string(), signature },
+            );
+        }
+
+        let types = parser
+            .extract_types(&content, &relative)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", file.display(), e))?;
+        for type_def in types {
+            if !public.contains(&type_def.start_line) {
+                continue;
+            }
+            let mut signature = declaration_text(&content, type_def.start_line, type_def.end_line);
+            if !type_def.fields.is_empty() {
+                signature = format!("{} {{ {} }}", signature, type_def.fields.join(", "));
+            }
+            api.insert(
+                format!("{}::{}", relative, type_def.name),
+                ApiItem { kind: type_def.kind.clone(), signature },
+            );
+        }
+    }
+
+    Ok(api)
+}
+
+/// Declaration text of an item up to its body, with whitespace collapsed
+fn declaration_text(content: &str, start_line: u32, end_line: u32) -> String {
+    let text = content
+        .lines()
+        .skip(start_line.saturating_sub(1) as usize)
+        .take((end_line + 1).saturating_sub(start_line) as usize)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let head = match text.find('{') {
+        Some(pos) => &text[..pos],
+        None => text.trim_end().trim_end_matches(';'),
+    };
+
+    head.split_whitespace().collect::<Vec<_>>().join(" ")
+}
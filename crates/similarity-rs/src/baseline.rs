@@ -0,0 +1,96 @@
+//! Baseline files: duplicate pairs accepted as known, so that only new ones
+//! are reported.
+//!
+//! A function is identified by its file, its qualified name and the
+//! structural hash of its tree rather than by its lines, so edits elsewhere
+//! in the file do not turn a known pair into a new one, while a new copy of a
+//! known function still makes new pairs.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use similarity_core::language_parser::GenericFunctionDef;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// Identifies one function of a pair
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FunctionFingerprint {
+    /// The path relative to the directory of the baseline file
+    file: String,
+    /// The name, prefixed with the type of its impl for methods
+    name: String,
+    hash: String,
+}
+
+/// The fingerprints of the two functions of a pair, in sorted order
+pub type PairFingerprint = (FunctionFingerprint, FunctionFingerprint);
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Baseline {
+    pairs: BTreeSet<PairFingerprint>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read baseline {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Invalid baseline {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content + "\n")
+            .with_context(|| format!("Failed to write baseline {}", path.display()))
+    }
+
+    pub fn insert(&mut self, pair: PairFingerprint) {
+        self.pairs.insert(pair);
+    }
+
+    pub fn contains(&self, pair: &PairFingerprint) -> bool {
+        self.pairs.contains(pair)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+}
+
+/// Fingerprint of a pair of functions, each given with its file and the
+/// structural hash of the tree it was compared as. Files are identified
+/// relative to `root`, so the fingerprint does not depend on the directory
+/// the scan runs from or on how its paths are spelled.
+pub fn pair_fingerprint(
+    root: &Path,
+    ends: [(&Path, &GenericFunctionDef, u64); 2],
+) -> PairFingerprint {
+    let [first, second] = ends.map(|(file, func, hash)| FunctionFingerprint {
+        file: relative_path(root, file),
+        name: match &func.class_name {
+            Some(class_name) => format!("{}::{}", class_name, func.name),
+            None => func.name.clone(),
+        },
+        hash: format!("{:016x}", hash),
+    });
+    if first <= second {
+        (first, second)
+    } else {
+        (second, first)
+    }
+}
+
+/// `file` relative to `root` with `/` separators, or its absolute path when
+/// it is outside `root`. `root` is expected to be canonical.
+fn relative_path(root: &Path, file: &Path) -> String {
+    let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    let relative = file.strip_prefix(root).unwrap_or(&file);
+    relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,165 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use similarity_core::cli_output::format_function_output;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use tree_sitter::{Node, Parser};
+
+/// A statement of a function body, in the order of the file
+struct Statement {
+    function: String,
+    start_line: u32,
+    end_line: u32,
+    /// Its tokens, so that formatting and comments do not matter
+    tokens: String,
+}
+
+/// Report runs of at least `block_size` statements repeated within a file.
+///
+/// The statements of all function bodies form one stream, so a repeated run
+/// may end in one function and continue in the next.
+pub fn check_blocks(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    block_size: usize,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = Parser::new();
+    parser.set_language(&tree_sitter_rust::LANGUAGE.into())?;
+
+    let mut total = 0;
+    for file in &files {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        let Some(tree) = parser.parse(&content, None) else {
+            eprintln!("Error parsing {}", file.display());
+            continue;
+        };
+
+        let mut statements = Vec::new();
+        collect_statements(tree.root_node(), &content, &mut statements);
+
+        let file_path = file.to_string_lossy();
+        for (first, second, length) in repeated_runs(&statements, block_size) {
+            let describe = |start: usize| {
+                let run = &statements[start..start + length];
+                let mut functions: Vec<&str> = Vec::new();
+                for statement in run {
+                    if !functions.contains(&statement.function.as_str()) {
+                        functions.push(&statement.function);
+                    }
+                }
+                format_function_output(
+                    &file_path,
+                    &format!("in {}", functions.join(", ")),
+                    run[0].start_line,
+                    run[length - 1].end_line,
+                )
+            };
+            println!("\n  {} <-> {}", describe(first), describe(second));
+            println!("  Statements: {}", length);
+            total += 1;
+        }
+    }
+
+    if total == 0 {
+        println!("\nNo repeated blocks found!");
+    } else {
+        println!("\nTotal repeated blocks: {}", total);
+    }
+
+    Ok(())
+}
+
+fn collect_statements(node: Node, source: &str, statements: &mut Vec<Statement>) {
+    if node.kind() == "function_item" {
+        let name = node.child_by_field_name("name").map(|name| &source[name.byte_range()]);
+        if let (Some(name), Some(body)) = (name, node.child_by_field_name("body")) {
+            for statement in body.named_children(&mut body.walk()) {
+                if statement.is_extra() {
+                    continue;
+                }
+                let mut tokens = Vec::new();
+                collect_tokens(statement, source, &mut tokens);
+                statements.push(Statement {
+                    function: name.to_string(),
+                    start_line: (statement.start_position().row + 1) as u32,
+                    end_line: (statement.end_position().row + 1) as u32,
+                    tokens: tokens.join(" "),
+                });
+            }
+        }
+        return;
+    }
+
+    for child in node.children(&mut node.walk()) {
+        collect_statements(child, source, statements);
+    }
+}
+
+fn collect_tokens<'a>(node: Node, source: &'a str, tokens: &mut Vec<&'a str>) {
+    if node.is_extra() {
+        return;
+    }
+    if node.child_count() == 0 {
+        tokens.push(&source[node.byte_range()]);
+        return;
+    }
+    for child in node.children(&mut node.walk()) {
+        collect_tokens(child, source, tokens);
+    }
+}
+
+/// Maximal non-overlapping runs of identical statements, as
+/// `(first start, second start, length)`
+fn repeated_runs(statements: &[Statement], block_size: usize) -> Vec<(usize, usize, usize)> {
+    if block_size == 0 || statements.len() < block_size * 2 {
+        return Vec::new();
+    }
+
+    // Starts of the windows of `block_size` statements, keyed by their tokens
+    let mut windows: HashMap<Vec<&str>, Vec<usize>> = HashMap::new();
+    for start in 0..=statements.len() - block_size {
+        let key = statements[start..start + block_size].iter().map(|s| s.tokens.as_str()).collect();
+        windows.entry(key).or_default().push(start);
+    }
+    let mut matches = HashSet::new();
+    for starts in windows.values() {
+        for (i, &first) in starts.iter().enumerate() {
+            for &second in &starts[i + 1..] {
+                if second >= first + block_size {
+                    matches.insert((first, second));
+                }
+            }
+        }
+    }
+
+    // Extend each match that does not continue an earlier one as far as it goes
+    let mut runs: Vec<(usize, usize, usize)> = matches
+        .iter()
+        .filter(|&&(first, second)| first == 0 || !matches.contains(&(first - 1, second - 1)))
+        .map(|&(first, second)| {
+            let mut length = block_size;
+            while matches
+                .contains(&(first + length - block_size + 1, second + length - block_size + 1))
+                && first + length < second
+            {
+                length += 1;
+            }
+            (first, second, length)
+        })
+        .collect();
+    runs.sort_unstable();
+    runs
+}
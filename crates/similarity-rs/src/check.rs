@@ -1,14 +1,37 @@
 #![allow(clippy::uninlined_format_args)]
 
-use crate::parallel::check_within_file_duplicates_parallel;
+use crate::baseline::{pair_fingerprint, Baseline};
+use crate::checkpoint::Checkpoint;
+use crate::contracts::leading_contract;
+use crate::parallel::{
+    check_within_file_duplicates_parallel, estimate_comparisons, explain_exclusions,
+    load_files_parallel, sample_functions, LoadOptions, PairCallback, Prefilter, RustFileData,
+};
+use serde::Serialize;
 use similarity_core::{
-    cli_file_utils::collect_files,
-    cli_output::{format_function_output, show_function_code},
-    cli_parallel::SimilarityResult,
-    language_parser::GenericFunctionDef,
+    approximate_vertex_cover,
+    cli_file_utils::{collect_files_with, WalkOptions},
+    cli_output::{
+        escape_identifier, extract_lines_from_content, format_function_output, show_function_code,
+        sort_groups,
+    },
+    cli_parallel::{
+        canonical_order, canonicalize_pairs, run_with_threads, transitive_dedup, LocatedPair,
+        SimilarityResult,
+    },
+    connected_components,
+    function_metrics::FunctionMetrics,
+    language_parser::{GenericFunctionDef, Language, LanguageParser},
+    sarif::{SarifLevel, SarifLocation, SarifResult, SarifWriter},
+    tsed::calculate_tsed,
     TSEDOptions,
 };
-use std::path::PathBuf;
+use similarity_rs::rust_parser::RustParser;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 /// Structure to hold all similarity results
 struct DuplicateResult {
@@ -16,9 +39,194 @@ struct DuplicateResult {
     #[allow(dead_code)]
     file2: PathBuf,
     result: SimilarityResult<GenericFunctionDef>,
+    metrics: Option<(FunctionMetrics, FunctionMetrics)>,
+    /// Similarity of the leading assertions and guards of both functions,
+    /// with `--with-contracts` when both have some
+    contract_similarity: Option<f64>,
+    /// Structural hashes of the trees of both functions, when they were
+    /// compared in this run or taken from a checkpoint
+    tree_hashes: Option<(u64, u64)>,
+}
+
+/// Severity band of a reported pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    /// Identical apart from a few renamed identifiers (98% or more)
+    Exact,
+    /// Similarity of 90% or more
+    High,
+    /// Anything else above the threshold
+    Moderate,
+}
+
+impl Severity {
+    const ALL: [Severity; 3] = [Severity::Exact, Severity::High, Severity::Moderate];
+
+    fn from_similarity(similarity: f64) -> Self {
+        if similarity >= 0.98 {
+            Severity::Exact
+        } else if similarity >= 0.9 {
+            Severity::High
+        } else {
+            Severity::Moderate
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Severity::Exact => "exact.json",
+            Severity::High => "high.json",
+            Severity::Moderate => "moderate.json",
+        }
+    }
+}
+
+/// Format of the similarity report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// One JSON event per line, for editor integrations (`--watch` only)
+    Jsonl,
+    /// A Markdown checklist of the pairs to refactor, grouped by file
+    MarkdownTodo,
+    /// A JSON plan per cluster of duplicates: the function to keep and the ones
+    /// to replace with calls to it
+    Fixplan,
+    /// A SARIF 2.1.0 log, written as pairs are found
+    Sarif,
+    /// A JSON document with the pairs and a summary of the run
+    Json,
+}
+
+impl OutputFormat {
+    /// Whether the report is a document meant for tools or checklists, which
+    /// is printed without banners and keeps stdout to itself
+    pub fn is_document(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::MarkdownTodo
+                | OutputFormat::Fixplan
+                | OutputFormat::Sarif
+                | OutputFormat::Json
+        )
+    }
+}
+
+/// JSON report of `--format json`
+#[derive(Debug, Serialize)]
+pub struct JsonReport {
+    pub pairs: Vec<PairRecord>,
+    pub summary: JsonSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JsonSummary {
+    pub functions_scanned: usize,
+    pub pairs: usize,
+}
+
+/// JSON record of a reported pair, as written by `--split-output`, `--watch`
+/// and `--format json`
+#[derive(Debug, Clone, Serialize)]
+pub struct PairRecord {
+    pub file1: String,
+    pub file2: String,
+    pub similarity: f64,
+    pub function1: FunctionRecord,
+    pub function2: FunctionRecord,
+    /// Source of the first function, with `--embed-source`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source1: Option<String>,
+    /// Source of the second function, with `--embed-source`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source2: Option<String>,
+    /// Similarity of the leading assertions and guards, with `--with-contracts`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_similarity: Option<f64>,
+    /// Metrics of the first function, with `--with-metrics`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics1: Option<MetricsRecord>,
+    /// Metrics of the second function, with `--with-metrics`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics2: Option<MetricsRecord>,
+}
+
+/// JSON record of the metrics printed by `--with-metrics`
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsRecord {
+    pub lines: u32,
+    pub complexity: u32,
+    pub tokens: usize,
+    pub params: usize,
+}
+
+impl MetricsRecord {
+    fn new(metrics: &FunctionMetrics) -> Self {
+        MetricsRecord {
+            lines: metrics.lines,
+            complexity: metrics.complexity,
+            tokens: metrics.subtree_size,
+            params: metrics.parameters,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FunctionRecord {
+    pub name: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    /// `method` for functions of an impl or trait, `function` otherwise
+    pub function_type: &'static str,
+}
+
+impl FunctionRecord {
+    fn new(func: &GenericFunctionDef) -> Self {
+        FunctionRecord {
+            name: func.name.clone(),
+            start_line: func.start_line,
+            end_line: func.end_line,
+            function_type: if func.is_method { "method" } else { "function" },
+        }
+    }
 }
 
 impl DuplicateResult {
+    /// JSON record of the pair; `contents` holds the loaded source of the
+    /// scanned files, from which `--embed-source` takes the function text
+    fn to_record(
+        &self,
+        check_options: &CheckOptions,
+        contents: &HashMap<&PathBuf, &str>,
+    ) -> PairRecord {
+        let source = |file: &PathBuf, func: &GenericFunctionDef| {
+            let content = contents.get(file).filter(|_| check_options.embed_source)?;
+            Some(function_source(content, func, check_options.max_embed_lines))
+        };
+        PairRecord {
+            file1: self.file1.to_string_lossy().to_string(),
+            file2: self.file2.to_string_lossy().to_string(),
+            similarity: self.result.similarity,
+            function1: FunctionRecord::new(&self.result.func1),
+            function2: FunctionRecord::new(&self.result.func2),
+            source1: source(&self.file1, &self.result.func1),
+            source2: source(&self.file2, &self.result.func2),
+            contract_similarity: self.contract_similarity,
+            metrics1: self.metrics.as_ref().map(|(metrics1, _)| MetricsRecord::new(metrics1)),
+            metrics2: self.metrics.as_ref().map(|(_, metrics2)| MetricsRecord::new(metrics2)),
+        }
+    }
+
+    /// Spell the function and type names with ASCII characters only
+    fn escape_names(&mut self) {
+        for func in [&mut self.result.func1, &mut self.result.func2] {
+            func.name = escape_identifier(&func.name);
+            func.class_name = func.class_name.as_deref().map(escape_identifier);
+        }
+    }
+
     fn priority(&self) -> f64 {
         // Score = Similarity × Average lines
         let avg_lines = ((self.result.func1.end_line - self.result.func1.start_line + 1)
@@ -29,154 +237,1269 @@ impl DuplicateResult {
     }
 }
 
-#[allow(clippy::too_many_arguments)]
-pub fn check_paths(
-    paths: Vec<String>,
-    threshold: f64,
-    rename_cost: f64,
-    extensions: Option<&Vec<String>>,
-    min_lines: u32,
-    min_tokens: Option<u32>,
-    no_size_penalty: bool,
-    print: bool,
-    _fast_mode: bool, // Rust doesn't support fast mode yet
-    filter_function: Option<&String>,
-    filter_function_body: Option<&String>,
-    _exclude_patterns: &[String],
-    skip_test: bool,
-) -> anyhow::Result<()> {
+impl LocatedPair for DuplicateResult {
+    fn ends(&self) -> [(&Path, &GenericFunctionDef); 2] {
+        [(&self.file1, &self.result.func1), (&self.file2, &self.result.func2)]
+    }
+
+    fn swap_ends(&mut self) {
+        std::mem::swap(&mut self.file1, &mut self.file2);
+        std::mem::swap(&mut self.result.func1, &mut self.result.func2);
+        if let Some((metrics1, metrics2)) = &mut self.metrics {
+            std::mem::swap(metrics1, metrics2);
+        }
+        if let Some((hash1, hash2)) = &mut self.tree_hashes {
+            std::mem::swap(hash1, hash2);
+        }
+    }
+}
+
+/// Settings for the function similarity scan, mirroring the CLI flags
+pub struct CheckOptions {
+    pub threshold: f64,
+    pub rename_cost: f64,
+    pub extensions: Option<Vec<String>>,
+    pub min_lines: u32,
+    pub min_tokens: Option<u32>,
+    pub no_size_penalty: bool,
+    pub print: bool,
+    #[allow(dead_code)]
+    pub fast_mode: bool, // Rust doesn't support fast mode yet
+    pub filter_function: Option<String>,
+    #[allow(dead_code)]
+    pub filter_function_body: Option<String>, // Body filtering is not implemented yet
+    pub exclude_patterns: Vec<String>,
+    pub include_patterns: Vec<String>,
+    pub no_ignore: bool,
+    pub hidden: bool,
+    pub skip_test: bool,
+    pub skip_trivial_impls: bool,
+    pub include_closures: bool,
+    pub skip_parse_errors: bool,
+    pub max_comparisons: Option<u64>,
+    pub normalize_pipelines: bool,
+    pub normalize_boolean: bool,
+    pub normalize_containers: bool,
+    pub ignore_literals: bool,
+    pub exclude_receiver: bool,
+    pub normalize_async: bool,
+    pub normalize_primitive_types: bool,
+    pub no_size_prefilter: bool,
+    pub structure_weight: f64,
+    pub with_metrics: bool,
+    pub with_contracts: bool,
+    pub prefilter: Prefilter,
+    pub split_output: Option<PathBuf>,
+    pub io_threads: Option<usize>,
+    pub compare_threads: Option<usize>,
+    pub sample: Option<usize>,
+    pub seed: u64,
+    pub explain_filter: bool,
+    pub report_empty: bool,
+    pub ignore_cfg_variants: bool,
+    pub stream: bool,
+    pub cover_set: bool,
+    pub one_per_cluster: bool,
+    pub group: bool,
+    pub transitive_dedup: bool,
+    pub embed_source: bool,
+    pub max_embed_lines: Option<usize>,
+    pub format: OutputFormat,
+    pub max_duplication_ratio: Option<f64>,
+    pub fail_on_duplicates: bool,
+    pub baseline: Option<PathBuf>,
+    pub write_baseline: bool,
+    pub max_allowed: Option<usize>,
+    pub checkpoint: Option<PathBuf>,
+    pub resume: bool,
+    pub ascii_escape: bool,
+}
+
+impl CheckOptions {
+    pub fn tsed_options(&self) -> TSEDOptions {
+        let mut options = TSEDOptions::default();
+        options.apted_options.rename_cost = self.rename_cost;
+        options.apted_options.compare_values = true; // Rust: compare both node labels and values
+        options.min_lines = self.min_lines;
+        options.min_tokens = self.min_tokens;
+        options.size_penalty = !self.no_size_penalty;
+        options.skip_test = self.skip_test;
+        options.normalize_pipelines = self.normalize_pipelines;
+        options.normalize_boolean = self.normalize_boolean;
+        options.normalize_containers = self.normalize_containers;
+        options.ignore_literals = self.ignore_literals;
+        options.include_receiver_in_tree = !self.exclude_receiver;
+        options.normalize_async = self.normalize_async;
+        options.normalize_primitive_types = self.normalize_primitive_types;
+        options.size_prefilter = !self.no_size_prefilter;
+        options.structure_weight = self.structure_weight;
+        options.skip_parse_errors = self.skip_parse_errors;
+        options
+    }
+
+    pub fn load_options(&self) -> LoadOptions {
+        LoadOptions {
+            skip_trivial_impls: self.skip_trivial_impls,
+            include_closures: self.include_closures,
+        }
+    }
+
+    pub fn file_filter(&self) -> FileFilter {
+        FileFilter {
+            walk_options: WalkOptions { respect_ignore: !self.no_ignore, hidden: self.hidden },
+            include_patterns: self.include_patterns.clone(),
+            exclude_patterns: self.exclude_patterns.clone(),
+        }
+    }
+}
+
+pub fn check_paths(paths: Vec<String>, check_options: &CheckOptions) -> anyhow::Result<()> {
     let default_extensions = vec!["rs"];
-    let exts: Vec<&str> =
-        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let exts: Vec<&str> = check_options
+        .extensions
+        .as_ref()
+        .map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+
+    let files = check_options.file_filter().collect(&paths, &exts)?;
 
-    let files = collect_files(&paths, &exts)?;
+    // Documents are written even when empty, so that their readers can parse
+    // them; the diagnostics around them go to stderr
+    let document = check_options.format.is_document();
+    let mut diagnostics: Box<dyn Write> =
+        if document { Box::new(io::stderr()) } else { Box::new(io::stdout()) };
+
+    if files.is_empty() {
+        writeln!(diagnostics, "No Rust files found in the specified paths.")?;
+        if !document {
+            return Ok(());
+        }
+    }
+
+    if check_options.format == OutputFormat::Text {
+        println!("Checking {} files for duplicates...", files.len());
+    }
+
+    let options = check_options.tsed_options();
+
+    let mut file_data = run_with_threads(check_options.io_threads, || {
+        load_files_parallel(&files, check_options.load_options())
+    })?;
+
+    if check_options.report_empty {
+        print_empty_files(&file_data, &mut diagnostics)?;
+    }
+
+    if let Some(sample) = check_options.sample {
+        let total = sample_functions(&mut file_data, sample, check_options.seed, &options);
+        writeln!(
+            diagnostics,
+            "Sampled {} of {} functions (seed {}); results are an estimate from this sample.",
+            sample.min(total),
+            total,
+            check_options.seed
+        )?;
+    }
+
+    if check_options.explain_filter {
+        print_exclusions(&file_data, &options, &mut diagnostics)?;
+    }
+
+    // Refuse runaway runs before doing any O(n²) work
+    if let Some(max_comparisons) = check_options.max_comparisons {
+        let estimated = estimate_comparisons(&file_data, &options);
+        if estimated > max_comparisons {
+            anyhow::bail!(
+                "Estimated {} function comparisons exceeds --max-comparisons {}. \
+                 Narrow the scan with --min-lines or --skip-test, \
+                 analyze fewer paths, or raise the limit.",
+                estimated,
+                max_comparisons
+            );
+        }
+    }
+
+    // When streaming, print each pair from the comparison loop; the lock keeps
+    // the lines of pairs found on different threads together
+    let output_lock = Mutex::new(());
+    let stream_pair = |path: &Path, result: &SimilarityResult<GenericFunctionDef>| {
+        let mut dup = DuplicateResult {
+            file1: path.to_path_buf(),
+            file2: path.to_path_buf(),
+            result: result.clone(),
+            metrics: None,
+            contract_similarity: None,
+            tree_hashes: None,
+        };
+        if !passes_filters(&dup, check_options) {
+            return false;
+        }
+        if check_options.ascii_escape {
+            dup.escape_names();
+        }
+        if check_options.with_metrics {
+            attach_metrics(std::slice::from_mut(&mut dup), &file_data);
+        }
+        if check_options.with_contracts {
+            attach_contract_similarity(std::slice::from_mut(&mut dup), &file_data, &options);
+        }
+
+        let _guard = output_lock.lock().unwrap_or_else(|e| e.into_inner());
+        print_pair(&dup, check_options.print);
+        let _ = io::stdout().flush();
+        true
+    };
+
+    // SARIF results are written from the comparison loop too, so that the
+    // pairs are never held in memory, unless an option needs all of them
+    // first. Within-file pairs are found once each, so putting each in
+    // location order is all the canonicalization they need.
+    let stream_sarif = check_options.format == OutputFormat::Sarif
+        && check_options.baseline.is_none()
+        && !check_options.transitive_dedup
+        && check_options.split_output.is_none()
+        && !check_options.cover_set;
+    let streamed = Mutex::new(StreamedPairs::default());
+    let sarif = (check_options.format == OutputFormat::Sarif)
+        .then(|| {
+            SarifWriter::new(
+                io::stdout(),
+                "similarity-rs",
+                env!("CARGO_PKG_VERSION"),
+                "duplicate-function",
+                "Similar functions",
+            )
+        })
+        .transpose()?
+        .map(Mutex::new);
+    let sarif_pair = |path: &Path, result: &SimilarityResult<GenericFunctionDef>| {
+        let mut dup = DuplicateResult {
+            file1: path.to_path_buf(),
+            file2: path.to_path_buf(),
+            result: result.clone(),
+            metrics: None,
+            contract_similarity: None,
+            tree_hashes: None,
+        };
+        canonical_order(&mut dup);
+        if !passes_filters(&dup, check_options) {
+            return false;
+        }
+        if check_options.ascii_escape {
+            dup.escape_names();
+        }
+        if let Some(writer) = &sarif {
+            let mut writer = writer.lock().unwrap_or_else(|e| e.into_inner());
+            if let Err(e) = writer.write_result(&sarif_result(&dup)) {
+                eprintln!("Error writing SARIF output: {}", e);
+            }
+            streamed.lock().unwrap_or_else(|e| e.into_inner()).record(&dup);
+        }
+        false
+    };
+
+    let on_pair: Option<PairCallback> = if check_options.stream {
+        Some(&stream_pair)
+    } else if stream_sarif {
+        Some(&sarif_pair)
+    } else {
+        None
+    };
+
+    let all_results = find_duplicates(&file_data, check_options, &options, on_pair)?;
+
+    // Test hook: hold a streaming scan between its last comparison and its
+    // summary until a line arrives on stdin, so that tests can tell pairs
+    // printed during the scan from pairs printed at exit without timing
+    if check_options.stream && std::env::var_os("SIMILARITY_RS_HOLD_BEFORE_SUMMARY").is_some() {
+        let _ = io::stdin().read_line(&mut String::new());
+    }
+
+    if let Some(dir) = &check_options.split_output {
+        write_split_output(dir, &all_results, check_options, &contents_by_path(&file_data))?;
+    }
+
+    let cover_set = check_options.cover_set.then(|| cover_set(&all_results));
+    let (ratio, pair_count) = if stream_sarif {
+        let streamed = streamed.into_inner().unwrap_or_else(|e| e.into_inner());
+        let spans = streamed.spans.iter().map(|(file, start, end)| (file.as_path(), *start, *end));
+        (duplication_ratio(&file_data, spans), streamed.count)
+    } else {
+        (duplication_ratio(&file_data, function_spans(&all_results)), all_results.len())
+    };
+
+    // Display results
+    if let Some(writer) = sarif {
+        let mut writer = writer.into_inner().unwrap_or_else(|e| e.into_inner());
+        if !stream_sarif {
+            for dup in &all_results {
+                writer.write_result(&sarif_result(dup))?;
+            }
+        }
+        writer.finish()?;
+    } else if check_options.stream {
+        if all_results.is_empty() {
+            println!("\nNo duplicate functions found!");
+        } else {
+            println!("\nTotal duplicate pairs found: {}", all_results.len());
+        }
+    } else if check_options.format == OutputFormat::MarkdownTodo {
+        display_markdown_todo(all_results);
+    } else if check_options.format == OutputFormat::Json {
+        let contents = contents_by_path(&file_data);
+        let report = JsonReport {
+            pairs: all_results.iter().map(|dup| dup.to_record(check_options, &contents)).collect(),
+            summary: JsonSummary {
+                functions_scanned: file_data.iter().map(|data| data.functions.len()).sum(),
+                pairs: all_results.len(),
+            },
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else if check_options.format == OutputFormat::Fixplan {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&fix_plan(&all_results, &contents_by_path(&file_data)))?
+        );
+    } else if check_options.group {
+        display_groups(&all_results);
+    } else if check_options.one_per_cluster {
+        display_cluster_representatives(cluster_representatives(all_results), check_options.print);
+    } else {
+        display_all_results(all_results, check_options.print);
+    }
+
+    if let Some(cover_set) = cover_set {
+        print_cover_set(&cover_set, &mut diagnostics)?;
+    }
+
+    if let Some(budget) = check_options.max_duplication_ratio {
+        writeln!(diagnostics, "\nDuplication ratio: {:.2}% (budget {}%)", ratio, budget)?;
+        if ratio > budget {
+            anyhow::bail!("Duplication ratio {:.2}% exceeds the budget of {}%", ratio, budget);
+        }
+    }
+
+    if check_options.fail_on_duplicates {
+        let allowed = check_options.max_allowed.unwrap_or(0);
+        if pair_count > allowed {
+            anyhow::bail!("Found {} duplicate pairs, {} allowed", pair_count, allowed);
+        }
+    }
+
+    Ok(())
+}
+
+/// Percentage of the scanned lines that lie in a function of a reported pair.
+/// Lines are counted once, however many pairs their function is part of.
+#[allow(clippy::cast_precision_loss)]
+fn duplication_ratio<'a>(
+    file_data: &[RustFileData],
+    spans: impl IntoIterator<Item = (&'a Path, u32, u32)>,
+) -> f64 {
+    let total_lines: usize = file_data.iter().map(|data| data.content.lines().count()).sum();
+    if total_lines == 0 {
+        return 0.0;
+    }
+
+    let mut duplicated: HashMap<&Path, Vec<bool>> = HashMap::new();
+    for data in file_data {
+        duplicated.insert(data.path.as_path(), vec![false; data.content.lines().count()]);
+    }
+    for (file, start_line, end_line) in spans {
+        if let Some(lines) = duplicated.get_mut(file) {
+            let end = (end_line as usize).min(lines.len());
+            let start = (start_line as usize).saturating_sub(1).min(end);
+            lines[start..end].iter_mut().for_each(|line| *line = true);
+        }
+    }
+
+    let duplicated_lines =
+        duplicated.values().map(|lines| lines.iter().filter(|line| **line).count()).sum::<usize>();
+    duplicated_lines as f64 * 100.0 / total_lines as f64
+}
+
+/// File and lines of both functions of every pair
+fn function_spans(results: &[DuplicateResult]) -> impl Iterator<Item = (&Path, u32, u32)> {
+    results
+        .iter()
+        .flat_map(|dup| dup.ends().map(|(file, func)| (file, func.start_line, func.end_line)))
+}
+
+/// What `duplication_ratio` and the exit gates need of pairs that were
+/// written out as they were found
+#[derive(Default)]
+struct StreamedPairs {
+    count: usize,
+    spans: HashSet<(PathBuf, u32, u32)>,
+}
+
+impl StreamedPairs {
+    fn record(&mut self, dup: &DuplicateResult) {
+        self.count += 1;
+        for (file, func) in dup.ends() {
+            self.spans.insert((file.to_path_buf(), func.start_line, func.end_line));
+        }
+    }
+}
+
+/// A function of the cover set with the number of duplicate pairs it is part of
+struct CoverMember {
+    file: String,
+    start_line: u32,
+    end_line: u32,
+    name: String,
+    pair_count: usize,
+}
+
+/// Pick a small set of functions such that every duplicate pair involves at
+/// least one of them: reviewing these addresses all reported duplication
+fn cover_set(results: &[DuplicateResult]) -> Vec<CoverMember> {
+    let node = |file: &Path, func: &GenericFunctionDef| {
+        (file.to_string_lossy().to_string(), func.start_line, func.end_line, func.name.clone())
+    };
+    let edges: Vec<_> = results
+        .iter()
+        .map(|dup| (node(&dup.file1, &dup.result.func1), node(&dup.file2, &dup.result.func2)))
+        .collect();
+
+    approximate_vertex_cover(&edges)
+        .into_iter()
+        .map(|member| {
+            let pair_count = edges.iter().filter(|(a, b)| *a == member || *b == member).count();
+            let (file, start_line, end_line, name) = member;
+            CoverMember { file, start_line, end_line, name, pair_count }
+        })
+        .collect()
+}
+
+fn print_cover_set(cover_set: &[CoverMember], out: &mut dyn Write) -> io::Result<()> {
+    if cover_set.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(out, "\n=== Cover Set ===")?;
+    writeln!(out, "Review these {} functions to address every duplicate pair:", cover_set.len())?;
+    for member in cover_set {
+        writeln!(
+            out,
+            "  {} ({} {})",
+            format_function_output(&member.file, &member.name, member.start_line, member.end_line),
+            member.pair_count,
+            if member.pair_count == 1 { "pair" } else { "pairs" }
+        )?;
+    }
+    Ok(())
+}
+
+/// The files a scan reads: the directory walk filters and the include and
+/// exclude globs given on the command line. Every mode collects its files
+/// through it.
+#[derive(Debug, Clone, Default)]
+pub struct FileFilter {
+    pub walk_options: WalkOptions,
+    pub include_patterns: Vec<String>,
+    pub exclude_patterns: Vec<String>,
+}
+
+impl FileFilter {
+    /// The files with one of `extensions` under `paths`, keeping those matched
+    /// by the include globs (all files when there are none) and not matched by
+    /// the exclude globs. Globs are matched against the path relative to the
+    /// scanned root the file was found under, as well as against the path as
+    /// collected.
+    pub fn collect(&self, paths: &[String], extensions: &[&str]) -> anyhow::Result<Vec<PathBuf>> {
+        let files = collect_files_with(paths, extensions, &self.walk_options)?;
+        let include = glob_set(&self.include_patterns)?;
+        let exclude = glob_set(&self.exclude_patterns)?;
+        let matches = |set: &globset::GlobSet, file: &Path| {
+            set.is_match(file) || relative_paths(file, paths).any(|relative| set.is_match(relative))
+        };
+        Ok(files
+            .into_iter()
+            .filter(|file| self.include_patterns.is_empty() || matches(&include, file))
+            .filter(|file| !matches(&exclude, file))
+            .collect())
+    }
+}
+
+/// The path of `file` relative to each root it was found under; the file
+/// name when the root is the file itself
+fn relative_paths<'a>(file: &'a Path, roots: &'a [String]) -> impl Iterator<Item = &'a Path> {
+    roots.iter().filter_map(move |root| match file.strip_prefix(root) {
+        Ok(relative) if relative.as_os_str().is_empty() => file.file_name().map(Path::new),
+        Ok(relative) => Some(relative),
+        Err(_) => None,
+    })
+}
+
+/// Globs where `*` stops at path separators and only `**` crosses them
+fn glob_set(patterns: &[String]) -> anyhow::Result<globset::GlobSet> {
+    let mut builder = globset::GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(globset::GlobBuilder::new(pattern).literal_separator(true).build()?);
+    }
+    Ok(builder.build()?)
+}
+
+/// Print the files a scan would parse, with counts per language, without
+/// parsing or comparing anything
+pub fn dry_run(paths: &[String], check_options: &CheckOptions) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> = check_options
+        .extensions
+        .as_ref()
+        .map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+
+    let files = check_options.file_filter().collect(paths, &exts)?;
 
     if files.is_empty() {
         println!("No Rust files found in the specified paths.");
         return Ok(());
     }
 
-    println!("Checking {} files for duplicates...", files.len());
+    println!("Files that would be scanned ({}):", files.len());
+    let mut per_language: BTreeMap<String, usize> = BTreeMap::new();
+    for file in &files {
+        println!("  {}", file.display());
 
-    let mut options = TSEDOptions::default();
-    options.apted_options.rename_cost = rename_cost;
-    options.apted_options.compare_values = true; // Rust: compare both node labels and values
-    options.min_lines = min_lines;
-    options.min_tokens = min_tokens;
-    options.size_penalty = !no_size_penalty;
-    options.skip_test = skip_test;
+        let extension =
+            file.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        let language = Language::from_extension(&extension)
+            .map_or_else(|| format!(".{}", extension), |language| format!("{:?}", language));
+        *per_language.entry(language).or_default() += 1;
+    }
+
+    println!("\nFiles per language:");
+    for (language, count) in &per_language {
+        println!("  {}: {}", language, count);
+    }
 
+    Ok(())
+}
+
+/// Compare the functions of each file and return the pairs above the
+/// threshold, filtered and sorted the way they are reported
+fn find_duplicates(
+    file_data: &[RustFileData],
+    check_options: &CheckOptions,
+    options: &TSEDOptions,
+    on_pair: Option<PairCallback>,
+) -> anyhow::Result<Vec<DuplicateResult>> {
     let mut all_results = Vec::new();
 
+    // The pairs of a file are recorded before the report filters, so only
+    // the settings deciding the compared functions and their scores count
+    let settings = format!(
+        "{} {:?} {:?} {:?}",
+        check_options.threshold,
+        options,
+        check_options.prefilter,
+        check_options.load_options()
+    );
+    let checkpoint = match &check_options.checkpoint {
+        Some(path) if check_options.resume => Some(Checkpoint::resume(path, &settings)?),
+        Some(path) => Some(Checkpoint::create(path, &settings)?),
+        None => None,
+    };
+
     // Check within each file
-    let within_file_results = check_within_file_duplicates_parallel(&files, threshold, &options);
+    let within_file_results = run_with_threads(check_options.compare_threads, || {
+        check_within_file_duplicates_parallel(
+            file_data,
+            check_options.threshold,
+            options,
+            check_options.prefilter,
+            on_pair,
+            checkpoint.as_ref(),
+        )
+    })?;
 
     // Collect within-file duplicates
     for (file, similar_pairs) in within_file_results {
-        for result in similar_pairs {
-            all_results.push(DuplicateResult { file1: file.clone(), file2: file.clone(), result });
+        for (result, tree_hashes) in similar_pairs {
+            all_results.push(DuplicateResult {
+                file1: file.clone(),
+                file2: file.clone(),
+                result,
+                metrics: None,
+                contract_similarity: None,
+                tree_hashes: Some(tree_hashes),
+            });
         }
     }
 
     // For now, we only support within-file duplicates for Rust
     // Cross-file support can be added later
 
-    // Display results
-    display_all_results(all_results, print, filter_function, filter_function_body);
+    if check_options.with_metrics {
+        attach_metrics(&mut all_results, file_data);
+    }
+    if check_options.with_contracts {
+        attach_contract_similarity(&mut all_results, file_data, options);
+    }
+
+    all_results.retain(|dup| passes_filters(dup, check_options));
+    canonicalize_pairs(&mut all_results);
+    if let Some(path) = &check_options.baseline {
+        apply_baseline(&mut all_results, path, check_options.write_baseline)?;
+    }
+    if check_options.transitive_dedup {
+        all_results = transitive_dedup(all_results);
+    }
+    if check_options.ascii_escape {
+        all_results.iter_mut().for_each(DuplicateResult::escape_names);
+    }
+
+    // Sort by priority (higher similarity × larger functions first)
+    all_results.sort_by(|a, b| {
+        b.priority().partial_cmp(&a.priority()).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    Ok(all_results)
+}
+
+/// Load the given files and return their similar pairs as JSON records
+pub fn scan_pairs(
+    files: &[PathBuf],
+    check_options: &CheckOptions,
+) -> anyhow::Result<Vec<PairRecord>> {
+    let options = check_options.tsed_options();
+    let file_data = run_with_threads(check_options.io_threads, || {
+        load_files_parallel(files, check_options.load_options())
+    })?;
+    let results = find_duplicates(&file_data, check_options, &options, None)?;
+    let contents = contents_by_path(&file_data);
+    Ok(results.iter().map(|dup| dup.to_record(check_options, &contents)).collect())
+}
+
+/// The text of a function, keeping at most `max_lines` lines followed by a
+/// marker telling how many were left out
+fn function_source(content: &str, func: &GenericFunctionDef, max_lines: Option<usize>) -> String {
+    let source = extract_lines_from_content(content, func.start_line, func.end_line);
+
+    let line_count = source.lines().count();
+    match max_lines {
+        Some(max_lines) if line_count > max_lines => {
+            let kept: Vec<&str> = source.lines().take(max_lines).collect();
+            format!("{}\n... ({} more lines)", kept.join("\n"), line_count - max_lines)
+        }
+        _ => source,
+    }
+}
+
+/// The loaded source of every scanned file by path
+fn contents_by_path(file_data: &[RustFileData]) -> HashMap<&PathBuf, &str> {
+    file_data.iter().map(|data| (&data.path, data.content.as_str())).collect()
+}
+
+/// Write the results into one JSON file per severity band
+fn write_split_output(
+    dir: &Path,
+    results: &[DuplicateResult],
+    check_options: &CheckOptions,
+    contents: &HashMap<&PathBuf, &str>,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    for severity in Severity::ALL {
+        let records: Vec<PairRecord> = results
+            .iter()
+            .filter(|dup| Severity::from_similarity(dup.result.similarity) == severity)
+            .map(|dup| dup.to_record(check_options, contents))
+            .collect();
+
+        let path = dir.join(severity.file_name());
+        fs::write(&path, serde_json::to_string_pretty(&records)?)?;
+        eprintln!("Wrote {} pairs to {}", records.len(), path.display());
+    }
 
     Ok(())
 }
 
-/// Display similarity results
-fn display_all_results(
-    mut all_results: Vec<DuplicateResult>,
-    print: bool,
-    filter_function: Option<&String>,
-    filter_function_body: Option<&String>,
+/// List parsed files no function was extracted from, which points at
+/// constant-only modules as well as constructs the parser misses
+fn print_empty_files(file_data: &[RustFileData], out: &mut dyn Write) -> io::Result<()> {
+    let mut empty: Vec<&Path> = file_data
+        .iter()
+        .filter(|data| data.functions.is_empty())
+        .map(|data| data.path.as_path())
+        .collect();
+    if empty.is_empty() {
+        return writeln!(out, "\nEvery parsed file contains at least one function.");
+    }
+    empty.sort();
+
+    writeln!(out, "\nFiles without functions ({}):", empty.len())?;
+    for path in empty {
+        writeln!(out, "  {}", path.display())?;
+    }
+    Ok(())
+}
+
+/// Print every function excluded from the comparisons and why
+fn print_exclusions(
+    file_data: &[RustFileData],
+    options: &TSEDOptions,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let excluded = explain_exclusions(file_data, options);
+    if excluded.is_empty() {
+        return writeln!(out, "\nNo functions were excluded by filters.");
+    }
+
+    writeln!(out, "\nExcluded functions:")?;
+    for (path, func, reason) in &excluded {
+        writeln!(
+            out,
+            "  {}: {}",
+            format_function_output(
+                &path.to_string_lossy(),
+                &format!("{} {}", if func.is_method { "method" } else { "function" }, func.name),
+                func.start_line,
+                func.end_line
+            ),
+            reason
+        )?;
+    }
+    Ok(())
+}
+
+/// Compute per-function metrics for both sides of every reported pair
+fn attach_metrics(results: &mut [DuplicateResult], file_data: &[RustFileData]) {
+    let Ok(mut parser) = RustParser::new() else {
+        return;
+    };
+
+    let contents: HashMap<&PathBuf, &str> =
+        file_data.iter().map(|data| (&data.path, data.content.as_str())).collect();
+
+    for dup in results.iter_mut() {
+        let (Some(content1), Some(content2)) = (contents.get(&dup.file1), contents.get(&dup.file2))
+        else {
+            continue;
+        };
+
+        let metrics1 = function_metrics(&mut parser, content1, &dup.result.func1);
+        let metrics2 = function_metrics(&mut parser, content2, &dup.result.func2);
+        if let (Some(metrics1), Some(metrics2)) = (metrics1, metrics2) {
+            dup.metrics = Some((metrics1, metrics2));
+        }
+    }
+}
+
+/// Compare the leading assertions and guard clauses of both sides of every
+/// reported pair
+fn attach_contract_similarity(
+    results: &mut [DuplicateResult],
+    file_data: &[RustFileData],
+    options: &TSEDOptions,
 ) {
-    if all_results.is_empty() {
-        println!("\nNo duplicate functions found!");
+    let Ok(mut parser) = crate::rust_parser::RustParser::new() else {
         return;
+    };
+    let contents: HashMap<&PathBuf, &str> =
+        file_data.iter().map(|data| (&data.path, data.content.as_str())).collect();
+
+    for dup in results.iter_mut() {
+        let (Some(content1), Some(content2)) = (contents.get(&dup.file1), contents.get(&dup.file2))
+        else {
+            continue;
+        };
+
+        let mut contract = |content: &str, func: &GenericFunctionDef| {
+            let code = extract_lines_from_content(content, func.start_line, func.end_line);
+            leading_contract(&code, &mut parser)
+        };
+        let contract1 = contract(content1, &dup.result.func1);
+        let contract2 = contract(content2, &dup.result.func2);
+        if let (Some(contract1), Some(contract2)) = (contract1, contract2) {
+            dup.contract_similarity = Some(calculate_tsed(&contract1, &contract2, options));
+        }
     }
+}
 
-    // Apply filters if specified
-    if filter_function.is_some() || filter_function_body.is_some() {
-        all_results.retain(|dup| {
-            // Check function name filter
-            if let Some(filter) = filter_function {
-                if !dup.result.func1.name.contains(filter)
-                    && !dup.result.func2.name.contains(filter)
-                {
-                    return false;
-                }
+/// Record the pairs into the baseline at `path`, or drop the pairs it lists
+fn apply_baseline(
+    results: &mut Vec<DuplicateResult>,
+    path: &Path,
+    write: bool,
+) -> anyhow::Result<()> {
+    // Paths are recorded relative to the baseline, which usually sits at the
+    // root of the project
+    let root = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let root = root.canonicalize().map_err(|e| {
+        anyhow::anyhow!("Failed to resolve the directory of baseline {}: {}", path.display(), e)
+    })?;
+    let fingerprint = |dup: &DuplicateResult| {
+        let (hash1, hash2) = dup.tree_hashes?;
+        Some(pair_fingerprint(
+            &root,
+            [(&dup.file1, &dup.result.func1, hash1), (&dup.file2, &dup.result.func2, hash2)],
+        ))
+    };
+
+    if write {
+        let mut baseline = Baseline::default();
+        for dup in results.iter() {
+            if let Some(pair) = fingerprint(dup) {
+                baseline.insert(pair);
             }
+        }
+        baseline.save(path)?;
+        eprintln!("Wrote {} duplicate pairs to baseline {}", baseline.len(), path.display());
+        return Ok(());
+    }
 
-            // For body filter, we'd need to read the file content
-            // This is a simplified version
-            true
-        });
+    let baseline = Baseline::load(path)?;
+    let before = results.len();
+    results.retain(|dup| !fingerprint(dup).is_some_and(|pair| baseline.contains(&pair)));
+    let suppressed = before - results.len();
+    if suppressed > 0 {
+        eprintln!(
+            "Suppressed {} duplicate pairs listed in baseline {}",
+            suppressed,
+            path.display()
+        );
     }
+    Ok(())
+}
 
-    // Sort by priority (higher similarity × larger functions first)
-    all_results.sort_by(|a, b| {
-        b.priority().partial_cmp(&a.priority()).unwrap_or(std::cmp::Ordering::Equal)
-    });
+fn function_metrics(
+    parser: &mut RustParser,
+    content: &str,
+    func: &GenericFunctionDef,
+) -> Option<FunctionMetrics> {
+    let code = extract_lines_from_content(content, func.start_line, func.end_line);
+    let tree = parser.parse(&code, &func.name).ok()?;
+    Some(FunctionMetrics::new(func, &tree))
+}
+
+fn format_metrics(metrics: &FunctionMetrics) -> String {
+    format!(
+        "{{lines: {}, tokens: {}, complexity: {}, params: {}}}",
+        metrics.lines, metrics.subtree_size, metrics.complexity, metrics.parameters
+    )
+}
 
-    // Group by file
-    let mut file_groups = std::collections::HashMap::new();
+/// Whether a result matches the function name filter and is not a `cfg` variant pair
+/// excluded by `--ignore-cfg-variants`
+fn passes_filters(dup: &DuplicateResult, check_options: &CheckOptions) -> bool {
+    // Check function name filter
+    if let Some(filter) = &check_options.filter_function {
+        if !dup.result.func1.name.contains(filter.as_str())
+            && !dup.result.func2.name.contains(filter.as_str())
+        {
+            return false;
+        }
+    }
+
+    // For body filter, we'd need to read the file content
+
+    !(check_options.ignore_cfg_variants
+        && is_cfg_variant_pair(&dup.result.func1, &dup.result.func2))
+}
+
+/// The `#[cfg(...)]` attributes of a function, with whitespace removed
+fn cfg_gates(func: &GenericFunctionDef) -> Vec<String> {
+    let mut gates: Vec<String> = func
+        .decorators
+        .iter()
+        .map(|attr| attr.split_whitespace().collect::<String>())
+        .filter(|attr| attr.starts_with("#[cfg("))
+        .collect();
+    gates.sort();
+    gates
+}
+
+/// Same-named functions selected by different `cfg` gates are platform
+/// variants of one function rather than duplicates
+fn is_cfg_variant_pair(func1: &GenericFunctionDef, func2: &GenericFunctionDef) -> bool {
+    func1.name == func2.name
+        && func1.class_name == func2.class_name
+        && cfg_gates(func1) != cfg_gates(func2)
+}
+
+/// Display similarity results
+fn display_all_results(all_results: Vec<DuplicateResult>, print: bool) {
+    if all_results.is_empty() {
+        println!("\nNo duplicate functions found!");
+        return;
+    }
+
+    // Group by file, in an order that is stable across runs
+    let mut file_groups = BTreeMap::new();
     for dup in all_results {
         let file_path = dup.file1.to_string_lossy().to_string();
         file_groups.entry(file_path).or_insert_with(Vec::new).push(dup);
     }
+    let mut file_groups: Vec<Vec<DuplicateResult>> = file_groups.into_values().collect();
+    sort_groups(&mut file_groups, |dup| {
+        (dup.file1.clone(), dup.result.func1.start_line, dup.result.func2.start_line)
+    });
 
     // Display results grouped by file
     let mut total_count = 0;
-    for (file_path, duplicates) in file_groups {
+    for duplicates in file_groups {
+        let file_path = duplicates[0].file1.to_string_lossy().to_string();
         println!("\nDuplicates in {}:", file_path);
         println!("{}", "-".repeat(60));
 
         for dup in &duplicates {
-            let func1 = &dup.result.func1;
-            let func2 = &dup.result.func2;
+            print_pair(dup, print);
+            total_count += 1;
+        }
+    }
 
+    println!("\nTotal duplicate pairs found: {}", total_count);
+}
+
+/// Print the pairs as a Markdown checklist grouped by file, ready to paste
+/// into an issue
+fn display_markdown_todo(all_results: Vec<DuplicateResult>) {
+    println!("# Duplicate functions to refactor");
+
+    if all_results.is_empty() {
+        println!("\nNo duplicate functions found!");
+        return;
+    }
+
+    let mut file_groups = BTreeMap::new();
+    for dup in all_results {
+        let file_path = dup.file1.to_string_lossy().to_string();
+        file_groups.entry(file_path).or_insert_with(Vec::new).push(dup);
+    }
+    let mut file_groups: Vec<Vec<DuplicateResult>> = file_groups.into_values().collect();
+    sort_groups(&mut file_groups, |dup| {
+        (dup.file1.clone(), dup.result.func1.start_line, dup.result.func2.start_line)
+    });
+
+    let cwd = std::env::current_dir().ok();
+    let link = |file: &Path, func: &GenericFunctionDef| {
+        format!(
+            "[`{}`]({}#L{}-L{})",
+            func.name,
+            link_target(file, cwd.as_deref()),
+            func.start_line,
+            func.end_line
+        )
+    };
+
+    for duplicates in &file_groups {
+        println!("\n## {}\n", duplicates[0].file1.to_string_lossy());
+        for dup in duplicates {
             println!(
-                "  {} <-> {}",
-                format_function_output(
-                    &file_path,
-                    &format!(
-                        "{} {}",
-                        if func1.is_method { "method" } else { "function" },
-                        &func1.name
-                    ),
-                    func1.start_line,
-                    func1.end_line
-                ),
-                format_function_output(
-                    &file_path,
-                    &format!(
-                        "{} {}",
-                        if func2.is_method { "method" } else { "function" },
-                        &func2.name
-                    ),
-                    func2.start_line,
-                    func2.end_line
-                )
+                "- [ ] {} and {} ({:.2}% similar)",
+                link(&dup.file1, &dup.result.func1),
+                link(&dup.file2, &dup.result.func2),
+                dup.result.similarity * 100.0
             );
-            println!("  Similarity: {:.2}%", dup.result.similarity * 100.0);
+        }
+    }
+}
 
-            if let (Some(class1), Some(class2)) = (&func1.class_name, &func2.class_name) {
-                println!("  Classes: {} <-> {}", class1, class2);
-            }
+/// Link target of a file in a Markdown report: relative to the working
+/// directory (the repository root, for an issue), with `/` separators and
+/// the characters Markdown links choke on percent-encoded
+fn link_target(file: &Path, cwd: Option<&Path>) -> String {
+    let relative = cwd.and_then(|cwd| file.strip_prefix(cwd).ok()).unwrap_or(file);
+    let relative = relative.strip_prefix(".").unwrap_or(relative);
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+        .replace('%', "%25")
+        .replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+/// SARIF result of a pair: the first function, related to the second
+fn sarif_result(dup: &DuplicateResult) -> SarifResult {
+    let location = |file: &Path, func: &GenericFunctionDef| SarifLocation {
+        uri: file.to_string_lossy().to_string(),
+        start_line: func.start_line,
+        end_line: func.end_line,
+        message: Some(func.name.clone()),
+    };
+    // Near-identical pairs are the ones worth a warning on a pull request
+    let level = if dup.result.similarity > 0.95 { SarifLevel::Warning } else { SarifLevel::Note };
+    let mut properties = serde_json::Map::new();
+    properties.insert("similarity".to_string(), serde_json::json!(dup.result.similarity));
+    SarifResult {
+        level,
+        message: format!(
+            "Function {} is {:.2}% similar to {}",
+            dup.result.func1.name,
+            dup.result.similarity * 100.0,
+            dup.result.func2.name
+        ),
+        location: location(&dup.file1, &dup.result.func1),
+        related_locations: vec![location(&dup.file2, &dup.result.func2)],
+        properties,
+    }
+}
+
+/// Plan of an automated refactoring: one entry per cluster of duplicates
+#[derive(Debug, Serialize)]
+struct FixPlan {
+    clusters: Vec<FixCluster>,
+}
+
+#[derive(Debug, Serialize)]
+struct FixCluster {
+    /// The function the others are replaced with calls to
+    keep: FixTarget,
+    replace: Vec<FixTarget>,
+}
 
-            if print {
-                show_function_code(&file_path, &func1.name, func1.start_line, func1.end_line);
-                show_function_code(&file_path, &func2.name, func2.start_line, func2.end_line);
-                println!();
+#[derive(Debug, Serialize)]
+struct FixTarget {
+    file: String,
+    name: String,
+    start_line: u32,
+    end_line: u32,
+    signature: String,
+    /// Similarity to the kept function, when the two were reported as a pair
+    #[serde(skip_serializing_if = "Option::is_none")]
+    similarity: Option<f64>,
+}
+
+/// Group the pairs into clusters of transitively similar functions. The
+/// function with the most pairs in its cluster is kept, ties going to the
+/// first one by location, and the functions paired with it are listed as
+/// replace targets. The rest of the cluster is planned the same way.
+fn fix_plan(results: &[DuplicateResult], contents: &HashMap<&PathBuf, &str>) -> FixPlan {
+    let mut functions: BTreeMap<FunctionKey, (&Path, &GenericFunctionDef)> = BTreeMap::new();
+    for dup in results {
+        functions
+            .insert(function_key(&dup.file1, &dup.result.func1), (&dup.file1, &dup.result.func1));
+        functions
+            .insert(function_key(&dup.file2, &dup.result.func2), (&dup.file2, &dup.result.func2));
+    }
+    let keys: Vec<&FunctionKey> = functions.keys().collect();
+    let indices: HashMap<&FunctionKey, usize> =
+        keys.iter().enumerate().map(|(i, k)| (*k, i)).collect();
+    let index = |k: &FunctionKey| indices[k];
+
+    let mut edges = Vec::with_capacity(results.len());
+    let mut similarities = HashMap::new();
+    for dup in results {
+        let a = index(&function_key(&dup.file1, &dup.result.func1));
+        let b = index(&function_key(&dup.file2, &dup.result.func2));
+        similarities.insert((a.min(b), a.max(b)), dup.result.similarity);
+        edges.push((a, b));
+    }
+    // Indices follow the location order, so the components come out sorted
+    let clusters = connected_components(&edges);
+
+    let target = |i: usize, similarity: Option<f64>| {
+        let (file, func) = functions[keys[i]];
+        FixTarget {
+            file: file.to_string_lossy().to_string(),
+            name: func.name.clone(),
+            start_line: func.start_line,
+            end_line: func.end_line,
+            signature: contents
+                .get(&file.to_path_buf())
+                .map(|content| function_signature(content, func))
+                .unwrap_or_default(),
+            similarity,
+        }
+    };
+
+    let mut plan = Vec::new();
+    for mut members in clusters {
+        // A function is only replaced by one it was reported similar to, so
+        // members not paired with the kept function get a keeper of their own
+        while members.len() > 1 {
+            let paired = |a: usize, b: usize| similarities.get(&(a.min(b), a.max(b))).copied();
+            let pairs_within =
+                |i: usize| members.iter().filter(|&&j| paired(i, j).is_some()).count();
+            // Members are in location order, so `max_by_key` would prefer the last
+            let keep = members.iter().copied().rev().max_by_key(|&i| pairs_within(i)).unwrap();
+            let (replaced, rest): (Vec<usize>, Vec<usize>) =
+                members.iter().filter(|&&i| i != keep).partition(|&&i| paired(i, keep).is_some());
+            if replaced.is_empty() {
+                break;
             }
+            plan.push(FixCluster {
+                keep: target(keep, None),
+                replace: replaced.iter().map(|&i| target(i, paired(i, keep))).collect(),
+            });
+            members = rest;
+        }
+    }
 
-            total_count += 1;
+    FixPlan { clusters: plan }
+}
+
+/// Identifies a function across the pairs it is reported in
+type FunctionKey = (String, u32, u32, String);
+
+fn function_key(file: &Path, func: &GenericFunctionDef) -> FunctionKey {
+    (file.to_string_lossy().to_string(), func.start_line, func.end_line, func.name.clone())
+}
+
+/// Keep the most similar pair of each cluster of transitively similar
+/// functions, ties going to the first reported, paired with the number of
+/// functions in the cluster
+fn cluster_representatives(results: Vec<DuplicateResult>) -> Vec<(DuplicateResult, usize)> {
+    let mut indices: HashMap<FunctionKey, usize> = HashMap::new();
+    let mut ends = Vec::with_capacity(results.len());
+    for dup in &results {
+        let mut index = |file: &Path, func: &GenericFunctionDef| {
+            let next = indices.len();
+            *indices.entry(function_key(file, func)).or_insert(next)
+        };
+        ends.push((index(&dup.file1, &dup.result.func1), index(&dup.file2, &dup.result.func2)));
+    }
+
+    let mut cluster_of = vec![0; indices.len()];
+    let clusters = connected_components(&ends);
+    for (cluster, members) in clusters.iter().enumerate() {
+        for &member in members {
+            cluster_of[member] = cluster;
         }
     }
 
-    println!("\nTotal duplicate pairs found: {}", total_count);
+    let mut best: HashMap<usize, usize> = HashMap::new();
+    for (i, &(a, _)) in ends.iter().enumerate() {
+        let similarity = results[i].result.similarity;
+        best.entry(cluster_of[a])
+            .and_modify(|best| {
+                if similarity > results[*best].result.similarity {
+                    *best = i;
+                }
+            })
+            .or_insert(i);
+    }
+    let sizes: HashMap<usize, usize> =
+        best.into_iter().map(|(cluster, i)| (i, clusters[cluster].len())).collect();
+
+    results
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, dup)| sizes.get(&i).map(|&size| (dup, size)))
+        .collect()
+}
+
+/// Print each cluster of transitively similar functions as one block, led
+/// by its longest function
+fn display_groups(results: &[DuplicateResult]) {
+    if results.is_empty() {
+        println!("\nNo duplicate functions found!");
+        return;
+    }
+
+    let mut functions: HashMap<FunctionKey, (&Path, &GenericFunctionDef)> = HashMap::new();
+    let mut edges = Vec::with_capacity(results.len());
+    for dup in results {
+        let (key1, key2) = (
+            function_key(&dup.file1, &dup.result.func1),
+            function_key(&dup.file2, &dup.result.func2),
+        );
+        functions.insert(key1.clone(), (&dup.file1, &dup.result.func1));
+        functions.insert(key2.clone(), (&dup.file2, &dup.result.func2));
+        edges.push((key1, key2));
+    }
+
+    let describe = |(file, func): (&Path, &GenericFunctionDef)| {
+        format_function_output(
+            &file.to_string_lossy(),
+            &format!("{} {}", if func.is_method { "method" } else { "function" }, func.name),
+            func.start_line,
+            func.end_line,
+        )
+    };
+
+    let groups = connected_components(&edges);
+    for (index, members) in groups.iter().enumerate() {
+        let members: Vec<(&Path, &GenericFunctionDef)> =
+            members.iter().map(|key| functions[key]).collect();
+        // `max_by_key` keeps the last maximum, so search from the end
+        let representative = members
+            .iter()
+            .rev()
+            .max_by_key(|(_, func)| func.end_line - func.start_line)
+            .copied()
+            .unwrap_or(members[0]);
+
+        println!("\nGroup {} ({} functions)", index + 1, members.len());
+        println!("{}", "-".repeat(60));
+        println!("  Representative: {}", describe(representative));
+        for member in members {
+            println!("    {}", describe(member));
+        }
+    }
+
+    let function_count: usize = groups.iter().map(Vec::len).sum();
+    println!("\nTotal groups found: {} ({} functions)", groups.len(), function_count);
+}
+
+fn display_cluster_representatives(representatives: Vec<(DuplicateResult, usize)>, print: bool) {
+    if representatives.is_empty() {
+        println!("\nNo duplicate functions found!");
+        return;
+    }
+
+    println!("\nMost similar pair of each cluster:");
+    println!("{}", "-".repeat(60));
+    for (dup, size) in &representatives {
+        print_pair(dup, print);
+        println!("  Representative of cluster size {}", size);
+    }
+
+    println!("\nTotal clusters found: {}", representatives.len());
+}
+
+/// The declaration of a function up to its body, on one line
+fn function_signature(content: &str, func: &GenericFunctionDef) -> String {
+    let source = function_source(content, func, None);
+    let declaration = source.split('{').next().unwrap_or_default();
+    declaration.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Print one pair with its similarity, and its code when `print` is set
+fn print_pair(dup: &DuplicateResult, print: bool) {
+    let file_path = dup.file1.to_string_lossy();
+    let func1 = &dup.result.func1;
+    let func2 = &dup.result.func2;
+
+    println!(
+        "  {} <-> {}",
+        format_function_output(
+            &file_path,
+            &format!("{} {}", if func1.is_method { "method" } else { "function" }, &func1.name),
+            func1.start_line,
+            func1.end_line
+        ),
+        format_function_output(
+            &file_path,
+            &format!("{} {}", if func2.is_method { "method" } else { "function" }, &func2.name),
+            func2.start_line,
+            func2.end_line
+        )
+    );
+    println!("  Similarity: {:.2}%", dup.result.similarity * 100.0);
+
+    if let (Some(class1), Some(class2)) = (&func1.class_name, &func2.class_name) {
+        println!("  Classes: {} <-> {}", class1, class2);
+    }
+
+    if let Some((metrics1, metrics2)) = &dup.metrics {
+        println!("  Metrics: {} <-> {}", format_metrics(metrics1), format_metrics(metrics2));
+    }
+
+    if let Some(contract_similarity) = dup.contract_similarity {
+        println!("  Contract similarity: {:.2}%", contract_similarity * 100.0);
+    }
+
+    if print {
+        show_function_code(&file_path, &func1.name, func1.start_line, func1.end_line);
+        show_function_code(&file_path, &func2.name, func2.start_line, func2.end_line);
+        println!();
+    }
 }
@@ -0,0 +1,113 @@
+//! Checkpoints of a scan: the files already compared and the pairs found in
+//! each, so that an interrupted run can be resumed without comparing them
+//! again.
+//!
+//! The checkpoint starts with a header line holding a hash of the settings
+//! that decide the pairs of a file, and holds one JSON line per file after it,
+//! flushed as soon as the file is done. A checkpoint is only resumed with the
+//! same settings, a file is only taken from it while its content is unchanged,
+//! and a line cut short by the interruption is ignored.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::parallel::{HashedPair, RustFileData};
+
+type Pairs = Vec<HashedPair>;
+
+#[derive(Deserialize, Serialize)]
+struct Header {
+    settings: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Entry {
+    file: PathBuf,
+    hash: String,
+    pairs: Pairs,
+}
+
+pub struct Checkpoint {
+    done: HashMap<PathBuf, Entry>,
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl Checkpoint {
+    /// Start an empty checkpoint at `path` for a run with `settings`,
+    /// replacing any file there
+    pub fn create(path: &Path, settings: &str) -> anyhow::Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create checkpoint {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+        let header = Header { settings: content_hash(settings) };
+        writeln!(writer, "{}", serde_json::to_string(&header)?)?;
+        writer.flush()?;
+        Ok(Checkpoint { done: HashMap::new(), writer: Mutex::new(writer) })
+    }
+
+    /// Read the files completed in the checkpoint at `path` and keep adding
+    /// to it. Fails when the checkpoint was recorded with other `settings`,
+    /// as its pairs would not be the ones of this run.
+    pub fn resume(path: &Path, settings: &str) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read checkpoint {}", path.display()))?;
+        let mut lines = content.lines();
+        let header = lines.next().and_then(|line| serde_json::from_str::<Header>(line).ok());
+        if header.is_none_or(|header| header.settings != content_hash(settings)) {
+            anyhow::bail!(
+                "Checkpoint {} was recorded with other settings; start over with --checkpoint",
+                path.display()
+            );
+        }
+        let done = lines
+            .filter_map(|line| serde_json::from_str::<Entry>(line).ok())
+            .map(|entry| (entry.file.clone(), entry))
+            .collect();
+
+        // Drop a partial last line so that new entries start on their own line
+        let complete = content.rfind('\n').map_or(0, |end| end + 1);
+        let file = OpenOptions::new()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_len(complete as u64))
+            .and_then(|()| OpenOptions::new().append(true).open(path))
+            .with_context(|| format!("Failed to open checkpoint {}", path.display()))?;
+
+        Ok(Checkpoint { done, writer: Mutex::new(BufWriter::new(file)) })
+    }
+
+    /// The pairs found in `data` by an earlier run, when it has not changed since
+    pub fn completed(&self, data: &RustFileData) -> Option<&Pairs> {
+        self.done
+            .get(&data.path)
+            .filter(|entry| entry.hash == content_hash(&data.content))
+            .map(|entry| &entry.pairs)
+    }
+
+    /// Mark `data` as compared, with the pairs found in it
+    pub fn record(&self, data: &RustFileData, pairs: &Pairs) -> anyhow::Result<()> {
+        let entry = Entry {
+            file: data.path.clone(),
+            hash: content_hash(&data.content),
+            pairs: pairs.clone(),
+        };
+        let line = serde_json::to_string(&entry)?;
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(writer, "{}", line)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// FNV-1a hash of a file content or of settings, the same across builds
+fn content_hash(content: &str) -> String {
+    let hash = content.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
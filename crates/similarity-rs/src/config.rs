@@ -0,0 +1,240 @@
+//! `.similarityrc`: per-project defaults for the command-line flags.
+//!
+//! The file is TOML with one key per flag (`threshold = 0.9`,
+//! `min-lines = 5`, `exclude = ["target/**"]`, ...). It is looked up from the
+//! first analyzed path upwards, or taken from `--config`. Flags given on the
+//! command line always win over the file, and the file over the built-in
+//! defaults.
+//!
+//! The file holds the project's settings: the flags that choose the files and
+//! functions compared, those that change how they are scored, and the exit
+//! gates, which are the fields of [`FileConfig`]. The flags that pick a report
+//! mode (`--impls`, `--blocks`, ...), shape the output (`--format`, `--print`,
+//! `--group`, ...) or control a single run (`--watch`, `--checkpoint`,
+//! `--sample`, `--io-threads`, ...) are command-line only, and are rejected
+//! as unknown keys.
+
+use crate::parallel::Prefilter;
+use crate::Cli;
+use anyhow::Context;
+use clap::{parser::ValueSource, ArgMatches};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE_NAME: &str = ".similarityrc";
+
+/// Settings read from a config file; unset keys leave the flag alone
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FileConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_lines: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rename_cost: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub structure_weight: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extensions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_size_penalty: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_test: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_trivial_impls: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_closures: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_parse_errors: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_literals: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_receiver: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalize_pipelines: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalize_boolean: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalize_containers: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalize_async: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normalize_primitive_types: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_size_prefilter: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefilter: Option<Prefilter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ignore_cfg_variants: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_comparisons: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_duplication_ratio: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fail_on_duplicates: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_allowed: Option<usize>,
+    /// Globs of files to skip
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<String>>,
+    /// Globs of files to analyze; when set, other files are skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_ignore: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden: Option<bool>,
+}
+
+impl FileConfig {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+
+    /// The settings in effect after merging, in config file form
+    pub fn effective(cli: &Cli) -> Self {
+        FileConfig {
+            threshold: Some(cli.threshold),
+            min_lines: cli.min_lines,
+            min_tokens: cli.min_tokens,
+            rename_cost: Some(cli.rename_cost),
+            structure_weight: Some(cli.structure_weight),
+            extensions: cli.extensions.clone(),
+            no_size_penalty: Some(cli.no_size_penalty),
+            skip_test: Some(cli.skip_test),
+            skip_trivial_impls: Some(cli.skip_trivial_impls),
+            include_closures: Some(cli.include_closures),
+            skip_parse_errors: Some(cli.skip_parse_errors),
+            ignore_literals: Some(cli.ignore_literals),
+            exclude_receiver: Some(cli.exclude_receiver),
+            normalize_pipelines: Some(cli.normalize_pipelines),
+            normalize_boolean: Some(cli.normalize_boolean),
+            normalize_containers: Some(cli.normalize_containers),
+            normalize_async: Some(cli.normalize_async),
+            normalize_primitive_types: Some(cli.normalize_primitive_types),
+            no_size_prefilter: Some(cli.no_size_prefilter),
+            prefilter: Some(cli.prefilter),
+            ignore_cfg_variants: Some(cli.ignore_cfg_variants),
+            max_comparisons: cli.max_comparisons,
+            max_duplication_ratio: cli.max_duplication_ratio,
+            fail_on_duplicates: Some(cli.fail_on_duplicates),
+            max_allowed: cli.max_allowed,
+            exclude: Some(cli.exclude.clone()),
+            include: Some(cli.include.clone()),
+            no_ignore: Some(cli.no_ignore),
+            hidden: Some(cli.hidden),
+        }
+    }
+
+    /// Copy every setting into `cli` unless the flag was given on the command line
+    pub fn apply(self, cli: &mut Cli, matches: &ArgMatches) {
+        let unset = |id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+
+        if unset("threshold") {
+            set(&mut cli.threshold, self.threshold);
+        }
+        if unset("min_lines") && self.min_lines.is_some() {
+            cli.min_lines = self.min_lines;
+        }
+        if unset("min_tokens") && self.min_tokens.is_some() {
+            cli.min_tokens = self.min_tokens;
+        }
+        if unset("rename_cost") {
+            set(&mut cli.rename_cost, self.rename_cost);
+        }
+        if unset("structure_weight") {
+            set(&mut cli.structure_weight, self.structure_weight);
+        }
+        if unset("extensions") && self.extensions.is_some() {
+            cli.extensions = self.extensions;
+        }
+        if unset("no_size_penalty") {
+            set(&mut cli.no_size_penalty, self.no_size_penalty);
+        }
+        if unset("skip_test") {
+            set(&mut cli.skip_test, self.skip_test);
+        }
+        if unset("skip_trivial_impls") {
+            set(&mut cli.skip_trivial_impls, self.skip_trivial_impls);
+        }
+        if unset("include_closures") {
+            set(&mut cli.include_closures, self.include_closures);
+        }
+        if unset("skip_parse_errors") {
+            set(&mut cli.skip_parse_errors, self.skip_parse_errors);
+        }
+        if unset("ignore_literals") {
+            set(&mut cli.ignore_literals, self.ignore_literals);
+        }
+        if unset("exclude_receiver") {
+            set(&mut cli.exclude_receiver, self.exclude_receiver);
+        }
+        if unset("normalize_pipelines") {
+            set(&mut cli.normalize_pipelines, self.normalize_pipelines);
+        }
+        if unset("normalize_boolean") {
+            set(&mut cli.normalize_boolean, self.normalize_boolean);
+        }
+        if unset("normalize_containers") {
+            set(&mut cli.normalize_containers, self.normalize_containers);
+        }
+        if unset("normalize_async") {
+            set(&mut cli.normalize_async, self.normalize_async);
+        }
+        if unset("normalize_primitive_types") {
+            set(&mut cli.normalize_primitive_types, self.normalize_primitive_types);
+        }
+        if unset("no_size_prefilter") {
+            set(&mut cli.no_size_prefilter, self.no_size_prefilter);
+        }
+        if unset("prefilter") {
+            set(&mut cli.prefilter, self.prefilter);
+        }
+        if unset("ignore_cfg_variants") {
+            set(&mut cli.ignore_cfg_variants, self.ignore_cfg_variants);
+        }
+        if unset("max_comparisons") && self.max_comparisons.is_some() {
+            cli.max_comparisons = self.max_comparisons;
+        }
+        if unset("max_duplication_ratio") && self.max_duplication_ratio.is_some() {
+            cli.max_duplication_ratio = self.max_duplication_ratio;
+        }
+        if unset("fail_on_duplicates") {
+            set(&mut cli.fail_on_duplicates, self.fail_on_duplicates);
+        }
+        if unset("max_allowed") && self.max_allowed.is_some() {
+            cli.max_allowed = self.max_allowed;
+        }
+        if unset("exclude") {
+            set(&mut cli.exclude, self.exclude);
+        }
+        if unset("include") {
+            set(&mut cli.include, self.include);
+        }
+        if unset("no_ignore") {
+            set(&mut cli.no_ignore, self.no_ignore);
+        }
+        if unset("hidden") {
+            set(&mut cli.hidden, self.hidden);
+        }
+    }
+}
+
+fn set<T>(target: &mut T, value: Option<T>) {
+    if let Some(value) = value {
+        *target = value;
+    }
+}
+
+/// Find `.similarityrc` in the directory of `start` or any of its ancestors
+pub fn discover(start: &Path) -> Option<PathBuf> {
+    let start = start.canonicalize().ok()?;
+    let dir = if start.is_file() { start.parent()? } else { start.as_path() };
+    dir.ancestors().map(|dir| dir.join(CONFIG_FILE_NAME)).find(|path| path.is_file())
+}
@@ -0,0 +1,216 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_output::format_function_output,
+    language_parser::LanguageParser,
+    tree::TreeNode,
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use tree_sitter::{Node, Parser};
+
+/// Assertion macros that state a precondition
+const ASSERTION_MACROS: &[&str] =
+    &["assert", "assert_eq", "assert_ne", "debug_assert", "debug_assert_eq", "debug_assert_ne"];
+
+/// Macros that end a guard branch
+const EXIT_MACROS: &[&str] = &["panic", "unreachable", "bail"];
+
+/// A function split into its leading contract and the logic after it
+struct FunctionContract {
+    file: PathBuf,
+    name: String,
+    start_line: u32,
+    end_line: u32,
+    contract_tree: Rc<TreeNode>,
+    body_tree: Rc<TreeNode>,
+}
+
+/// Report functions whose leading assertions and guard clauses are similar,
+/// along with how similar the rest of their bodies is
+pub fn check_contracts(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = Parser::new();
+    parser.set_language(&tree_sitter_rust::LANGUAGE.into())?;
+    let mut rust_parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let mut contracts = Vec::new();
+    for file in &files {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        let Some(tree) = parser.parse(&content, None) else {
+            eprintln!("Error parsing {}", file.display());
+            continue;
+        };
+        collect_contracts(tree.root_node(), &content, file, &mut rust_parser, &mut contracts);
+    }
+
+    let mut similar = Vec::new();
+    for i in 0..contracts.len() {
+        for j in (i + 1)..contracts.len() {
+            let (f1, f2) = (&contracts[i], &contracts[j]);
+            let contract_similarity = calculate_tsed(&f1.contract_tree, &f2.contract_tree, options);
+            if contract_similarity >= threshold {
+                let body_similarity = calculate_tsed(&f1.body_tree, &f2.body_tree, options);
+                similar.push((i, j, contract_similarity, body_similarity));
+            }
+        }
+    }
+    // Identical validation around diverging logic first
+    similar.sort_by(|a, b| {
+        b.2.partial_cmp(&a.2)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.3.partial_cmp(&b.3).unwrap_or(std::cmp::Ordering::Equal))
+    });
+
+    if similar.is_empty() {
+        println!("\nNo similar function contracts found!");
+        return Ok(());
+    }
+
+    for (i, j, contract_similarity, body_similarity) in &similar {
+        println!("\n  {} <-> {}", describe(&contracts[*i]), describe(&contracts[*j]));
+        println!("  Contract similarity: {:.2}%", contract_similarity * 100.0);
+        println!("  Body similarity: {:.2}%", body_similarity * 100.0);
+    }
+
+    println!("\nTotal function pairs with similar contracts: {}", similar.len());
+
+    Ok(())
+}
+
+/// The leading contract of the function in `code`, or `None` when it has none
+pub fn leading_contract(code: &str, rust_parser: &mut RustParser) -> Option<Rc<TreeNode>> {
+    let mut parser = Parser::new();
+    parser.set_language(&tree_sitter_rust::LANGUAGE.into()).ok()?;
+    let tree = parser.parse(code, None)?;
+
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "function_item" {
+            return split_contract(node, code, Path::new(""), rust_parser)
+                .map(|contract| contract.contract_tree);
+        }
+        stack.extend(node.children(&mut node.walk()).collect::<Vec<_>>().into_iter().rev());
+    }
+    None
+}
+
+fn describe(contract: &FunctionContract) -> String {
+    format_function_output(
+        &contract.file.to_string_lossy(),
+        &contract.name,
+        contract.start_line,
+        contract.end_line,
+    )
+}
+
+fn collect_contracts(
+    node: Node,
+    source: &str,
+    file: &Path,
+    rust_parser: &mut RustParser,
+    contracts: &mut Vec<FunctionContract>,
+) {
+    if node.kind() == "function_item" {
+        if let Some(contract) = split_contract(node, source, file, rust_parser) {
+            contracts.push(contract);
+        }
+    }
+
+    for child in node.children(&mut node.walk()) {
+        collect_contracts(child, source, file, rust_parser, contracts);
+    }
+}
+
+/// Split a function body into its leading contract statements and the rest.
+/// Functions without a contract are skipped.
+fn split_contract(
+    function: Node,
+    source: &str,
+    file: &Path,
+    rust_parser: &mut RustParser,
+) -> Option<FunctionContract> {
+    let name = &source[function.child_by_field_name("name")?.byte_range()];
+    let body = function.child_by_field_name("body")?;
+
+    let statements: Vec<Node> = body.named_children(&mut body.walk()).collect();
+    let contract_len = statements.iter().take_while(|s| is_contract_statement(**s, source)).count();
+    if contract_len == 0 {
+        return None;
+    }
+
+    let text = |nodes: &[Node]| {
+        nodes.iter().map(|n| &source[n.byte_range()]).collect::<Vec<_>>().join("\n")
+    };
+    let contract_code = format!("fn contract() {{\n{}\n}}", text(&statements[..contract_len]));
+    let body_code = format!("fn body() {{\n{}\n}}", text(&statements[contract_len..]));
+
+    Some(FunctionContract {
+        file: file.to_path_buf(),
+        name: name.to_string(),
+        start_line: (function.start_position().row + 1) as u32,
+        end_line: (function.end_position().row + 1) as u32,
+        contract_tree: rust_parser.parse(&contract_code, name).ok()?,
+        body_tree: rust_parser.parse(&body_code, name).ok()?,
+    })
+}
+
+/// An assertion, a `let ... else` or an `if` without `else` that leaves the function
+fn is_contract_statement(statement: Node, source: &str) -> bool {
+    match statement.kind() {
+        "expression_statement" => match statement.named_child(0) {
+            Some(expr) if expr.kind() == "macro_invocation" => {
+                macro_name(expr, source).is_some_and(|name| ASSERTION_MACROS.contains(&name))
+            }
+            Some(expr) if expr.kind() == "if_expression" => {
+                expr.child_by_field_name("alternative").is_none()
+                    && expr
+                        .child_by_field_name("consequence")
+                        .is_some_and(|block| exits_function(block, source))
+            }
+            _ => false,
+        },
+        "let_declaration" => statement.child_by_field_name("alternative").is_some(),
+        _ => false,
+    }
+}
+
+/// Whether a guard block ends by returning or panicking
+fn exits_function(block: Node, source: &str) -> bool {
+    let Some(last) = block.named_children(&mut block.walk()).last() else {
+        return false;
+    };
+    let expr = if last.kind() == "expression_statement" { last.named_child(0) } else { Some(last) };
+    expr.is_some_and(|expr| match expr.kind() {
+        "return_expression" => true,
+        "macro_invocation" => {
+            macro_name(expr, source).is_some_and(|name| EXIT_MACROS.contains(&name))
+        }
+        _ => false,
+    })
+}
+
+fn macro_name<'a>(invocation: Node, source: &'a str) -> Option<&'a str> {
+    let name = &source[invocation.child_by_field_name("macro")?.byte_range()];
+    Some(name.rsplit("::").next().unwrap_or(name))
+}
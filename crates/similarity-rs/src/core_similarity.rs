@@ -0,0 +1,92 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::patterns::parse_functions;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_output::format_function_output,
+    core_region::{directional_core_similarity, CoreSimilarity},
+    language_parser::GenericFunctionDef,
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::path::Path;
+
+/// Minimum share of the shorter function's statements the core must cover,
+/// so that a single shared statement does not count as a core
+const MIN_CORE_COVERAGE: f64 = 0.5;
+
+/// Report functions whose shared core is similar even though they differ in
+/// leading setup or trailing cleanup, logging or return wrapping
+pub fn check_core_similarity(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut functions = Vec::new();
+    for file in &files {
+        match parse_functions(&mut parser, file, options.skip_parse_errors) {
+            Ok(parsed) => functions.extend(
+                parsed
+                    .into_iter()
+                    .filter(|(func, _)| func.end_line - func.start_line + 1 >= options.min_lines)
+                    .map(|(func, tree)| (file.clone(), func, tree)),
+            ),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+
+    let mut similar: Vec<(usize, usize, CoreSimilarity, f64)> = Vec::new();
+    for i in 0..functions.len() {
+        for j in (i + 1)..functions.len() {
+            let (tree1, tree2) = (&functions[i].2, &functions[j].2);
+            let Some(core) = directional_core_similarity(tree1, tree2, options) else {
+                continue;
+            };
+            if core.similarity >= threshold && core.coverage() >= MIN_CORE_COVERAGE {
+                let overall = calculate_tsed(tree1, tree2, options);
+                similar.push((i, j, core, overall));
+            }
+        }
+    }
+    similar.sort_by(|a, b| {
+        b.2.similarity.partial_cmp(&a.2.similarity).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    if similar.is_empty() {
+        println!("\nNo functions with a similar core found!");
+        return Ok(());
+    }
+
+    for (i, j, core, overall) in &similar {
+        let (file1, func1, _) = &functions[*i];
+        let (file2, func2, _) = &functions[*j];
+        println!("\n  {} <-> {}", describe(file1, func1), describe(file2, func2));
+        println!("  Core similarity: {:.2}%", core.similarity * 100.0);
+        println!("  Overall similarity: {:.2}%", overall * 100.0);
+        println!(
+            "  Core: statements {}-{} of {} <-> {}-{} of {}",
+            core.range1.0 + 1,
+            core.range1.1,
+            core.statements1,
+            core.range2.0 + 1,
+            core.range2.1,
+            core.statements2
+        );
+    }
+
+    println!("\nTotal functions with a similar core: {}", similar.len());
+
+    Ok(())
+}
+
+fn describe(file: &Path, func: &GenericFunctionDef) -> String {
+    format_function_output(&file.to_string_lossy(), &func.name, func.start_line, func.end_line)
+}
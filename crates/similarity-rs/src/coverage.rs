@@ -0,0 +1,184 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    extract_functions,
+    generic_tree_sitter_parser::GenericTreeSitterParser,
+    language_parser::{Language, LanguageParser},
+};
+use std::collections::{hash_map::Entry, BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+/// Parser coverage of the files of one language
+#[derive(Default)]
+struct LanguageCoverage {
+    files: usize,
+    functions: usize,
+    bytes: usize,
+    function_bytes: usize,
+    failed: Vec<PathBuf>,
+    /// No function extractor is available for the language
+    unsupported: bool,
+}
+
+/// Function extractor of a language: oxc for JavaScript and TypeScript,
+/// tree-sitter for the others
+enum FunctionExtractor {
+    Oxc,
+    TreeSitter(Box<dyn LanguageParser>),
+}
+
+impl FunctionExtractor {
+    fn for_language(language: Language) -> anyhow::Result<Option<Self>> {
+        let name = match language {
+            Language::JavaScript | Language::TypeScript => return Ok(Some(FunctionExtractor::Oxc)),
+            Language::Rust => {
+                let parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+                return Ok(Some(FunctionExtractor::TreeSitter(Box::new(parser))));
+            }
+            Language::Go => "go",
+            Language::Java => "java",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::CSharp => "csharp",
+            Language::Ruby => "ruby",
+            Language::Php => "php",
+            Language::Python | Language::Unknown => return Ok(None),
+        };
+        let parser = GenericTreeSitterParser::from_language_name(name)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+        Ok(Some(FunctionExtractor::TreeSitter(Box::new(parser))))
+    }
+
+    /// Line spans of the functions of a file
+    fn function_spans(&mut self, content: &str, filename: &str) -> anyhow::Result<Vec<(u32, u32)>> {
+        match self {
+            FunctionExtractor::Oxc => Ok(extract_functions(filename, content)
+                .map_err(|e| anyhow::anyhow!("{}", e))?
+                .iter()
+                .map(|func| (func.start_line, func.end_line))
+                .collect()),
+            FunctionExtractor::TreeSitter(parser) => Ok(parser
+                .extract_functions(content, filename)
+                .map_err(|e| anyhow::anyhow!("{}", e))?
+                .iter()
+                .map(|func| (func.start_line, func.end_line))
+                .collect()),
+        }
+    }
+}
+
+/// Report, per language, how many files and functions were recognized and how
+/// much of the source lies inside function spans. Low coverage hints at
+/// parser gaps or code the extractor does not understand.
+///
+/// Each file is read with the extractor of its language; languages without
+/// one are listed as unsupported.
+pub fn report_coverage(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    if files.is_empty() {
+        println!("No files found in the specified paths.");
+        return Ok(());
+    }
+
+    let mut extractors: HashMap<Language, Option<FunctionExtractor>> = HashMap::new();
+    let mut per_language: BTreeMap<String, LanguageCoverage> = BTreeMap::new();
+    for file in &files {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
+            }
+        };
+
+        let extension =
+            file.extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+        let language = Language::from_extension(&extension);
+        let name = language
+            .map_or_else(|| format!(".{}", extension), |language| format!("{:?}", language));
+        let coverage = per_language.entry(name).or_default();
+        coverage.files += 1;
+        coverage.bytes += content.len();
+
+        let language = language.unwrap_or(Language::Unknown);
+        let extractor = match extractors.entry(language) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(FunctionExtractor::for_language(language)?),
+        };
+        let Some(extractor) = extractor else {
+            coverage.unsupported = true;
+            continue;
+        };
+
+        match extractor.function_spans(&content, &file.to_string_lossy()) {
+            Ok(spans) => {
+                coverage.functions += spans.len();
+                // Methods lie inside their impl block, so count each line once
+                let lines: Vec<&str> = content.split_inclusive('\n').collect();
+                let mut covered = vec![false; lines.len()];
+                for &(start_line, end_line) in &spans {
+                    let start = (start_line as usize).saturating_sub(1);
+                    let end = (end_line as usize).min(lines.len());
+                    covered[start.min(end)..end].iter_mut().for_each(|line| *line = true);
+                }
+                coverage.function_bytes += lines
+                    .iter()
+                    .zip(&covered)
+                    .filter(|(_, covered)| **covered)
+                    .map(|(line, _)| line.len())
+                    .sum::<usize>();
+            }
+            Err(e) => {
+                eprintln!("Error parsing {}: {}", file.display(), e);
+                coverage.failed.push(file.clone());
+            }
+        }
+    }
+
+    println!("Parser coverage:");
+    for (language, coverage) in &per_language {
+        if coverage.unsupported {
+            println!(
+                "  {}: {} files, no function extractor for this language",
+                language, coverage.files
+            );
+            continue;
+        }
+        println!(
+            "  {}: {} files, {} functions, {:.1}% of {} bytes inside functions",
+            language,
+            coverage.files,
+            coverage.functions,
+            percentage(coverage.function_bytes, coverage.bytes),
+            coverage.bytes
+        );
+        if !coverage.failed.is_empty() {
+            println!("    Failed to parse {} files:", coverage.failed.len());
+            for file in &coverage.failed {
+                println!("      {}", file.display());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percentage(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
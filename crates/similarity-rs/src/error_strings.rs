@@ -0,0 +1,143 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser};
+
+/// Macros whose first string argument is a user-facing error message
+const ERROR_MACROS: &[&str] =
+    &["panic", "unreachable", "todo", "unimplemented", "bail", "anyhow", "format_err"];
+
+/// Methods taking an error message as their first argument
+const ERROR_METHODS: &[&str] = &["expect", "expect_err"];
+
+/// Associated functions that build an error from a message, when called on a
+/// path mentioning `Error` (e.g. `io::Error::new(kind, "...")`)
+const ERROR_CONSTRUCTORS: &[&str] = &["new", "msg", "custom", "other"];
+
+/// Where an error message literal was found
+struct ErrorStringSite {
+    file: PathBuf,
+    line: u32,
+    origin: String,
+}
+
+/// Report error messages passed to panics and error constructors more than once
+pub fn check_error_strings(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = Parser::new();
+    parser.set_language(&tree_sitter_rust::LANGUAGE.into())?;
+
+    let mut messages: BTreeMap<String, Vec<ErrorStringSite>> = BTreeMap::new();
+    for file in &files {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        let Some(tree) = parser.parse(&content, None) else {
+            eprintln!("Error parsing {}", file.display());
+            continue;
+        };
+        collect_error_strings(tree.root_node(), &content, file, &mut messages);
+    }
+
+    let mut duplicates: Vec<(String, Vec<ErrorStringSite>)> =
+        messages.into_iter().filter(|(_, sites)| sites.len() > 1).collect();
+    duplicates.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+
+    if duplicates.is_empty() {
+        println!("\nNo duplicated error strings found!");
+        return Ok(());
+    }
+
+    for (message, sites) in &duplicates {
+        println!("\n{} ({} occurrences)", message, sites.len());
+        for site in sites {
+            println!("  {}:{} {}", site.file.display(), site.line, site.origin);
+        }
+    }
+
+    println!("\nTotal duplicated error strings: {}", duplicates.len());
+
+    Ok(())
+}
+
+fn collect_error_strings(
+    node: Node,
+    source: &str,
+    file: &Path,
+    messages: &mut BTreeMap<String, Vec<ErrorStringSite>>,
+) {
+    if let Some((literal, origin)) = error_message_literal(node, source) {
+        let text = &source[literal.byte_range()];
+        messages.entry(text.to_string()).or_default().push(ErrorStringSite {
+            file: file.to_path_buf(),
+            line: (literal.start_position().row + 1) as u32,
+            origin,
+        });
+    }
+
+    for child in node.children(&mut node.walk()) {
+        collect_error_strings(child, source, file, messages);
+    }
+}
+
+/// Return the message literal of a panic/error-constructor site, with a short
+/// description of the site
+fn error_message_literal<'a>(node: Node<'a>, source: &str) -> Option<(Node<'a>, String)> {
+    match node.kind() {
+        "macro_invocation" => {
+            let name = node.child_by_field_name("macro")?;
+            let name = &source[name.byte_range()];
+            let short_name = name.rsplit("::").next().unwrap_or(name);
+            if !ERROR_MACROS.contains(&short_name) {
+                return None;
+            }
+            let token_tree = node.children(&mut node.walk()).find(|c| c.kind() == "token_tree")?;
+            let literal = first_string_literal(token_tree)?;
+            Some((literal, format!("{}!", short_name)))
+        }
+        "call_expression" => {
+            let function = node.child_by_field_name("function")?;
+            let arguments = node.child_by_field_name("arguments")?;
+
+            let origin = match function.kind() {
+                "field_expression" => {
+                    let method = function.child_by_field_name("field")?;
+                    let method = &source[method.byte_range()];
+                    ERROR_METHODS.contains(&method).then(|| format!(".{}()", method))?
+                }
+                "scoped_identifier" => {
+                    let path = &source[function.byte_range()];
+                    let (type_path, constructor) = path.rsplit_once("::")?;
+                    (type_path.contains("Error") && ERROR_CONSTRUCTORS.contains(&constructor))
+                        .then(|| path.to_string())?
+                }
+                _ => return None,
+            };
+
+            let literal = arguments
+                .named_children(&mut arguments.walk())
+                .find(|c| c.kind() == "string_literal")?;
+            Some((literal, origin))
+        }
+        _ => None,
+    }
+}
+
+fn first_string_literal(node: Node) -> Option<Node> {
+    node.named_children(&mut node.walk()).find(|c| c.kind() == "string_literal")
+}
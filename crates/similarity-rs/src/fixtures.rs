@@ -0,0 +1,200 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_output::format_function_output,
+    language_parser::LanguageParser,
+    tree::TreeNode,
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use tree_sitter::{Node, Parser};
+
+/// A single statement rarely deserves a fixture of its own
+const MIN_SETUP_STATEMENTS: usize = 2;
+
+/// The statements of a test function before its first assertion
+struct TestSetup {
+    file: PathBuf,
+    test_name: String,
+    start_line: u32,
+    end_line: u32,
+    tree: Rc<TreeNode>,
+}
+
+/// Report test functions whose setup, the statements before their first
+/// assertion, is similar: candidates for a shared fixture or helper.
+pub fn check_test_fixtures(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = Parser::new();
+    parser.set_language(&tree_sitter_rust::LANGUAGE.into())?;
+    let mut rust_parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let mut setups = Vec::new();
+    for file in &files {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        let Some(tree) = parser.parse(&content, None) else {
+            eprintln!("Error parsing {}", file.display());
+            continue;
+        };
+        collect_setups(tree.root_node(), &content, file, &mut rust_parser, &mut setups);
+    }
+
+    let mut similar = Vec::new();
+    for i in 0..setups.len() {
+        for j in (i + 1)..setups.len() {
+            let similarity = calculate_tsed(&setups[i].tree, &setups[j].tree, options);
+            if similarity >= threshold {
+                similar.push((i, j, similarity));
+            }
+        }
+    }
+    similar.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+
+    if similar.is_empty() {
+        println!("\nNo duplicated test setups found!");
+        return Ok(());
+    }
+
+    for (i, j, similarity) in &similar {
+        println!("\n  {} <-> {}", describe(&setups[*i]), describe(&setups[*j]));
+        println!("  Similarity: {:.2}%", similarity * 100.0);
+    }
+
+    println!("\nTotal duplicated test setups: {}", similar.len());
+    println!("Consider moving the shared setup into a fixture or helper function.");
+
+    Ok(())
+}
+
+fn describe(setup: &TestSetup) -> String {
+    format_function_output(
+        &setup.file.to_string_lossy(),
+        &format!("{} setup", setup.test_name),
+        setup.start_line,
+        setup.end_line,
+    )
+}
+
+fn collect_setups(
+    node: Node,
+    source: &str,
+    file: &Path,
+    rust_parser: &mut RustParser,
+    setups: &mut Vec<TestSetup>,
+) {
+    if node.kind() == "function_item" && is_test(node, source) {
+        if let Some(setup) = extract_setup(node, source, file, rust_parser) {
+            setups.push(setup);
+        }
+        return;
+    }
+
+    for child in node.children(&mut node.walk()) {
+        collect_setups(child, source, file, rust_parser, setups);
+    }
+}
+
+/// A function marked `#[test]` (or `#[tokio::test]`, ...) or named `test_*`
+fn is_test(function: Node, source: &str) -> bool {
+    let name = function.child_by_field_name("name").map(|name| &source[name.byte_range()]);
+    if name.is_some_and(|name| name.starts_with("test_")) {
+        return true;
+    }
+
+    let mut sibling = function.prev_sibling();
+    while let Some(prev) = sibling {
+        match prev.kind() {
+            "attribute_item" if is_test_attribute(prev, source) => return true,
+            "attribute_item" | "line_comment" | "block_comment" => {}
+            _ => break,
+        }
+        sibling = prev.prev_sibling();
+    }
+    false
+}
+
+/// Whether an attribute's path ends in `test`: `#[test]`, `#[tokio::test]`
+/// or `#[tokio::test(flavor = "multi_thread")]`, but not `#[cfg(test)]` or
+/// `#[cfg(not(test))]`, which mention `test` only in their arguments
+fn is_test_attribute(attribute_item: Node, source: &str) -> bool {
+    let Some(attribute) = attribute_item.named_child(0).filter(|n| n.kind() == "attribute") else {
+        return false;
+    };
+    let Some(path) = attribute.named_child(0) else {
+        return false;
+    };
+    let last_segment = match path.kind() {
+        "scoped_identifier" => path.child_by_field_name("name"),
+        "identifier" => Some(path),
+        _ => None,
+    };
+    last_segment.is_some_and(|segment| &source[segment.byte_range()] == "test")
+}
+
+/// The leading statements of a test up to its first assertion. Tests with
+/// fewer than `MIN_SETUP_STATEMENTS` setup statements are skipped.
+fn extract_setup(
+    function: Node,
+    source: &str,
+    file: &Path,
+    rust_parser: &mut RustParser,
+) -> Option<TestSetup> {
+    let name = &source[function.child_by_field_name("name")?.byte_range()];
+    let body = function.child_by_field_name("body")?;
+
+    let statements: Vec<Node> = body
+        .named_children(&mut body.walk())
+        .filter(|statement| !statement.is_extra())
+        .take_while(|statement| !contains_assertion(*statement, source))
+        .collect();
+    if statements.len() < MIN_SETUP_STATEMENTS {
+        return None;
+    }
+
+    let text = statements.iter().map(|n| &source[n.byte_range()]).collect::<Vec<_>>().join("\n");
+    let first = statements.first()?;
+    let last = statements.last()?;
+
+    Some(TestSetup {
+        file: file.to_path_buf(),
+        test_name: name.to_string(),
+        start_line: (first.start_position().row + 1) as u32,
+        end_line: (last.end_position().row + 1) as u32,
+        tree: rust_parser.parse(&format!("fn setup() {{\n{}\n}}", text), name).ok()?,
+    })
+}
+
+/// Whether a statement invokes an `assert*!` macro anywhere inside it
+fn contains_assertion(node: Node, source: &str) -> bool {
+    if node.kind() == "macro_invocation" {
+        let is_assertion = node.child_by_field_name("macro").is_some_and(|name| {
+            let name = &source[name.byte_range()];
+            name.rsplit("::").next().unwrap_or(name).starts_with("assert")
+        });
+        if is_assertion {
+            return true;
+        }
+    }
+
+    node.children(&mut node.walk()).any(|child| contains_assertion(child, source))
+}
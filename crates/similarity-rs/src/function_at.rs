@@ -0,0 +1,117 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::parallel::passes_candidate_filters;
+use crate::patterns::parse_functions;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_output::{extract_lines_from_content, format_function_output},
+    find_similar_to,
+    language_parser::{function_at_line, GenericFunctionDef, LanguageParser},
+    tree::TreeNode,
+    tsed::TSEDOptions,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// A scanned function with its file, and its tree
+pub type ScannedFunction = ((PathBuf, GenericFunctionDef), Rc<TreeNode>);
+
+/// Report the functions of the scanned paths that are similar to the function
+/// containing `line` in `file`
+pub fn similar_to_function_at(
+    file: &Path,
+    line: u32,
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let content = fs::read_to_string(file)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", file.display(), e))?;
+    let functions = parser
+        .extract_functions(&content, &file.to_string_lossy())
+        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", file.display(), e))?;
+    let Some(target) = function_at_line(&functions, line) else {
+        anyhow::bail!("No function in {} contains line {}", file.display(), line);
+    };
+    let code = extract_lines_from_content(&content, target.start_line, target.end_line);
+    let target_tree = parser
+        .parse(&code, &target.name)
+        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", target.name, e))?;
+
+    println!("Function at {}:{}: {}", file.display(), line, describe(file, target));
+
+    let target_file = file.canonicalize()?;
+    let mut corpus = scan_functions(&mut parser, paths, extensions, file_filter, options)?;
+    corpus.retain(|((scanned_file, func), _)| {
+        func.start_line != target.start_line
+            || !scanned_file.canonicalize().is_ok_and(|path| path == target_file)
+    });
+
+    let target = (file.to_path_buf(), target.clone());
+    print_similar(&target, &target_tree, &corpus, threshold, options);
+    Ok(())
+}
+
+/// Extract and parse the functions of the files with `extensions` (`rs` by
+/// default) under `paths`, reporting and skipping the files that fail
+pub fn scan_functions(
+    parser: &mut RustParser,
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    options: &TSEDOptions,
+) -> anyhow::Result<Vec<ScannedFunction>> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+
+    let mut scanned = Vec::new();
+    for file in file_filter.collect(paths, &exts)? {
+        match parse_functions(parser, &file, options.skip_parse_errors) {
+            Ok(functions) => scanned
+                .extend(functions.into_iter().map(|(func, tree)| ((file.clone(), func), tree))),
+            Err(e) => eprintln!("Skipping {}: {}", file.display(), e),
+        }
+    }
+    Ok(scanned)
+}
+
+/// Print the functions of `corpus` similar to `target`, most similar first.
+/// The functions `check` would leave out, such as those below `--min-lines`,
+/// are not reported.
+pub fn print_similar(
+    target: &(PathBuf, GenericFunctionDef),
+    target_tree: &Rc<TreeNode>,
+    corpus: &[ScannedFunction],
+    threshold: f64,
+    options: &TSEDOptions,
+) {
+    let corpus: Vec<ScannedFunction> = corpus
+        .iter()
+        .filter(|((_, func), tree)| passes_candidate_filters(func, tree, options))
+        .cloned()
+        .collect();
+    let similar = find_similar_to(target, target_tree, &corpus, threshold, options);
+    if similar.is_empty() {
+        println!("\nNo similar functions found!");
+        return;
+    }
+
+    for result in &similar {
+        let (file, func) = &result.func2;
+        println!("\n  {}", describe(file, func));
+        println!("  Similarity: {:.2}%", result.similarity * 100.0);
+    }
+
+    println!("\nTotal similar functions: {}", similar.len());
+}
+
+pub fn describe(file: &Path, func: &GenericFunctionDef) -> String {
+    format_function_output(&file.to_string_lossy(), &func.name, func.start_line, func.end_line)
+}
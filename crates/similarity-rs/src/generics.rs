@@ -0,0 +1,124 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::patterns::parse_functions;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_output::format_function_output,
+    language_parser::GenericFunctionDef,
+    tree::TreeNode,
+    tree_normalizer::{normalize_type_names, TYPE_NAME_LABELS},
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// A function with its tree and the concrete types it mentions
+struct TypedFunction {
+    file: PathBuf,
+    func: GenericFunctionDef,
+    normalized_tree: Rc<TreeNode>,
+    types: BTreeSet<String>,
+}
+
+/// Report functions that are similar once their type names are replaced by a
+/// placeholder but mention different concrete types, e.g. `process_i32` and
+/// `process_u64`: candidates for a single generic function
+pub fn check_generic_duplicates(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut functions = Vec::new();
+    for file in &files {
+        let parsed = match parse_functions(&mut parser, file, options.skip_parse_errors) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+        for (func, tree) in parsed {
+            let mut types = BTreeSet::new();
+            collect_type_names(&tree, &mut types);
+            functions.push(TypedFunction {
+                file: file.clone(),
+                func,
+                normalized_tree: normalize_type_names(&tree),
+                types,
+            });
+        }
+    }
+
+    let mut similar = Vec::new();
+    for i in 0..functions.len() {
+        for j in (i + 1)..functions.len() {
+            let (f1, f2) = (&functions[i], &functions[j]);
+            // Functions over the same types are plain duplicates
+            if f1.types == f2.types {
+                continue;
+            }
+            let similarity = calculate_tsed(&f1.normalized_tree, &f2.normalized_tree, options);
+            if similarity >= threshold {
+                similar.push((i, j, similarity));
+            }
+        }
+    }
+    similar.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+
+    if similar.is_empty() {
+        println!("\nNo generic-able duplicates found!");
+        return Ok(());
+    }
+
+    for (i, j, similarity) in &similar {
+        let (f1, f2) = (&functions[*i], &functions[*j]);
+        println!("\n  {} <-> {}", describe(f1), describe(f2));
+        println!("  Similarity (types normalized): {:.2}%", similarity * 100.0);
+        println!(
+            "  Types: {} <-> {}",
+            join(f1.types.difference(&f2.types)),
+            join(f2.types.difference(&f1.types))
+        );
+    }
+
+    println!("\nTotal generic-able duplicates: {}", similar.len());
+
+    Ok(())
+}
+
+fn describe(function: &TypedFunction) -> String {
+    format_function_output(
+        &function.file.to_string_lossy(),
+        &function.func.name,
+        function.func.start_line,
+        function.func.end_line,
+    )
+}
+
+fn join<'a>(types: impl Iterator<Item = &'a String>) -> String {
+    let types: Vec<&str> = types.map(String::as_str).collect();
+    if types.is_empty() {
+        "-".to_string()
+    } else {
+        types.join(", ")
+    }
+}
+
+fn collect_type_names(node: &TreeNode, types: &mut BTreeSet<String>) {
+    if node.children.is_empty() && TYPE_NAME_LABELS.contains(&node.label.as_str()) {
+        types.insert(node.value.clone());
+    }
+    for child in &node.children {
+        collect_type_names(child, types);
+    }
+}
@@ -0,0 +1,122 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::patterns::parse_functions;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_file_utils::collect_files_with,
+    cli_output::format_function_output,
+    language_parser::GenericFunctionDef,
+    tree::TreeNode,
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+struct ParsedFunction {
+    file: PathBuf,
+    func: GenericFunctionDef,
+    tree: Rc<TreeNode>,
+}
+
+/// Match every scanned function to its most similar function under
+/// `golden_dir` and report how far each copy has drifted from it
+/// (`1 - similarity`), most drifted first. Functions whose best match is
+/// below `threshold` are not considered copies and are left out.
+pub fn check_golden(
+    paths: &[String],
+    golden_dir: &Path,
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    if !golden_dir.is_dir() {
+        anyhow::bail!("{} is not a directory", golden_dir.display());
+    }
+
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let golden_dir = golden_dir.canonicalize()?;
+
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    // The globs name scanned files, so the golden directory is only walked
+    // with the walk filters
+    let golden_files = collect_files_with(
+        &[golden_dir.to_string_lossy().to_string()],
+        &exts,
+        &file_filter.walk_options,
+    )?;
+    let golden = parse_all(&mut parser, &golden_files, options);
+    // The golden directory may lie inside the scanned paths
+    let scanned_files: Vec<PathBuf> = file_filter
+        .collect(paths, &exts)?
+        .into_iter()
+        .filter(|file| !file.canonicalize().is_ok_and(|file| file.starts_with(&golden_dir)))
+        .collect();
+    let scanned = parse_all(&mut parser, &scanned_files, options);
+
+    println!("Comparing functions against the golden implementations in {}", golden_dir.display());
+
+    let mut drifted = Vec::new();
+    for (i, function) in scanned.iter().enumerate() {
+        let best = golden
+            .iter()
+            .enumerate()
+            .map(|(j, reference)| (j, calculate_tsed(&function.tree, &reference.tree, options)))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        if let Some((j, similarity)) = best.filter(|(_, similarity)| *similarity >= threshold) {
+            drifted.push((i, j, 1.0 - similarity));
+        }
+    }
+    drifted.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+
+    if drifted.is_empty() {
+        println!("\nNo copies of golden functions found!");
+        return Ok(());
+    }
+
+    for (i, j, drift) in &drifted {
+        println!("\n  {} -> {}", describe(&scanned[*i]), describe(&golden[*j]));
+        println!("  Drift: {:.2}%", drift * 100.0);
+    }
+
+    println!("\nTotal copies of golden functions: {}", drifted.len());
+
+    Ok(())
+}
+
+/// The functions of `files` spanning at least `min_lines`. Files that cannot
+/// be read or parsed are reported and skipped.
+fn parse_all(
+    parser: &mut RustParser,
+    files: &[PathBuf],
+    options: &TSEDOptions,
+) -> Vec<ParsedFunction> {
+    let mut functions = Vec::new();
+    for file in files {
+        let parsed = match parse_functions(parser, file, options.skip_parse_errors) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("Skipping {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        for (func, tree) in parsed {
+            if func.end_line - func.start_line + 1 >= options.min_lines {
+                functions.push(ParsedFunction { file: file.clone(), func, tree });
+            }
+        }
+    }
+    functions
+}
+
+fn describe(function: &ParsedFunction) -> String {
+    format_function_output(
+        &function.file.to_string_lossy(),
+        &function.func.name,
+        function.func.start_line,
+        function.func.end_line,
+    )
+}
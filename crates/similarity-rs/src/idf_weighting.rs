@@ -0,0 +1,77 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::patterns::parse_functions;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_output::format_function_output,
+    identifier_weighting::{collect_identifiers, idf_weighted_similarity, IdentifierFrequencies},
+    language_parser::GenericFunctionDef,
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::path::Path;
+
+/// Report similar functions with TSED adjusted by identifier frequency: sharing
+/// rare, domain-specific names counts more than sharing `i` or `result`.
+/// Frequencies are counted over every function of the scanned paths.
+pub fn check_idf_weighted(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut functions = Vec::new();
+    for file in &files {
+        match parse_functions(&mut parser, file, options.skip_parse_errors) {
+            Ok(parsed) => functions.extend(parsed.into_iter().map(|(func, tree)| {
+                let identifiers = collect_identifiers(&tree);
+                (file.clone(), func, tree, identifiers)
+            })),
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+
+    // Short functions still count towards how common an identifier is
+    let frequencies = IdentifierFrequencies::from_documents(functions.iter().map(|f| &f.3));
+    functions.retain(|(_, func, _, _)| func.end_line - func.start_line + 1 >= options.min_lines);
+
+    let mut similar = Vec::new();
+    for i in 0..functions.len() {
+        for j in (i + 1)..functions.len() {
+            let (f1, f2) = (&functions[i], &functions[j]);
+            let tsed = calculate_tsed(&f1.2, &f2.2, options);
+            let weighted = idf_weighted_similarity(tsed, &f1.3, &f2.3, &frequencies);
+            if weighted >= threshold {
+                similar.push((i, j, weighted, tsed));
+            }
+        }
+    }
+    similar.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+
+    if similar.is_empty() {
+        println!("\nNo IDF-weighted duplicates found!");
+        return Ok(());
+    }
+
+    for (i, j, weighted, tsed) in &similar {
+        let (file1, func1, _, _) = &functions[*i];
+        let (file2, func2, _, _) = &functions[*j];
+        println!("\n  {} <-> {}", describe(file1, func1), describe(file2, func2));
+        println!("  Similarity: {:.2}% (TSED {:.2}%)", weighted * 100.0, tsed * 100.0);
+    }
+
+    println!("\nTotal IDF-weighted duplicates: {}", similar.len());
+
+    Ok(())
+}
+
+fn describe(file: &Path, func: &GenericFunctionDef) -> String {
+    format_function_output(&file.to_string_lossy(), &func.name, func.start_line, func.end_line)
+}
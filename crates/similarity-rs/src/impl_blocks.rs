@@ -0,0 +1,159 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::rust_parser::{ImplBlock, RustParser};
+use similarity_core::{
+    cli_output::{extract_lines_from_content, format_function_output},
+    tree::TreeNode,
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// An impl block with the parsed tree of each of its methods
+struct ParsedImpl {
+    file: PathBuf,
+    block: ImplBlock,
+    method_trees: Vec<Rc<TreeNode>>,
+}
+
+/// Similarity of two impl blocks and the method pairing behind it
+struct ImplSimilarity {
+    similarity: f64,
+    /// `(method in first block, method in second block, similarity)`
+    method_pairs: Vec<(usize, usize, f64)>,
+}
+
+/// Report pairs of `impl` blocks whose method sets are similar
+pub fn check_impls(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut impls = Vec::new();
+
+    for file in &files {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        let blocks = match parser.extract_impl_blocks(&content) {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                eprintln!("Error parsing {}: {}", file.display(), e);
+                continue;
+            }
+        };
+
+        for block in blocks {
+            let method_trees: Vec<Rc<TreeNode>> = block
+                .methods
+                .iter()
+                .filter_map(|method| {
+                    let code =
+                        extract_lines_from_content(&content, method.start_line, method.end_line);
+                    let (tree, errors) = parser.parse_counting_errors(&code, &method.name).ok()?;
+                    (!options.skip_parse_errors || errors == 0).then_some(tree)
+                })
+                .collect();
+
+            // Blocks whose methods could not all be parsed (cleanly, with
+            // --skip-parse-errors) would be compared unfairly
+            if method_trees.is_empty() || method_trees.len() != block.methods.len() {
+                continue;
+            }
+            impls.push(ParsedImpl { file: file.clone(), block, method_trees });
+        }
+    }
+
+    let mut similar = Vec::new();
+    for i in 0..impls.len() {
+        for j in (i + 1)..impls.len() {
+            let result = compare_impls(&impls[i], &impls[j], options);
+            if result.similarity >= threshold {
+                similar.push((i, j, result));
+            }
+        }
+    }
+    similar.sort_by(|a, b| {
+        b.2.similarity.partial_cmp(&a.2.similarity).unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    if similar.is_empty() {
+        println!("\nNo similar impl blocks found!");
+        return Ok(());
+    }
+
+    for (i, j, result) in &similar {
+        let (impl1, impl2) = (&impls[*i], &impls[*j]);
+        println!("\n  {} <-> {}", describe(impl1), describe(impl2));
+        println!("  Similarity: {:.2}%", result.similarity * 100.0);
+        for (m1, m2, similarity) in &result.method_pairs {
+            println!(
+                "    {} <-> {}: {:.2}%",
+                impl1.block.methods[*m1].name,
+                impl2.block.methods[*m2].name,
+                similarity * 100.0
+            );
+        }
+    }
+
+    println!("\nTotal similar impl blocks: {}", similar.len());
+
+    Ok(())
+}
+
+fn describe(parsed: &ParsedImpl) -> String {
+    format_function_output(
+        &parsed.file.to_string_lossy(),
+        &parsed.block.header(),
+        parsed.block.start_line,
+        parsed.block.end_line,
+    )
+}
+
+/// Score two impl blocks by the aggregate similarity of their method sets.
+///
+/// Every method is matched to its most similar counterpart in the other
+/// block; the score averages these best matches over the methods of both
+/// blocks, so a method without counterpart pulls the score down.
+fn compare_impls(impl1: &ParsedImpl, impl2: &ParsedImpl, options: &TSEDOptions) -> ImplSimilarity {
+    let scores: Vec<Vec<f64>> = impl1
+        .method_trees
+        .iter()
+        .map(|tree1| {
+            impl2.method_trees.iter().map(|tree2| calculate_tsed(tree1, tree2, options)).collect()
+        })
+        .collect();
+
+    let best = |row: &[f64]| row.iter().cloned().fold(0.0, f64::max);
+
+    let mut method_pairs = Vec::new();
+    let mut total = 0.0;
+    for (m1, row) in scores.iter().enumerate() {
+        let score = best(row);
+        total += score;
+        if let Some(m2) = row.iter().position(|&s| s == score) {
+            method_pairs.push((m1, m2, score));
+        }
+    }
+    for m2 in 0..impl2.method_trees.len() {
+        let column: Vec<f64> = scores.iter().map(|row| row[m2]).collect();
+        total += best(&column);
+    }
+
+    let method_count = (impl1.method_trees.len() + impl2.method_trees.len()) as f64;
+    ImplSimilarity { similarity: total / method_count, method_pairs }
+}
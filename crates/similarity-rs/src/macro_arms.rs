@@ -0,0 +1,82 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::rust_parser::{MacroArm, RustParser};
+use similarity_core::{
+    cli_output::format_function_output,
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::fs;
+use std::path::Path;
+
+/// Report rules of the same `macro_rules!` macro whose matcher and transcriber,
+/// compared as one tree, are similar
+pub fn check_macro_arms(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let mut similar = Vec::new();
+    for file in &files {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        let arms = match parser.extract_macro_arms(&content) {
+            Ok(arms) => arms,
+            Err(e) => {
+                eprintln!("Error parsing {}: {}", file.display(), e);
+                continue;
+            }
+        };
+
+        for i in 0..arms.len() {
+            for j in (i + 1)..arms.len() {
+                let (arm1, arm2) = (&arms[i], &arms[j]);
+                if arm1.macro_name != arm2.macro_name {
+                    continue;
+                }
+                let similarity = calculate_tsed(&arm1.tree, &arm2.tree, options);
+                if similarity >= threshold {
+                    similar.push((file.clone(), arm1.clone(), arm2.clone(), similarity));
+                }
+            }
+        }
+    }
+    similar.sort_by(|a, b| b.3.partial_cmp(&a.3).unwrap_or(std::cmp::Ordering::Equal));
+
+    if similar.is_empty() {
+        println!("\nNo similar macro arms found!");
+        return Ok(());
+    }
+
+    for (file, arm1, arm2, similarity) in &similar {
+        println!("\n  {} <-> {}", describe(file, arm1), describe(file, arm2));
+        println!("  Similarity: {:.2}%", similarity * 100.0);
+    }
+
+    println!("\nTotal similar macro arms: {}", similar.len());
+
+    Ok(())
+}
+
+fn describe(file: &Path, arm: &MacroArm) -> String {
+    format_function_output(
+        &file.to_string_lossy(),
+        &format!("{}! arm {}", arm.macro_name, arm.index),
+        arm.start_line,
+        arm.end_line,
+    )
+}
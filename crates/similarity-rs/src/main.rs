@@ -1,15 +1,47 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{error::ErrorKind, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+mod across;
+mod api_diff;
+mod baseline;
+mod blocks;
 mod check;
+mod checkpoint;
+mod config;
+mod contracts;
+mod core_similarity;
+mod coverage;
+mod error_strings;
+mod fixtures;
+mod function_at;
+mod generics;
+mod golden;
+mod idf_weighting;
+mod impl_blocks;
+mod macro_arms;
+mod mirrors;
+mod modules;
 mod parallel;
+mod param_clusters;
+mod patterns;
+mod query;
+mod renames;
 mod rust_parser;
+mod top_k;
+mod types_catalog;
+mod watch;
 
 #[derive(Parser)]
 #[command(name = "similarity-rs")]
 #[command(about = "Rust code similarity analyzer")]
 #[command(version)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Paths to analyze (files or directories)
     #[arg(default_value = ".")]
     paths: Vec<String>,
@@ -18,7 +50,7 @@ struct Cli {
     #[arg(short, long)]
     print: bool,
 
-    /// Similarity threshold (0.0-1.0)
+    /// Similarity threshold (0.0-1.0); 0 reports every compared pair with its score
     #[arg(short, long, default_value = "0.85")]
     threshold: f64,
 
@@ -27,19 +59,19 @@ struct Cli {
     extensions: Option<Vec<String>>,
 
     /// Minimum lines for functions to be considered
-    #[arg(short, long, default_value = "3")]
+    #[arg(short, long, default_value = "3", global = true)]
     min_lines: Option<u32>,
 
     /// Minimum tokens for functions to be considered
-    #[arg(long, default_value = "30")]
+    #[arg(long, default_value = "30", global = true)]
     min_tokens: Option<u32>,
 
     /// Rename cost for APTED algorithm
-    #[arg(short, long, default_value = "0.3")]
+    #[arg(short, long, default_value = "0.3", global = true)]
     rename_cost: f64,
 
     /// Disable size penalty for very different sized functions
-    #[arg(long)]
+    #[arg(long, global = true)]
     no_size_penalty: bool,
 
     /// Filter functions by name (substring match)
@@ -54,14 +86,38 @@ struct Cli {
     #[arg(long)]
     no_fast: bool,
 
-    /// Exclude directories matching the given patterns (can be specified multiple times)
+    /// Skip files matching the given glob, relative to the scanned path (can be specified multiple times)
     #[arg(long)]
     exclude: Vec<String>,
 
-    /// Skip test functions (functions starting with 'test_' or annotated with #[test])
+    /// Only analyze files matching one of the given globs, relative to the scanned path (can be specified multiple times)
+    #[arg(long)]
+    include: Vec<String>,
+
+    /// Also analyze files matched by `.gitignore`, `.ignore` or the global gitignore
+    #[arg(long)]
+    no_ignore: bool,
+
+    /// Also analyze hidden files and directories
     #[arg(long)]
+    hidden: bool,
+
+    /// Skip test functions (functions starting with 'test_' or annotated with #[test])
+    #[arg(long, global = true)]
     skip_test: bool,
 
+    /// Skip one-expression methods of standard trait impls (`From`, `Display`, `Default`, ...)
+    #[arg(long)]
+    skip_trivial_impls: bool,
+
+    /// Also compare closures, named `closure@line:col` in the output
+    #[arg(long)]
+    include_closures: bool,
+
+    /// Skip functions whose syntax tree contains parse errors
+    #[arg(long, global = true)]
+    skip_parse_errors: bool,
+
     /// Enable experimental overlap detection mode
     #[arg(long = "experimental-overlap")]
     overlap: bool,
@@ -77,36 +133,555 @@ struct Cli {
     /// Size tolerance for overlap detection (0.0-1.0)
     #[arg(long, default_value = "0.25")]
     overlap_size_tolerance: f64,
+
+    /// Abort when the estimated number of function comparisons exceeds this limit
+    #[arg(long)]
+    max_comparisons: Option<u64>,
+
+    /// Fail only when the share of scanned lines inside duplicated functions exceeds PCT percent
+    #[arg(long, value_name = "PCT")]
+    max_duplication_ratio: Option<f64>,
+
+    /// Exit with a non-zero status when any duplicate pair is reported
+    #[arg(long, alias = "error-exit")]
+    fail_on_duplicates: bool,
+
+    /// Number of duplicate pairs tolerated by --fail-on-duplicates
+    #[arg(long, value_name = "N", requires = "fail_on_duplicates")]
+    max_allowed: Option<usize>,
+
+    /// Hide the duplicate pairs recorded in this baseline file, reporting only new ones
+    #[arg(long, value_name = "FILE", conflicts_with = "stream")]
+    baseline: Option<PathBuf>,
+
+    /// Record the pairs found into the --baseline file instead of hiding them
+    #[arg(long, requires = "baseline")]
+    write_baseline: bool,
+
+    /// Record each compared file and its pairs in this checkpoint, so that an
+    /// interrupted run can be continued with --resume
+    #[arg(long, value_name = "FILE", conflicts_with = "watch")]
+    checkpoint: Option<PathBuf>,
+
+    /// Continue the run recorded in this checkpoint, comparing only the files
+    /// it does not list or that changed since
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["checkpoint", "watch"])]
+    resume: Option<PathBuf>,
+
+    /// Compare iterator chains as ordered pipeline stages (map, filter, fold, ...)
+    #[arg(long, global = true)]
+    normalize_pipelines: bool,
+
+    /// Experimental: treat `!(a == b)` as `a != b` and ignore operand order of `&&`/`||` on pure operands
+    #[arg(long, global = true)]
+    normalize_boolean: bool,
+
+    /// Treat `vec![..]`, `Vec::from([..])` and `[..].to_vec()` as the same construction
+    #[arg(long, global = true)]
+    normalize_containers: bool,
+
+    /// Ignore the values of string and number literals, including raw strings
+    #[arg(long, global = true)]
+    ignore_literals: bool,
+
+    /// Leave `self` receivers out of method trees, so methods compare with free functions
+    #[arg(long, global = true)]
+    exclude_receiver: bool,
+
+    /// Compare `let x = f().await; ..` and `f().then(|x| ..)` as the same code
+    #[arg(long, global = true)]
+    normalize_async: bool,
+
+    /// Compare primitive types by kind of value, so `i32` and `u64` match
+    #[arg(long, global = true)]
+    normalize_primitive_types: bool,
+
+    /// Run APTED on every pair, even when the tree sizes rule out the threshold
+    #[arg(long, global = true)]
+    no_size_prefilter: bool,
+
+    /// Weight of structure over naming: 0 compares names too, 1 ignores identifier names
+    #[arg(long, value_name = "W", default_value = "0.0", global = true)]
+    structure_weight: f64,
+
+    /// Show line count, token count, complexity and parameter count for each reported function
+    #[arg(long)]
+    with_metrics: bool,
+
+    /// Show how similar the leading assertions and guard clauses of each reported pair are
+    #[arg(long)]
+    with_contracts: bool,
+
+    /// Prefilter strategy used to skip unlikely pairs before the tree comparison
+    #[arg(long, value_enum, default_value = "none")]
+    prefilter: parallel::Prefilter,
+
+    /// Compare `impl` blocks by the similarity of their method sets
+    #[arg(long)]
+    impls: bool,
+
+    /// Compare whole files and directories by how well their functions match; report pairs above this score
+    #[arg(long, value_name = "T")]
+    module_threshold: Option<f64>,
+
+    /// Compare the leading assertions and guard clauses of functions separately from their logic
+    #[arg(long)]
+    contracts: bool,
+
+    /// Report error messages duplicated across panics, `expect` calls and error constructors
+    #[arg(long)]
+    error_strings: bool,
+
+    /// Compare the rules of each `macro_rules!` macro to find near-identical arms
+    #[arg(long)]
+    macro_arms: bool,
+
+    /// Report functions that differ only in the concrete types they use (candidates for generics)
+    #[arg(long)]
+    generic_dups: bool,
+
+    /// Group functions taking the same parameter list (candidates for a parameter struct)
+    #[arg(long)]
+    param_clusters: bool,
+
+    /// Compare the shared core of functions, ignoring differing leading and trailing statements
+    #[arg(long)]
+    core: bool,
+
+    /// Weight similarity by identifier frequency so that shared rare names count more than common ones
+    #[arg(long)]
+    idf_weighting: bool,
+
+    /// Report test functions sharing the same setup before their first assertion
+    #[arg(long)]
+    test_fixtures: bool,
+
+    /// Report runs of statements repeated within a file, even across function boundaries
+    #[arg(long)]
+    blocks: bool,
+
+    /// Minimum number of statements in a repeated run reported by --blocks
+    #[arg(long, value_name = "N", default_value = "4", value_parser = clap::value_parser!(u64).range(1..))]
+    block_size: u64,
+
+    /// Report pairs of functions where one undoes the other (encode/decode, push/pop, ...)
+    #[arg(long)]
+    mirrors: bool,
+
+    /// Report the K most similar functions of every function, to spot ambiguous duplicates
+    #[arg(long, value_name = "K", value_parser = clap::value_parser!(u64).range(1..))]
+    top_k: Option<u64>,
+
+    /// Only report duplicates with one function under DIR_A and the other under DIR_B
+    #[arg(long, num_args = 2, value_names = ["DIR_A", "DIR_B"])]
+    across: Option<Vec<PathBuf>>,
+
+    /// Match every function to its closest copy under DIR and report how far it has drifted from it
+    #[arg(long, value_name = "DIR", conflicts_with = "across")]
+    golden: Option<PathBuf>,
+
+    /// Number of threads used to read and parse files (defaults to the number of CPUs)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    io_threads: Option<u64>,
+
+    /// Number of threads used to compare functions (defaults to the number of CPUs)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    compare_threads: Option<u64>,
+
+    /// Only compare a random sample of N functions for a quick duplication estimate
+    #[arg(long, value_name = "N")]
+    sample: Option<usize>,
+
+    /// Seed for --sample, so that the same functions are picked on every run
+    #[arg(long, default_value = "0")]
+    seed: u64,
+
+    /// List functions excluded from comparison and the filter that removed each
+    #[arg(long)]
+    explain_filter: bool,
+
+    /// List parsed files from which no function was extracted
+    #[arg(long)]
+    report_empty: bool,
+
+    /// Also write results into exact.json, high.json and moderate.json in this directory
+    #[arg(long, value_name = "DIR")]
+    split_output: Option<PathBuf>,
+
+    /// Do not report same-named functions that differ only by their `#[cfg(...)]` gate
+    #[arg(long)]
+    ignore_cfg_variants: bool,
+
+    /// Print each similar pair as soon as it is found instead of a sorted report at the end
+    #[arg(long)]
+    stream: bool,
+
+    /// Also list a small set of functions that together touch every duplicate pair
+    #[arg(long)]
+    cover_set: bool,
+
+    /// Report only the most similar pair of each cluster of similar functions, with the cluster size
+    #[arg(long, conflicts_with = "stream")]
+    one_per_cluster: bool,
+
+    /// Print each cluster of similar functions as one block, led by its longest function
+    #[arg(long, conflicts_with_all = ["stream", "one_per_cluster"])]
+    group: bool,
+
+    /// Report a cluster whose functions are all similar to each other as its longest function against each of the others
+    #[arg(long, conflicts_with = "stream")]
+    transitive_dedup: bool,
+
+    /// Include the source of both functions in JSON pairs (--split-output, --watch --format jsonl)
+    #[arg(long)]
+    embed_source: bool,
+
+    /// Truncate embedded sources to this many lines
+    #[arg(long, value_name = "N", requires = "embed_source")]
+    max_embed_lines: Option<usize>,
+
+    /// List the files that would be scanned, with counts per language, and exit
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Keep running and re-check files whenever they change
+    #[arg(long)]
+    watch: bool,
+
+    /// Polling interval of --watch in milliseconds
+    #[arg(long, value_name = "MS", default_value = "500")]
+    watch_interval: u64,
+
+    /// Read settings from this file instead of the nearest `.similarityrc`; it holds the
+    /// file, function and scoring flags and the exit gates, while mode, output and run flags
+    /// are command-line only
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Print the settings in effect after merging the config file and flags, then exit
+    #[arg(long)]
+    print_config: bool,
+
+    /// Escape non-ASCII characters of function and type names as `\u{...}` in the output
+    #[arg(long)]
+    ascii_escape: bool,
+
+    /// Output format: `markdown-todo` prints a checklist of the pairs; `fixplan` prints a JSON
+    /// plan of the functions to keep and replace; `sarif` streams a SARIF log; `json` prints
+    /// the pairs and a summary as one JSON document; `jsonl` writes one JSON event per change
+    /// cycle of --watch. The documents cover the function pairs only and cannot be combined
+    /// with the analysis modes (--impls, --blocks, --across, ...)
+    #[arg(long, value_enum, default_value = "text")]
+    format: check::OutputFormat,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Compare public function signatures and types between two versions of a crate
+    ApiDiff {
+        /// Directory of the old version
+        old: PathBuf,
+        /// Directory of the new version
+        new: PathBuf,
+    },
+    /// Report functions similar to known patterns, one exemplar file per pattern
+    MatchPatterns {
+        /// Directory of pattern files; each pattern is named after its file
+        #[arg(long)]
+        patterns: PathBuf,
+        /// Paths to scan (files or directories)
+        #[arg(default_value = ".")]
+        paths: Vec<String>,
+        /// Similarity threshold (0.0-1.0)
+        #[arg(short, long, default_value = "0.85")]
+        threshold: f64,
+    },
+    /// Report functions similar to the function containing a given line
+    At {
+        /// File containing the function
+        #[arg(long)]
+        file: PathBuf,
+        /// 1-based line inside the function
+        #[arg(long)]
+        line: u32,
+        /// Paths to scan (files or directories)
+        #[arg(default_value = ".")]
+        paths: Vec<String>,
+        /// File extensions to check
+        #[arg(short, long, value_delimiter = ',')]
+        extensions: Option<Vec<String>>,
+        /// Similarity threshold (0.0-1.0)
+        #[arg(short, long, default_value = "0.85")]
+        threshold: f64,
+    },
+    /// Report functions similar to the function with a given name, most similar first
+    Query {
+        /// Name of the function to look for
+        #[arg(long)]
+        function: String,
+        /// Paths to scan (files or directories)
+        #[arg(long = "in", value_name = "PATH", default_value = ".")]
+        paths: Vec<String>,
+        /// File extensions to check
+        #[arg(short, long, value_delimiter = ',')]
+        extensions: Option<Vec<String>>,
+        /// Similarity threshold (0.0-1.0)
+        #[arg(short, long, default_value = "0.85")]
+        threshold: f64,
+    },
+    /// List the types of the scanned code with their normalized properties
+    Types {
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: types_catalog::CatalogFormat,
+        /// Compare tuple structs and tuple type aliases by their element types
+        #[arg(long)]
+        tuples: bool,
+        /// Report duplicate types instead of listing them
+        #[arg(long, conflicts_with = "format")]
+        duplicates: bool,
+        /// Similarity threshold for --duplicates (0.0-1.0)
+        #[arg(short, long, default_value = "0.9", requires = "duplicates")]
+        threshold: f64,
+        /// Paths to scan (files or directories)
+        #[arg(default_value = ".")]
+        paths: Vec<String>,
+    },
+    /// Pair each function deleted between two revisions with the most similar added function
+    Renames {
+        /// Base revision
+        base: String,
+        /// Head revision
+        #[arg(default_value = "HEAD")]
+        head: String,
+        /// Git repository to inspect
+        #[arg(long, default_value = ".")]
+        repo: PathBuf,
+    },
+    /// Report per language how many files, functions and source bytes the parser recognizes
+    Coverage {
+        /// File extensions to check
+        #[arg(short, long, value_delimiter = ',')]
+        extensions: Option<Vec<String>>,
+        /// Paths to scan (files or directories)
+        #[arg(default_value = ".")]
+        paths: Vec<String>,
+    },
 }
 
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches)?;
+    // Documents have a layout of their own, which `--group` would silently lose
+    if cli.group && cli.format.is_document() {
+        let format = cli.format.to_possible_value().expect("formats are not hidden");
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!("--group cannot be used with --format {}", format.get_name()),
+            )
+            .exit();
+    }
+    // The analysis modes only have a text report, which would follow the document
+    if let Some(mode) = text_only_mode(&cli).filter(|_| cli.format.is_document()) {
+        let format = cli.format.to_possible_value().expect("formats are not hidden");
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                format!("{mode} cannot be used with --format {}", format.get_name()),
+            )
+            .exit();
+    }
+
+    let config_path = cli.config.clone().or_else(|| config::discover(Path::new(&cli.paths[0])));
+    if let Some(path) = &config_path {
+        config::FileConfig::load(path)?.apply(&mut cli, &matches);
+    }
+
+    if cli.print_config {
+        if let Some(path) = &config_path {
+            println!("# Loaded from {}", path.display());
+        }
+        print!("{}", toml::to_string(&config::FileConfig::effective(&cli))?);
+        return Ok(());
+    }
+
+    let check_options = check::CheckOptions {
+        threshold: cli.threshold,
+        rename_cost: cli.rename_cost,
+        extensions: cli.extensions.clone(),
+        min_lines: cli.min_lines.unwrap_or(3),
+        min_tokens: cli.min_tokens,
+        no_size_penalty: cli.no_size_penalty,
+        print: cli.print,
+        fast_mode: !cli.no_fast,
+        filter_function: cli.filter_function.clone(),
+        filter_function_body: cli.filter_function_body.clone(),
+        exclude_patterns: cli.exclude.clone(),
+        include_patterns: cli.include.clone(),
+        no_ignore: cli.no_ignore,
+        hidden: cli.hidden,
+        skip_test: cli.skip_test,
+        skip_trivial_impls: cli.skip_trivial_impls,
+        include_closures: cli.include_closures,
+        skip_parse_errors: cli.skip_parse_errors,
+        max_comparisons: cli.max_comparisons,
+        max_duplication_ratio: cli.max_duplication_ratio,
+        fail_on_duplicates: cli.fail_on_duplicates,
+        max_allowed: cli.max_allowed,
+        baseline: cli.baseline.clone(),
+        write_baseline: cli.write_baseline,
+        checkpoint: cli.resume.clone().or_else(|| cli.checkpoint.clone()),
+        resume: cli.resume.is_some(),
+        normalize_pipelines: cli.normalize_pipelines,
+        normalize_boolean: cli.normalize_boolean,
+        normalize_containers: cli.normalize_containers,
+        ignore_literals: cli.ignore_literals,
+        exclude_receiver: cli.exclude_receiver,
+        normalize_async: cli.normalize_async,
+        normalize_primitive_types: cli.normalize_primitive_types,
+        no_size_prefilter: cli.no_size_prefilter,
+        structure_weight: cli.structure_weight,
+        with_metrics: cli.with_metrics,
+        with_contracts: cli.with_contracts,
+        prefilter: cli.prefilter,
+        split_output: cli.split_output.clone(),
+        io_threads: cli.io_threads.map(|n| n as usize),
+        compare_threads: cli.compare_threads.map(|n| n as usize),
+        sample: cli.sample,
+        seed: cli.seed,
+        explain_filter: cli.explain_filter,
+        report_empty: cli.report_empty,
+        ignore_cfg_variants: cli.ignore_cfg_variants,
+        stream: cli.stream,
+        cover_set: cli.cover_set,
+        one_per_cluster: cli.one_per_cluster,
+        group: cli.group,
+        transitive_dedup: cli.transitive_dedup,
+        embed_source: cli.embed_source,
+        max_embed_lines: cli.max_embed_lines,
+        format: cli.format,
+        ascii_escape: cli.ascii_escape,
+    };
+
+    let file_filter = check_options.file_filter();
+
+    match &cli.command {
+        Some(Command::ApiDiff { old, new }) => return api_diff::api_diff(old, new, &file_filter),
+        Some(Command::MatchPatterns { patterns, paths, threshold }) => {
+            return patterns::match_patterns(
+                patterns,
+                paths,
+                &file_filter,
+                *threshold,
+                &check_options.tsed_options(),
+            );
+        }
+        Some(Command::At { file, line, paths, extensions, threshold }) => {
+            return function_at::similar_to_function_at(
+                file,
+                *line,
+                paths,
+                extensions.as_ref(),
+                &file_filter,
+                *threshold,
+                &check_options.tsed_options(),
+            );
+        }
+        Some(Command::Query { function, paths, extensions, threshold }) => {
+            return query::query_function(
+                function,
+                paths,
+                extensions.as_ref(),
+                &file_filter,
+                *threshold,
+                &check_options.tsed_options(),
+            );
+        }
+        Some(Command::Types { format, tuples, duplicates, threshold, paths }) => {
+            return if *duplicates {
+                types_catalog::report_duplicate_types(paths, &file_filter, *tuples, *threshold)
+            } else {
+                types_catalog::list_types(paths, &file_filter, *format, *tuples)
+            };
+        }
+        Some(Command::Renames { base, head, repo }) => {
+            return renames::match_renames(repo, base, head);
+        }
+        Some(Command::Coverage { extensions, paths }) => {
+            return coverage::report_coverage(paths, extensions.as_ref(), &file_filter);
+        }
+        None => {}
+    }
+
+    if cli.dry_run {
+        return check::dry_run(&cli.paths, &check_options);
+    }
+
+    match cli.format {
+        check::OutputFormat::Jsonl if !cli.watch => {
+            anyhow::bail!("--format jsonl requires --watch");
+        }
+        check::OutputFormat::MarkdownTodo if cli.watch || cli.stream => {
+            anyhow::bail!("--format markdown-todo cannot be used with --watch or --stream");
+        }
+        check::OutputFormat::Fixplan if cli.watch => {
+            anyhow::bail!("--format fixplan cannot be used with --watch");
+        }
+        check::OutputFormat::Json if cli.watch || cli.stream => {
+            anyhow::bail!("--format json cannot be used with --watch or --stream");
+        }
+        check::OutputFormat::Sarif if cli.watch || cli.stream => {
+            anyhow::bail!("--format sarif cannot be used with --watch or --stream");
+        }
+        _ => {}
+    }
+
+    if cli.watch {
+        return watch::watch(
+            &cli.paths,
+            &check_options,
+            cli.format,
+            Duration::from_millis(cli.watch_interval),
+        );
+    }
 
     let functions_enabled = true; // Rust always has functions enabled
     let overlap_enabled = cli.overlap;
 
-    println!("Analyzing Rust code similarity...\n");
+    // Reports meant for tools or checklists are printed without banners
+    if !cli.format.is_document() {
+        println!("Analyzing Rust code similarity...\n");
+    }
 
     let separator = "-".repeat(60);
 
     // Run functions analysis
     if !overlap_enabled || functions_enabled {
-        println!("=== Function Similarity ===");
-        check::check_paths(
-            cli.paths.clone(),
-            cli.threshold,
-            cli.rename_cost,
-            cli.extensions.as_ref(),
-            cli.min_lines.unwrap_or(3),
-            cli.min_tokens,
-            cli.no_size_penalty,
-            cli.print,
-            !cli.no_fast,
-            cli.filter_function.as_ref(),
-            cli.filter_function_body.as_ref(),
-            &cli.exclude,
-            cli.skip_test,
-        )?;
+        if !cli.format.is_document() {
+            println!("=== Function Similarity ===");
+        }
+        if let Some(dirs) = &cli.across {
+            across::check_across(
+                &dirs[0],
+                &dirs[1],
+                cli.extensions.as_ref(),
+                &file_filter,
+                cli.threshold,
+                &check_options.tsed_options(),
+            )?;
+        } else if let Some(golden_dir) = &cli.golden {
+            golden::check_golden(
+                &cli.paths,
+                golden_dir,
+                cli.extensions.as_ref(),
+                &file_filter,
+                cli.threshold,
+                &check_options.tsed_options(),
+            )?;
+        } else {
+            check::check_paths(cli.paths.clone(), &check_options)?;
+        }
     }
 
     // Run overlap analysis if enabled
@@ -117,20 +692,192 @@ fn main() -> Result<()> {
     if overlap_enabled {
         println!("=== Overlap Detection ===");
         check_overlaps(
-            cli.paths,
+            cli.paths.clone(),
             cli.threshold,
             cli.extensions.as_ref(),
             cli.print,
             cli.overlap_min_window,
             cli.overlap_max_window,
             cli.overlap_size_tolerance,
-            &cli.exclude,
+            &file_filter,
+        )?;
+    }
+
+    if cli.impls {
+        println!("\n{separator}\n");
+        println!("=== Impl Block Similarity ===");
+        impl_blocks::check_impls(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            cli.threshold,
+            &check_options.tsed_options(),
+        )?;
+    }
+
+    if let Some(module_threshold) = cli.module_threshold {
+        println!("\n{separator}\n");
+        println!("=== Module Similarity ===");
+        modules::check_modules(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            module_threshold,
+            &check_options.tsed_options(),
+        )?;
+    }
+
+    if cli.contracts {
+        println!("\n{separator}\n");
+        println!("=== Function Contract Similarity ===");
+        contracts::check_contracts(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            cli.threshold,
+            &check_options.tsed_options(),
+        )?;
+    }
+
+    if cli.error_strings {
+        println!("\n{separator}\n");
+        println!("=== Duplicated Error Strings ===");
+        error_strings::check_error_strings(&cli.paths, cli.extensions.as_ref(), &file_filter)?;
+    }
+
+    if cli.macro_arms {
+        println!("\n{separator}\n");
+        println!("=== Similar Macro Arms ===");
+        macro_arms::check_macro_arms(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            cli.threshold,
+            &check_options.tsed_options(),
+        )?;
+    }
+
+    if cli.generic_dups {
+        println!("\n{separator}\n");
+        println!("=== Generic-able Duplicates ===");
+        generics::check_generic_duplicates(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            cli.threshold,
+            &check_options.tsed_options(),
+        )?;
+    }
+
+    if cli.param_clusters {
+        println!("\n{separator}\n");
+        println!("=== Parameter Clusters ===");
+        param_clusters::check_param_clusters(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            check_options.skip_parse_errors,
+        )?;
+    }
+
+    if cli.core {
+        println!("\n{separator}\n");
+        println!("=== Core Similarity ===");
+        core_similarity::check_core_similarity(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            cli.threshold,
+            &check_options.tsed_options(),
+        )?;
+    }
+
+    if let Some(k) = cli.top_k {
+        println!("\n{separator}\n");
+        println!("=== Top-{k} Matches ===");
+        top_k::check_top_k(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            cli.threshold,
+            k as usize,
+            &check_options.tsed_options(),
+        )?;
+    }
+
+    if cli.idf_weighting {
+        println!("\n{separator}\n");
+        println!("=== IDF-Weighted Similarity ===");
+        idf_weighting::check_idf_weighted(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            cli.threshold,
+            &check_options.tsed_options(),
+        )?;
+    }
+
+    if cli.test_fixtures {
+        println!("\n{separator}\n");
+        println!("=== Duplicated Test Setup ===");
+        fixtures::check_test_fixtures(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            cli.threshold,
+            &check_options.tsed_options(),
+        )?;
+    }
+
+    if cli.blocks {
+        println!("\n{separator}\n");
+        println!("=== Repeated Blocks ===");
+        blocks::check_blocks(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            cli.block_size as usize,
+        )?;
+    }
+
+    if cli.mirrors {
+        println!("\n{separator}\n");
+        println!("=== Mirrored Functions ===");
+        mirrors::check_mirrors(
+            &cli.paths,
+            cli.extensions.as_ref(),
+            &file_filter,
+            &check_options.tsed_options(),
         )?;
     }
 
     Ok(())
 }
 
+/// The first enabled analysis mode that reports as text only
+fn text_only_mode(cli: &Cli) -> Option<&'static str> {
+    [
+        ("--across", cli.across.is_some()),
+        ("--golden", cli.golden.is_some()),
+        ("--overlap", cli.overlap),
+        ("--impls", cli.impls),
+        ("--module-threshold", cli.module_threshold.is_some()),
+        ("--contracts", cli.contracts),
+        ("--error-strings", cli.error_strings),
+        ("--macro-arms", cli.macro_arms),
+        ("--generic-dups", cli.generic_dups),
+        ("--param-clusters", cli.param_clusters),
+        ("--core", cli.core),
+        ("--top-k", cli.top_k.is_some()),
+        ("--idf-weighting", cli.idf_weighting),
+        ("--test-fixtures", cli.test_fixtures),
+        ("--blocks", cli.blocks),
+        ("--mirrors", cli.mirrors),
+    ]
+    .into_iter()
+    .find_map(|(flag, enabled)| enabled.then_some(flag))
+}
+
 #[allow(clippy::too_many_arguments)]
 fn check_overlaps(
     paths: Vec<String>,
@@ -140,78 +887,17 @@ fn check_overlaps(
     min_window_size: u32,
     max_window_size: u32,
     size_tolerance: f64,
-    exclude_patterns: &[String],
+    file_filter: &check::FileFilter,
 ) -> anyhow::Result<()> {
     use crate::rust_parser::RustParser;
-    use ignore::WalkBuilder;
     use similarity_core::{find_overlaps_across_files_generic, OverlapOptions};
-    use std::collections::{HashMap, HashSet};
+    use std::collections::HashMap;
     use std::fs;
-    use std::path::Path;
-
     let default_extensions = vec!["rs"];
     let exts: Vec<&str> =
         extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
 
-    let exclude_matcher = create_exclude_matcher(exclude_patterns);
-    let mut files = Vec::new();
-    let mut visited = HashSet::new();
-
-    // Process each path
-    for path_str in &paths {
-        let path = Path::new(path_str);
-
-        if path.is_file() {
-            // If it's a file, check extension and add it
-            if let Some(ext) = path.extension() {
-                if let Some(ext_str) = ext.to_str() {
-                    if exts.contains(&ext_str) {
-                        if let Ok(canonical) = path.canonicalize() {
-                            if visited.insert(canonical.clone()) {
-                                files.push(path.to_path_buf());
-                            }
-                        }
-                    }
-                }
-            }
-        } else if path.is_dir() {
-            // If it's a directory, walk it respecting .gitignore
-            let walker = WalkBuilder::new(path).follow_links(false).build();
-
-            for entry in walker {
-                let entry = entry?;
-                let entry_path = entry.path();
-
-                // Skip if not a file
-                if !entry_path.is_file() {
-                    continue;
-                }
-
-                // Check if path should be excluded
-                if let Some(ref matcher) = exclude_matcher {
-                    if matcher.is_match(entry_path) {
-                        continue;
-                    }
-                }
-
-                // Check extension
-                if let Some(ext) = entry_path.extension() {
-                    if let Some(ext_str) = ext.to_str() {
-                        if exts.contains(&ext_str) {
-                            // Get canonical path to avoid duplicates
-                            if let Ok(canonical) = entry_path.canonicalize() {
-                                if visited.insert(canonical.clone()) {
-                                    files.push(entry_path.to_path_buf());
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        } else {
-            eprintln!("Warning: Path not found: {path_str}");
-        }
-    }
+    let files = file_filter.collect(&paths, &exts)?;
 
     if files.is_empty() {
         println!("No Rust files found in specified paths");
@@ -312,23 +998,6 @@ fn check_overlaps(
     Ok(())
 }
 
-fn create_exclude_matcher(exclude_patterns: &[String]) -> Option<globset::GlobSet> {
-    if exclude_patterns.is_empty() {
-        return None;
-    }
-
-    let mut builder = globset::GlobSetBuilder::new();
-    for pattern in exclude_patterns {
-        if let Ok(glob) = globset::Glob::new(pattern) {
-            builder.add(glob);
-        } else {
-            eprintln!("Warning: Invalid glob pattern: {pattern}");
-        }
-    }
-
-    builder.build().ok()
-}
-
 fn get_relative_path(file_path: &str) -> String {
     if let Ok(current_dir) = std::env::current_dir() {
         std::path::Path::new(file_path)
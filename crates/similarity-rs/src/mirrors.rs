@@ -0,0 +1,228 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_output::format_function_output,
+    tree::TreeNode,
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use tree_sitter::{Node, Parser};
+
+/// Operators and methods undoing each other, in both directions. `*` is left
+/// out, since it also dereferences and integer division does not undo it.
+const INVERSE_OPERATIONS: &[(&str, &str)] = &[
+    ("+", "-"),
+    ("+=", "-="),
+    ("<<", ">>"),
+    ("<<=", ">>="),
+    ("push", "pop"),
+    ("push_back", "pop_back"),
+    ("push_front", "pop_front"),
+    ("insert", "remove"),
+    ("wrapping_add", "wrapping_sub"),
+    ("rotate_left", "rotate_right"),
+    ("encode", "decode"),
+    ("encrypt", "decrypt"),
+    ("compress", "decompress"),
+    ("serialize", "deserialize"),
+    ("to_be_bytes", "from_be_bytes"),
+    ("to_le_bytes", "from_le_bytes"),
+    ("write", "read"),
+];
+
+/// Mirrors are only reported between functions at least this similar
+const MIN_MIRROR_SIMILARITY: f64 = 0.5;
+
+/// A statement reduced to its kind and the invertible operations in it
+struct Step {
+    kind: String,
+    operations: Vec<String>,
+}
+
+struct ParsedFunction {
+    file: PathBuf,
+    name: String,
+    start_line: u32,
+    end_line: u32,
+    steps: Vec<Step>,
+    tree: Rc<TreeNode>,
+}
+
+/// Report pairs of functions where one undoes the other: the statements of
+/// one, taken in reverse order, apply the inverse operations of the other's.
+pub fn check_mirrors(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = Parser::new();
+    parser.set_language(&tree_sitter_rust::LANGUAGE.into())?;
+    let mut rust_parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let mut functions = Vec::new();
+    for file in &files {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        let Some(tree) = parser.parse(&content, None) else {
+            eprintln!("Error parsing {}", file.display());
+            continue;
+        };
+        collect_functions(
+            tree.root_node(),
+            &content,
+            file,
+            &mut rust_parser,
+            options.skip_parse_errors,
+            &mut functions,
+        );
+    }
+
+    let mut mirrors = Vec::new();
+    for i in 0..functions.len() {
+        for j in (i + 1)..functions.len() {
+            if !is_mirror(&functions[i].steps, &functions[j].steps) {
+                continue;
+            }
+            let similarity = calculate_tsed(&functions[i].tree, &functions[j].tree, options);
+            if similarity >= MIN_MIRROR_SIMILARITY {
+                mirrors.push((i, j, similarity));
+            }
+        }
+    }
+
+    if mirrors.is_empty() {
+        println!("\nNo mirrored functions found!");
+        return Ok(());
+    }
+
+    for (i, j, similarity) in &mirrors {
+        println!("\n  {} <-> {}", describe(&functions[*i]), describe(&functions[*j]));
+        println!("  Similarity: {:.2}%", similarity * 100.0);
+        println!("  mirror: true");
+    }
+
+    println!("\nTotal mirrored pairs: {}", mirrors.len());
+
+    Ok(())
+}
+
+fn describe(function: &ParsedFunction) -> String {
+    format_function_output(
+        &function.file.to_string_lossy(),
+        &format!("function {}", function.name),
+        function.start_line,
+        function.end_line,
+    )
+}
+
+fn collect_functions(
+    node: Node,
+    source: &str,
+    file: &Path,
+    rust_parser: &mut RustParser,
+    skip_parse_errors: bool,
+    functions: &mut Vec<ParsedFunction>,
+) {
+    if node.kind() == "function_item" {
+        let name = node.child_by_field_name("name").map(|name| &source[name.byte_range()]);
+        if let (Some(name), Some(body)) = (name, node.child_by_field_name("body")) {
+            // The trailing expression only hands back the result
+            let steps: Vec<Step> = body
+                .named_children(&mut body.walk())
+                .filter(|child| matches!(child.kind(), "let_declaration" | "expression_statement"))
+                .map(|statement| {
+                    let mut operations = Vec::new();
+                    collect_operations(statement, source, &mut operations);
+                    operations.sort_unstable();
+                    Step { kind: statement.kind().to_string(), operations }
+                })
+                .collect();
+            let parsed = rust_parser.parse_counting_errors(&source[node.byte_range()], name);
+            if let Some((tree, _)) =
+                parsed.ok().filter(|(_, errors)| !skip_parse_errors || *errors == 0)
+            {
+                functions.push(ParsedFunction {
+                    file: file.to_path_buf(),
+                    name: name.to_string(),
+                    start_line: (node.start_position().row + 1) as u32,
+                    end_line: (node.end_position().row + 1) as u32,
+                    steps,
+                    tree,
+                });
+            }
+        }
+        return;
+    }
+
+    for child in node.children(&mut node.walk()) {
+        collect_functions(child, source, file, rust_parser, skip_parse_errors, functions);
+    }
+}
+
+/// Invertible binary operators, and names of invertible methods and functions,
+/// below `node`. A unary `-` negates rather than subtracts and is skipped.
+fn collect_operations(node: Node, source: &str, operations: &mut Vec<String>) {
+    if node.child_count() == 0 {
+        let token = &source[node.byte_range()];
+        let operand = node.is_named();
+        let binary = node.parent().is_some_and(|parent| {
+            matches!(parent.kind(), "binary_expression" | "compound_assignment_expr")
+        });
+        if (operand || binary) && inverse(token).is_some() {
+            operations.push(token.to_string());
+        }
+        return;
+    }
+    for child in node.children(&mut node.walk()) {
+        collect_operations(child, source, operations);
+    }
+}
+
+fn inverse(operation: &str) -> Option<&'static str> {
+    INVERSE_OPERATIONS.iter().find_map(|&(a, b)| match operation {
+        _ if operation == a => Some(b),
+        _ if operation == b => Some(a),
+        _ => None,
+    })
+}
+
+/// Whether the steps of `second`, last to first, invert those of `first`.
+///
+/// Conservative: the functions need at least two statements of matching
+/// kinds, every invertible operation must meet its inverse, and at least one
+/// statement must contain one.
+fn is_mirror(first: &[Step], second: &[Step]) -> bool {
+    if first.len() < 2 || first.len() != second.len() {
+        return false;
+    }
+
+    let mut inverted_any = false;
+    for (step, mirrored) in first.iter().zip(second.iter().rev()) {
+        if step.kind != mirrored.kind {
+            return false;
+        }
+        let mut inverted: Vec<&str> =
+            step.operations.iter().filter_map(|operation| inverse(operation)).collect();
+        inverted.sort_unstable();
+        if inverted != mirrored.operations {
+            return false;
+        }
+        inverted_any |= !inverted.is_empty();
+    }
+    inverted_any
+}
@@ -0,0 +1,139 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::patterns::parse_functions;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    tree::TreeNode,
+    tsed::{calculate_tsed, max_similarity_of_sizes, TSEDOptions},
+};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// A file, or the files directly inside a directory, with the parsed tree of
+/// each of its functions
+struct Module {
+    path: PathBuf,
+    is_directory: bool,
+    function_trees: Vec<Rc<TreeNode>>,
+}
+
+/// Report pairs of files, and of directories, whose functions mutually match
+pub fn check_modules(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut modules = Vec::new();
+    let mut directories: BTreeMap<PathBuf, Vec<Rc<TreeNode>>> = BTreeMap::new();
+    for file in &files {
+        let function_trees: Vec<Rc<TreeNode>> =
+            match parse_functions(&mut parser, file, options.skip_parse_errors) {
+                Ok(parsed) => parsed
+                    .into_iter()
+                    .filter(|(func, _)| func.end_line - func.start_line + 1 >= options.min_lines)
+                    .map(|(_, tree)| tree)
+                    .collect(),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    continue;
+                }
+            };
+        if function_trees.is_empty() {
+            continue;
+        }
+        if let Some(dir) = file.parent() {
+            directories.entry(dir.to_path_buf()).or_default().extend(function_trees.clone());
+        }
+        modules.push(Module { path: file.clone(), is_directory: false, function_trees });
+    }
+    modules.extend(directories.into_iter().map(|(path, function_trees)| Module {
+        path,
+        is_directory: true,
+        function_trees,
+    }));
+
+    // Files are compared with files and directories with directories
+    let mut similar = Vec::new();
+    for i in 0..modules.len() {
+        for j in (i + 1)..modules.len() {
+            if modules[i].is_directory != modules[j].is_directory {
+                continue;
+            }
+            let similarity = compare_modules(&modules[i], &modules[j], options);
+            if similarity >= threshold {
+                similar.push((i, j, similarity));
+            }
+        }
+    }
+    similar.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+
+    if similar.is_empty() {
+        println!("\nNo similar modules found!");
+        return Ok(());
+    }
+
+    for (i, j, similarity) in &similar {
+        let (module1, module2) = (&modules[*i], &modules[*j]);
+        println!("\n  {} <-> {}", describe(module1), describe(module2));
+        println!(
+            "  Similarity: {:.2}% ({} <-> {} functions)",
+            similarity * 100.0,
+            module1.function_trees.len(),
+            module2.function_trees.len()
+        );
+    }
+
+    println!("\nTotal similar modules: {}", similar.len());
+
+    Ok(())
+}
+
+fn describe(module: &Module) -> String {
+    let path = module.path.to_string_lossy();
+    if module.is_directory {
+        format!("{}/", path)
+    } else {
+        path.to_string()
+    }
+}
+
+/// Average similarity of every function to its best match in the other
+/// module, over the functions of both modules.
+///
+/// Only the best matches count, so a pair is skipped when its sizes alone
+/// keep it from beating the best match of both of its functions.
+fn compare_modules(module1: &Module, module2: &Module, options: &TSEDOptions) -> f64 {
+    let mut row_best = vec![0.0f64; module1.function_trees.len()];
+    let mut column_best = vec![0.0f64; module2.function_trees.len()];
+    for (i, tree1) in module1.function_trees.iter().enumerate() {
+        for (j, tree2) in module2.function_trees.iter().enumerate() {
+            if options.size_prefilter {
+                let bound = max_similarity_of_sizes(
+                    tree1.get_subtree_size(),
+                    tree2.get_subtree_size(),
+                    options,
+                );
+                if bound <= row_best[i] && bound <= column_best[j] {
+                    continue;
+                }
+            }
+            let similarity = calculate_tsed(tree1, tree2, options);
+            row_best[i] = row_best[i].max(similarity);
+            column_best[j] = column_best[j].max(similarity);
+        }
+    }
+
+    let total: f64 = row_best.iter().chain(&column_best).sum();
+    let function_count = (row_best.len() + column_best.len()) as f64;
+    total / function_count
+}
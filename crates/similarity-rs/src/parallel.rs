@@ -1,40 +1,72 @@
 #![allow(clippy::uninlined_format_args)]
 
+use crate::checkpoint::Checkpoint;
+use crate::rust_parser::ClosureTree;
 use rayon::prelude::*;
 use similarity_core::{
     cli_parallel::{FileData, SimilarityResult},
-    language_parser::{GenericFunctionDef, LanguageParser},
-    tsed::TSEDOptions,
+    control_flow::cfg_candidate_pairs,
+    function_extractor::FunctionType,
+    language_parser::{GenericFunctionDef, Language},
+    parser_pool::with_pooled_parser,
+    tree::TreeNode,
+    tsed::{KeyedTrees, TSEDOptions},
 };
+use similarity_rs::rust_parser::RustParser;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 /// Rust file with its content and extracted functions
-#[allow(dead_code)]
 pub type RustFileData = FileData<GenericFunctionDef>;
 
-/// Load and parse Rust files in parallel
-#[allow(dead_code)]
-pub fn load_files_parallel(files: &[PathBuf]) -> Vec<RustFileData> {
+/// What [`load_files_parallel`] extracts besides functions and methods
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoadOptions {
+    /// Leave out the one-expression methods of standard trait impls,
+    /// near-identical boilerplate across types
+    pub skip_trivial_impls: bool,
+    /// Also extract closures, as functions of kind [`FunctionType::Closure`]
+    pub include_closures: bool,
+}
+
+/// Load and parse Rust files in parallel, parsing each file once for
+/// everything `options` asks for
+pub fn load_files_parallel(files: &[PathBuf], options: LoadOptions) -> Vec<RustFileData> {
     files
         .par_iter()
         .filter_map(|file| {
             match fs::read_to_string(file) {
                 Ok(content) => {
-                    let filename = file.to_string_lossy();
-                    // Create Rust parser
-                    match similarity_rs::rust_parser::RustParser::new() {
-                        Ok(mut parser) => {
-                            // Extract functions
-                            match parser.extract_functions(&content, &filename) {
-                                Ok(functions) => {
-                                    Some(FileData { path: file.clone(), content, functions })
-                                }
-                                Err(e) => {
-                                    eprintln!("Error parsing {}: {}", file.display(), e);
-                                    None
-                                }
+                    // Reuse this thread's Rust parser across files
+                    let extracted = with_pooled_parser(
+                        Language::Rust,
+                        similarity_rs::rust_parser::RustParser::new,
+                        |parser| {
+                            let tree = parser.parse_source(&content)?;
+                            let mut functions = parser.functions_in(&tree, &content);
+                            if options.skip_trivial_impls {
+                                let trivial =
+                                    crate::rust_parser::RustParser::trivial_trait_methods_in(
+                                        &tree, &content,
+                                    );
+                                functions.retain(|func| !trivial.contains(&func.start_line));
+                            }
+                            if options.include_closures {
+                                functions.extend(crate::rust_parser::RustParser::closures_in(
+                                    &tree, &content,
+                                ));
                             }
+                            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(functions)
+                        },
+                    );
+                    match extracted {
+                        Ok(Ok(functions)) => {
+                            Some(FileData { path: file.clone(), content, functions })
+                        }
+                        Ok(Err(e)) => {
+                            eprintln!("Error parsing {}: {}", file.display(), e);
+                            None
                         }
                         Err(e) => {
                             eprintln!("Error creating parser for {}: {}", file.display(), e);
@@ -51,122 +83,313 @@ pub fn load_files_parallel(files: &[PathBuf]) -> Vec<RustFileData> {
         .collect()
 }
 
-/// Check for duplicates within Rust files in parallel
+/// Reason a function is excluded by the line-based filters, if any
+fn line_filter_reason(func: &GenericFunctionDef, options: &TSEDOptions) -> Option<String> {
+    // Filter out test functions if skip_test is enabled
+    if options.skip_test {
+        // Skip if function name starts with "test_"
+        if func.name.starts_with("test_") {
+            return Some("test function excluded by --skip-test".to_string());
+        }
+        // Skip if function has #[test] attribute
+        if func.decorators.iter().any(|d| d.contains("test")) {
+            return Some("test function excluded by --skip-test".to_string());
+        }
+    }
+
+    // Skip if function doesn't meet minimum requirements
+    let lines = func.end_line - func.start_line + 1;
+    if lines < options.min_lines {
+        return Some(format!("below min_lines ({} < {})", lines, options.min_lines));
+    }
+
+    None
+}
+
+/// Reason a parsed function is excluded by the tree-based filters, if any.
+/// `errors` counts the nodes its parse needed error recovery for.
+fn tree_filter_reason(tree: &TreeNode, errors: usize, options: &TSEDOptions) -> Option<String> {
+    let size = tree.get_subtree_size();
+    if size == 0 {
+        return Some("empty syntax tree".to_string());
+    }
+    if options.skip_parse_errors && errors > 0 {
+        return Some(format!("{} parse errors, excluded by --skip-parse-errors", errors));
+    }
+    match options.min_tokens {
+        Some(min_tokens) if (size as u32) < min_tokens => {
+            Some(format!("below min_tokens ({} < {})", size, min_tokens))
+        }
+        _ => None,
+    }
+}
+
+/// Check whether a function takes part in comparisons under the given options
+fn is_candidate(func: &GenericFunctionDef, options: &TSEDOptions) -> bool {
+    line_filter_reason(func, options).is_none()
+}
+
+/// Check whether a parsed function passes the line and tree filters of the
+/// comparisons, for modes that parse their functions themselves and have
+/// already left out those with parse errors
+pub fn passes_candidate_filters(
+    func: &GenericFunctionDef,
+    tree: &TreeNode,
+    options: &TSEDOptions,
+) -> bool {
+    is_candidate(func, options) && tree_filter_reason(tree, 0, options).is_none()
+}
+
+/// List every function left out of the comparisons together with the reason,
+/// in file order
+pub fn explain_exclusions(
+    file_data: &[RustFileData],
+    options: &TSEDOptions,
+) -> Vec<(PathBuf, GenericFunctionDef, String)> {
+    let Ok(mut parser) = similarity_rs::rust_parser::RustParser::new() else {
+        return Vec::new();
+    };
+
+    let mut excluded = Vec::new();
+    for data in file_data {
+        let lines: Vec<&str> = data.content.lines().collect();
+        let closures = closure_trees(&mut parser, data);
+        for func in &data.functions {
+            let reason = line_filter_reason(func, options).or_else(|| {
+                match function_tree(&mut parser, &lines, &closures, func, &func.name) {
+                    Some((tree, errors)) => tree_filter_reason(&tree, errors, options),
+                    None => Some("could not be parsed".to_string()),
+                }
+            });
+            if let Some(reason) = reason {
+                excluded.push((data.path.clone(), func.clone(), reason));
+            }
+        }
+    }
+
+    excluded
+}
+
+/// Estimate the number of function pairs that will be compared.
+///
+/// Only the cheap line-based filters are applied here, so the estimate is an
+/// upper bound: `min_tokens` can only remove more pairs once trees are built.
+pub fn estimate_comparisons(file_data: &[RustFileData], options: &TSEDOptions) -> u64 {
+    file_data
+        .iter()
+        .map(|data| {
+            let n = data.functions.iter().filter(|f| is_candidate(f, options)).count() as u64;
+            n * n.saturating_sub(1) / 2
+        })
+        .sum()
+}
+
+/// Keep a random sample of `sample` candidate functions across all files,
+/// dropping every other function. The same `seed` always picks the same sample.
+///
+/// Returns the number of candidate functions the sample was drawn from.
+pub fn sample_functions(
+    file_data: &mut [RustFileData],
+    sample: usize,
+    seed: u64,
+    options: &TSEDOptions,
+) -> usize {
+    let mut candidates: Vec<(usize, usize)> = file_data
+        .iter()
+        .enumerate()
+        .flat_map(|(file_idx, data)| {
+            data.functions
+                .iter()
+                .enumerate()
+                .filter(|(_, func)| is_candidate(func, options))
+                .map(move |(func_idx, _)| (file_idx, func_idx))
+        })
+        .collect();
+    let total = candidates.len();
+
+    fastrand::Rng::with_seed(seed).shuffle(&mut candidates);
+    candidates.truncate(sample);
+    candidates.sort_unstable();
+
+    for (file_idx, data) in file_data.iter_mut().enumerate() {
+        let functions = std::mem::take(&mut data.functions);
+        data.functions = functions
+            .into_iter()
+            .enumerate()
+            .filter(|(func_idx, _)| candidates.binary_search(&(file_idx, *func_idx)).is_ok())
+            .map(|(_, func)| func)
+            .collect();
+    }
+
+    total
+}
+
+/// Strategy used to skip function pairs before running APTED
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Default,
+    clap::ValueEnum,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum Prefilter {
+    /// Compare every candidate pair
+    #[default]
+    None,
+    /// Only compare functions whose control-flow skeletons match or differ by one construct
+    Cfg,
+}
+
+/// Callback invoked with each similar pair as soon as it is found. It returns
+/// whether the pair is also kept in the returned results, so that a callback
+/// writing pairs out can leave them out of memory.
+pub type PairCallback<'a> =
+    &'a (dyn Fn(&Path, &SimilarityResult<GenericFunctionDef>) -> bool + Sync);
+
+/// A similar pair with the structural hashes of the trees of its two
+/// functions, which identify them regardless of where they sit in the file
+pub type HashedPair = (SimilarityResult<GenericFunctionDef>, (u64, u64));
+
+/// Check for duplicates within Rust files in parallel.
+///
+/// Files completed in `checkpoint` are not compared again, and their recorded
+/// pairs are passed to `on_pair` like new ones; every newly compared file is
+/// added to it.
 pub fn check_within_file_duplicates_parallel(
-    files: &[PathBuf],
+    file_data: &[RustFileData],
     threshold: f64,
     options: &TSEDOptions,
-) -> Vec<(PathBuf, Vec<SimilarityResult<GenericFunctionDef>>)> {
-    files
+    prefilter: Prefilter,
+    on_pair: Option<PairCallback>,
+    checkpoint: Option<&Checkpoint>,
+) -> Vec<(PathBuf, Vec<HashedPair>)> {
+    file_data
         .par_iter()
-        .filter_map(|file| match fs::read_to_string(file) {
-            Ok(code) => {
-                let file_str = file.to_string_lossy();
-
-                // Create Rust parser
-                match similarity_rs::rust_parser::RustParser::new() {
-                    Ok(mut parser) => {
-                        // Extract functions
-                        match parser.extract_functions(&code, &file_str) {
-                            Ok(mut functions) => {
-                                // Filter out test functions if skip_test is enabled
-                                if options.skip_test {
-                                    functions.retain(|f| {
-                                        // Skip if function name starts with "test_"
-                                        if f.name.starts_with("test_") {
-                                            return false;
-                                        }
-                                        // Skip if function has #[test] attribute
-                                        !f.decorators.iter().any(|d| d.contains("test"))
-                                    });
-                                }
-                                let mut similar_pairs = Vec::new();
-
-                                // Compare all pairs within the file
-                                for i in 0..functions.len() {
-                                    for j in (i + 1)..functions.len() {
-                                        let func1 = &functions[i];
-                                        let func2 = &functions[j];
-
-                                        // Skip if functions don't meet minimum requirements
-                                        if func1.end_line - func1.start_line + 1 < options.min_lines
-                                            || func2.end_line - func2.start_line + 1
-                                                < options.min_lines
-                                        {
-                                            continue;
-                                        }
-
-                                        // Extract function bodies
-                                        let lines: Vec<&str> = code.lines().collect();
-                                        let body1 = extract_function_body(&lines, func1);
-                                        let body2 = extract_function_body(&lines, func2);
-
-                                        // Parse function bodies to trees
-                                        let (tree1_opt, tree2_opt) = match (
-                                            parser.parse(&body1, &format!("{}:func1", file_str)),
-                                            parser.parse(&body2, &format!("{}:func2", file_str)),
-                                        ) {
-                                            (Ok(tree1), Ok(tree2)) => {
-                                                // Skip if either tree is empty
-                                                if tree1.get_subtree_size() == 0
-                                                    || tree2.get_subtree_size() == 0
-                                                {
-                                                    (None, None)
-                                                } else {
-                                                    (Some(tree1), Some(tree2))
-                                                }
-                                            }
-                                            _ => (None, None),
-                                        };
-
-                                        // Calculate similarity
-                                        let similarity = match (tree1_opt, tree2_opt) {
-                                            (Some(tree1), Some(tree2)) => {
-                                                // Check minimum tokens if specified
-                                                if let Some(min_tokens) = options.min_tokens {
-                                                    let tokens1 = tree1.get_subtree_size() as u32;
-                                                    let tokens2 = tree2.get_subtree_size() as u32;
-                                                    if tokens1 < min_tokens || tokens2 < min_tokens
-                                                    {
-                                                        continue;
-                                                    }
-                                                }
-                                                // For Rust, use TSED instead of enhanced similarity
-                                                // to better handle short functions
-                                                similarity_core::tsed::calculate_tsed(
-                                                    &tree1, &tree2, options,
-                                                )
-                                            }
-                                            _ => 0.0,
-                                        };
-
-                                        if similarity >= threshold {
-                                            similar_pairs.push(SimilarityResult::new(
-                                                func1.clone(),
-                                                func2.clone(),
-                                                similarity,
-                                            ));
-                                        }
-                                    }
-                                }
-
-                                if similar_pairs.is_empty() {
-                                    None
-                                } else {
-                                    Some((file.clone(), similar_pairs))
-                                }
-                            }
-                            Err(_) => None,
+        .filter_map(|data| {
+            if let Some(pairs) = checkpoint.and_then(|checkpoint| checkpoint.completed(data)) {
+                let kept: Vec<_> = pairs
+                    .iter()
+                    .filter(|(pair, _)| on_pair.is_none_or(|on_pair| on_pair(&data.path, pair)))
+                    .cloned()
+                    .collect();
+                return (!kept.is_empty()).then(|| (data.path.clone(), kept));
+            }
+
+            let code = &data.content;
+            let file_str = data.path.to_string_lossy();
+
+            let lines: Vec<&str> = code.lines().collect();
+
+            // Parse each candidate once; empty trees and trees below min_tokens drop out
+            let mut functions: Vec<&GenericFunctionDef> = Vec::new();
+            let mut trees: Vec<Rc<TreeNode>> = Vec::new();
+            with_pooled_parser(
+                Language::Rust,
+                similarity_rs::rust_parser::RustParser::new,
+                |parser| {
+                    let closures = closure_trees(parser, data);
+                    for func in data.functions.iter().filter(|f| is_candidate(f, options)) {
+                        let name = format!("{}:{}", file_str, func.name);
+                        let Some((tree, errors)) =
+                            function_tree(parser, &lines, &closures, func, &name)
+                        else {
+                            continue;
+                        };
+                        if tree_filter_reason(&tree, errors, options).is_some() {
+                            continue;
                         }
+                        functions.push(func);
+                        trees.push(tree);
                     }
-                    Err(_) => None,
+                },
+            )
+            .ok()?;
+
+            // Exact duplicates share a key and skip APTED
+            let keyed = KeyedTrees::new(&trees, options);
+
+            let pairs: Vec<(usize, usize)> = match prefilter {
+                Prefilter::None => (0..functions.len())
+                    .flat_map(|i| ((i + 1)..functions.len()).map(move |j| (i, j)))
+                    .collect(),
+                Prefilter::Cfg => {
+                    let tree_refs: Vec<&TreeNode> = trees.iter().map(|t| t.as_ref()).collect();
+                    cfg_candidate_pairs(&tree_refs)
+                }
+            };
+
+            let mut similar_pairs = Vec::new();
+            let mut kept = Vec::new();
+            for (i, j) in pairs {
+                // For Rust, use TSED instead of enhanced similarity
+                // to better handle short functions
+                let similarity = keyed.similarity(i, j, threshold);
+
+                if let Some(similarity) = similarity {
+                    let result = SimilarityResult::new(
+                        functions[i].clone(),
+                        functions[j].clone(),
+                        similarity,
+                    );
+                    kept.push(on_pair.is_none_or(|on_pair| on_pair(&data.path, &result)));
+                    let hashes = (trees[i].structural_hash(), trees[j].structural_hash());
+                    similar_pairs.push((result, hashes));
                 }
             }
-            Err(_) => None,
+
+            if let Some(checkpoint) = checkpoint {
+                if let Err(e) = checkpoint.record(data, &similar_pairs) {
+                    eprintln!("Error writing checkpoint: {}", e);
+                }
+            }
+
+            let mut kept = kept.into_iter();
+            similar_pairs.retain(|_| kept.next().unwrap_or(true));
+            if similar_pairs.is_empty() {
+                None
+            } else {
+                Some((data.path.clone(), similar_pairs))
+            }
         })
         .collect()
 }
 
+/// Trees of the closures of a file, when closures were extracted from it.
+/// Syntax trees stay on the thread that built them, so the file is parsed
+/// again here, once for all of its closures.
+fn closure_trees(parser: &mut RustParser, data: &RustFileData) -> Vec<ClosureTree> {
+    if !data.functions.iter().any(|func| func.kind == FunctionType::Closure) {
+        return Vec::new();
+    }
+    match parser.parse_source(&data.content) {
+        Ok(tree) => crate::rust_parser::RustParser::closure_trees_in(&tree, &data.content),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Tree of a function parsed from its lines, or of a closure taken from
+/// `closures`, with the number of parse errors in it
+fn function_tree(
+    parser: &mut RustParser,
+    lines: &[&str],
+    closures: &[ClosureTree],
+    func: &GenericFunctionDef,
+    name: &str,
+) -> Option<(Rc<TreeNode>, usize)> {
+    if func.kind == FunctionType::Closure {
+        return closures
+            .iter()
+            .find(|(closure, _, _)| closure.name == func.name)
+            .map(|(_, tree, errors)| (Rc::clone(tree), *errors));
+    }
+    parser.parse_counting_errors(&extract_function_body(lines, func), name).ok()
+}
+
 /// Extract complete function from lines (including signature)
 fn extract_function_body(lines: &[&str], func: &GenericFunctionDef) -> String {
     // Use the complete function, not just the body
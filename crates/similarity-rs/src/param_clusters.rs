@@ -0,0 +1,207 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::patterns::parse_functions;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_output::{format_function_output, sort_groups_by_size},
+    connected_components,
+    tree::TreeNode,
+};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// Parameter lists shorter than this are not worth a parameter struct
+const MIN_CLUSTER_PARAMS: usize = 4;
+
+/// Share of the longer of two lists the shared parameters must make up for
+/// the lists to be near-identical: one in five parameters may differ
+const MIN_SHARED_SHARE: f64 = 0.8;
+
+/// A function and its parameters as `(name, type)`, sorted by name
+struct ParameterList {
+    file: PathBuf,
+    name: String,
+    start_line: u32,
+    end_line: u32,
+    parameters: Vec<(String, String)>,
+}
+
+/// Report groups of functions taking nearly the same parameters (same names
+/// and types, in any order, with at most one in five differing): candidates
+/// for a parameter struct.
+///
+/// The receiver is ignored, so methods and free functions can share a cluster.
+/// With `skip_parse_errors`, functions whose parse needed error recovery are
+/// left out.
+pub fn check_param_clusters(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    skip_parse_errors: bool,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut lists = Vec::new();
+    for file in &files {
+        let functions = match parse_functions(&mut parser, file, skip_parse_errors) {
+            Ok(functions) => functions,
+            Err(e) => {
+                eprintln!("{}", e);
+                continue;
+            }
+        };
+        lists.extend(functions.into_iter().filter_map(|(func, tree)| {
+            let parameters = typed_parameters(&tree)?;
+            (parameters.len() >= MIN_CLUSTER_PARAMS).then(|| ParameterList {
+                file: file.clone(),
+                name: func.name,
+                start_line: func.start_line,
+                end_line: func.end_line,
+                parameters,
+            })
+        }));
+    }
+
+    let mut edges = Vec::new();
+    for i in 0..lists.len() {
+        for j in (i + 1)..lists.len() {
+            if near_identical(&lists[i].parameters, &lists[j].parameters) {
+                edges.push((i, j));
+            }
+        }
+    }
+    let mut clusters: Vec<Vec<&ParameterList>> = connected_components(&edges)
+        .into_iter()
+        .map(|component| component.into_iter().map(|index| &lists[index]).collect())
+        .collect();
+    sort_groups_by_size(&mut clusters, |list| (list.file.clone(), list.start_line));
+
+    if clusters.is_empty() {
+        println!("\nNo parameter clusters found!");
+        return Ok(());
+    }
+
+    for cluster in &clusters {
+        let shared: Vec<&(String, String)> = cluster[0]
+            .parameters
+            .iter()
+            .filter(|param| cluster.iter().all(|list| list.parameters.contains(param)))
+            .collect();
+        let parameters: Vec<String> = shared.iter().map(|param| format_param(param)).collect();
+        println!(
+            "\n  {} functions take ({}) [{} parameters]",
+            cluster.len(),
+            parameters.join(", "),
+            parameters.len()
+        );
+        for list in cluster {
+            let extra: Vec<String> = list
+                .parameters
+                .iter()
+                .filter(|param| !shared.contains(param))
+                .map(format_param)
+                .collect();
+            let location = format_function_output(
+                &list.file.to_string_lossy(),
+                &list.name,
+                list.start_line,
+                list.end_line,
+            );
+            if extra.is_empty() {
+                println!("    {}", location);
+            } else {
+                println!("    {} (+ {})", location, extra.join(", "));
+            }
+        }
+    }
+
+    println!("\nTotal parameter clusters: {}", clusters.len());
+
+    Ok(())
+}
+
+fn format_param((name, ty): &(String, String)) -> String {
+    format!("{}: {}", name, ty)
+}
+
+/// Whether two sorted parameter lists share enough parameters to be one
+/// parameter struct
+fn near_identical(params1: &[(String, String)], params2: &[(String, String)]) -> bool {
+    let shared = params1.iter().filter(|param| params2.binary_search(param).is_ok()).count();
+    shared >= MIN_CLUSTER_PARAMS
+        && shared as f64 >= MIN_SHARED_SHARE * params1.len().max(params2.len()) as f64
+}
+
+/// The `(name, type)` parameters of a parsed function, sorted by name; `None`
+/// when the tree holds no function
+fn typed_parameters(tree: &TreeNode) -> Option<Vec<(String, String)>> {
+    let function = find_node(tree, "function_item")?;
+    let parameters = function.children.iter().find(|child| child.label == "parameters")?;
+    let mut typed: Vec<(String, String)> = parameters
+        .children
+        .iter()
+        .filter(|param| param.label == "parameter")
+        .filter_map(|param| {
+            // The pattern and the type sit on either side of the `:`
+            let colon = param.children.iter().position(|child| child.label == ":")?;
+            let pattern = &param.children[..colon];
+            let ty = &param.children[colon + 1..];
+            // `mut` is a detail of the body, not of the parameter list
+            let name = pattern.iter().filter(|node| node.label != "mutable_specifier");
+            Some((source_text(name), source_text(ty.iter())))
+        })
+        .collect();
+    typed.sort();
+    Some(typed)
+}
+
+fn find_node<'a>(node: &'a TreeNode, label: &str) -> Option<&'a TreeNode> {
+    if node.label == label {
+        return Some(node);
+    }
+    node.children.iter().find_map(|child| find_node(child, label))
+}
+
+/// Rebuild the text of a few subtrees from their tokens, spaced only between
+/// words and after commas, so that `&mut  Vec< u8 >` and `&mut Vec<u8>`
+/// compare equal
+fn source_text<'a>(nodes: impl Iterator<Item = &'a Rc<TreeNode>>) -> String {
+    let mut tokens = Vec::new();
+    for node in nodes {
+        collect_tokens(node, &mut tokens);
+    }
+
+    let mut text = String::new();
+    for token in tokens {
+        let word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        if (word(text.chars().last()) && word(token.chars().next()))
+            || text.ends_with(',')
+            || text.ends_with("->")
+            || token == "->"
+        {
+            text.push(' ');
+        }
+        text.push_str(token);
+    }
+    text
+}
+
+/// Leaf tokens in source order: the text of identifiers and operators, the
+/// label of keywords and punctuation
+fn collect_tokens<'a>(node: &'a TreeNode, tokens: &mut Vec<&'a str>) {
+    if node.children.is_empty() {
+        tokens.push(match (node.label.as_str(), node.value.as_str()) {
+            ("mutable_specifier", _) => "mut",
+            (label, "") => label,
+            (_, value) => value,
+        });
+    }
+    for child in &node.children {
+        collect_tokens(child, tokens);
+    }
+}
@@ -0,0 +1,133 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_file_utils::collect_files_with,
+    cli_output::{extract_lines_from_content, format_function_output},
+    language_parser::{GenericFunctionDef, LanguageParser},
+    tree::TreeNode,
+    tsed::{calculate_tsed, TSEDOptions},
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// A function exemplar of a known pattern
+struct Pattern {
+    name: String,
+    file: PathBuf,
+    tree: Rc<TreeNode>,
+}
+
+/// A parsed function of the scanned code
+struct ScannedFunction {
+    file: PathBuf,
+    func: GenericFunctionDef,
+    tree: Rc<TreeNode>,
+}
+
+/// Report scanned functions similar to any function exemplar found in the
+/// pattern directory. Each pattern is named after the file it comes from.
+pub fn match_patterns(
+    patterns_dir: &Path,
+    paths: &[String],
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    if !patterns_dir.is_dir() {
+        anyhow::bail!("{} is not a directory", patterns_dir.display());
+    }
+
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let mut patterns = Vec::new();
+    // The globs name scanned files, so the pattern directory is only walked
+    // with the walk filters
+    let pattern_files = collect_files_with(
+        &[patterns_dir.to_string_lossy().to_string()],
+        &["rs"],
+        &file_filter.walk_options,
+    )?;
+    for file in pattern_files {
+        let name = file.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        for (_, tree) in parse_functions(&mut parser, &file, options.skip_parse_errors)? {
+            patterns.push(Pattern { name: name.clone(), file: file.clone(), tree });
+        }
+    }
+    if patterns.is_empty() {
+        anyhow::bail!("No pattern functions found in {}", patterns_dir.display());
+    }
+
+    let mut scanned = Vec::new();
+    for file in file_filter.collect(paths, &["rs"])? {
+        for (func, tree) in parse_functions(&mut parser, &file, options.skip_parse_errors)? {
+            scanned.push(ScannedFunction { file: file.clone(), func, tree });
+        }
+    }
+
+    println!(
+        "Matching {} functions against {} pattern exemplars from {}",
+        scanned.len(),
+        patterns.len(),
+        patterns_dir.display()
+    );
+
+    let mut matches = Vec::new();
+    for function in &scanned {
+        for pattern in &patterns {
+            let similarity = calculate_tsed(&function.tree, &pattern.tree, options);
+            if similarity >= threshold {
+                matches.push((function, pattern, similarity));
+            }
+        }
+    }
+    matches.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+
+    if matches.is_empty() {
+        println!("\nNo functions matching the patterns found!");
+        return Ok(());
+    }
+
+    for (function, pattern, similarity) in &matches {
+        println!(
+            "\n  {}",
+            format_function_output(
+                &function.file.to_string_lossy(),
+                &function.func.name,
+                function.func.start_line,
+                function.func.end_line
+            )
+        );
+        println!("  Pattern: {} ({})", pattern.name, pattern.file.display());
+        println!("  Similarity: {:.2}%", similarity * 100.0);
+    }
+
+    println!("\nTotal pattern matches: {}", matches.len());
+
+    Ok(())
+}
+
+/// Extract and parse every function of a file. With `skip_parse_errors`,
+/// functions whose parse needed error recovery are left out.
+pub fn parse_functions(
+    parser: &mut RustParser,
+    file: &Path,
+    skip_parse_errors: bool,
+) -> anyhow::Result<Vec<(GenericFunctionDef, Rc<TreeNode>)>> {
+    let content = fs::read_to_string(file)?;
+    let filename = file.to_string_lossy();
+    let functions = parser
+        .extract_functions(&content, &filename)
+        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", file.display(), e))?;
+
+    Ok(functions
+        .into_iter()
+        .filter_map(|func| {
+            let code = extract_lines_from_content(&content, func.start_line, func.end_line);
+            let (tree, errors) = parser.parse_counting_errors(&code, &func.name).ok()?;
+            (!skip_parse_errors || errors == 0).then_some((func, tree))
+        })
+        .collect())
+}
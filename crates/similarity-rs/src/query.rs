@@ -0,0 +1,41 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::function_at::{describe, print_similar, scan_functions};
+use crate::rust_parser::RustParser;
+use similarity_core::tsed::TSEDOptions;
+
+/// Report the functions of `paths` similar to the function named `name`
+/// found in them, most similar first
+pub fn query_function(
+    name: &str,
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let (mut targets, corpus): (Vec<_>, Vec<_>) =
+        scan_functions(&mut parser, paths, extensions, file_filter, options)?
+            .into_iter()
+            .partition(|((_, func), _)| func.name == name);
+
+    let (target, target_tree) = match targets.len() {
+        0 => anyhow::bail!("No function named {} found", name),
+        1 => targets.remove(0),
+        _ => {
+            let locations: Vec<String> =
+                targets.iter().map(|((file, func), _)| describe(file, func)).collect();
+            anyhow::bail!(
+                "Several functions are named {}; use `at --file --line` to pick one:\n  {}",
+                name,
+                locations.join("\n  ")
+            );
+        }
+    };
+
+    println!("Function: {}", describe(&target.0, &target.1));
+    print_similar(&target, &target_tree, &corpus, threshold, options);
+    Ok(())
+}
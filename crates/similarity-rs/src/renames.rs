@@ -0,0 +1,142 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_output::{extract_lines_from_content, format_function_output},
+    language_parser::{GenericFunctionDef, LanguageParser},
+    tree::TreeNode,
+    tsed::{TSEDOptions, TsedQuery},
+};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+use std::rc::Rc;
+
+/// A function of one side of the diff
+struct RevisionFunction {
+    file: String,
+    func: GenericFunctionDef,
+    tree: Rc<TreeNode>,
+}
+
+impl RevisionFunction {
+    /// Functions are matched by file, impl type and name; anything else is a
+    /// deletion on one side and an addition on the other
+    fn key(&self) -> (&str, Option<&str>, &str) {
+        (&self.file, self.func.class_name.as_deref(), &self.func.name)
+    }
+
+    fn describe(&self) -> String {
+        format_function_output(
+            &self.file,
+            &self.func.name,
+            self.func.start_line,
+            self.func.end_line,
+        )
+    }
+}
+
+/// Pair each function deleted between `base` and `head` with the most similar
+/// added function, to confirm that renames and moves kept the behavior.
+///
+/// Only the Rust files changed between the two revisions are parsed.
+pub fn match_renames(repo: &Path, base: &str, head: &str) -> anyhow::Result<()> {
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut base_functions = Vec::new();
+    let mut head_functions = Vec::new();
+
+    let diff = git(repo, &["diff", "--name-status", "--no-renames", base, head, "--", "*.rs"])?;
+    for line in diff.lines() {
+        let Some((status, file)) = line.split_once('\t') else {
+            continue;
+        };
+        let sides = [
+            (status != "A", base, &mut base_functions),
+            (status != "D", head, &mut head_functions),
+        ];
+        for (present, revision, functions) in sides {
+            if !present {
+                continue;
+            }
+            // A file that cannot be read or parsed leaves the others to compare
+            match parse_revision(&mut parser, repo, revision, file) {
+                Ok(parsed) => functions.extend(parsed),
+                Err(e) => eprintln!("Skipping {} at {}: {}", file, revision, e),
+            }
+        }
+    }
+
+    let base_keys: BTreeSet<_> = base_functions.iter().map(RevisionFunction::key).collect();
+    let head_keys: BTreeSet<_> = head_functions.iter().map(RevisionFunction::key).collect();
+    let deleted: Vec<&RevisionFunction> =
+        base_functions.iter().filter(|f| !head_keys.contains(&f.key())).collect();
+    let added: Vec<&RevisionFunction> =
+        head_functions.iter().filter(|f| !base_keys.contains(&f.key())).collect();
+
+    println!("Renamed or moved functions: {} -> {}", base, head);
+
+    if deleted.is_empty() {
+        println!("\nNo deleted functions found!");
+        return Ok(());
+    }
+
+    let mut options = TSEDOptions::default();
+    options.apted_options.compare_values = true;
+    let added_trees: Vec<Rc<TreeNode>> = added.iter().map(|f| Rc::clone(&f.tree)).collect();
+
+    for old in &deleted {
+        println!("\n  - {}", old.describe());
+        let query = TsedQuery::new(&old.tree, &options);
+        match query.find_similar(&added_trees, 0.0).first() {
+            Some(&(index, similarity)) => {
+                println!("  + {}", added[index].describe());
+                println!("  Similarity: {:.2}%", similarity * 100.0);
+            }
+            None => println!("  No added function to match"),
+        }
+    }
+
+    println!("\nTotal deleted functions: {}", deleted.len());
+
+    Ok(())
+}
+
+/// The functions of `file` as of `revision`
+fn parse_revision(
+    parser: &mut RustParser,
+    repo: &Path,
+    revision: &str,
+    file: &str,
+) -> anyhow::Result<Vec<RevisionFunction>> {
+    let content = &git(repo, &["show", &format!("{}:{}", revision, file)])?;
+    let functions = parser
+        .extract_functions(content, file)
+        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", file, e))?;
+
+    Ok(functions
+        .into_iter()
+        .filter_map(|func| {
+            let code = extract_lines_from_content(content, func.start_line, func.end_line);
+            let tree = parser.parse(&code, &func.name).ok()?;
+            Some(RevisionFunction { file: file.to_string(), func, tree })
+        })
+        .collect())
+}
+
+/// Run a git command in `repo` and return its standard output
+fn git(repo: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| anyhow::anyhow!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
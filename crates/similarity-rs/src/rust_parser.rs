@@ -1,7 +1,10 @@
+use similarity_core::function_extractor::FunctionType;
 use similarity_core::language_parser::{
     GenericFunctionDef, GenericTypeDef, Language, LanguageParser,
 };
-use similarity_core::tree::TreeNode;
+use similarity_core::tree::{TreeNode, DEFAULT_MAX_TREE_DEPTH};
+use similarity_core::type_extractor::TypeDefinition;
+use std::collections::HashMap;
 use std::error::Error;
 use std::rc::Rc;
 use tree_sitter::{Node, Parser};
@@ -10,6 +13,52 @@ pub struct RustParser {
     parser: Parser,
 }
 
+/// An `impl` block together with the methods it defines
+#[derive(Debug, Clone)]
+pub struct ImplBlock {
+    /// The implementing type as written, e.g. `Stack<T>`
+    pub self_type: String,
+    /// The implemented trait for `impl Trait for Type` blocks
+    pub trait_name: Option<String>,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub methods: Vec<GenericFunctionDef>,
+}
+
+/// A struct, enum or type alias together with the item kind it was declared as
+#[derive(Debug, Clone)]
+pub struct DeclaredType {
+    /// `struct`, `enum` or `type_alias`
+    pub kind: String,
+    pub definition: TypeDefinition,
+}
+
+/// A closure together with the tree of its own node and the number of parse
+/// errors in it
+pub type ClosureTree = (GenericFunctionDef, Rc<TreeNode>, usize);
+
+/// One rule of a `macro_rules!` definition
+#[derive(Debug, Clone)]
+pub struct MacroArm {
+    pub macro_name: String,
+    /// Position of the rule within the macro, starting at 1
+    pub index: usize,
+    pub start_line: u32,
+    pub end_line: u32,
+    /// A `macro_rule` node holding the matcher followed by the transcriber
+    pub tree: Rc<TreeNode>,
+}
+
+impl ImplBlock {
+    /// Short description such as `impl Display for Point`
+    pub fn header(&self) -> String {
+        match &self.trait_name {
+            Some(trait_name) => format!("impl {} for {}", trait_name, self.self_type),
+            None => format!("impl {}", self.self_type),
+        }
+    }
+}
+
 impl RustParser {
     pub fn new() -> Result<Self, Box<dyn Error + Send + Sync>> {
         let mut parser = Parser::new();
@@ -26,7 +75,12 @@ impl RustParser {
         source: &'a str,
         functions: &mut Vec<GenericFunctionDef>,
         skip_test: bool,
+        depth: usize,
     ) {
+        // Items are never this deep; what is are expressions outside functions
+        if depth >= DEFAULT_MAX_TREE_DEPTH {
+            return;
+        }
         match node.kind() {
             "function_item" => {
                 // Skip test functions if requested
@@ -62,12 +116,316 @@ impl RustParser {
             _ => {
                 // Recursively process children
                 for child in node.children(&mut node.walk()) {
-                    self.extract_functions_from_node(child, source, functions, skip_test);
+                    self.extract_functions_from_node(
+                        child,
+                        source,
+                        functions,
+                        skip_test,
+                        depth + 1,
+                    );
                 }
             }
         }
     }
 
+    /// Every closure as a function of kind [`FunctionType::Closure`] named
+    /// `closure@line:col` after its 1-based start position, with its parameter
+    /// patterns, read from an already parsed tree
+    pub fn closures_in(tree: &tree_sitter::Tree, source: &str) -> Vec<GenericFunctionDef> {
+        let mut nodes = Vec::new();
+        collect_closures(tree.root_node(), &mut nodes);
+        nodes.into_iter().map(|node| closure_definition(node, source)).collect()
+    }
+
+    /// [`Self::closures_in`] along with the tree of each closure. Closures
+    /// often share lines with the code around them, so their trees are built
+    /// from the closure alone rather than from their lines.
+    pub fn closure_trees_in(tree: &tree_sitter::Tree, source: &str) -> Vec<ClosureTree> {
+        let mut nodes = Vec::new();
+        collect_closures(tree.root_node(), &mut nodes);
+        nodes
+            .into_iter()
+            .map(|node| {
+                let mut id_counter = 0;
+                (
+                    closure_definition(node, source),
+                    Self::convert_node_to_tree(node, source, &mut id_counter, 0),
+                    count_parse_errors(node),
+                )
+            })
+            .collect()
+    }
+
+    /// [`LanguageParser::parse`] along with the number of `ERROR` and
+    /// `MISSING` nodes tree-sitter's error recovery left in the parse. The
+    /// tree is the same either way; the count serves `--skip-parse-errors`.
+    pub fn parse_counting_errors(
+        &mut self,
+        source: &str,
+        filename: &str,
+    ) -> Result<(Rc<TreeNode>, usize), Box<dyn Error + Send + Sync>> {
+        // If the source looks like a function body (starts with whitespace or directly with code),
+        // wrap it in a minimal function context for parsing
+        let wrapped_source = if source.trim_start() != source || !source.starts_with("fn ") {
+            format!("fn __dummy() {{ {source} }}")
+        } else {
+            source.to_string()
+        };
+
+        let tree = self.parser.parse(&wrapped_source, None).ok_or_else(|| {
+            Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Failed to parse {filename}"),
+            )) as Box<dyn Error + Send + Sync>
+        })?;
+
+        let root_node = tree.root_node();
+        let errors = count_parse_errors(root_node);
+
+        // If we wrapped the source, extract just the function body
+        if wrapped_source != source {
+            // Find the function node
+            if let Some(func_node) = find_first_function(root_node) {
+                // Find the block node
+                for child in func_node.children(&mut func_node.walk()) {
+                    if child.kind() == "block" {
+                        // Extract the content inside the block
+                        // Id 0 is reserved for the synthetic root below
+                        let mut id_counter = 1;
+                        let mut block_children = Vec::new();
+                        for block_child in child.children(&mut child.walk()) {
+                            if block_child.kind() != "{" && block_child.kind() != "}" {
+                                block_children.push(Self::convert_node_to_tree(
+                                    block_child,
+                                    &wrapped_source,
+                                    &mut id_counter,
+                                    1,
+                                ));
+                            }
+                        }
+
+                        // Create a synthetic root node containing just the body content
+                        let mut root = TreeNode::new("block_content".to_string(), String::new(), 0);
+                        for child in block_children {
+                            root.add_child(child);
+                        }
+                        return Ok((Rc::new(root), errors));
+                    }
+                }
+            }
+        }
+
+        let mut id_counter = 0;
+        Ok((Self::convert_node_to_tree(root_node, &wrapped_source, &mut id_counter, 0), errors))
+    }
+
+    /// Extract every `impl` block with its methods
+    pub fn extract_impl_blocks(
+        &mut self,
+        source: &str,
+    ) -> Result<Vec<ImplBlock>, Box<dyn Error + Send + Sync>> {
+        let tree = self.parser.parse(source, None).ok_or_else(|| {
+            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse source"))
+                as Box<dyn Error + Send + Sync>
+        })?;
+
+        let mut blocks = Vec::new();
+        self.extract_impl_blocks_from_node(tree.root_node(), source, &mut blocks);
+        Ok(blocks)
+    }
+
+    /// Parse `source` into a syntax tree the `*_in` extractors can share, so a
+    /// file is parsed once however many of them run over it
+    pub fn parse_source(
+        &mut self,
+        source: &str,
+    ) -> Result<tree_sitter::Tree, Box<dyn Error + Send + Sync>> {
+        self.parser.parse(source, None).ok_or_else(|| {
+            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse source"))
+                as Box<dyn Error + Send + Sync>
+        })
+    }
+
+    /// [`LanguageParser::extract_functions`] over an already parsed tree
+    pub fn functions_in(&self, tree: &tree_sitter::Tree, source: &str) -> Vec<GenericFunctionDef> {
+        let mut functions = Vec::new();
+        self.extract_functions_from_node(tree.root_node(), source, &mut functions, false, 0);
+        functions
+    }
+
+    /// Start lines of the trivial methods of standard trait impls: methods of
+    /// `impl From<..> for ..`, `impl Display for ..`, ... whose body is a
+    /// single expression or statement. Such impls are idiomatic boilerplate
+    /// and near-identical across types.
+    pub fn trivial_trait_methods_in(tree: &tree_sitter::Tree, source: &str) -> Vec<u32> {
+        let mut lines = Vec::new();
+        collect_trivial_trait_methods(tree.root_node(), source, &mut lines);
+        lines
+    }
+
+    /// Start lines of the items declared with a plain `pub` visibility:
+    /// functions, methods, structs, enums and type aliases. Restricted
+    /// visibilities such as `pub(crate)` are not part of the public API and
+    /// are left out.
+    pub fn public_items(&mut self, source: &str) -> Result<Vec<u32>, Box<dyn Error + Send + Sync>> {
+        let tree = self.parser.parse(source, None).ok_or_else(|| {
+            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse source"))
+                as Box<dyn Error + Send + Sync>
+        })?;
+
+        let mut lines = Vec::new();
+        collect_public_items(tree.root_node(), source, &mut lines);
+        Ok(lines)
+    }
+
+    /// Extract the rules of every `macro_rules!` definition
+    pub fn extract_macro_arms(
+        &mut self,
+        source: &str,
+    ) -> Result<Vec<MacroArm>, Box<dyn Error + Send + Sync>> {
+        let tree = self.parser.parse(source, None).ok_or_else(|| {
+            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse source"))
+                as Box<dyn Error + Send + Sync>
+        })?;
+
+        let mut arms = Vec::new();
+        self.extract_macro_arms_from_node(tree.root_node(), source, &mut arms);
+        Ok(arms)
+    }
+
+    /// Extract struct, enum and type alias definitions as `TypeDefinition`s.
+    ///
+    /// Struct fields carry their type annotation; a field of type `Option<T>`
+    /// becomes an optional property of type `T`. Enum variants become
+    /// properties typed by their payload as written (`(f64)`, `{ x: i32 }`),
+    /// or without a type for unit variants.
+    ///
+    /// With `include_tuples`, tuple structs and aliases of tuple types (`type
+    /// Span = (usize, usize);`) get one property per element, named by its
+    /// position, so that they are compared by their element types.
+    pub fn extract_type_definitions(
+        &mut self,
+        source: &str,
+        file_path: &str,
+        include_tuples: bool,
+    ) -> Result<Vec<DeclaredType>, Box<dyn Error + Send + Sync>> {
+        let tree = self.parser.parse(source, None).ok_or_else(|| {
+            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse source"))
+                as Box<dyn Error + Send + Sync>
+        })?;
+
+        let mut types = Vec::new();
+        self.extract_types_from_node(tree.root_node(), source, &mut types);
+        let mut member_types = HashMap::new();
+        collect_member_types(tree.root_node(), source, &mut member_types);
+
+        let mut type_defs: Vec<DeclaredType> = types
+            .iter()
+            .map(|def| {
+                let mut type_def = TypeDefinition::from_generic(def, file_path);
+                let span = (def.start_line, def.end_line, def.name.clone());
+                let members = member_types.get(&span).map_or(&[][..], Vec::as_slice);
+                for (property, annotation) in type_def.properties.iter_mut().zip(members) {
+                    let optional = (def.kind == "struct")
+                        .then(|| annotation.strip_prefix("Option<")?.strip_suffix('>'))
+                        .flatten();
+                    match optional {
+                        Some(inner) => {
+                            property.type_annotation = inner.trim().to_string();
+                            property.optional = true;
+                        }
+                        None => property.type_annotation = annotation.clone(),
+                    }
+                }
+                DeclaredType { kind: def.kind.clone(), definition: type_def }
+            })
+            .collect();
+
+        if include_tuples {
+            let mut tuples = Vec::new();
+            collect_tuple_shapes(tree.root_node(), source, &mut tuples);
+            for tuple in tuples {
+                let mut type_def = TypeDefinition::from_generic(&tuple, file_path);
+                for (position, property) in type_def.properties.iter_mut().enumerate() {
+                    property.name = position.to_string();
+                }
+                let declared = DeclaredType { kind: tuple.kind.clone(), definition: type_def };
+                // A tuple struct was extracted as a struct without fields; items
+                // sharing a line are told apart by their span and name
+                let same_item = |t: &DeclaredType| {
+                    let (a, b) = (&t.definition, &declared.definition);
+                    (&a.file_path, a.start_line, a.end_line, &a.name)
+                        == (&b.file_path, b.start_line, b.end_line, &b.name)
+                };
+                match type_defs.iter_mut().find(|t| same_item(t)) {
+                    Some(existing) => *existing = declared,
+                    None => type_defs.push(declared),
+                }
+            }
+        }
+
+        Ok(type_defs)
+    }
+
+    fn extract_macro_arms_from_node(&self, node: Node, source: &str, arms: &mut Vec<MacroArm>) {
+        if node.kind() != "macro_definition" {
+            for child in node.children(&mut node.walk()) {
+                self.extract_macro_arms_from_node(child, source, arms);
+            }
+            return;
+        }
+
+        let Some(name) = node.child_by_field_name("name") else {
+            return;
+        };
+        let rules: Vec<Node> =
+            node.children(&mut node.walk()).filter(|child| child.kind() == "macro_rule").collect();
+        for (index, rule) in rules.into_iter().enumerate() {
+            let mut id_counter = 0;
+            arms.push(MacroArm {
+                macro_name: source[name.byte_range()].to_string(),
+                index: index + 1,
+                start_line: (rule.start_position().row + 1) as u32,
+                end_line: (rule.end_position().row + 1) as u32,
+                tree: Self::convert_node_to_tree(rule, source, &mut id_counter, 0),
+            });
+        }
+    }
+
+    fn extract_impl_blocks_from_node(&self, node: Node, source: &str, blocks: &mut Vec<ImplBlock>) {
+        if node.kind() != "impl_item" {
+            for child in node.children(&mut node.walk()) {
+                self.extract_impl_blocks_from_node(child, source, blocks);
+            }
+            return;
+        }
+
+        let Some(self_type) = node.child_by_field_name("type") else {
+            return;
+        };
+        let trait_name =
+            node.child_by_field_name("trait").map(|t| source[t.byte_range()].to_string());
+
+        let mut methods = Vec::new();
+        if let Some(body) = node.child_by_field_name("body") {
+            for item in body.children(&mut body.walk()) {
+                if item.kind() == "function_item" {
+                    if let Some(method) = self.extract_function_definition(item, source) {
+                        methods.push(method);
+                    }
+                }
+            }
+        }
+
+        blocks.push(ImplBlock {
+            self_type: source[self_type.byte_range()].to_string(),
+            trait_name,
+            start_line: (node.start_position().row + 1) as u32,
+            end_line: (node.end_position().row + 1) as u32,
+            methods,
+        });
+    }
+
     fn is_test_function(&self, node: Node, source: &str) -> bool {
         // Check if function has #[test] attribute
         if let Some(prev_sibling) = node.prev_sibling() {
@@ -104,14 +462,22 @@ impl RustParser {
         let mut body_end_line = 0;
         let mut decorators = Vec::new();
 
-        // Check for attributes (like #[test])
-        if let Some(prev_sibling) = node.prev_sibling() {
-            if prev_sibling.kind() == "attribute_item" {
-                let attr_text =
-                    &source[prev_sibling.byte_range().start..prev_sibling.byte_range().end];
-                decorators.push(attr_text.to_string());
+        // Collect the attributes (like #[test] or #[cfg(...)]) stacked above the function,
+        // stepping over interleaved comments
+        let mut sibling = node.prev_sibling();
+        while let Some(prev_sibling) = sibling {
+            match prev_sibling.kind() {
+                "attribute_item" => {
+                    let attr_text =
+                        &source[prev_sibling.byte_range().start..prev_sibling.byte_range().end];
+                    decorators.push(attr_text.to_string());
+                }
+                "line_comment" | "block_comment" => {}
+                _ => break,
             }
+            sibling = prev_sibling.prev_sibling();
         }
+        decorators.reverse();
 
         // Check for async
         for child in node.children(&mut node.walk()) {
@@ -126,16 +492,10 @@ impl RustParser {
                 if let Some(impl_node) = parent.parent() {
                     if impl_node.kind() == "impl_item" {
                         is_method = true;
-                        // Extract type name from impl block
-                        for child in impl_node.children(&mut impl_node.walk()) {
-                            if child.kind() == "type_identifier" {
-                                class_name = Some(
-                                    source[child.byte_range().start..child.byte_range().end]
-                                        .to_string(),
-                                );
-                                break;
-                            }
-                        }
+                        // The implementing type, not the trait of `impl Trait for Type`
+                        class_name = impl_node
+                            .child_by_field_name("type")
+                            .and_then(|ty| impl_type_name(ty, source));
                     }
                 }
             }
@@ -206,6 +566,7 @@ impl RustParser {
                 is_async,
                 is_generator: false, // Rust doesn't have generator functions like JS/Python
                 is_method,
+                kind: if is_method { FunctionType::Method } else { FunctionType::Function },
                 class_name,
                 decorators,
                 parameters,
@@ -216,13 +577,29 @@ impl RustParser {
     }
 
     #[allow(clippy::only_used_in_recursion)]
-    fn convert_node_to_tree(&self, node: Node, source: &str) -> Rc<TreeNode> {
+    fn convert_node_to_tree(
+        node: Node,
+        source: &str,
+        id_counter: &mut usize,
+        depth: usize,
+    ) -> Rc<TreeNode> {
+        let current_id = *id_counter;
+        *id_counter += 1;
+
+        // Stop descending into pathologically deep input rather than overflow the stack
+        if depth >= DEFAULT_MAX_TREE_DEPTH {
+            return Rc::new(TreeNode::truncated(current_id));
+        }
+
         let label = node.kind().to_string();
 
+        // Primitive type names carry their text, so `i32` vs `u64` costs a rename
+        // (this lowers scores between copies that differ only in primitive types)
         let value = match node.kind() {
             // Identifiers and literals
             "identifier" | "string_literal" | "char_literal" | "integer_literal"
-            | "float_literal" | "true" | "false" | "type_identifier" | "field_identifier" => {
+            | "float_literal" | "true" | "false" | "type_identifier" | "primitive_type"
+            | "field_identifier" => {
                 source[node.byte_range().start..node.byte_range().end].to_string()
             }
             // Operators
@@ -239,11 +616,16 @@ impl RustParser {
             _ => String::new(),
         };
 
-        let mut tree_node = TreeNode::new(label, value, 0);
+        let mut tree_node = TreeNode::new(label, value, current_id);
 
         for child in node.children(&mut node.walk()) {
             if !child.is_extra() {
-                tree_node.add_child(self.convert_node_to_tree(child, source));
+                tree_node.add_child(Self::convert_node_to_tree(
+                    child,
+                    source,
+                    id_counter,
+                    depth + 1,
+                ));
             }
         }
 
@@ -385,6 +767,234 @@ impl RustParser {
     }
 }
 
+/// Number of `ERROR` and `MISSING` nodes below `node`, extras included
+fn count_parse_errors(node: Node) -> usize {
+    let mut count = 0;
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        count += usize::from(node.is_error() || node.is_missing());
+        stack.extend(node.children(&mut node.walk()).filter(|child| child.has_error()));
+    }
+    count
+}
+
+/// A `closure_expression` node as a function of kind [`FunctionType::Closure`]
+fn closure_definition(node: Node, source: &str) -> GenericFunctionDef {
+    let start = node.start_position();
+    let body = node.child_by_field_name("body").unwrap_or(node);
+    let parameters = node
+        .child_by_field_name("parameters")
+        .map(|params| {
+            params
+                .named_children(&mut params.walk())
+                .map(|param| param.child_by_field_name("pattern").unwrap_or(param))
+                .map(|pattern| source[pattern.byte_range()].to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    GenericFunctionDef {
+        name: format!("closure@{}:{}", start.row + 1, start.column + 1),
+        start_line: (start.row + 1) as u32,
+        end_line: (node.end_position().row + 1) as u32,
+        body_start_line: (body.start_position().row + 1) as u32,
+        body_end_line: (body.end_position().row + 1) as u32,
+        parameters,
+        is_method: false,
+        kind: FunctionType::Closure,
+        class_name: None,
+        is_async: node.children(&mut node.walk()).any(|child| child.kind() == "async"),
+        is_generator: false,
+        decorators: Vec::new(),
+    }
+}
+
+/// Every `closure_expression` below `node`, outer closures first
+fn collect_closures<'a>(node: Node<'a>, closures: &mut Vec<Node<'a>>) {
+    // Walked with an explicit stack, since expressions may nest arbitrarily deep
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        if node.kind() == "closure_expression" {
+            closures.push(node);
+        }
+        let children: Vec<_> = node.children(&mut node.walk()).collect();
+        stack.extend(children.into_iter().rev());
+    }
+}
+
+/// Lines and name of a type item, which tell apart items sharing a line
+type ItemSpan = (u32, u32, String);
+
+/// Record the field types of every struct and the variant payloads of every
+/// enum, keyed by the item's span
+fn collect_member_types(
+    node: Node,
+    source: &str,
+    member_types: &mut HashMap<ItemSpan, Vec<String>>,
+) {
+    if node.kind() == "enum_item" {
+        let payloads = node
+            .child_by_field_name("body")
+            .map(|body| {
+                body.children(&mut body.walk())
+                    .filter(|variant| {
+                        variant.kind() == "enum_variant"
+                            && variant.child_by_field_name("name").is_some()
+                    })
+                    .map(|variant| {
+                        variant
+                            .child_by_field_name("body")
+                            .map(|payload| source[payload.byte_range()].to_string())
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        member_types.insert(item_span(node, source), payloads);
+    }
+
+    if node.kind() == "struct_item" {
+        let fields = node
+            .child_by_field_name("body")
+            .filter(|body| body.kind() == "field_declaration_list")
+            .map(|body| {
+                body.children(&mut body.walk())
+                    .filter(|field| {
+                        field.kind() == "field_declaration"
+                            && field.child_by_field_name("name").is_some()
+                    })
+                    .map(|field| {
+                        field
+                            .child_by_field_name("type")
+                            .map(|t| source[t.byte_range()].to_string())
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        member_types.insert(item_span(node, source), fields);
+    }
+
+    for child in node.children(&mut node.walk()) {
+        collect_member_types(child, source, member_types);
+    }
+}
+
+fn item_span(node: Node, source: &str) -> ItemSpan {
+    let name = node.child_by_field_name("name").map_or("", |name| &source[name.byte_range()]);
+    ((node.start_position().row + 1) as u32, (node.end_position().row + 1) as u32, name.to_string())
+}
+
+/// Tuple structs and aliases of tuple types, with `:<element type>` fields
+fn collect_tuple_shapes(node: Node, source: &str, tuples: &mut Vec<GenericTypeDef>) {
+    let (kind, elements) = match node.kind() {
+        "struct_item" => (
+            "struct",
+            node.child_by_field_name("body")
+                .filter(|body| body.kind() == "ordered_field_declaration_list"),
+        ),
+        "type_item" => {
+            ("type_alias", node.child_by_field_name("type").filter(|ty| ty.kind() == "tuple_type"))
+        }
+        _ => ("", None),
+    };
+
+    if let (Some(elements), Some(name)) = (elements, node.child_by_field_name("name")) {
+        let fields = elements
+            .named_children(&mut elements.walk())
+            .filter(|element| element.kind() != "visibility_modifier")
+            .map(|element| format!(":{}", &source[element.byte_range()]))
+            .collect();
+        tuples.push(GenericTypeDef {
+            name: source[name.byte_range()].to_string(),
+            kind: kind.to_string(),
+            start_line: (node.start_position().row + 1) as u32,
+            end_line: (node.end_position().row + 1) as u32,
+            fields,
+        });
+        return;
+    }
+
+    for child in node.children(&mut node.walk()) {
+        collect_tuple_shapes(child, source, tuples);
+    }
+}
+
+/// Standard library traits whose impls are mostly boilerplate
+const STANDARD_TRAITS: &[&str] = &[
+    "Default",
+    "From",
+    "Into",
+    "TryFrom",
+    "FromStr",
+    "AsRef",
+    "AsMut",
+    "Borrow",
+    "Deref",
+    "DerefMut",
+    "Display",
+    "Debug",
+    "Clone",
+    "PartialEq",
+    "Eq",
+    "PartialOrd",
+    "Ord",
+    "Hash",
+];
+
+fn collect_trivial_trait_methods(node: Node, source: &str, lines: &mut Vec<u32>) {
+    if node.kind() != "impl_item" {
+        for child in node.children(&mut node.walk()) {
+            collect_trivial_trait_methods(child, source, lines);
+        }
+        return;
+    }
+
+    let is_standard = node.child_by_field_name("trait").and_then(|t| impl_type_name(t, source));
+    if !is_standard.is_some_and(|name| STANDARD_TRAITS.contains(&name.as_str())) {
+        return;
+    }
+    let Some(body) = node.child_by_field_name("body") else {
+        return;
+    };
+    for item in body.children(&mut body.walk()) {
+        let statements = item
+            .child_by_field_name("body")
+            .filter(|_| item.kind() == "function_item")
+            .map(|block| block.named_children(&mut block.walk()).filter(|s| !s.is_extra()).count());
+        if statements.is_some_and(|count| count <= 1) {
+            lines.push((item.start_position().row + 1) as u32);
+        }
+    }
+}
+
+fn collect_public_items(node: Node, source: &str, lines: &mut Vec<u32>) {
+    if matches!(node.kind(), "function_item" | "struct_item" | "enum_item" | "type_item") {
+        let is_public = node.children(&mut node.walk()).any(|child| {
+            child.kind() == "visibility_modifier" && &source[child.byte_range()] == "pub"
+        });
+        if is_public {
+            lines.push((node.start_position().row + 1) as u32);
+        }
+    }
+    for child in node.children(&mut node.walk()) {
+        collect_public_items(child, source, lines);
+    }
+}
+
+/// Name of the type of an `impl` block without its generic arguments or path,
+/// so that `impl<T> Stack<T>` and `impl fmt::Debug for crate::Stack<u8>` are
+/// both methods of `Stack`
+fn impl_type_name(ty: Node, source: &str) -> Option<String> {
+    match ty.kind() {
+        "type_identifier" => Some(source[ty.byte_range()].to_string()),
+        "generic_type" | "reference_type" => {
+            impl_type_name(ty.child_by_field_name("type")?, source)
+        }
+        "scoped_type_identifier" => impl_type_name(ty.child_by_field_name("name")?, source),
+        _ => None,
+    }
+}
+
 fn find_first_function(node: Node) -> Option<Node> {
     if node.kind() == "function_item" {
         return Some(node);
@@ -405,51 +1015,7 @@ impl LanguageParser for RustParser {
         source: &str,
         filename: &str,
     ) -> Result<Rc<TreeNode>, Box<dyn Error + Send + Sync>> {
-        // If the source looks like a function body (starts with whitespace or directly with code),
-        // wrap it in a minimal function context for parsing
-        let wrapped_source = if source.trim_start() != source || !source.starts_with("fn ") {
-            format!("fn __dummy() {{ {source} }}")
-        } else {
-            source.to_string()
-        };
-
-        let tree = self.parser.parse(&wrapped_source, None).ok_or_else(|| {
-            Box::new(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!("Failed to parse {filename}"),
-            )) as Box<dyn Error + Send + Sync>
-        })?;
-
-        let root_node = tree.root_node();
-
-        // If we wrapped the source, extract just the function body
-        if wrapped_source != source {
-            // Find the function node
-            if let Some(func_node) = find_first_function(root_node) {
-                // Find the block node
-                for child in func_node.children(&mut func_node.walk()) {
-                    if child.kind() == "block" {
-                        // Extract the content inside the block
-                        let mut block_children = Vec::new();
-                        for block_child in child.children(&mut child.walk()) {
-                            if block_child.kind() != "{" && block_child.kind() != "}" {
-                                block_children
-                                    .push(self.convert_node_to_tree(block_child, &wrapped_source));
-                            }
-                        }
-
-                        // Create a synthetic root node containing just the body content
-                        let mut root = TreeNode::new("block_content".to_string(), String::new(), 0);
-                        for child in block_children {
-                            root.add_child(child);
-                        }
-                        return Ok(Rc::new(root));
-                    }
-                }
-            }
-        }
-
-        Ok(self.convert_node_to_tree(root_node, &wrapped_source))
+        self.parse_counting_errors(source, filename).map(|(tree, _)| tree)
     }
 
     fn extract_functions(
@@ -457,15 +1023,8 @@ impl LanguageParser for RustParser {
         source: &str,
         _filename: &str,
     ) -> Result<Vec<GenericFunctionDef>, Box<dyn Error + Send + Sync>> {
-        let tree = self.parser.parse(source, None).ok_or_else(|| {
-            Box::new(std::io::Error::new(std::io::ErrorKind::InvalidData, "Failed to parse source"))
-                as Box<dyn Error + Send + Sync>
-        })?;
-
-        let root_node = tree.root_node();
-        let mut functions = Vec::new();
-        self.extract_functions_from_node(root_node, source, &mut functions, false);
-        Ok(functions)
+        let tree = self.parse_source(source)?;
+        Ok(self.functions_in(&tree, source))
     }
 
     fn extract_types(
@@ -499,6 +1058,19 @@ impl Default for RustParser {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_deeply_nested_source_is_truncated_instead_of_overflowing() {
+        let nesting = 20_000;
+        let source =
+            format!("fn f() -> i32 {{ {}1{} }}\n", "(".repeat(nesting), ")".repeat(nesting));
+
+        let mut parser = RustParser::new().unwrap();
+        let tree = parser.parse(&source, "deep.rs").unwrap();
+
+        assert!(tree.iter().any(|node| node.label == "truncated"));
+        assert!(tree.get_subtree_size() < nesting);
+    }
+
     #[test]
     fn test_rust_functions() {
         let mut parser = RustParser::new().unwrap();
@@ -585,4 +1157,177 @@ type Distance = f64;
         // assert_eq!(types[2].name, "Distance");
         // assert_eq!(types[2].kind, "type_alias");
     }
+
+    #[test]
+    fn test_extract_impl_blocks() {
+        let mut parser = RustParser::new().unwrap();
+        let source = r#"
+struct Point { x: i32 }
+
+impl Point {
+    fn new(x: i32) -> Self {
+        Point { x }
+    }
+
+    fn x(&self) -> i32 {
+        self.x
+    }
+}
+
+impl std::fmt::Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.x)
+    }
+}
+"#;
+
+        let blocks = parser.extract_impl_blocks(source).unwrap();
+        assert_eq!(blocks.len(), 2);
+
+        assert_eq!(blocks[0].header(), "impl Point");
+        let names: Vec<&str> = blocks[0].methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["new", "x"]);
+
+        assert_eq!(blocks[1].header(), "impl std::fmt::Display for Point");
+        assert_eq!(blocks[1].methods.len(), 1);
+        assert_eq!(blocks[1].methods[0].class_name.as_deref(), Some("Point"));
+    }
+
+    #[test]
+    fn test_closures_are_a_kind_of_function_from_the_same_parse() {
+        let mut parser = RustParser::new().unwrap();
+        let source = r#"
+struct Counter;
+
+impl Counter {
+    fn doubled(&self, xs: &[u32]) -> Vec<u32> {
+        xs.iter().map(|x| x * 2).collect()
+    }
+}
+
+fn closure_free() {}
+"#;
+
+        let tree = parser.parse_source(source).unwrap();
+        let functions = parser.functions_in(&tree, source);
+        let kinds: Vec<FunctionType> = functions.iter().map(|f| f.kind).collect();
+        assert_eq!(kinds, vec![FunctionType::Method, FunctionType::Function]);
+
+        let closures = RustParser::closures_in(&tree, source);
+        assert_eq!(closures.len(), 1);
+        assert_eq!(closures[0].kind, FunctionType::Closure);
+        assert_eq!(closures[0].name, "closure@6:23");
+        assert_eq!(closures[0].parameters, vec!["x"]);
+
+        let trees = RustParser::closure_trees_in(&tree, source);
+        assert_eq!(trees[0].0.name, closures[0].name);
+        assert_eq!(trees[0].1.label, "closure_expression");
+    }
+
+    #[test]
+    fn test_parse_errors_are_counted_without_changing_the_tree() {
+        let mut parser = RustParser::new().unwrap();
+        let clean = "fn total(a: u64, b: u64) -> u64 {\n    a + b\n}";
+        let (_, errors) = parser.parse_counting_errors(clean, "clean").unwrap();
+        assert_eq!(errors, 0);
+
+        // A missing operand and a stray token
+        let malformed = "fn total(a: u64, b: u64) -> u64 {\n    let c = a * ;\n    a + b )\n}";
+        let (tree, errors) = parser.parse_counting_errors(malformed, "malformed").unwrap();
+        assert!(errors > 0);
+        // Inserted tokens keep the kind of the token they stand for
+        assert!(tree.iter().all(|node| node.label != "MISSING"));
+    }
+
+    #[test]
+    fn test_function_attributes_are_collected_in_order() {
+        let mut parser = RustParser::new().unwrap();
+        let source = r#"
+#[cfg(target_os = "linux")]
+/// Documented between attributes
+#[inline]
+fn platform_name() -> &'static str {
+    "linux"
+}
+"#;
+
+        let functions = parser.extract_functions(source, "test.rs").unwrap();
+        assert_eq!(functions.len(), 1);
+        assert_eq!(
+            functions[0].decorators,
+            vec!["#[cfg(target_os = \"linux\")]".to_string(), "#[inline]".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_type_definitions_carry_field_types() {
+        let mut parser = RustParser::new().unwrap();
+        let source = r#"
+struct User {
+    id: u64,
+    email: Option<String>,
+}
+"#;
+
+        let types = parser.extract_type_definitions(source, "user.rs", false).unwrap();
+        assert_eq!(types.len(), 1);
+        assert_eq!(types[0].kind, "struct");
+        let types: Vec<TypeDefinition> = types.into_iter().map(|t| t.definition).collect();
+        let properties: Vec<(&str, &str, bool)> = types[0]
+            .properties
+            .iter()
+            .map(|p| (p.name.as_str(), p.type_annotation.as_str(), p.optional))
+            .collect();
+        assert_eq!(properties, vec![("id", "u64", false), ("email", "String", true)]);
+        assert_eq!(types[0].file_path, "user.rs");
+    }
+
+    #[test]
+    fn test_tuple_types_get_positional_properties() {
+        let mut parser = RustParser::new().unwrap();
+        let source = "pub struct Meters(pub f64, String);\ntype Span = (usize, usize);\n";
+
+        let shape = |types: &[TypeDefinition], name: &str| -> Vec<(String, String)> {
+            let type_def = types.iter().find(|t| t.name == name).unwrap();
+            type_def
+                .properties
+                .iter()
+                .map(|p| (p.name.clone(), p.type_annotation.clone()))
+                .collect()
+        };
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+
+        let definitions = |types: Vec<DeclaredType>| -> Vec<TypeDefinition> {
+            types.into_iter().map(|t| t.definition).collect()
+        };
+
+        let types = definitions(parser.extract_type_definitions(source, "units.rs", true).unwrap());
+        assert_eq!(shape(&types, "Meters"), vec![pair("0", "f64"), pair("1", "String")]);
+        assert_eq!(shape(&types, "Span"), vec![pair("0", "usize"), pair("1", "usize")]);
+
+        let types =
+            definitions(parser.extract_type_definitions(source, "units.rs", false).unwrap());
+        assert!(shape(&types, "Meters").is_empty());
+        assert!(types.iter().all(|t| t.name != "Span"));
+    }
+
+    #[test]
+    fn test_enum_variants_carry_their_payloads() {
+        let mut parser = RustParser::new().unwrap();
+        let source =
+            "enum Shape {\n    Circle(f64),\n    Rect { w: f64, h: f64 },\n    Empty,\n}\n";
+
+        let types = parser.extract_type_definitions(source, "shape.rs", false).unwrap();
+        assert_eq!(types[0].kind, "enum");
+        let variants: Vec<(&str, &str)> = types[0]
+            .definition
+            .properties
+            .iter()
+            .map(|p| (p.name.as_str(), p.type_annotation.as_str()))
+            .collect();
+        assert_eq!(
+            variants,
+            vec![("Circle", "(f64)"), ("Rect", "{ w: f64, h: f64 }"), ("Empty", "")]
+        );
+    }
 }
@@ -0,0 +1,93 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::patterns::parse_functions;
+use crate::rust_parser::RustParser;
+use similarity_core::{
+    cli_output::format_function_output,
+    language_parser::GenericFunctionDef,
+    tree::TreeNode,
+    tsed::{TSEDOptions, TsedQuery},
+};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Report, for every function, its `k` most similar functions above the
+/// threshold. A function with several close matches is an ambiguous
+/// duplicate: deduplicating it against only its best match misses the others.
+pub fn check_top_k(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    file_filter: &FileFilter,
+    threshold: f64,
+    k: usize,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = file_filter.collect(paths, &exts)?;
+
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut functions: Vec<(PathBuf, GenericFunctionDef)> = Vec::new();
+    let mut trees: Vec<Rc<TreeNode>> = Vec::new();
+    for file in &files {
+        match parse_functions(&mut parser, file, options.skip_parse_errors) {
+            Ok(parsed) => {
+                for (func, tree) in parsed {
+                    if func.end_line - func.start_line + 1 >= options.min_lines {
+                        functions.push((file.clone(), func));
+                        trees.push(tree);
+                    }
+                }
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+
+    let mut reported = 0;
+    for (index, tree) in trees.iter().enumerate() {
+        let others: Vec<Rc<TreeNode>> = trees
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != index)
+            .map(|(_, tree)| Rc::clone(tree))
+            .collect();
+        let matches = TsedQuery::new(tree, options).best_matches(&others, threshold, k);
+        if matches.is_empty() {
+            continue;
+        }
+        reported += 1;
+
+        let (file, func) = &functions[index];
+        println!(
+            "\n  {} ({} {})",
+            describe(file, func),
+            matches.len(),
+            if matches.len() == 1 { "match" } else { "matches" }
+        );
+        for (rank, (other, similarity)) in matches.iter().enumerate() {
+            // Indices of `others` skip the function itself
+            let other = if *other >= index { other + 1 } else { *other };
+            let (other_file, other_func) = &functions[other];
+            println!(
+                "    {}. {} ({:.2}%)",
+                rank + 1,
+                describe(other_file, other_func),
+                similarity * 100.0
+            );
+        }
+    }
+
+    if reported == 0 {
+        println!("\nNo functions with similar matches found!");
+    } else {
+        println!("\nTotal functions with similar matches: {}", reported);
+    }
+
+    Ok(())
+}
+
+fn describe(file: &Path, func: &GenericFunctionDef) -> String {
+    format_function_output(&file.to_string_lossy(), &func.name, func.start_line, func.end_line)
+}
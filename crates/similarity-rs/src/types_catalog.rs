@@ -0,0 +1,208 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::FileFilter;
+use crate::rust_parser::{DeclaredType, RustParser};
+use serde::Serialize;
+use similarity_core::{
+    type_comparator::{find_similar_types, TypeComparisonOptions},
+    type_extractor::TypeDefinition,
+    type_normalizer::{normalize_type, NormalizationOptions},
+};
+use std::fs;
+
+/// Output format of the `types` subcommand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CatalogFormat {
+    Text,
+    Json,
+}
+
+/// JSON catalog of every type of the scanned code
+#[derive(Debug, Serialize)]
+struct TypeCatalog {
+    types: Vec<TypeRecord>,
+}
+
+#[derive(Debug, Serialize)]
+struct TypeRecord {
+    name: String,
+    /// `struct`, `enum` or `type_alias`
+    kind: String,
+    file: String,
+    start_line: usize,
+    end_line: usize,
+    /// Normalized properties, sorted by name; empty for enums
+    properties: Vec<PropertyRecord>,
+    /// Enum variants in declaration order, as written
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    variants: Vec<VariantRecord>,
+    /// Normalized signature, equal for types with the same property set
+    signature: String,
+}
+
+#[derive(Debug, Serialize)]
+struct PropertyRecord {
+    name: String,
+    #[serde(rename = "type")]
+    type_name: String,
+    optional: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct VariantRecord {
+    name: String,
+    /// `(f64)` or `{ w: f64, h: f64 }`; absent for unit variants
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payload: Option<String>,
+}
+
+/// Print every struct, enum and type alias of the scanned paths with its
+/// normalized property set, as text or as a JSON catalog for other tools
+pub fn list_types(
+    paths: &[String],
+    file_filter: &FileFilter,
+    format: CatalogFormat,
+    include_tuples: bool,
+) -> anyhow::Result<()> {
+    let records: Vec<TypeRecord> =
+        extract_types(paths, file_filter, include_tuples)?.iter().map(to_record).collect();
+
+    match format {
+        CatalogFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&TypeCatalog { types: records })?);
+        }
+        CatalogFormat::Text => {
+            for record in &records {
+                println!(
+                    "{}:{}-{} {} ({})",
+                    record.file, record.start_line, record.end_line, record.name, record.kind
+                );
+                for property in &record.properties {
+                    let optional = if property.optional { "?" } else { "" };
+                    println!("  {}{}: {}", property.name, optional, property.type_name);
+                }
+                for variant in &record.variants {
+                    match variant.payload.as_deref() {
+                        Some(payload) if payload.starts_with('{') => {
+                            println!("  {} {}", variant.name, payload)
+                        }
+                        payload => println!("  {}{}", variant.name, payload.unwrap_or("")),
+                    }
+                }
+            }
+            println!("\nTotal types: {}", records.len());
+        }
+    }
+
+    Ok(())
+}
+
+/// Report pairs of types whose properties are at least `threshold` similar.
+/// Types without properties carry no shape to compare and are skipped.
+pub fn report_duplicate_types(
+    paths: &[String],
+    file_filter: &FileFilter,
+    include_tuples: bool,
+    threshold: f64,
+) -> anyhow::Result<()> {
+    let types: Vec<TypeDefinition> = extract_types(paths, file_filter, include_tuples)?
+        .into_iter()
+        .map(|declared| declared.definition)
+        .filter(|type_def| !type_def.properties.is_empty())
+        .collect();
+    let duplicates = find_similar_types(&types, threshold, &TypeComparisonOptions::default());
+
+    if duplicates.is_empty() {
+        println!("No duplicate types found!");
+        return Ok(());
+    }
+
+    for pair in &duplicates {
+        println!(
+            "{}:{} {} <-> {}:{} {}",
+            pair.type1.file_path,
+            pair.type1.start_line,
+            pair.type1.name,
+            pair.type2.file_path,
+            pair.type2.start_line,
+            pair.type2.name
+        );
+        println!("  Similarity: {:.2}%", pair.result.similarity * 100.0);
+    }
+    println!("\nTotal duplicate types: {}", duplicates.len());
+
+    Ok(())
+}
+
+fn extract_types(
+    paths: &[String],
+    file_filter: &FileFilter,
+    include_tuples: bool,
+) -> anyhow::Result<Vec<DeclaredType>> {
+    let mut parser = RustParser::new().map_err(|e| anyhow::anyhow!("{}", e))?;
+    let mut types = Vec::new();
+
+    for file in file_filter.collect(paths, &["rs"])? {
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        let file_path = file.to_string_lossy();
+        match parser.extract_type_definitions(&content, &file_path, include_tuples) {
+            Ok(file_types) => types.extend(file_types),
+            Err(e) => eprintln!("Error parsing {}: {}", file.display(), e),
+        }
+    }
+
+    Ok(types)
+}
+
+fn to_record(declared: &DeclaredType) -> TypeRecord {
+    let type_def = &declared.definition;
+    if declared.kind == "enum" {
+        let variants = type_def
+            .properties
+            .iter()
+            .map(|variant| VariantRecord {
+                name: variant.name.clone(),
+                payload: Some(variant.type_annotation.clone()).filter(|p| !p.is_empty()),
+            })
+            .collect();
+        return TypeRecord {
+            name: type_def.name.clone(),
+            kind: declared.kind.clone(),
+            file: type_def.file_path.clone(),
+            start_line: type_def.start_line,
+            end_line: type_def.end_line,
+            properties: Vec::new(),
+            variants,
+            signature: normalize_type(type_def, &NormalizationOptions::default()).signature,
+        };
+    }
+
+    let normalized = normalize_type(type_def, &NormalizationOptions::default());
+    let mut properties: Vec<PropertyRecord> = normalized
+        .properties
+        .iter()
+        .map(|(name, type_name)| PropertyRecord {
+            name: name.clone(),
+            type_name: type_name.clone(),
+            optional: normalized.optional_properties.contains(name),
+        })
+        .collect();
+    properties.sort_by(|a, b| a.name.cmp(&b.name));
+
+    TypeRecord {
+        name: type_def.name.clone(),
+        kind: declared.kind.clone(),
+        file: type_def.file_path.clone(),
+        start_line: type_def.start_line,
+        end_line: type_def.end_line,
+        properties,
+        variants: Vec::new(),
+        signature: normalized.signature,
+    }
+}
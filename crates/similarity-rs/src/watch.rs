@@ -0,0 +1,169 @@
+#![allow(clippy::uninlined_format_args)]
+
+use crate::check::{scan_pairs, CheckOptions, OutputFormat, PairRecord};
+use serde::Serialize;
+use similarity_core::cli_output::format_function_output;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// What changed in one watch cycle
+#[derive(Serialize)]
+struct WatchEvent<'a> {
+    /// `scan` for the initial run, `rescan` when a file changed or was removed
+    event: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file: Option<String>,
+    new_pairs: Vec<&'a PairRecord>,
+    resolved_pairs: Vec<&'a PairRecord>,
+}
+
+/// Modification time and length, used to notice changed files
+type FileStamp = (Option<SystemTime>, u64);
+
+/// Check the paths, then poll them and re-check every file that changes.
+///
+/// Each cycle reports the pairs that appeared and disappeared in the changed
+/// file. Runs until the process is interrupted.
+pub fn watch(
+    paths: &[String],
+    check_options: &CheckOptions,
+    format: OutputFormat,
+    interval: Duration,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["rs"];
+    let exts: Vec<&str> = check_options
+        .extensions
+        .as_ref()
+        .map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+
+    let mut stamps = file_stamps(&check_options.file_filter().collect(paths, &exts)?);
+    let files: Vec<PathBuf> = stamps.keys().cloned().collect();
+
+    let mut pairs_by_file: BTreeMap<PathBuf, Vec<PairRecord>> =
+        files.iter().map(|file| (file.clone(), Vec::new())).collect();
+    for record in scan_pairs(&files, check_options)? {
+        pairs_by_file.entry(PathBuf::from(&record.file1)).or_default().push(record);
+    }
+
+    if format == OutputFormat::Text {
+        println!("Watching {} files for changes (press Ctrl-C to stop)...", files.len());
+    }
+    let initial: Vec<&PairRecord> = pairs_by_file.values().flatten().collect();
+    emit(
+        &WatchEvent { event: "scan", file: None, new_pairs: initial, resolved_pairs: Vec::new() },
+        format,
+    )?;
+
+    loop {
+        thread::sleep(interval);
+
+        let current = file_stamps(&check_options.file_filter().collect(paths, &exts)?);
+        let changed: BTreeSet<PathBuf> = current
+            .iter()
+            .filter(|(file, stamp)| stamps.get(*file) != Some(stamp))
+            .map(|(file, _)| file.clone())
+            .chain(stamps.keys().filter(|file| !current.contains_key(*file)).cloned())
+            .collect();
+
+        for file in changed {
+            let new = if current.contains_key(&file) {
+                scan_pairs(std::slice::from_ref(&file), check_options)?
+            } else {
+                Vec::new()
+            };
+            let old = pairs_by_file.remove(&file).unwrap_or_default();
+
+            let old_keys: BTreeSet<_> = old.iter().map(pair_key).collect();
+            let new_keys: BTreeSet<_> = new.iter().map(pair_key).collect();
+            emit(
+                &WatchEvent {
+                    event: "rescan",
+                    file: Some(file.to_string_lossy().to_string()),
+                    new_pairs: new.iter().filter(|p| !old_keys.contains(&pair_key(p))).collect(),
+                    resolved_pairs: old
+                        .iter()
+                        .filter(|p| !new_keys.contains(&pair_key(p)))
+                        .collect(),
+                },
+                format,
+            )?;
+
+            if current.contains_key(&file) {
+                pairs_by_file.insert(file, new);
+            }
+        }
+
+        stamps = current;
+    }
+}
+
+fn file_stamps(files: &[PathBuf]) -> BTreeMap<PathBuf, FileStamp> {
+    files
+        .iter()
+        .filter_map(|file| {
+            let metadata = fs::metadata(file).ok()?;
+            Some((file.clone(), (metadata.modified().ok(), metadata.len())))
+        })
+        .collect()
+}
+
+/// Identify a pair across rescans; line numbers shift when a file is edited,
+/// so only the files and function names are used
+fn pair_key(pair: &PairRecord) -> (String, String, String, String) {
+    (
+        pair.file1.clone(),
+        pair.function1.name.clone(),
+        pair.file2.clone(),
+        pair.function2.name.clone(),
+    )
+}
+
+fn emit(event: &WatchEvent, format: OutputFormat) -> anyhow::Result<()> {
+    match format {
+        OutputFormat::Jsonl => println!("{}", serde_json::to_string(event)?),
+        OutputFormat::Text
+        | OutputFormat::MarkdownTodo
+        | OutputFormat::Fixplan
+        | OutputFormat::Sarif
+        | OutputFormat::Json => {
+            match &event.file {
+                Some(file) => println!(
+                    "\nRescanned {}: {} new, {} resolved",
+                    file,
+                    event.new_pairs.len(),
+                    event.resolved_pairs.len()
+                ),
+                None => println!("\nFound {} similar pairs", event.new_pairs.len()),
+            }
+            for pair in &event.new_pairs {
+                println!("  + {}", describe_pair(pair));
+            }
+            for pair in &event.resolved_pairs {
+                println!("  - {}", describe_pair(pair));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn describe_pair(pair: &PairRecord) -> String {
+    format!(
+        "{} <-> {} ({:.2}%)",
+        format_function_output(
+            &pair.file1,
+            &pair.function1.name,
+            pair.function1.start_line,
+            pair.function1.end_line
+        ),
+        format_function_output(
+            &pair.file2,
+            &pair.function2.name,
+            pair.function2.start_line,
+            pair.function2.end_line
+        ),
+        pair.similarity * 100.0
+    )
+}
@@ -0,0 +1,75 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const FRONTEND: &str = r#"
+fn format_price(amount: f64, currency: &str) -> String {
+    let rounded = (amount * 100.0).round() / 100.0;
+    let mut text = String::new();
+    text.push_str(currency);
+    text.push_str(&rounded.to_string());
+    text
+}
+
+fn count_active(items: &[Item]) -> usize {
+    let mut count = 0;
+    for item in items {
+        if item.active && !item.hidden {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn count_visible(entries: &[Item]) -> usize {
+    let mut total = 0;
+    for entry in entries {
+        if entry.active && !entry.hidden {
+            total += 1;
+        }
+    }
+    total
+}
+"#;
+
+const SHARED: &str = r#"
+fn format_money(value: f64, symbol: &str) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    let mut text = String::new();
+    text.push_str(symbol);
+    text.push_str(&rounded.to_string());
+    text
+}
+"#;
+
+#[test]
+fn test_across_reports_only_duplicates_spanning_both_directories() {
+    let dir = tempdir().unwrap();
+    let frontend = dir.path().join("frontend");
+    let shared = dir.path().join("shared");
+    fs::create_dir(&frontend).unwrap();
+    fs::create_dir(&shared).unwrap();
+    fs::write(frontend.join("view.rs"), FRONTEND).unwrap();
+    fs::write(shared.join("money.rs"), SHARED).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("--across")
+        .arg(&frontend)
+        .arg(&shared)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("format_price"))
+        .stdout(predicate::str::contains("format_money"))
+        .stdout(predicate::str::contains("count_active").not())
+        .stdout(predicate::str::contains("Total duplicates across directories: 1"));
+
+    // Without --across the within-frontend duplicate is reported
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&frontend)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("count_active"));
+}
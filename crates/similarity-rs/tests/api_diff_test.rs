@@ -0,0 +1,102 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_added_parameter_is_reported_as_signature_change() {
+    let old = tempdir().unwrap();
+    let new = tempdir().unwrap();
+
+    fs::write(
+        old.path().join("lib.rs"),
+        r#"
+pub fn connect(host: &str) -> bool {
+    !host.is_empty()
+}
+
+pub fn legacy() {}
+
+fn private_helper() {}
+
+pub struct Config {
+    pub host: String,
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        new.path().join("lib.rs"),
+        r#"
+pub fn connect(host: &str, port: u16) -> bool {
+    !host.is_empty() && port > 0
+}
+
+pub fn disconnect() {}
+
+fn private_helper(verbose: bool) {}
+
+pub struct Config {
+    pub host: String,
+}
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("api-diff")
+        .arg(old.path())
+        .arg(new.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Signature changed (1):"))
+        .stdout(predicate::str::contains("~ fn lib.rs::connect"))
+        .stdout(predicate::str::contains("old: pub fn connect(host: &str) -> bool"))
+        .stdout(predicate::str::contains("new: pub fn connect(host: &str, port: u16) -> bool"))
+        .stdout(predicate::str::contains("+ fn lib.rs::disconnect"))
+        .stdout(predicate::str::contains("- fn lib.rs::legacy"))
+        .stdout(predicate::str::contains("private_helper").not())
+        .stdout(predicate::str::contains("Config").not());
+}
+
+#[test]
+fn test_identical_versions_report_no_changes() {
+    let old = tempdir().unwrap();
+    let new = tempdir().unwrap();
+    let source = "pub fn run(x: i32) -> i32 {\n    x + 1\n}\n";
+    fs::write(old.path().join("lib.rs"), source).unwrap();
+    fs::write(new.path().join("lib.rs"), source).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("api-diff")
+        .arg(old.path())
+        .arg(new.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No API changes found."));
+}
+
+#[test]
+fn test_visibility_is_read_from_the_item_not_the_line() {
+    let old = tempdir().unwrap();
+    let new = tempdir().unwrap();
+
+    fs::write(
+        old.path().join("lib.rs"),
+        "#[inline] pub fn fast() {}\n\npub(crate) fn internal() {}\n",
+    )
+    .unwrap();
+    fs::write(new.path().join("lib.rs"), "pub fn other() {}\n").unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("api-diff")
+        .arg(old.path())
+        .arg(new.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- fn lib.rs::fast"))
+        .stdout(predicate::str::contains("internal").not());
+}
@@ -0,0 +1,62 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+const CODE: &str = r#"
+fn somme_données(valeurs: &[u64]) -> u64 {
+    let mut total = 0;
+    for valeur in valeurs {
+        total += valeur;
+    }
+    total
+}
+
+fn somme_entrées(valeurs: &[u64]) -> u64 {
+    let mut total = 0;
+    for valeur in valeurs {
+        total += valeur;
+    }
+    total
+}
+"#;
+
+fn run(dir: &std::path::Path, extra: &[&str]) -> String {
+    let output =
+        Command::cargo_bin("similarity-rs").unwrap().arg(dir).args(extra).output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).expect("output is valid UTF-8")
+}
+
+#[test]
+fn test_non_ascii_names_in_utf8_and_escaped_output() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("somme.rs"), CODE).unwrap();
+
+    let stdout = run(dir.path(), &[]);
+    assert!(stdout.contains("function somme_données"), "{stdout}");
+    assert!(stdout.contains("function somme_entrées"), "{stdout}");
+
+    let stdout = run(dir.path(), &["--ascii-escape"]);
+    assert!(stdout.contains(r"function somme_donn\u{e9}es"), "{stdout}");
+    assert!(stdout.contains(r"function somme_entr\u{e9}es"), "{stdout}");
+    assert!(!stdout.contains('é'), "{stdout}");
+}
+
+#[test]
+fn test_streamed_sarif_filters_on_the_original_names() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("données.rs"), CODE).unwrap();
+
+    let stdout = run(
+        dir.path(),
+        &["--format", "sarif", "--ascii-escape", "--filter-function", "somme_données"],
+    );
+    let document: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let results = document["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1, "{stdout}");
+    let message = results[0]["message"]["text"].as_str().unwrap();
+    assert!(message.contains(r"somme_donn\u{e9}es"), "{message}");
+    let uri =
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"].as_str().unwrap();
+    assert!(uri.ends_with("donn%C3%A9es.rs"), "{uri}");
+}
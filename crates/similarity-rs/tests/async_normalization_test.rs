@@ -0,0 +1,49 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::tsed::{calculate_tsed, TSEDOptions};
+use similarity_rs::rust_parser::RustParser;
+
+fn async_similarity(code1: &str, code2: &str, normalize_async: bool) -> f64 {
+    let mut parser = RustParser::new().unwrap();
+    let tree1 = parser.parse(code1, "a.rs").unwrap();
+    let tree2 = parser.parse(code2, "b.rs").unwrap();
+
+    let mut options = TSEDOptions::default();
+    options.apted_options.compare_values = true;
+    options.size_penalty = false;
+    options.normalize_async = normalize_async;
+    calculate_tsed(&tree1, &tree2, &options)
+}
+
+const AWAITED: &str = r#"
+fn load_user(id: u64) -> impl Future<Output = User> {
+    let user = fetch_user(id).await;
+    cache.insert(id, user.clone());
+    log::debug!("loaded {}", id);
+    user
+}
+"#;
+
+const THEN: &str = r#"
+fn load_user(id: u64) -> impl Future<Output = User> {
+    fetch_user(id).then(|user| {
+        cache.insert(id, user.clone());
+        log::debug!("loaded {}", id);
+        user
+    })
+}
+"#;
+
+#[test]
+fn test_await_matches_equivalent_then_chain() {
+    let plain = async_similarity(AWAITED, THEN, false);
+    let normalized = async_similarity(AWAITED, THEN, true);
+
+    assert!(normalized > plain, "normalized {normalized} vs plain {plain}");
+    assert_eq!(normalized, 1.0);
+}
+
+#[test]
+fn test_then_with_destructuring_callback_is_kept() {
+    let destructuring = THEN.replace("|user|", "|(user, _)|");
+    assert!(async_similarity(AWAITED, &destructuring, true) < 1.0);
+}
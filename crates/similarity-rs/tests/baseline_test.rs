@@ -0,0 +1,170 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+const KNOWN: &str = "fn sum_prices(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.price;
+    }
+    total
+}
+
+fn sum_weights(parcels: &[Item]) -> u64 {
+    let mut total = 0;
+    for parcel in parcels {
+        total += parcel.weight;
+    }
+    total
+}
+";
+
+// Pushes the known pair further down the file
+const UNRELATED: &str = "fn describe(name: &str) -> String {
+    match name.len() {
+        0 => String::from(\"anonymous\"),
+        _ => format!(\"user {}\", name),
+    }
+}
+
+";
+
+const NEW: &str = "
+fn collect_names(users: &[User]) -> Vec<String> {
+    let mut names = Vec::new();
+    for user in users {
+        if user.active {
+            names.push(user.name.clone());
+        }
+    }
+    names
+}
+
+fn collect_emails(accounts: &[User]) -> Vec<String> {
+    let mut emails = Vec::new();
+    for account in accounts {
+        if account.active {
+            emails.push(account.email.clone());
+        }
+    }
+    emails
+}
+";
+
+fn run(dir: &Path, extra: &[&str]) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir)
+        .args(["--threshold", "0.93"])
+        .args(extra)
+        .assert()
+}
+
+#[test]
+fn test_only_new_duplicates_are_reported() {
+    let dir = tempdir().unwrap();
+    let source = dir.path().join("lib.rs");
+    let baseline = dir.path().join("baseline.json");
+    let baseline_arg = baseline.to_str().unwrap();
+    fs::write(&source, KNOWN).unwrap();
+
+    run(dir.path(), &["--baseline", baseline_arg, "--write-baseline"])
+        .success()
+        .stdout(predicate::str::contains("sum_prices"))
+        .stderr(predicate::str::contains("Wrote 1 duplicate pairs to baseline"));
+
+    // The known pair moved down the file, and a new pair appeared
+    fs::write(&source, format!("{UNRELATED}{KNOWN}{NEW}")).unwrap();
+    let output = run(dir.path(), &["--baseline", baseline_arg]).success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let stderr = String::from_utf8(output.get_output().stderr.clone()).unwrap();
+    assert!(stdout.contains("collect_names"), "{stdout}");
+    assert!(!stdout.contains("sum_prices"), "{stdout}");
+    assert!(stdout.contains("Total duplicate pairs found: 1"), "{stdout}");
+    assert!(stderr.contains("Suppressed 1 duplicate pairs"), "{stderr}");
+
+    // Without the baseline both pairs are reported
+    run(dir.path(), &[]).success().stdout(predicate::str::contains("sum_prices"));
+}
+
+#[test]
+fn test_missing_baseline_is_an_error() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), KNOWN).unwrap();
+
+    run(dir.path(), &["--baseline", dir.path().join("missing.json").to_str().unwrap()])
+        .failure()
+        .stderr(predicate::str::contains("Failed to read baseline"));
+}
+
+fn constructor(type_name: &str) -> String {
+    format!(
+        "impl {type_name} {{
+    fn new(items: &[Item]) -> Self {{
+        let mut total = 0;
+        for item in items {{
+            total += item.price;
+        }}
+        Self {{ total }}
+    }}
+}}
+
+"
+    )
+}
+
+#[test]
+fn test_new_copy_of_a_baselined_function_is_reported() {
+    let dir = tempdir().unwrap();
+    let source = dir.path().join("lib.rs");
+    let baseline = dir.path().join("baseline.json");
+    let baseline_arg = baseline.to_str().unwrap();
+    fs::write(&source, format!("{}{}", constructor("Cart"), constructor("Order"))).unwrap();
+
+    run(dir.path(), &["--baseline", baseline_arg, "--write-baseline"])
+        .success()
+        .stderr(predicate::str::contains("Wrote 1 duplicate pairs to baseline"));
+
+    // A third identical `new` pairs with both known ones
+    let code = format!("{}{}{}", constructor("Cart"), constructor("Order"), constructor("Invoice"));
+    fs::write(&source, code).unwrap();
+    let output = run(dir.path(), &["--baseline", baseline_arg]).success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    assert!(stdout.contains("Total duplicate pairs found: 2"), "{stdout}");
+    let pairs: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.contains(" <-> ") && !line.trim_start().starts_with("Classes:"))
+        .collect();
+    assert_eq!(pairs.len(), 2, "{stdout}");
+    assert!(pairs.iter().all(|pair| pair.ends_with(":22-28 method new")), "{stdout}");
+}
+
+#[test]
+fn test_baseline_matches_however_the_paths_are_spelled() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("src")).unwrap();
+    fs::write(dir.path().join("src/lib.rs"), KNOWN).unwrap();
+    let baseline = dir.path().join("baseline.json");
+
+    let scan = |cwd: &Path, target: &str, baseline: &str, extra: &[&str]| {
+        Command::cargo_bin("similarity-rs")
+            .unwrap()
+            .current_dir(cwd)
+            .arg(target)
+            .args(["--threshold", "0.93", "--baseline", baseline])
+            .args(extra)
+            .assert()
+            .success()
+    };
+    scan(dir.path(), "src", "baseline.json", &["--write-baseline"]);
+
+    // From the project root with another spelling, and from elsewhere
+    scan(dir.path(), "./src", "./baseline.json", &[])
+        .stderr(predicate::str::contains("Suppressed 1 duplicate pairs"));
+    let elsewhere = tempdir().unwrap();
+    let src = dir.path().join("src");
+    scan(elsewhere.path(), src.to_str().unwrap(), baseline.to_str().unwrap(), &[])
+        .stderr(predicate::str::contains("Suppressed 1 duplicate pairs"));
+}
@@ -0,0 +1,53 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+// The four statements from `let path` to `file.flush` were copied into
+// `export` and later split between `open` and `write` in the original
+const CODE: &str = r#"fn open(config: &Config) -> File {
+    let name = config.name.clone();
+    let path = config.dir.join(&name);
+    let mut file = File::create(&path).unwrap();
+}
+
+fn write(file: &mut File, data: &[u8]) {
+    file.write_all(data).unwrap();
+    file.flush().unwrap();
+    println!("written");
+}
+
+fn export(config: &Config, data: &[u8]) {
+    log::info!("exporting");
+    let path = config.dir.join(&name);
+    let mut file = File::create(&path).unwrap();
+    file.write_all(data).unwrap();
+    file.flush().unwrap();
+}
+"#;
+
+fn run(dir: &std::path::Path, block_size: &str) -> String {
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir)
+        .args(["--blocks", "--block-size", block_size])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    stdout.split("=== Repeated Blocks ===").nth(1).unwrap().to_string()
+}
+
+#[test]
+fn test_block_spanning_two_functions_is_reported() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("io.rs"), CODE).unwrap();
+
+    let report = run(dir.path(), "4");
+    assert!(report.contains(":3-9 in open, write <-> "), "{report}");
+    assert!(report.contains(":15-18 in export"), "{report}");
+    assert!(report.contains("Statements: 4"), "{report}");
+    assert!(report.contains("Total repeated blocks: 1"), "{report}");
+
+    let report = run(dir.path(), "5");
+    assert!(report.contains("No repeated blocks found!"), "{report}");
+}
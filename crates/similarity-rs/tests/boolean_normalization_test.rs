@@ -0,0 +1,35 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::tsed::{calculate_tsed, TSEDOptions};
+use similarity_rs::rust_parser::RustParser;
+
+fn boolean_similarity(code1: &str, code2: &str, normalize_boolean: bool) -> f64 {
+    let mut parser = RustParser::new().unwrap();
+    let tree1 = parser.parse(code1, "a.rs").unwrap();
+    let tree2 = parser.parse(code2, "b.rs").unwrap();
+
+    let mut options = TSEDOptions::default();
+    options.apted_options.compare_values = true;
+    options.size_penalty = false;
+    options.normalize_boolean = normalize_boolean;
+    calculate_tsed(&tree1, &tree2, &options)
+}
+
+#[test]
+fn test_negated_equality_matches_inequality() {
+    let code1 = "fn differs(a: i32, b: i32) -> bool {\n    !(a == b)\n}";
+    let code2 = "fn differs(a: i32, b: i32) -> bool {\n    a != b\n}";
+
+    assert!(boolean_similarity(code1, code2, false) < 1.0);
+    assert_eq!(boolean_similarity(code1, code2, true), 1.0);
+}
+
+#[test]
+fn test_commutative_operands_match_only_when_pure() {
+    let code1 = "fn both(a: bool, b: bool) -> bool {\n    a && b\n}";
+    let code2 = "fn both(a: bool, b: bool) -> bool {\n    b && a\n}";
+    assert_eq!(boolean_similarity(code1, code2, true), 1.0);
+
+    let code1 = "fn both(a: bool) -> bool {\n    a && ready()\n}";
+    let code2 = "fn both(a: bool) -> bool {\n    ready() && a\n}";
+    assert!(boolean_similarity(code1, code2, true) < 1.0);
+}
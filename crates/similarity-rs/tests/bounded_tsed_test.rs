@@ -0,0 +1,65 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::tsed::{calculate_tsed, calculate_tsed_bounded, TSEDOptions};
+use similarity_rs::rust_parser::RustParser;
+
+const SOURCES: &[&str] = &[
+    "fn total(items: &[i32]) -> i32 {\n    let mut acc = 0;\n    for item in items {\n        acc += item;\n    }\n    acc\n}",
+    "fn sum(values: &[i32]) -> i32 {\n    let mut total = 0;\n    for value in values {\n        total += value;\n    }\n    total\n}",
+    "fn sum_positive(values: &[i32]) -> i32 {\n    let mut total = 0;\n    for value in values {\n        if *value > 0 {\n            total += value;\n        }\n    }\n    total\n}",
+    "fn filtered(values: &[i32]) -> i32 {\n    values.iter().filter(|v| **v > 0).sum()\n}",
+    "fn greet(name: &str) -> String {\n    match name.len() {\n        0 => String::from(\"anonymous\"),\n        _ => format!(\"user {}\", name),\n    }\n}",
+    "fn tiny() {}",
+];
+
+#[test]
+fn test_bounded_similarity_matches_unbounded() {
+    let mut parser = RustParser::new().unwrap();
+    let trees: Vec<_> =
+        SOURCES.iter().map(|source| parser.parse(source, "lib.rs").unwrap()).collect();
+
+    for structure_weight in [0.0, 0.4, 1.0] {
+        let mut options = TSEDOptions { structure_weight, ..TSEDOptions::default() };
+        options.apted_options.compare_values = true;
+
+        for tree1 in &trees {
+            for tree2 in &trees {
+                let similarity = calculate_tsed(tree1, tree2, &options);
+                for threshold in [0.0, 0.3, 0.5, 0.7, 0.85, 0.95, 1.0] {
+                    let expected = (similarity >= threshold).then_some(similarity);
+                    assert_eq!(
+                        calculate_tsed_bounded(tree1, tree2, &options, threshold),
+                        expected,
+                        "threshold {threshold}, structure weight {structure_weight}"
+                    );
+                }
+                // At its own similarity the pair is just kept
+                assert_eq!(
+                    calculate_tsed_bounded(tree1, tree2, &options, similarity),
+                    Some(similarity)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_size_prefilter_can_be_turned_off_without_changing_results() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("lib.rs"), SOURCES.join("\n\n")).unwrap();
+
+    let run = |extra: &[&str]| {
+        let output = assert_cmd::Command::cargo_bin("similarity-rs")
+            .unwrap()
+            .arg(dir.path())
+            .args(["--threshold", "0.5", "--min-lines", "1", "--min-tokens", "1"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let prefiltered = run(&[]);
+    assert!(prefiltered.contains("Similarity:"), "{prefiltered}");
+    assert_eq!(prefiltered, run(&["--no-size-prefilter"]));
+}
@@ -0,0 +1,86 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const SOURCE: &str = r#"
+fn sum_positive(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for v in values {
+        if *v > 0 {
+            total += v;
+        }
+    }
+    total
+}
+
+fn add_positive(items: &[i32]) -> i32 {
+    let mut sum = 0;
+    for i in items {
+        if *i > 0 {
+            sum += i;
+        }
+    }
+    sum
+}
+
+fn describe(code: u32) -> &'static str {
+    match code {
+        0 => "zero",
+        1 => "one",
+        _ => {
+            while code > 10 {
+                return "large";
+            }
+            "other"
+        }
+    }
+}
+"#;
+
+fn run(prefilter: &str) -> String {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, SOURCE).unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--threshold")
+        .arg("0.7")
+        .arg("--prefilter")
+        .arg(prefilter)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8_lossy(&output).to_string()
+}
+
+#[test]
+fn test_cfg_prefilter_still_finds_known_duplicates() {
+    let stdout = run("cfg");
+    assert!(stdout.contains("sum_positive"), "duplicate missing: {stdout}");
+    assert!(stdout.contains("add_positive"), "duplicate missing: {stdout}");
+}
+
+#[test]
+fn test_cfg_prefilter_skips_pairs_with_different_control_flow() {
+    // At this low threshold the match-based function still scores against the loops
+    assert!(run("none").contains("describe"));
+    assert!(!run("cfg").contains("describe"));
+}
+
+#[test]
+fn test_unknown_prefilter_is_rejected() {
+    let dir = tempdir().unwrap();
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--prefilter")
+        .arg("bogus")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value"));
+}
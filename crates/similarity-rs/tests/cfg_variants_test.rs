@@ -0,0 +1,64 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const PLATFORM_CODE: &str = r#"
+/// Locate the configuration file
+#[cfg(unix)]
+#[inline]
+fn config_path(home: &str) -> String {
+    let mut path = String::from(home);
+    if !path.ends_with('/') {
+        path.push('/');
+    }
+    path.push_str(".config/app.toml");
+    path
+}
+
+/// Locate the configuration file
+#[cfg(windows)]
+#[inline]
+fn config_path(home: &str) -> String {
+    let mut path = String::from(home);
+    if !path.ends_with('\\') {
+        path.push('\\');
+    }
+    path.push_str("AppData\\app.toml");
+    path
+}
+"#;
+
+#[test]
+fn test_cfg_variants_reported_by_default() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("platform.rs");
+    fs::write(&file_path, PLATFORM_CODE).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--threshold")
+        .arg("0.7")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("config_path"))
+        .stdout(predicate::str::contains("Total duplicate pairs found: 1"));
+}
+
+#[test]
+fn test_ignore_cfg_variants_suppresses_platform_variants() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("platform.rs");
+    fs::write(&file_path, PLATFORM_CODE).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--threshold")
+        .arg("0.7")
+        .arg("--ignore-cfg-variants")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No duplicate functions found!"));
+}
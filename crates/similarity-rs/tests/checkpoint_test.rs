@@ -0,0 +1,119 @@
+mod common;
+
+use common::{output, run, source};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn run_json(dir: &Path, extra: &[&str]) -> Vec<String> {
+    let stdout = run(dir, &[&["--format", "json", "--threshold", "0.8"], extra].concat());
+    let report: serde_json::Value =
+        serde_json::from_str(&stdout).unwrap_or_else(|e| panic!("{e}: {stdout}"));
+    let mut pairs: Vec<String> =
+        report["pairs"].as_array().unwrap().iter().map(|pair| pair.to_string()).collect();
+    pairs.sort();
+    pairs
+}
+
+#[test]
+fn test_resumed_run_matches_an_uninterrupted_one() {
+    let dir = tempdir().unwrap();
+    for noun in ["price", "weight", "count"] {
+        fs::write(dir.path().join(format!("{noun}.rs")), source(noun)).unwrap();
+    }
+    let state = tempdir().unwrap();
+    let checkpoint = state.path().join("scan.checkpoint");
+    let checkpoint_arg = checkpoint.to_str().unwrap();
+
+    let uninterrupted = run_json(dir.path(), &["--checkpoint", checkpoint_arg]);
+    assert_eq!(uninterrupted.len(), 3);
+
+    // Interrupt after the first file, in the middle of writing the second
+    let content = fs::read_to_string(&checkpoint).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 4);
+    let second = lines[2];
+    let interrupted = format!("{}\n{}\n{}", lines[0], lines[1], &second[..second.len() / 2]);
+    fs::write(&checkpoint, interrupted).unwrap();
+
+    let resumed = run_json(dir.path(), &["--resume", checkpoint_arg]);
+    assert_eq!(resumed, uninterrupted);
+
+    let content = fs::read_to_string(&checkpoint).unwrap();
+    assert_eq!(content.lines().count(), 4, "{content}");
+    assert!(content.lines().all(|line| serde_json::from_str::<serde_json::Value>(line).is_ok()));
+}
+
+#[test]
+fn test_resume_skips_completed_files() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("price.rs"), source("price")).unwrap();
+    let state = tempdir().unwrap();
+    let checkpoint = state.path().join("scan.checkpoint");
+    let checkpoint_arg = checkpoint.to_str().unwrap();
+
+    assert_eq!(run_json(dir.path(), &["--checkpoint", checkpoint_arg]).len(), 1);
+
+    // A completed file is taken from the checkpoint as is
+    let content = fs::read_to_string(&checkpoint).unwrap();
+    let (header, entry) = content.trim().split_once('\n').unwrap();
+    let mut entry: serde_json::Value = serde_json::from_str(entry).unwrap();
+    entry["pairs"] = serde_json::json!([]);
+    fs::write(&checkpoint, format!("{header}\n{entry}\n")).unwrap();
+    assert!(run_json(dir.path(), &["--resume", checkpoint_arg]).is_empty());
+
+    // Until it changes
+    fs::write(dir.path().join("price.rs"), source("cost")).unwrap();
+    assert_eq!(run_json(dir.path(), &["--resume", checkpoint_arg]).len(), 1);
+}
+
+#[test]
+fn test_resumed_sarif_includes_the_pairs_of_completed_files() {
+    let dir = tempdir().unwrap();
+    for noun in ["price", "weight"] {
+        fs::write(dir.path().join(format!("{noun}.rs")), source(noun)).unwrap();
+    }
+    let state = tempdir().unwrap();
+    let checkpoint = state.path().join("scan.checkpoint");
+    let checkpoint_arg = checkpoint.to_str().unwrap();
+
+    let result_count = |flag: &str| {
+        let stdout =
+            run(dir.path(), &["--format", "sarif", "--threshold", "0.8", flag, checkpoint_arg]);
+        let log: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+        log["runs"][0]["results"].as_array().unwrap().len()
+    };
+
+    assert_eq!(result_count("--checkpoint"), 2);
+    assert_eq!(result_count("--resume"), 2);
+}
+
+#[test]
+fn test_resume_with_other_settings_is_refused() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("price.rs"), source("price")).unwrap();
+    let state = tempdir().unwrap();
+    let checkpoint = state.path().join("scan.checkpoint");
+    let checkpoint_arg = checkpoint.to_str().unwrap();
+
+    assert_eq!(run_json(dir.path(), &["--checkpoint", checkpoint_arg]).len(), 1);
+
+    // The recorded pair is above 0.8 but not above 0.99
+    let resumed = output(dir.path(), &["--threshold", "0.99", "--resume", checkpoint_arg]);
+    assert!(!resumed.status.success());
+    let stderr = String::from_utf8(resumed.stderr).unwrap();
+    assert!(stderr.contains("recorded with other settings"), "{stderr}");
+
+    assert_eq!(run_json(dir.path(), &["--resume", checkpoint_arg]).len(), 1);
+}
+
+#[test]
+fn test_checkpoint_is_rejected_in_watch_mode() {
+    let dir = tempdir().unwrap();
+    let checkpoint = dir.path().join("scan.checkpoint");
+    for flag in ["--checkpoint", "--resume"] {
+        let watched = output(dir.path(), &["--watch", flag, checkpoint.to_str().unwrap()]);
+        assert_eq!(watched.status.code(), Some(2));
+    }
+    assert!(!checkpoint.exists());
+}
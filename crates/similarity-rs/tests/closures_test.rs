@@ -0,0 +1,58 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+const CODE: &str = r#"
+fn totals(orders: &[Order]) -> Vec<u64> {
+    orders
+        .iter()
+        .map(|order| {
+            let mut total = 0;
+            for line in &order.lines {
+                total += line.price * line.quantity;
+            }
+            total
+        })
+        .collect()
+}
+
+fn weights(shipments: &[Shipment]) -> Vec<String> {
+    let label = shipments.len().to_string();
+    shipments
+        .iter()
+        .map(|shipment| {
+            let mut weight = 0;
+            for parcel in &shipment.parcels {
+                weight += parcel.mass * parcel.count;
+            }
+            weight
+        })
+        .map(|weight| format!("{label}: {weight}"))
+        .collect()
+}
+"#;
+
+fn run(dir: &std::path::Path, extra: &[&str]) -> String {
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir)
+        .args(["--threshold", "0.9"])
+        .args(extra)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_identical_closures_are_flagged_with_include_closures() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("report.rs"), CODE).unwrap();
+
+    let stdout = run(dir.path(), &[]);
+    assert!(!stdout.contains("closure@"), "{stdout}");
+
+    let stdout = run(dir.path(), &["--include-closures"]);
+    assert!(stdout.contains("closure@5:14"), "{stdout}");
+    assert!(stdout.contains("closure@19:14"), "{stdout}");
+}
@@ -0,0 +1,61 @@
+//! Helpers shared by the integration tests: generators of similar functions
+//! and a runner of the binary.
+
+// Each test crate uses only some of them
+#![allow(dead_code)]
+
+use assert_cmd::Command;
+use std::path::Path;
+use std::process::Output;
+
+/// A function summing the `field` of its items. `extra_line` adds a
+/// statement, making it the longest of otherwise equal functions.
+pub fn summing_function(name: &str, field: &str, extra_line: bool) -> String {
+    let extra = if extra_line { "    let _ = items.len();\n" } else { "" };
+    format!(
+        "fn {name}(items: &[Item]) -> u64 {{\n{extra}    let mut total = 0;\n    for item in items {{\n        total += item.{field};\n    }}\n    total\n}}\n\n"
+    )
+}
+
+/// A public function summing its values, whose copies differ only in name
+pub fn total_function(name: &str) -> String {
+    format!(
+        "pub fn {name}(values: &[u64]) -> u64 {{\n    let mut total = 0;\n    for value in values {{\n        total += value;\n    }}\n    total\n}}\n\n"
+    )
+}
+
+/// A file with `total_{noun}s` and `sum_{noun}s`, which differ only in names
+pub fn source(noun: &str) -> String {
+    format!(
+        r#"
+pub fn total_{noun}s(values: &[u64]) -> u64 {{
+    let mut total = 0;
+    for value in values {{
+        total += value;
+    }}
+    total
+}}
+
+pub fn sum_{noun}s(values: &[u64]) -> u64 {{
+    let mut sum = 0;
+    for value in values {{
+        sum += value;
+    }}
+    sum
+}}
+"#
+    )
+}
+
+/// Run similarity-rs on `dir` with `args`
+pub fn output(dir: &Path, args: &[&str]) -> Output {
+    Command::cargo_bin("similarity-rs").unwrap().arg(dir).args(args).output().unwrap()
+}
+
+/// Run similarity-rs on `dir` with `args`, expecting it to succeed, and
+/// return its stdout
+pub fn run(dir: &Path, args: &[&str]) -> String {
+    let output = output(dir, args);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
@@ -0,0 +1,93 @@
+use assert_cmd::Command;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn print_config(dir: &Path, extra: &[&str]) -> String {
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir)
+        .arg("--print-config")
+        .args(extra)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_command_line_overrides_config_file_over_defaults() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join(".similarityrc"), "threshold = 0.7\nmin-lines = 8\n").unwrap();
+    let nested = dir.path().join("src").join("nested");
+    fs::create_dir_all(&nested).unwrap();
+
+    // Discovered by walking up from the analyzed directory
+    let stdout = print_config(&nested, &[]);
+    assert!(stdout.contains(".similarityrc"), "{stdout}");
+    assert!(stdout.contains("threshold = 0.7\n"), "{stdout}");
+    assert!(stdout.contains("min-lines = 8\n"), "{stdout}");
+    // Keys missing from the file keep their defaults
+    assert!(stdout.contains("rename-cost = 0.3\n"), "{stdout}");
+
+    let stdout = print_config(&nested, &["--threshold", "0.95"]);
+    assert!(stdout.contains("threshold = 0.95\n"), "{stdout}");
+    assert!(stdout.contains("min-lines = 8\n"), "{stdout}");
+}
+
+#[test]
+fn test_explicit_config_and_exclude_globs() {
+    let dir = tempdir().unwrap();
+    let code = "fn total(values: &[u64]) -> u64 {\n    let mut total = 0;\n    for value in values {\n        total += value;\n    }\n    total\n}\n\nfn sum(items: &[u64]) -> u64 {\n    let mut sum = 0;\n    for item in items {\n        sum += item;\n    }\n    sum\n}\n";
+    fs::create_dir(dir.path().join("generated")).unwrap();
+    fs::write(dir.path().join("generated").join("lib.rs"), code).unwrap();
+    let config = dir.path().join("similarity.toml");
+    fs::write(&config, "exclude = [\"**/generated/**\"]\nthreshold = 0.8\n").unwrap();
+
+    let run = |extra: &[&str]| {
+        let output = Command::cargo_bin("similarity-rs")
+            .unwrap()
+            .arg(dir.path())
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let stdout = run(&["--threshold", "0.8"]);
+    assert!(stdout.contains("Similarity:"), "{stdout}");
+
+    let stdout = run(&["--config", config.to_str().unwrap()]);
+    assert!(stdout.contains("No Rust files found"), "{stdout}");
+
+    let stdout = run(&["--config", config.to_str().unwrap(), "--exclude", "**/other/**"]);
+    assert!(stdout.contains("Similarity:"), "{stdout}");
+}
+
+#[test]
+fn test_unknown_keys_are_rejected() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join(".similarityrc"), "treshold = 0.7\n").unwrap();
+
+    Command::cargo_bin("similarity-rs").unwrap().arg(dir.path()).assert().failure();
+}
+
+#[test]
+fn test_scoring_flags_and_exit_gates_are_read_from_the_config_file() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join(".similarityrc"),
+        "normalize-primitive-types = true\nprefilter = \"cfg\"\nfail-on-duplicates = true\nthreshold = 0.8\n",
+    )
+    .unwrap();
+
+    let stdout = print_config(dir.path(), &[]);
+    assert!(stdout.contains("normalize-primitive-types = true\n"), "{stdout}");
+    assert!(stdout.contains("prefilter = \"cfg\"\n"), "{stdout}");
+    assert!(stdout.contains("fail-on-duplicates = true\n"), "{stdout}");
+
+    let code = "fn total(values: &[u64]) -> u64 {\n    let mut total = 0;\n    for value in values {\n        total += value;\n    }\n    total\n}\n\nfn sum(items: &[u32]) -> u32 {\n    let mut sum = 0;\n    for item in items {\n        sum += item;\n    }\n    sum\n}\n";
+    fs::write(dir.path().join("lib.rs"), code).unwrap();
+    Command::cargo_bin("similarity-rs").unwrap().arg(dir.path()).assert().failure();
+}
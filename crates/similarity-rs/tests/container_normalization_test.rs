@@ -0,0 +1,42 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::tsed::{calculate_tsed, TSEDOptions};
+use similarity_rs::rust_parser::RustParser;
+
+fn container_similarity(code1: &str, code2: &str, normalize_containers: bool) -> f64 {
+    let mut parser = RustParser::new().unwrap();
+    let tree1 = parser.parse(code1, "a.rs").unwrap();
+    let tree2 = parser.parse(code2, "b.rs").unwrap();
+
+    let mut options = TSEDOptions::default();
+    options.apted_options.compare_values = true;
+    options.size_penalty = false;
+    options.normalize_containers = normalize_containers;
+    calculate_tsed(&tree1, &tree2, &options)
+}
+
+#[test]
+fn test_vec_macro_matches_vec_from_array() {
+    let code1 = "fn primes() -> Vec<u32> {\n    vec![2, 3, 5]\n}";
+    let code2 = "fn primes() -> Vec<u32> {\n    Vec::from([2, 3, 5])\n}";
+
+    let without = container_similarity(code1, code2, false);
+    let with = container_similarity(code1, code2, true);
+    assert!(with > without, "{with} should exceed {without}");
+    assert_eq!(with, 1.0);
+}
+
+#[test]
+fn test_vec_macro_matches_to_vec() {
+    let code1 = "fn primes() -> Vec<u32> {\n    vec![2, 3, 5]\n}";
+    let code2 = "fn primes() -> Vec<u32> {\n    [2, 3, 5].to_vec()\n}";
+
+    assert_eq!(container_similarity(code1, code2, true), 1.0);
+}
+
+#[test]
+fn test_different_elements_still_differ() {
+    let code1 = "fn primes() -> Vec<u32> {\n    vec![2, 3, 5]\n}";
+    let code2 = "fn primes() -> Vec<u32> {\n    Vec::from([2, 3, 7])\n}";
+
+    assert!(container_similarity(code1, code2, true) < 1.0);
+}
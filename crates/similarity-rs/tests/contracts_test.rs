@@ -0,0 +1,122 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_shared_guards_with_different_bodies_have_high_contract_similarity() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("orders.rs");
+    fs::write(
+        &file_path,
+        r#"
+fn ship_order(order: &Order, stock: &Stock) -> Result<Receipt, Error> {
+    assert!(!order.items.is_empty(), "order has no items");
+    debug_assert_eq!(order.currency, stock.currency);
+    if order.total <= 0 {
+        return Err(Error::InvalidTotal);
+    }
+    let carrier = stock.nearest_carrier(order.address)?;
+    let label = carrier.print_label(order.address, order.weight());
+    Ok(Receipt::shipped(label))
+}
+
+fn refund_order(order: &Order, stock: &Stock) -> Result<Receipt, Error> {
+    assert!(!order.items.is_empty(), "order has no items");
+    debug_assert_eq!(order.currency, stock.currency);
+    if order.total <= 0 {
+        return Err(Error::InvalidTotal);
+    }
+    for item in &order.items {
+        stock.restock(item.sku, item.quantity);
+    }
+    Ok(Receipt::refunded(order.total))
+}
+
+fn no_contract(values: &[i32]) -> i32 {
+    values.iter().sum()
+}
+"#,
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--contracts")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=== Function Contract Similarity ==="))
+        .stdout(predicate::str::contains("ship_order"))
+        .stdout(predicate::str::contains("refund_order"))
+        .stdout(predicate::str::contains("Contract similarity: 100.00%"))
+        .stdout(predicate::str::contains("Total function pairs with similar contracts: 1"))
+        .get_output()
+        .stdout
+        .clone();
+
+    let stdout = String::from_utf8(output).unwrap();
+    let body_similarity: f64 = stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Body similarity: "))
+        .and_then(|value| value.trim_end_matches('%').parse().ok())
+        .unwrap();
+    assert!(body_similarity < 85.0, "bodies should differ: {stdout}");
+    assert!(!stdout.contains("no_contract"));
+}
+
+#[test]
+fn test_with_contracts_reports_contract_similarity_on_each_pair() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("accounts.rs");
+    fs::write(
+        &file_path,
+        r#"
+fn deposit(account: &mut Account, amount: i64) -> Result<i64, Error> {
+    assert!(amount > 0, "amount must be positive");
+    if account.frozen {
+        return Err(Error::Frozen);
+    }
+    account.balance += amount;
+    account.history.push(amount);
+    Ok(account.balance)
+}
+
+fn withdraw(account: &mut Account, amount: i64) -> Result<i64, Error> {
+    assert!(amount > 0, "amount must be positive");
+    if account.frozen {
+        return Err(Error::Frozen);
+    }
+    account.balance -= amount;
+    account.history.push(-amount);
+    Ok(account.balance)
+}
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .args(["--threshold", "0.5", "--with-contracts"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("deposit"))
+        .stdout(predicate::str::contains("Contract similarity: 100.00%"));
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .args(["--threshold", "0.5", "--with-contracts", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"contract_similarity\": 1.0"));
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .args(["--threshold", "0.5"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Contract similarity").not());
+}
@@ -0,0 +1,49 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const CODE: &str = r#"
+fn total_price(items: &[Item]) -> Result<u64, Error> {
+    let mut total = 0;
+    for item in items {
+        if item.available {
+            total += item.price * item.quantity;
+        }
+    }
+    log::debug!("computed total {}", total);
+    metrics::record("total_price", total);
+    audit_trail.push(format!("priced {} items", items.len()));
+    Ok(total)
+}
+
+fn total_weight(parcels: &[Item]) -> u64 {
+    let mut sum = 0;
+    for parcel in parcels {
+        if parcel.available {
+            sum += parcel.weight * parcel.quantity;
+        }
+    }
+    sum
+}
+"#;
+
+#[test]
+fn test_shared_core_scores_higher_than_whole_functions() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, CODE).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--core")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=== Core Similarity ==="))
+        .stdout(predicate::str::contains("total_price <->"))
+        .stdout(predicate::str::contains("total_weight"))
+        .stdout(predicate::str::contains("Core similarity: 9"))
+        .stdout(predicate::str::contains("Core: statements 1-2 of 6 <-> 1-2 of 3"))
+        .stdout(predicate::str::contains("Total functions with a similar core: 1"));
+}
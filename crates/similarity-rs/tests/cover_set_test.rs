@@ -0,0 +1,85 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+const CODE: &str = r#"
+fn sum_values(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for value in values {
+        total += value;
+    }
+    total
+}
+
+fn sum_numbers(numbers: &[i32]) -> i32 {
+    let mut sum = 0;
+    for number in numbers {
+        sum += number;
+    }
+    sum
+}
+
+fn sum_items(items: &[i32]) -> i32 {
+    let mut acc = 0;
+    for item in items {
+        acc += item;
+    }
+    acc
+}
+
+fn greet(name: &str) -> String {
+    let greeting = format!("Hello, {}!", name);
+    println!("{}", greeting);
+    greeting
+}
+
+fn welcome(user: &str) -> String {
+    let message = format!("Hello, {}!", user);
+    println!("{}", message);
+    message
+}
+"#;
+
+#[test]
+fn test_cover_set_touches_every_duplicate_pair() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), CODE).unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--cover-set")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    let (report, cover) = stdout.split_once("=== Cover Set ===").expect(&stdout);
+    let members: Vec<&str> = cover
+        .lines()
+        .filter(|line| line.ends_with(" pair)") || line.ends_with(" pairs)"))
+        .map(|line| line.trim().rsplit_once(" (").unwrap().0)
+        .collect();
+
+    // Pair lines read `file:s-e function name <-> file:s-e function name`
+    let pairs: Vec<(String, String)> = report
+        .lines()
+        .filter_map(|line| line.trim().split_once(" <-> "))
+        .map(|(a, b)| (a.replacen(" function ", " ", 1), b.replacen(" function ", " ", 1)))
+        .collect();
+
+    assert_eq!(pairs.len(), 4, "{stdout}");
+    assert_eq!(members.len(), 3, "{stdout}");
+    for (a, b) in &pairs {
+        assert!(members.contains(&a.as_str()) || members.contains(&b.as_str()), "{stdout}");
+    }
+}
+
+#[test]
+fn test_cover_set_not_printed_by_default() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), CODE).unwrap();
+
+    let output = Command::cargo_bin("similarity-rs").unwrap().arg(dir.path()).output().unwrap();
+    assert!(!String::from_utf8(output.stdout).unwrap().contains("Cover Set"));
+}
@@ -0,0 +1,75 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_coverage_reports_functions_per_language() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("lib.rs"),
+        "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\nfn sub(a: i32, b: i32) -> i32 {\n    a - b\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("types.rs"),
+        "pub struct Point {\n    x: i32,\n}\n\nimpl Point {\n    fn x(&self) -> i32 {\n        self.x\n    }\n}\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("coverage")
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Parser coverage:"))
+        .stdout(
+            predicate::str::is_match(r"Rust: 2 files, 3 functions, \d+\.\d% of \d+ bytes").unwrap(),
+        );
+}
+
+#[test]
+fn test_coverage_of_file_without_functions_is_zero() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("consts.rs"), "pub const LIMIT: u32 = 10;\n").unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("coverage")
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Rust: 1 files, 0 functions, 0.0% of 27 bytes"));
+}
+
+#[test]
+fn test_coverage_uses_the_parser_of_each_language() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n")
+        .unwrap();
+    fs::write(
+        dir.path().join("math.ts"),
+        "export function add(a: number, b: number): number {\n  return a + b;\n}\n\nconst sub = (a: number, b: number) => a - b;\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("main.go"),
+        "package main\n\nfunc add(a int, b int) int {\n\treturn a + b\n}\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("tool.py"), "def add(a, b):\n    return a + b\n").unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("coverage")
+        .args(["--extensions", "rs,ts,go,py"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"Rust: 1 files, 1 functions, 100\.0%").unwrap())
+        .stdout(predicate::str::is_match(r"TypeScript: 1 files, 2 functions").unwrap())
+        .stdout(predicate::str::is_match(r"Go: 1 files, 1 functions").unwrap())
+        .stdout(predicate::str::contains("Python: 1 files, no function extractor"))
+        .stdout(predicate::str::contains("Failed to parse").not());
+}
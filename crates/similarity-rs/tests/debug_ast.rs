@@ -53,6 +53,7 @@ fn func2(y: i32) -> i32 {
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: true,
+            ..Default::default()
         },
     };
     let similarity = calculate_enhanced_similarity(&tree1, &tree2, &options);
@@ -0,0 +1,50 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_deeply_nested_file_does_not_crash() {
+    let dir = tempdir().unwrap();
+    let nesting = 10_000;
+    let expression = format!("{}1{}", "(".repeat(nesting), ")".repeat(nesting));
+    fs::write(
+        dir.path().join("deep.rs"),
+        format!(
+            "const DEEP: i32 = {expression};\n\nfn deep() -> i32 {{\n    {expression}\n}}\n\nfn deeper() -> i32 {{\n    {expression}\n}}\n"
+        ),
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("totals.rs"),
+        r#"
+fn total_price(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.price * item.quantity;
+    }
+    total
+}
+
+fn total_weight(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.weight * item.quantity;
+    }
+    total
+}
+"#,
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .args(["--threshold", "0.7"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("total_price"));
+    assert!(stdout.contains("total_weight"));
+}
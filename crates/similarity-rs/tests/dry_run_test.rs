@@ -0,0 +1,62 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const DUPLICATES: &str = r#"
+fn sum_values(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for value in values {
+        total += value;
+    }
+    total
+}
+
+fn sum_numbers(numbers: &[i32]) -> i32 {
+    let mut sum = 0;
+    for number in numbers {
+        sum += number;
+    }
+    sum
+}
+"#;
+
+#[test]
+fn test_dry_run_lists_files_without_comparing() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("src/nested")).unwrap();
+    fs::write(dir.path().join("src/lib.rs"), DUPLICATES).unwrap();
+    fs::write(dir.path().join("src/nested/util.rs"), DUPLICATES).unwrap();
+    fs::write(dir.path().join("src/notes.txt"), "not rust").unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files that would be scanned (2):"))
+        .stdout(predicate::str::contains("lib.rs"))
+        .stdout(predicate::str::contains("util.rs"))
+        .stdout(predicate::str::contains("notes.txt").not())
+        .stdout(predicate::str::contains("Rust: 2"))
+        .stdout(predicate::str::contains("Similarity").not())
+        .stdout(predicate::str::contains("Duplicates").not());
+}
+
+#[test]
+fn test_dry_run_follows_extension_filter() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), DUPLICATES).unwrap();
+    fs::write(dir.path().join("template.rs.in"), DUPLICATES).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .args(["--dry-run", "--extensions", "rs,in"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files that would be scanned (2):"))
+        .stdout(predicate::str::contains("Rust: 1"))
+        .stdout(predicate::str::contains(".in: 1"));
+}
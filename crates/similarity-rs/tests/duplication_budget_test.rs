@@ -0,0 +1,54 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+// 20 lines, 14 of them in the duplicated pair: a duplication ratio of 70%
+const CODE: &str = "fn sum_prices(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.price;
+    }
+    total
+}
+fn sum_weights(parcels: &[Item]) -> u64 {
+    let mut total = 0;
+    for parcel in parcels {
+        total += parcel.weight;
+    }
+    total
+}
+fn describe(name: &str) -> String {
+    match name.len() {
+        0 => String::from(\"anonymous\"),
+        _ => format!(\"user {}\", name),
+    }
+}
+";
+
+fn check_with_budget(budget: &str) -> assert_cmd::assert::Assert {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, CODE).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .args(["--threshold", "0.9", "--max-duplication-ratio", budget])
+        .assert()
+}
+
+#[test]
+fn test_ratio_within_budget_succeeds() {
+    check_with_budget("70")
+        .success()
+        .stdout(predicate::str::contains("Duplication ratio: 70.00% (budget 70%)"));
+}
+
+#[test]
+fn test_ratio_over_budget_fails() {
+    check_with_budget("69.9")
+        .failure()
+        .stdout(predicate::str::contains("Duplication ratio: 70.00% (budget 69.9%)"))
+        .stderr(predicate::str::contains("exceeds the budget of 69.9%"));
+}
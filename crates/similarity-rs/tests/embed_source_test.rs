@@ -0,0 +1,79 @@
+use assert_cmd::Command;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+const SOURCE: &str = r#"
+fn sum_positive(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for v in values {
+        if *v > 0 {
+            total += v;
+        }
+    }
+    total
+}
+
+fn sum_positive_copy(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for v in values {
+        if *v > 0 {
+            total += v;
+        }
+    }
+    total
+}
+"#;
+
+/// Run with `--split-output` and return the pairs written to exact.json
+fn exact_pairs(dir: &Path, extra_args: &[&str]) -> Vec<serde_json::Value> {
+    let file_path = dir.join("lib.rs");
+    fs::write(&file_path, SOURCE).unwrap();
+    let out_dir = dir.join("report");
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--split-output")
+        .arg(&out_dir)
+        .args(extra_args)
+        .assert()
+        .success();
+
+    let text = fs::read_to_string(out_dir.join("exact.json")).unwrap();
+    serde_json::from_str(&text).unwrap()
+}
+
+#[test]
+fn test_embed_source_includes_function_text() {
+    let dir = tempdir().unwrap();
+    let pairs = exact_pairs(dir.path(), &["--embed-source"]);
+
+    assert_eq!(pairs.len(), 1);
+    let source1 = pairs[0]["source1"].as_str().unwrap();
+    let source2 = pairs[0]["source2"].as_str().unwrap();
+    assert!(source1.starts_with("fn sum_positive(values: &[i32]) -> i32 {"));
+    assert!(source2.starts_with("fn sum_positive_copy(values: &[i32]) -> i32 {"));
+    assert!(source1.ends_with("    total\n}"));
+}
+
+#[test]
+fn test_max_embed_lines_truncates_with_marker() {
+    let dir = tempdir().unwrap();
+    let pairs = exact_pairs(dir.path(), &["--embed-source", "--max-embed-lines", "2"]);
+
+    let source1 = pairs[0]["source1"].as_str().unwrap();
+    assert_eq!(
+        source1,
+        "fn sum_positive(values: &[i32]) -> i32 {\n    let mut total = 0;\n... (7 more lines)"
+    );
+}
+
+#[test]
+fn test_sources_omitted_by_default() {
+    let dir = tempdir().unwrap();
+    let pairs = exact_pairs(dir.path(), &[]);
+
+    assert!(pairs[0].get("source1").is_none());
+    assert!(pairs[0].get("source2").is_none());
+}
@@ -0,0 +1,69 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_same_panic_message_is_reported() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("lib.rs"),
+        r#"
+fn load(path: &str) -> String {
+    if path.is_empty() {
+        panic!("configuration file is missing");
+    }
+    std::fs::read_to_string(path).expect("failed to read input")
+}
+
+fn reload(path: &str) -> String {
+    if path.is_empty() {
+        panic!("configuration file is missing");
+    }
+    path.to_string()
+}
+
+fn unique() {
+    panic!("this message appears once");
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("other.rs"),
+        r#"
+fn read_other(path: &str) -> String {
+    std::fs::read_to_string(path).expect("failed to read input")
+}
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--error-strings")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=== Duplicated Error Strings ==="))
+        .stdout(predicate::str::contains("\"configuration file is missing\" (2 occurrences)"))
+        .stdout(predicate::str::contains("lib.rs:4 panic!"))
+        .stdout(predicate::str::contains("lib.rs:11 panic!"))
+        .stdout(predicate::str::contains("\"failed to read input\" (2 occurrences)"))
+        .stdout(predicate::str::contains("other.rs:3 .expect()"))
+        .stdout(predicate::str::contains("this message appears once").not());
+}
+
+#[test]
+fn test_error_strings_section_is_opt_in() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), "fn a() { panic!(\"x\"); }\nfn b() { panic!(\"x\"); }\n")
+        .unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Duplicated Error Strings").not());
+}
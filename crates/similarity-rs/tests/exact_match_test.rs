@@ -0,0 +1,101 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::tsed::{
+    calculate_tsed_bounded, calculate_tsed_keyed, exact_match_key, KeyedTrees, TSEDOptions,
+};
+use similarity_rs::rust_parser::RustParser;
+
+const SOURCES: &[&str] = &[
+    "fn total(items: &[i32]) -> i32 {\n    let mut acc = 0;\n    for item in items {\n        acc += item;\n    }\n    acc\n}",
+    "fn sum(values: &[i32]) -> i32 {\n    let mut total = 0;\n    for value in values {\n        total += value;\n    }\n    total\n}",
+    "fn sum_again(values: &[i32]) -> i32 {\n    let mut total = 0;\n    for value in values {\n        total += value;\n    }\n    total\n}",
+    "fn sum_positive(values: &[i32]) -> i32 {\n    let mut total = 0;\n    for value in values {\n        if *value > 0 {\n            total += value;\n        }\n    }\n    total\n}",
+    "fn tiny() {}",
+    "fn tiny_too() {}",
+];
+
+#[test]
+fn test_exact_match_fast_path_agrees_with_apted() {
+    let mut parser = RustParser::new().unwrap();
+    let trees: Vec<_> =
+        SOURCES.iter().map(|source| parser.parse(source, "lib.rs").unwrap()).collect();
+
+    for compare_values in [false, true] {
+        for size_penalty in [false, true] {
+            let mut options = TSEDOptions { size_penalty, ..TSEDOptions::default() };
+            options.apted_options.compare_values = compare_values;
+            let keys: Vec<u64> = trees.iter().map(|tree| exact_match_key(tree, &options)).collect();
+
+            for (tree1, key1) in trees.iter().zip(&keys) {
+                for (tree2, key2) in trees.iter().zip(&keys) {
+                    for threshold in [0.0, 0.5, 0.9] {
+                        assert_eq!(
+                            calculate_tsed_keyed(
+                                (tree1, *key1),
+                                (tree2, *key2),
+                                &options,
+                                threshold
+                            ),
+                            calculate_tsed_bounded(tree1, tree2, &options, threshold),
+                            "compare values {compare_values}, size penalty {size_penalty}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_renamed_duplicates_share_a_key_when_values_are_ignored() {
+    let mut parser = RustParser::new().unwrap();
+    let total = parser.parse(SOURCES[0], "lib.rs").unwrap();
+    let sum = parser.parse(SOURCES[1], "lib.rs").unwrap();
+
+    let mut options = TSEDOptions::default();
+    options.apted_options.compare_values = false;
+    assert_eq!(exact_match_key(&total, &options), exact_match_key(&sum, &options));
+    assert_eq!(
+        calculate_tsed_keyed(
+            (&total, exact_match_key(&total, &options)),
+            (&sum, exact_match_key(&sum, &options)),
+            &TSEDOptions { size_penalty: false, ..options.clone() },
+            0.0
+        ),
+        Some(1.0)
+    );
+
+    options.apted_options.compare_values = true;
+    assert_ne!(exact_match_key(&total, &options), exact_match_key(&sum, &options));
+}
+
+#[test]
+fn test_keyed_trees_bucket_duplicates_and_agree_with_apted() {
+    let mut parser = RustParser::new().unwrap();
+    let trees: Vec<_> =
+        SOURCES.iter().map(|source| parser.parse(source, "lib.rs").unwrap()).collect();
+
+    for compare_values in [false, true] {
+        let mut options = TSEDOptions::default();
+        options.apted_options.compare_values = compare_values;
+        let keyed = KeyedTrees::new(&trees, &options);
+
+        for i in 0..trees.len() {
+            for j in 0..trees.len() {
+                for threshold in [0.0, 0.5, 0.9] {
+                    assert_eq!(
+                        keyed.similarity(i, j, threshold),
+                        calculate_tsed_bounded(&trees[i], &trees[j], &options, threshold),
+                        "compare values {compare_values}"
+                    );
+                }
+            }
+        }
+
+        // `total`, `sum` and `sum_again` only differ in their names
+        assert_eq!(keyed.are_duplicates(0, 1), !compare_values);
+        assert_eq!(keyed.are_duplicates(1, 2), !compare_values);
+        assert_eq!(keyed.are_duplicates(4, 5), !compare_values);
+        assert!(keyed.are_duplicates(3, 3));
+        assert!(!keyed.are_duplicates(1, 3));
+    }
+}
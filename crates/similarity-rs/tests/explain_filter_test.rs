@@ -0,0 +1,65 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const SOURCE: &str = r#"
+fn tiny() -> i32 { 1 }
+
+fn sum_positive(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for v in values {
+        if *v > 0 {
+            total += v;
+        }
+    }
+    total
+}
+
+fn short_but_tall(
+    a: i32,
+) -> i32 {
+    a
+}
+
+#[test]
+fn test_sum() {
+    assert_eq!(sum_positive(&[1, -2, 3]), 4);
+}
+"#;
+
+#[test]
+fn test_explain_filter_reports_reasons() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, SOURCE).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--explain-filter")
+        .arg("--skip-test")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Excluded functions:"))
+        .stdout(predicate::str::contains("function tiny: below min_lines (1 < 3)"))
+        .stdout(predicate::str::contains("function short_but_tall: below min_tokens"))
+        .stdout(predicate::str::contains(
+            "function test_sum: test function excluded by --skip-test",
+        ))
+        .stdout(predicate::str::contains("sum_positive:").not());
+}
+
+#[test]
+fn test_exclusions_hidden_by_default() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, SOURCE).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Excluded functions:").not());
+}
@@ -0,0 +1,79 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const DUPLICATED: &str = "fn sum_prices(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.price;
+    }
+    total
+}
+fn sum_weights(parcels: &[Item]) -> u64 {
+    let mut total = 0;
+    for parcel in parcels {
+        total += parcel.weight;
+    }
+    total
+}
+";
+
+const DISTINCT: &str = "fn sum_prices(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.price;
+    }
+    total
+}
+fn describe(name: &str) -> String {
+    match name.len() {
+        0 => String::from(\"anonymous\"),
+        _ => format!(\"user {}\", name),
+    }
+}
+";
+
+fn check(code: &str, extra: &[&str]) -> assert_cmd::assert::Assert {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, code).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .args(["--threshold", "0.9"])
+        .args(extra)
+        .assert()
+}
+
+#[test]
+fn test_duplicates_fail_the_run() {
+    check(DUPLICATED, &[]).success();
+    check(DUPLICATED, &["--fail-on-duplicates"])
+        .code(1)
+        .stdout(predicate::str::contains("Similarity:"))
+        .stderr(predicate::str::contains("Found 1 duplicate pairs, 0 allowed"));
+    check(DUPLICATED, &["--error-exit"]).code(1);
+}
+
+#[test]
+fn test_no_duplicates_succeed() {
+    check(DISTINCT, &["--fail-on-duplicates"])
+        .success()
+        .stdout(predicate::str::contains("No duplicate functions found!"));
+}
+
+#[test]
+fn test_known_duplicates_within_the_allowance_succeed() {
+    check(DUPLICATED, &["--fail-on-duplicates", "--max-allowed", "1"]).success();
+}
+
+#[test]
+fn test_duplicates_fail_a_sarif_run() {
+    check(DUPLICATED, &["--format", "sarif", "--fail-on-duplicates"])
+        .code(1)
+        .stdout(predicate::str::contains("\"ruleId\":\"duplicate-function\""))
+        .stderr(predicate::str::contains("Found 1 duplicate pairs, 0 allowed"));
+    check(DISTINCT, &["--format", "sarif", "--fail-on-duplicates"]).success();
+}
@@ -32,6 +32,7 @@ fn multiply(x: i32, y: i32) -> i32 {
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: true, // Compare both label and value
+            ..Default::default()
         },
     };
 
@@ -70,6 +71,7 @@ fn bar() {}
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: true,
+            ..Default::default()
         },
     };
 
@@ -109,6 +111,7 @@ fn test_multiplication() {
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: true,
+            ..Default::default()
         },
     };
 
@@ -158,6 +161,7 @@ fn handle_items(data: &[i32]) -> Vec<i32> {
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: true,
+            ..Default::default()
         },
     };
 
@@ -0,0 +1,76 @@
+mod common;
+
+use common::{run, total_function};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_fixplan_keeps_one_function_per_cluster() {
+    let dir = tempdir().unwrap();
+    let code: String =
+        ["total_prices", "total_weights", "total_fees"].iter().map(|n| total_function(n)).collect();
+    fs::write(dir.path().join("lib.rs"), code).unwrap();
+
+    let stdout = run(dir.path(), &["--format", "fixplan"]);
+    let plan: serde_json::Value = serde_json::from_str(&stdout).unwrap_or_else(|e| {
+        panic!("{e}: {stdout}");
+    });
+
+    let clusters = plan["clusters"].as_array().unwrap();
+    assert_eq!(clusters.len(), 1);
+    let cluster = &clusters[0];
+    // Every function is paired with both others, so the first one is kept
+    assert_eq!(cluster["keep"]["name"], "total_prices");
+    assert_eq!(cluster["keep"]["signature"], "pub fn total_prices(values: &[u64]) -> u64");
+
+    let replace = cluster["replace"].as_array().unwrap();
+    let names: Vec<&str> = replace.iter().map(|t| t["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["total_weights", "total_fees"]);
+    assert_eq!(replace[0]["start_line"], 9);
+    assert_eq!(replace[0]["signature"], "pub fn total_weights(values: &[u64]) -> u64");
+    assert!(replace[0]["similarity"].as_f64().unwrap() > 0.9);
+}
+
+#[test]
+fn test_fixplan_only_replaces_functions_paired_with_the_kept_one() {
+    // Each function adds three statements to the previous one, so only
+    // neighbours are similar enough: first - second - third - fourth
+    let statements = [
+        "let a = load(values);",
+        "let b = a.len() * 2;",
+        "let c = b + offset;",
+        "println!(\"{}\", c);",
+        "store(c, b);",
+        "let d = c.pow(2);",
+        "log(d);",
+        "total += d;",
+        "let e = d - a;",
+        "emit(e);",
+        "total -= e;",
+        "flush(total);",
+    ];
+    let code: String = ["first", "second", "third", "fourth"]
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            format!(
+                "pub fn {name}(values: &[u64], offset: u64) -> u64 {{\n    let mut total = 0;\n    {}\n    total\n}}\n\n",
+                statements[..3 * (i + 1)].join("\n    ")
+            )
+        })
+        .collect();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), code).unwrap();
+
+    let stdout = run(dir.path(), &["--threshold", "0.85", "--format", "fixplan"]);
+    let plan: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+
+    let clusters = plan["clusters"].as_array().unwrap();
+    assert_eq!(clusters.len(), 1, "{stdout}");
+    assert_eq!(clusters[0]["keep"]["name"], "second");
+    let replace = clusters[0]["replace"].as_array().unwrap();
+    let names: Vec<&str> = replace.iter().map(|t| t["name"].as_str().unwrap()).collect();
+    // `fourth` is only similar to `third`, which is itself replaced
+    assert_eq!(names, vec!["first", "third"]);
+    assert!(replace.iter().all(|t| t["similarity"].as_f64().unwrap() >= 0.85));
+}
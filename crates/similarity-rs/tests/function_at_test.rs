@@ -0,0 +1,104 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const TARGET: &str = r#"fn unrelated() -> bool {
+    true
+}
+
+fn sum_values(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for value in values {
+        total += value;
+    }
+    total
+}
+"#;
+
+const SCANNED: &str = r#"fn sum_numbers(numbers: &[i32]) -> i32 {
+    let mut sum = 0;
+    for number in numbers {
+        sum += number;
+    }
+    sum
+}
+
+fn greet(name: &str) -> String {
+    format!("Hello, {name}!")
+}
+"#;
+
+#[test]
+fn test_at_reports_duplicates_of_function_containing_line() {
+    let dir = tempdir().unwrap();
+    let target = dir.path().join("target.rs");
+    fs::write(&target, TARGET).unwrap();
+    fs::write(dir.path().join("scanned.rs"), SCANNED).unwrap();
+
+    // Line 7 is the `for` loop inside `sum_values`
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("at")
+        .arg("--file")
+        .arg(&target)
+        .arg("--line")
+        .arg("7")
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("sum_values"))
+        .stdout(predicate::str::contains("scanned.rs:1-7 sum_numbers"))
+        .stdout(predicate::str::contains("greet").not())
+        .stdout(predicate::str::contains("Total similar functions: 1"));
+}
+
+#[test]
+fn test_at_fails_when_no_function_contains_line() {
+    let dir = tempdir().unwrap();
+    let target = dir.path().join("target.rs");
+    fs::write(&target, TARGET).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("at")
+        .arg("--file")
+        .arg(&target)
+        .arg("--line")
+        .arg("4")
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No function in"))
+        .stderr(predicate::str::contains("contains line 4"));
+}
+
+#[test]
+fn test_at_skips_unreadable_files_and_uses_scoring_flags() {
+    let dir = tempdir().unwrap();
+    let target = dir.path().join("target.rs");
+    fs::write(&target, TARGET).unwrap();
+    fs::write(dir.path().join("scanned.rs"), SCANNED).unwrap();
+    fs::write(dir.path().join("broken.rs"), [0xff, 0xfe, 0x00]).unwrap();
+
+    let run = |rename_cost: &str| {
+        Command::cargo_bin("similarity-rs")
+            .unwrap()
+            .arg("at")
+            .arg("--file")
+            .arg(&target)
+            .arg("--line")
+            .arg("7")
+            .arg(dir.path())
+            .args(["--rename-cost", rename_cost])
+            .assert()
+            .success()
+    };
+
+    run("0.3")
+        .stdout(predicate::str::contains("sum_numbers"))
+        .stderr(predicate::str::contains("Skipping"))
+        .stderr(predicate::str::contains("broken.rs"));
+    // Renaming every identifier at full cost drops the pair below the threshold
+    run("1.0").stdout(predicate::str::contains("No similar functions found!"));
+}
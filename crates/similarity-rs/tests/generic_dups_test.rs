@@ -0,0 +1,63 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const CODE: &str = r#"
+fn process_i32(values: &[i32]) -> Vec<i32> {
+    let mut result: Vec<i32> = Vec::new();
+    for value in values {
+        if *value > 0 {
+            result.push(*value * 2);
+        }
+    }
+    result
+}
+
+fn process_u64(values: &[u64]) -> Vec<u64> {
+    let mut result: Vec<u64> = Vec::new();
+    for value in values {
+        if *value > 0 {
+            result.push(*value * 2);
+        }
+    }
+    result
+}
+
+fn describe(name: &str) -> String {
+    let mut text = String::new();
+    text.push_str("name: ");
+    text.push_str(name);
+    text
+}
+"#;
+
+#[test]
+fn test_type_specialized_copies_are_reported_as_generic_able() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, CODE).unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--generic-dups")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=== Generic-able Duplicates ==="))
+        .stdout(predicate::str::contains("process_i32 <->"))
+        .stdout(predicate::str::contains("process_u64"))
+        .stdout(predicate::str::contains("Types: i32 <-> u64"))
+        .stdout(predicate::str::contains("Total generic-able duplicates: 1"))
+        .get_output()
+        .stdout
+        .clone();
+
+    let stdout = String::from_utf8(output).unwrap();
+    let similarity: f64 = stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Similarity (types normalized): "))
+        .and_then(|value| value.trim_end_matches('%').parse().ok())
+        .expect("similarity line");
+    assert!(similarity >= 85.0, "similarity {similarity} below the default threshold");
+}
@@ -0,0 +1,84 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+const DUPLICATED: &str = "fn sum_prices(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.price;
+    }
+    total
+}
+fn sum_weights(parcels: &[Item]) -> u64 {
+    let mut total = 0;
+    for parcel in parcels {
+        total += parcel.weight;
+    }
+    total
+}
+";
+
+const DISTINCT: &str = "fn describe(name: &str) -> String {
+    match name.len() {
+        0 => String::from(\"anonymous\"),
+        _ => format!(\"user {}\", name),
+    }
+}
+";
+
+/// A project whose only duplicates sit in an ignored and in a hidden directory
+fn project(root: &Path) {
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::create_dir_all(root.join("generated")).unwrap();
+    fs::create_dir_all(root.join(".cache")).unwrap();
+    fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+    fs::write(root.join("src/lib.rs"), DISTINCT).unwrap();
+    fs::write(root.join("generated/bindings.rs"), DUPLICATED).unwrap();
+    fs::write(root.join(".cache/snapshot.rs"), DUPLICATED).unwrap();
+}
+
+fn check(root: &Path, extra: &[&str]) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(root)
+        .args(["--threshold", "0.9"])
+        .args(extra)
+        .assert()
+}
+
+#[test]
+fn test_ignored_and_hidden_files_are_skipped() {
+    let dir = tempdir().unwrap();
+    project(dir.path());
+
+    check(dir.path(), &[])
+        .success()
+        .stdout(predicate::str::contains("Checking 1 files for duplicates"))
+        .stdout(predicate::str::contains("sum_prices").not());
+}
+
+#[test]
+fn test_no_ignore_scans_ignored_files() {
+    let dir = tempdir().unwrap();
+    project(dir.path());
+
+    check(dir.path(), &["--no-ignore"])
+        .success()
+        .stdout(predicate::str::contains("Checking 2 files for duplicates"))
+        .stdout(predicate::str::contains("generated/bindings.rs"))
+        .stdout(predicate::str::contains(".cache").not());
+}
+
+#[test]
+fn test_hidden_scans_hidden_files() {
+    let dir = tempdir().unwrap();
+    project(dir.path());
+
+    check(dir.path(), &["--hidden"])
+        .success()
+        .stdout(predicate::str::contains("Checking 2 files for duplicates"))
+        .stdout(predicate::str::contains(".cache/snapshot.rs"))
+        .stdout(predicate::str::contains("generated").not());
+}
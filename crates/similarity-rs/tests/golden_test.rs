@@ -0,0 +1,86 @@
+use assert_cmd::Command;
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::tsed::{calculate_tsed, TSEDOptions};
+use similarity_rs::rust_parser::RustParser;
+use std::fs;
+use tempfile::tempdir;
+
+const GOLDEN: &str = "fn total_price(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.price * item.quantity;
+    }
+    total
+}
+";
+
+const DRIFTED: &str = "fn total_price(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        if item.quantity > 0 {
+            total += item.price * item.quantity;
+        }
+    }
+    total
+}
+";
+
+#[test]
+fn test_drift_from_golden_function_is_reported() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("golden")).unwrap();
+    fs::create_dir_all(dir.path().join("app")).unwrap();
+    fs::write(dir.path().join("golden").join("pricing.rs"), GOLDEN).unwrap();
+    fs::write(dir.path().join("app").join("cart.rs"), DRIFTED).unwrap();
+    fs::write(dir.path().join("app").join("copy.rs"), GOLDEN).unwrap();
+
+    let mut parser = RustParser::new().unwrap();
+    let golden = parser.parse(GOLDEN, "pricing.rs").unwrap();
+    let drifted = parser.parse(DRIFTED, "cart.rs").unwrap();
+    let mut options = TSEDOptions { size_penalty: false, ..TSEDOptions::default() };
+    options.apted_options.rename_cost = 0.3;
+    options.apted_options.compare_values = true;
+    let drift = 1.0 - calculate_tsed(&drifted, &golden, &options);
+    assert!(drift > 0.0);
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--golden")
+        .arg(dir.path().join("golden"))
+        .args(["--threshold", "0.5", "--no-size-penalty"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    // Most drifted first; the golden file itself is not scanned
+    let drifts: Vec<&str> = stdout.lines().filter(|line| line.contains("Drift:")).collect();
+    assert_eq!(drifts, vec![format!("  Drift: {:.2}%", drift * 100.0).as_str(), "  Drift: 0.00%"]);
+    assert!(stdout.contains("cart.rs:1-9 total_price -> "), "{stdout}");
+    assert!(stdout.contains("pricing.rs:1-7 total_price"), "{stdout}");
+    assert!(stdout.contains("Total copies of golden functions: 2"), "{stdout}");
+}
+
+#[test]
+fn test_unreadable_file_is_reported_and_skipped() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("golden")).unwrap();
+    fs::create_dir_all(dir.path().join("app")).unwrap();
+    fs::write(dir.path().join("golden").join("pricing.rs"), GOLDEN).unwrap();
+    fs::write(dir.path().join("app").join("copy.rs"), GOLDEN).unwrap();
+    fs::write(dir.path().join("app").join("latin1.rs"), b"fn caf\xe9() {}\n").unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path().join("app"))
+        .arg("--golden")
+        .arg(dir.path().join("golden"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Skipping ") && stderr.contains("latin1.rs"), "{stderr}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Total copies of golden functions: 1"), "{stdout}");
+}
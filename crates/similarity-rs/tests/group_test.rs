@@ -0,0 +1,46 @@
+mod common;
+
+use common::{output, run, summing_function};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_five_similar_functions_form_one_group() {
+    let dir = tempdir().unwrap();
+    let code: String = [
+        ("sum_prices", "price", false),
+        ("sum_weights", "weight", false),
+        ("sum_fees", "fee", true),
+        ("sum_taxes", "tax", false),
+        ("sum_counts", "count", false),
+    ]
+    .iter()
+    .map(|(name, field, extra)| summing_function(name, field, *extra))
+    .collect();
+    fs::write(dir.path().join("lib.rs"), code).unwrap();
+
+    let stdout = run(dir.path(), &["--threshold", "0.8", "--group"]);
+
+    assert!(stdout.contains("Group 1 (5 functions)"), "{stdout}");
+    assert!(!stdout.contains("Group 2"), "{stdout}");
+    // The longest function represents the group
+    assert!(stdout.contains("Representative: "), "{stdout}");
+    let representative = stdout.lines().find(|line| line.contains("Representative:")).unwrap();
+    assert!(representative.ends_with("function sum_fees"), "{stdout}");
+    assert_eq!(stdout.lines().filter(|line| line.starts_with("    ")).count(), 5, "{stdout}");
+    assert!(!stdout.contains(" <-> "), "{stdout}");
+    assert!(stdout.contains("Total groups found: 1 (5 functions)"), "{stdout}");
+}
+
+#[test]
+fn test_group_is_rejected_with_document_formats() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), summing_function("sum_prices", "price", false)).unwrap();
+
+    for format in ["json", "fixplan"] {
+        let output = output(dir.path(), &["--group", "--format", format]);
+        assert_eq!(output.status.code(), Some(2));
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("--group cannot be used with --format"), "{stderr}");
+    }
+}
@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+/// `count` near-identical summing functions with the given name prefix
+fn summing_functions(prefix: &str, count: usize) -> String {
+    (0..count)
+        .map(|i| {
+            format!(
+                "fn {prefix}_{i}(values: &[i32]) -> i32 {{\n    let mut total = 0;\n    for value in values {{\n        total += value * {i};\n    }}\n    total\n}}\n\n"
+            )
+        })
+        .collect()
+}
+
+fn run(dir: &Path) -> String {
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir)
+        .arg("--threshold")
+        .arg("0.9")
+        .arg("--no-size-penalty")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_grouped_output_is_identical_across_runs() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.rs"), summing_functions("alpha", 2)).unwrap();
+    fs::write(dir.path().join("b.rs"), summing_functions("beta", 3)).unwrap();
+    fs::write(dir.path().join("c.rs"), summing_functions("gamma", 2)).unwrap();
+    fs::write(dir.path().join("d.rs"), summing_functions("delta", 2)).unwrap();
+
+    let first = run(dir.path());
+    let second = run(dir.path());
+    assert_eq!(first, second);
+
+    // Files come in path order, whatever their number of pairs
+    let headers: Vec<&str> =
+        first.lines().filter(|line| line.starts_with("Duplicates in")).collect();
+    let order: Vec<bool> = ["a.rs", "b.rs", "c.rs", "d.rs"]
+        .iter()
+        .zip(&headers)
+        .map(|(file, header)| header.ends_with(&format!("{file}:")))
+        .collect();
+    assert_eq!(headers.len(), 4, "{first}");
+    assert!(order.iter().all(|&ok| ok), "{first}");
+
+    // Members of a group are sorted by line
+    let b_group: Vec<&str> = first
+        .split("Duplicates in")
+        .find(|group| group.contains("b.rs:"))
+        .unwrap()
+        .lines()
+        .filter(|line| line.contains("<->"))
+        .collect();
+    let mut sorted = b_group.clone();
+    sorted.sort_by_key(|line| {
+        let location = line.trim().split(' ').next().unwrap();
+        location.rsplit(':').next().unwrap().split('-').next().unwrap().parse::<u32>().unwrap()
+    });
+    assert_eq!(b_group, sorted);
+}
@@ -0,0 +1,64 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::parser::parse_and_convert_to_tree;
+use similarity_core::tree::TreeNode;
+use similarity_core::tree_normalizer::normalize_identifier_case;
+use similarity_core::tsed::{calculate_tsed, TSEDOptions};
+use similarity_rs::rust_parser::RustParser;
+
+fn options(normalize_identifier_case: bool) -> TSEDOptions {
+    let mut options = TSEDOptions::default();
+    options.apted_options.compare_values = true;
+    options.size_penalty = false;
+    options.normalize_identifier_case = normalize_identifier_case;
+    options
+}
+
+/// The identifier names of a Rust tree, in source order
+fn rust_names(tree: &TreeNode) -> Vec<String> {
+    tree.iter()
+        .filter(|node| node.children.is_empty() && node.label == "identifier")
+        .map(|node| node.value.clone())
+        .collect()
+}
+
+/// The names of a TypeScript tree, in source order
+fn typescript_names(tree: &TreeNode) -> Vec<String> {
+    tree.iter()
+        .filter(|node| {
+            ["FunctionDeclaration", "Parameter", "Identifier"].contains(&node.value.as_str())
+        })
+        .map(|node| node.label.clone())
+        .collect()
+}
+
+#[test]
+fn test_rust_functions_differing_in_identifier_case_match() {
+    let mut parser = RustParser::new().unwrap();
+    let snake = parser
+        .parse("fn get_user(user_id: u64) -> User {\n    find_user_by_id(user_id)\n}", "a.rs")
+        .unwrap();
+    let camel = parser
+        .parse("fn getUser(userId: u64) -> User {\n    findUserById(userId)\n}", "b.rs")
+        .unwrap();
+
+    assert!(calculate_tsed(&snake, &camel, &options(false)) < 1.0);
+    assert_eq!(calculate_tsed(&snake, &camel, &options(true)), 1.0);
+}
+
+#[test]
+fn test_rust_and_typescript_names_normalize_to_the_same_spelling() {
+    let mut parser = RustParser::new().unwrap();
+    let rust = parser
+        .parse("fn get_user(user_id: u64) -> User {\n    find_user_by_id(user_id)\n}", "a.rs")
+        .unwrap();
+    let typescript = parse_and_convert_to_tree(
+        "b.ts",
+        "function getUser(userId: number) { return findUserById(userId); }",
+    )
+    .unwrap();
+
+    let expected = ["get_user", "user_id", "find_user_by_id", "user_id"];
+    assert_eq!(rust_names(&normalize_identifier_case(&rust)), expected);
+    assert_ne!(typescript_names(&typescript), expected);
+    assert_eq!(typescript_names(&normalize_identifier_case(&typescript)), expected);
+}
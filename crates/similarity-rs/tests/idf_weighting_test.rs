@@ -0,0 +1,78 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+// Both pairs have the same structure and differ in the same number of names;
+// one pair shares the rare `invoice_total`, the other only the common `value`
+const CODE: &str = r#"
+fn apply_tax(invoice_total: u64) -> u64 {
+    let mut result = 0;
+    for i in 0..invoice_total {
+        result += i * TAX_RATE;
+    }
+    result
+}
+
+fn apply_fee(invoice_total: u64) -> u64 {
+    let mut result = 0;
+    for i in 0..invoice_total {
+        result += i * FEE_RATE;
+    }
+    result
+}
+
+fn scale_width(value: u64) -> u64 {
+    let mut result = 0;
+    for i in 0..value {
+        result += i * WIDTH_STEP;
+    }
+    result
+}
+
+fn scale_depth(value: u64) -> u64 {
+    let mut result = 0;
+    for i in 0..value {
+        result += i * DEPTH_STEP;
+    }
+    result
+}
+
+fn double(value: u64) -> u64 { let result = value * 2; result }
+fn halve(value: u64) -> u64 { let result = value / 2; result }
+fn square(value: u64) -> u64 { let result = value * value; result }
+"#;
+
+#[test]
+fn test_shared_rare_identifier_ranks_above_shared_common_ones() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, CODE).unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .args(["--idf-weighting", "--threshold", "0.5"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let section = stdout.split("=== IDF-Weighted Similarity ===").nth(1).unwrap();
+
+    // "  Similarity: 93.21% (TSED 98.67%)" -> (93.21, 98.67)
+    let similarity_of = |first: &str, second: &str| -> (f64, f64) {
+        let mut lines = section.lines();
+        lines.find(|line| line.contains(first) && line.contains(second)).unwrap();
+        let scores: Vec<f64> = lines
+            .next()
+            .unwrap()
+            .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .filter_map(|part| part.parse().ok())
+            .collect();
+        (scores[0], scores[1])
+    };
+
+    let (rare, rare_tsed) = similarity_of("apply_tax", "apply_fee");
+    let (common, common_tsed) = similarity_of("scale_width", "scale_depth");
+    assert_eq!(rare_tsed, common_tsed);
+    assert!(rare > common, "rare {rare} vs common {common}\n{section}");
+}
@@ -0,0 +1,112 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_impl_blocks_with_matching_methods_are_reported() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("shapes.rs"),
+        r#"
+pub struct Circle {
+    items: Vec<f64>,
+}
+
+pub struct Square {
+    values: Vec<f64>,
+}
+
+impl Circle {
+    pub fn total(&self) -> f64 {
+        let mut sum = 0.0;
+        for item in &self.items {
+            sum += item;
+        }
+        sum
+    }
+
+    pub fn largest(&self) -> f64 {
+        let mut max = 0.0;
+        for item in &self.items {
+            if *item > max {
+                max = *item;
+            }
+        }
+        max
+    }
+}
+
+impl Square {
+    pub fn total(&self) -> f64 {
+        let mut acc = 0.0;
+        for value in &self.values {
+            acc += value;
+        }
+        acc
+    }
+
+    pub fn largest(&self) -> f64 {
+        let mut best = 0.0;
+        for value in &self.values {
+            if *value > best {
+                best = *value;
+            }
+        }
+        best
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--impls")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=== Impl Block Similarity ==="))
+        .stdout(predicate::str::contains("impl Circle"))
+        .stdout(predicate::str::contains("impl Square"))
+        .stdout(predicate::str::contains("total <-> total"))
+        .stdout(predicate::str::contains("largest <-> largest"))
+        .stdout(predicate::str::contains("Total similar impl blocks: 1"));
+}
+
+#[test]
+fn test_unrelated_impl_blocks_are_not_reported() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("lib.rs"),
+        r#"
+pub struct Counter(u32);
+pub struct Name(String);
+
+impl Counter {
+    pub fn bump(&mut self) {
+        self.0 += 1;
+    }
+}
+
+impl Name {
+    pub fn describe(&self) -> String {
+        match self.0.len() {
+            0 => "empty".to_string(),
+            n if n > 10 => format!("long name {}", self.0),
+            _ => self.0.clone(),
+        }
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--impls")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No similar impl blocks found!"));
+}
@@ -0,0 +1,104 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+const DUPLICATED: &str = "fn sum_prices(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.price;
+    }
+    total
+}
+fn sum_weights(parcels: &[Item]) -> u64 {
+    let mut total = 0;
+    for parcel in parcels {
+        total += parcel.weight;
+    }
+    total
+}
+";
+
+/// Duplicates in `src/`, in `src/generated/` and in `benches/`
+fn project(root: &Path) {
+    fs::create_dir_all(root.join("src/generated")).unwrap();
+    fs::create_dir_all(root.join("benches")).unwrap();
+    fs::write(root.join("src/lib.rs"), DUPLICATED).unwrap();
+    fs::write(root.join("src/generated/bindings.rs"), DUPLICATED).unwrap();
+    fs::write(root.join("benches/prices.rs"), DUPLICATED).unwrap();
+}
+
+fn check(root: &Path, extra: &[&str]) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(root)
+        .args(["--threshold", "0.9"])
+        .args(extra)
+        .assert()
+}
+
+#[test]
+fn test_excluded_folder_is_skipped() {
+    let dir = tempdir().unwrap();
+    project(dir.path());
+
+    check(dir.path(), &["--exclude", "**/generated/**"])
+        .success()
+        .stdout(predicate::str::contains("Checking 2 files for duplicates"))
+        .stdout(predicate::str::contains("src/lib.rs"))
+        .stdout(predicate::str::contains("benches/prices.rs"))
+        .stdout(predicate::str::contains("bindings.rs").not());
+}
+
+#[test]
+fn test_include_globs_are_relative_to_the_scanned_path() {
+    let dir = tempdir().unwrap();
+    project(dir.path());
+
+    check(dir.path(), &["--include", "src/**/*.rs", "--exclude", "**/generated/**"])
+        .success()
+        .stdout(predicate::str::contains("Checking 1 files for duplicates"))
+        .stdout(predicate::str::contains("src/lib.rs"))
+        .stdout(predicate::str::contains("bindings.rs").not())
+        .stdout(predicate::str::contains("benches").not());
+}
+
+#[test]
+fn test_any_include_admits_a_file() {
+    let dir = tempdir().unwrap();
+    project(dir.path());
+
+    check(dir.path(), &["--include", "src/*.rs", "--include", "benches/*.rs"])
+        .success()
+        .stdout(predicate::str::contains("Checking 2 files for duplicates"))
+        .stdout(predicate::str::contains("bindings.rs").not());
+}
+
+#[test]
+fn test_other_modes_honour_the_globs() {
+    let dir = tempdir().unwrap();
+    project(dir.path());
+
+    check(dir.path(), &["--top-k", "10", "--exclude", "**/generated/**"])
+        .success()
+        .stdout(predicate::str::contains("src/lib.rs"))
+        .stdout(predicate::str::contains("bindings.rs").not());
+}
+
+#[test]
+fn test_overlap_mode_applies_the_same_globs() {
+    let dir = tempdir().unwrap();
+    project(dir.path());
+
+    check(
+        dir.path(),
+        &["--experimental-overlap", "--include", "src/*.rs", "--exclude", "src/lib.rs"],
+    )
+    .success()
+    .stdout(predicate::str::contains("No Rust files found in specified paths"));
+
+    check(dir.path(), &["--experimental-overlap", "--include", "src/**/*.rs", "--exclude", "*.rs"])
+        .success()
+        .stdout(predicate::str::contains("Checking 2 files for overlapping code"));
+}
@@ -0,0 +1,140 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+const SOURCE: &str = r#"
+pub fn total_prices(values: &[u64]) -> u64 {
+    let mut total = 0;
+    for value in values {
+        total += value;
+    }
+    total
+}
+
+pub struct Cart;
+
+impl Cart {
+    pub fn total_weights(&self, values: &[u64]) -> u64 {
+        let mut total = 0;
+        for value in values {
+            total += value;
+        }
+        total
+    }
+}
+
+pub fn describe(name: &str) -> String {
+    format!("cart {}", name)
+}
+"#;
+
+fn run_json(dir: &std::path::Path) -> serde_json::Value {
+    run_json_with(dir, &[])
+}
+
+fn run_json_with(dir: &std::path::Path, args: &[&str]) -> serde_json::Value {
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir)
+        .args(["--format", "json", "--threshold", "0.8"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    serde_json::from_str(&stdout).unwrap_or_else(|e| panic!("{e}: {stdout}"))
+}
+
+#[test]
+fn test_json_reports_pairs_and_summary() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), SOURCE).unwrap();
+
+    let report = run_json(dir.path());
+
+    let pairs = report["pairs"].as_array().unwrap();
+    assert_eq!(pairs.len(), 1);
+    let pair = &pairs[0];
+    assert!(pair["file1"].as_str().unwrap().ends_with("lib.rs"));
+    assert!(pair["similarity"].as_f64().unwrap() >= 0.8);
+
+    let mut functions = [&pair["function1"], &pair["function2"]];
+    functions.sort_by_key(|f| f["name"].as_str().unwrap().to_string());
+    assert_eq!(functions[0]["name"], "total_prices");
+    assert_eq!(functions[0]["function_type"], "function");
+    assert_eq!(functions[0]["start_line"], 2);
+    assert_eq!(functions[0]["end_line"], 8);
+    assert_eq!(functions[1]["name"], "total_weights");
+    assert_eq!(functions[1]["function_type"], "method");
+
+    assert_eq!(report["summary"]["pairs"], 1);
+    assert_eq!(report["summary"]["functions_scanned"], 3);
+}
+
+#[test]
+fn test_json_without_duplicates_is_still_a_document() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), "pub fn one() -> u32 {\n    1\n}\n").unwrap();
+
+    let report = run_json(dir.path());
+
+    assert_eq!(report["pairs"].as_array().unwrap().len(), 0);
+    assert_eq!(report["summary"]["pairs"], 0);
+}
+
+#[test]
+fn test_json_without_files_is_still_a_document() {
+    let dir = tempdir().unwrap();
+
+    let report = run_json(dir.path());
+
+    assert_eq!(report["pairs"].as_array().unwrap().len(), 0);
+    assert_eq!(report["summary"]["functions_scanned"], 0);
+}
+
+#[test]
+fn test_json_diagnostics_go_to_stderr() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), SOURCE).unwrap();
+    fs::write(dir.path().join("consts.rs"), "pub const LIMIT: u32 = 10;\n").unwrap();
+
+    let args =
+        ["--max-duplication-ratio", "100", "--explain-filter", "--report-empty", "--cover-set"];
+    let report = run_json_with(dir.path(), &args);
+    assert_eq!(report["summary"]["pairs"], 1);
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .args(["--format", "json", "--threshold", "0.8"])
+        .args(args)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Duplication ratio:"), "{stderr}");
+    assert!(stderr.contains("excluded by filters") || stderr.contains("Excluded"), "{stderr}");
+    assert!(stderr.contains("Files without functions (1):"), "{stderr}");
+    assert!(stderr.contains("=== Cover Set ==="), "{stderr}");
+}
+
+#[test]
+fn test_text_only_modes_are_rejected_with_document_formats() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), SOURCE).unwrap();
+
+    for (format, mode) in [("json", "--impls"), ("sarif", "--mirrors"), ("fixplan", "--blocks")] {
+        let output = Command::cargo_bin("similarity-rs")
+            .unwrap()
+            .arg(dir.path())
+            .args(["--format", format, mode])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(2));
+        assert!(output.stdout.is_empty());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(
+            stderr.contains(&format!("{mode} cannot be used with --format {format}")),
+            "{stderr}"
+        );
+    }
+}
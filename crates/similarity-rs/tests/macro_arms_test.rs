@@ -0,0 +1,53 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const MACRO: &str = r#"
+macro_rules! impl_getters {
+    ($name:ident, u32) => {
+        impl $name {
+            pub fn get(&self) -> u32 {
+                let value = self.value;
+                if value > 100 {
+                    return 100;
+                }
+                value
+            }
+        }
+    };
+    ($name:ident, u64) => {
+        impl $name {
+            pub fn get(&self) -> u64 {
+                let value = self.value;
+                if value > 100 {
+                    return 100;
+                }
+                value
+            }
+        }
+    };
+    ($name:ident, $limit:expr) => {
+        const $name: usize = $limit;
+    };
+}
+"#;
+
+#[test]
+fn test_near_identical_macro_arms_are_reported() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("macros.rs");
+    fs::write(&file_path, MACRO).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--macro-arms")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=== Similar Macro Arms ==="))
+        .stdout(predicate::str::contains("impl_getters! arm 1 <->"))
+        .stdout(predicate::str::contains("impl_getters! arm 2"))
+        .stdout(predicate::str::contains("arm 3").not())
+        .stdout(predicate::str::contains("Total similar macro arms: 1"));
+}
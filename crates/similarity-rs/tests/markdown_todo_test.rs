@@ -0,0 +1,74 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const DUPLICATES: &str = r#"
+fn sum_values(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for value in values {
+        total += value;
+    }
+    total
+}
+
+fn sum_numbers(numbers: &[i32]) -> i32 {
+    let mut sum = 0;
+    for number in numbers {
+        sum += number;
+    }
+    sum
+}
+"#;
+
+#[test]
+fn test_markdown_todo_lists_pairs_as_checklist() {
+    let dir = tempdir().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    fs::create_dir(root.join("my src")).unwrap();
+    let file_path = root.join("my src").join("lib.rs");
+    fs::write(&file_path, DUPLICATES).unwrap();
+    let file = "my%20src/lib.rs";
+
+    // Links are relative to the working directory, even for absolute arguments
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .current_dir(&root)
+        .arg(&file_path)
+        .args(["--format", "markdown-todo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("# Duplicate functions to refactor"))
+        .stdout(predicate::str::contains(format!("## {}", file_path.display())))
+        .stdout(predicate::str::contains(format!(
+            "- [ ] [`sum_values`]({file}#L2-L8) and [`sum_numbers`]({file}#L10-L16)"
+        )))
+        .stdout(predicate::str::contains("% similar)"))
+        .stdout(predicate::str::contains("=== Function Similarity ===").not());
+}
+
+#[test]
+fn test_markdown_todo_cannot_be_combined_with_watch() {
+    let dir = tempdir().unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .args(["--watch", "--format", "markdown-todo"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with --watch"));
+}
+
+#[test]
+fn test_markdown_todo_cannot_be_combined_with_stream() {
+    let dir = tempdir().unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .args(["--stream", "--format", "markdown-todo"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with --watch or --stream"));
+}
@@ -0,0 +1,123 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_scanned_function_matching_a_pattern_is_tagged() {
+    let dir = tempdir().unwrap();
+    let patterns = dir.path().join("patterns");
+    let src = dir.path().join("src");
+    fs::create_dir_all(&patterns).unwrap();
+    fs::create_dir_all(&src).unwrap();
+
+    fs::write(
+        patterns.join("shell_injection.rs"),
+        r#"
+fn run(input: &str) -> String {
+    let command = format!("sh -c {}", input);
+    let output = std::process::Command::new("sh").arg("-c").arg(&command).output().unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+"#,
+    )
+    .unwrap();
+
+    fs::write(
+        src.join("tools.rs"),
+        r#"
+fn execute_user_command(user_input: &str) -> String {
+    let cmd = format!("sh -c {}", user_input);
+    let result = std::process::Command::new("sh").arg("-c").arg(&cmd).output().unwrap();
+    String::from_utf8(result.stdout).unwrap()
+}
+
+fn average(values: &[f64]) -> f64 {
+    let mut total = 0.0;
+    for value in values {
+        total += value;
+    }
+    total / values.len() as f64
+}
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("match-patterns")
+        .arg("--patterns")
+        .arg(&patterns)
+        .arg(&src)
+        .arg("--threshold")
+        .arg("0.95")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("execute_user_command"))
+        .stdout(predicate::str::contains("Pattern: shell_injection"))
+        .stdout(predicate::str::contains("average").not())
+        .stdout(predicate::str::contains("Total pattern matches: 1"));
+}
+
+#[test]
+fn test_missing_pattern_directory_fails() {
+    let dir = tempdir().unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("match-patterns")
+        .arg("--patterns")
+        .arg(dir.path().join("missing"))
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not a directory"));
+}
+
+#[test]
+fn test_scoring_flags_apply_to_pattern_matching() {
+    let dir = tempdir().unwrap();
+    let patterns = dir.path().join("patterns");
+    let src = dir.path().join("src");
+    fs::create_dir_all(&patterns).unwrap();
+    fs::create_dir_all(&src).unwrap();
+
+    fs::write(
+        patterns.join("shell_injection.rs"),
+        r#"
+fn run(input: &str) -> String {
+    let command = format!("sh -c {}", input);
+    let output = std::process::Command::new("sh").arg("-c").arg(&command).output().unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        src.join("tools.rs"),
+        r#"
+fn execute_user_command(user_input: &str) -> String {
+    let cmd = format!("sh -c {}", user_input);
+    let result = std::process::Command::new("sh").arg("-c").arg(&cmd).output().unwrap();
+    String::from_utf8(result.stdout).unwrap()
+}
+"#,
+    )
+    .unwrap();
+
+    let run = |rename_cost: &str| {
+        Command::cargo_bin("similarity-rs")
+            .unwrap()
+            .arg("match-patterns")
+            .arg("--patterns")
+            .arg(&patterns)
+            .arg(&src)
+            .args(["--threshold", "0.95", "--rename-cost", rename_cost])
+            .assert()
+            .success()
+    };
+
+    // Every renamed identifier costs a full edit instead of the default 0.3
+    run("0.3").stdout(predicate::str::contains("execute_user_command"));
+    run("1.0").stdout(predicate::str::contains("No functions matching the patterns found!"));
+}
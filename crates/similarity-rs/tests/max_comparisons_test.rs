@@ -0,0 +1,59 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fmt::Write;
+use std::fs;
+use tempfile::tempdir;
+
+fn write_many_functions(path: &std::path::Path, count: usize) {
+    let mut content = String::new();
+    for i in 0..count {
+        writeln!(
+            content,
+            r#"
+fn compute_{i}(values: &[i32]) -> i32 {{
+    let mut total = 0;
+    for v in values {{
+        total += v * {i};
+    }}
+    total
+}}"#
+        )
+        .unwrap();
+    }
+    fs::write(path, content).unwrap();
+}
+
+#[test]
+fn test_max_comparisons_aborts_large_input() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("large.rs");
+    // 40 functions -> 780 within-file comparisons
+    write_many_functions(&file_path, 40);
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--max-comparisons")
+        .arg("100")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Estimated 780 function comparisons"))
+        .stderr(predicate::str::contains("--max-comparisons 100"))
+        .stderr(predicate::str::contains("--min-lines"));
+}
+
+#[test]
+fn test_max_comparisons_allows_small_input() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("small.rs");
+    write_many_functions(&file_path, 5);
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--max-comparisons")
+        .arg("100")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Checking 1 files for duplicates"));
+}
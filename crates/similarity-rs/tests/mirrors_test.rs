@@ -0,0 +1,95 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+const CODE: &str = r#"
+fn encode(value: u32) -> u32 {
+    let shifted = value << 3;
+    let mixed = shifted ^ KEY;
+    let offset = mixed + 7;
+    offset
+}
+
+fn decode(value: u32) -> u32 {
+    let offset = value - 7;
+    let mixed = offset ^ KEY;
+    let shifted = mixed >> 3;
+    shifted
+}
+
+fn checksum(value: u32) -> u32 {
+    let shifted = value << 3;
+    let mixed = shifted ^ KEY;
+    let offset = mixed + 7;
+    offset
+}
+"#;
+
+#[test]
+fn test_encode_decode_pair_is_flagged_as_mirror() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("codec.rs"), CODE).unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--mirrors")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let report = stdout.split("=== Mirrored Functions ===").nth(1).unwrap();
+
+    // `checksum` repeats `encode` rather than undoing it, but undoes `decode`
+    assert!(report.contains("function encode <-> "), "{report}");
+    assert!(report.contains(":9-14 function decode\n"), "{report}");
+    assert!(report.contains("mirror: true"), "{report}");
+    let pairs: Vec<&str> = report.lines().filter(|line| line.contains(" <-> ")).collect();
+    assert!(!pairs.iter().any(|line| line.contains("encode") && line.contains("checksum")));
+    assert!(report.contains("Total mirrored pairs: 2"), "{report}");
+}
+
+#[test]
+fn test_negation_and_multiplication_are_not_inverse_operations() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("steps.rs"),
+        r#"
+fn negate_then_raise(value: i32) -> i32 {
+    let negated = -value;
+    let raised = negated + 1;
+    raised
+}
+
+fn lower_then_shift(value: i32) -> i32 {
+    let raised = value - 1;
+    let negated = raised + OFFSET;
+    negated
+}
+
+fn double_then_triple(value: i32) -> i32 {
+    let doubled = value * 2;
+    let tripled = doubled * 3;
+    tripled
+}
+
+fn third_then_half(value: i32) -> i32 {
+    let tripled = value / 3;
+    let doubled = tripled / 2;
+    doubled
+}
+"#,
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--mirrors")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let report = stdout.split("=== Mirrored Functions ===").nth(1).unwrap();
+    assert!(report.contains("No mirrored functions found!"), "{report}");
+}
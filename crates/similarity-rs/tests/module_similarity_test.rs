@@ -0,0 +1,77 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+fn module(prefix: &str) -> String {
+    format!(
+        r#"
+pub fn {prefix}_total(lines: &[Line]) -> u64 {{
+    let mut total = 0;
+    for line in lines {{
+        total += line.price * line.quantity;
+    }}
+    total
+}}
+
+pub fn {prefix}_find(records: &[Record], id: u64) -> Option<&Record> {{
+    for record in records {{
+        if record.id == id {{
+            return Some(record);
+        }}
+    }}
+    None
+}}
+
+pub fn {prefix}_label(record: &Record) -> String {{
+    let status = if record.paid {{ "paid" }} else {{ "open" }};
+    format!("{{}} ({{}})", record.name, status)
+}}
+"#
+    )
+}
+
+const UNRELATED: &str = r#"
+pub fn parse_args(args: &[String]) -> Config {
+    let mut config = Config::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--verbose" => config.verbose = true,
+            "--out" => config.out = iter.next().cloned(),
+            _ => config.inputs.push(arg.clone()),
+        }
+    }
+    config
+}
+"#;
+
+#[test]
+fn test_near_duplicate_modules_are_reported() {
+    let dir = tempdir().unwrap();
+    for (name, content) in [
+        ("billing", module("invoice")),
+        ("invoicing", module("bill")),
+        ("cli", UNRELATED.to_string()),
+    ] {
+        fs::create_dir(dir.path().join(name)).unwrap();
+        fs::write(dir.path().join(name).join("mod.rs"), content).unwrap();
+    }
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .args(["--module-threshold", "0.9"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let report = stdout.split("=== Module Similarity ===").nth(1).unwrap();
+
+    let directory_match =
+        report.lines().any(|line| line.contains("billing/ <->") && line.contains("invoicing/"));
+    assert!(directory_match, "{report}");
+    assert!(report.contains("(3 <-> 3 functions)"), "{report}");
+    assert!(!report.contains("cli"), "{report}");
+    // One pair of files and one of directories
+    assert!(report.contains("Total similar modules: 2"), "{report}");
+}
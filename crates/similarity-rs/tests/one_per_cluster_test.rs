@@ -0,0 +1,28 @@
+mod common;
+
+use common::{run, summing_function};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_cluster_of_four_reports_one_pair() {
+    let dir = tempdir().unwrap();
+    let code: String = [
+        ("sum_prices", "price"),
+        ("sum_weights", "weight"),
+        ("sum_fees", "fee"),
+        ("sum_taxes", "tax"),
+    ]
+    .iter()
+    .map(|(name, field)| summing_function(name, field, false))
+    .collect();
+    fs::write(dir.path().join("lib.rs"), code).unwrap();
+
+    let stdout = run(dir.path(), &[]);
+    assert!(stdout.contains("Total duplicate pairs found: 6"), "{stdout}");
+
+    let stdout = run(dir.path(), &["--one-per-cluster"]);
+    assert_eq!(stdout.matches("Similarity:").count(), 1, "{stdout}");
+    assert!(stdout.contains("cluster size 4"), "{stdout}");
+    assert!(stdout.contains("Total clusters found: 1"), "{stdout}");
+}
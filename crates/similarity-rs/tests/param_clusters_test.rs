@@ -0,0 +1,57 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+const CODE: &str = r#"
+fn render(title: &str, width: u32, height: u32, theme: Theme, scale: f64) -> Frame {
+    Frame::new(title, width, height, theme, scale)
+}
+
+fn preview(width: u32, height: u32, title: &str, scale: f64, theme: Theme) -> Frame {
+    Frame::thumbnail(title, width, height, theme, scale)
+}
+
+impl Exporter {
+    fn export(&self, title: &str, width: u32, height: u32, mut theme: Theme, scale: f64) {
+        theme.flatten();
+        self.write(title, width, height, theme, scale);
+    }
+}
+
+fn resize(title: &str, width: u64, height: u64, theme: Theme, scale: f64) -> Frame {
+    Frame::resized(title, width, height, theme, scale)
+}
+
+fn print(title: &str, width: u32, height: u32, theme: Theme, scale: f64, dpi: u32) {
+    Printer::default().print(title, width, height, theme, scale, dpi);
+}
+"#;
+
+#[test]
+fn test_functions_sharing_a_parameter_list_form_a_cluster() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("frames.rs");
+    fs::write(&file_path, CODE).unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--param-clusters")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let (_, clusters) = stdout.split_once("=== Parameter Clusters ===").unwrap();
+    assert!(clusters.contains(
+        "4 functions take (height: u32, scale: f64, theme: Theme, title: &str, width: u32) [5 parameters]"
+    ));
+    assert!(clusters.contains(":2-4 render"));
+    assert!(clusters.contains(":6-8 preview"));
+    assert!(clusters.contains(":11-14 export"));
+    // One extra parameter out of six still shares the list
+    assert!(clusters.contains(":21-23 print (+ dpi: u32)"));
+    // Same names but different types
+    assert!(!clusters.contains("resize"));
+    assert!(clusters.contains("Total parameter clusters: 1"));
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::run;
+use std::fs;
+use tempfile::tempdir;
+
+const CODE: &str = r#"
+fn total_price(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.price * item.quantity;
+    }
+    total
+}
+
+fn total_weight(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.weight * ;
+    }
+    total
+}
+"#;
+
+#[test]
+fn test_functions_with_parse_errors_are_skipped() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("totals.rs"), CODE).unwrap();
+
+    let stdout = run(dir.path(), &["--threshold", "0.7"]);
+    assert!(stdout.contains("function total_weight"), "{stdout}");
+
+    let stdout =
+        run(dir.path(), &["--threshold", "0.7", "--skip-parse-errors", "--explain-filter"]);
+    assert!(!stdout.contains("Similarity:"), "{stdout}");
+    assert!(stdout.contains("parse errors, excluded by --skip-parse-errors"), "{stdout}");
+}
+
+#[test]
+fn test_other_modes_skip_functions_with_parse_errors() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("totals.rs"), CODE).unwrap();
+
+    let stdout = run(dir.path(), &["--threshold", "0.7", "--top-k", "1"]);
+    let top_k = stdout.split("=== Top-1 Matches ===").nth(1).unwrap();
+    assert!(top_k.contains("total_weight"), "{top_k}");
+
+    let stdout = run(dir.path(), &["--threshold", "0.7", "--top-k", "1", "--skip-parse-errors"]);
+    let top_k = stdout.split("=== Top-1 Matches ===").nth(1).unwrap();
+    assert!(!top_k.contains("total_weight"), "{top_k}");
+}
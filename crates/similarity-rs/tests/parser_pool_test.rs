@@ -0,0 +1,57 @@
+use similarity_core::language_parser::{Language, LanguageParser};
+use similarity_core::parser_pool::with_pooled_parser;
+use similarity_core::tree::TreeNode;
+use similarity_rs::rust_parser::RustParser;
+use std::fs;
+use std::path::Path;
+
+/// Labels and values of a tree in pre-order, ignoring node ids
+fn shape(node: &TreeNode, out: &mut Vec<(String, String)>) {
+    out.push((node.label.clone(), node.value.clone()));
+    for child in &node.children {
+        shape(child, out);
+    }
+}
+
+/// Name, line range and tree shape of an extracted function
+type FunctionSummary = (String, u32, u32, Vec<(String, String)>);
+
+/// Function names, ranges and tree shapes of a file
+fn summarize(parser: &mut RustParser, source: &str) -> Vec<FunctionSummary> {
+    let functions = parser.extract_functions(source, "file.rs").unwrap();
+    functions
+        .into_iter()
+        .map(|func| {
+            let lines: Vec<&str> = source.lines().collect();
+            let code = lines[(func.start_line - 1) as usize..func.end_line as usize].join("\n");
+            let mut nodes = Vec::new();
+            shape(&parser.parse(&code, &func.name).unwrap(), &mut nodes);
+            (func.name, func.start_line, func.end_line, nodes)
+        })
+        .collect()
+}
+
+#[test]
+fn test_pooled_parser_gives_the_same_results_as_fresh_parsers() {
+    let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+    let mut sources: Vec<String> = fs::read_dir(&src)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect();
+    sources.sort();
+    assert!(sources.len() > 5);
+
+    let mut compared = 0;
+    // Twice over, so the second pass parses with a parser that has seen every file
+    for source in sources.iter().chain(sources.iter()) {
+        let fresh = summarize(&mut RustParser::new().unwrap(), source);
+        let pooled =
+            with_pooled_parser(Language::Rust, RustParser::new, |parser| summarize(parser, source))
+                .unwrap();
+        assert_eq!(fresh, pooled);
+        compared += fresh.len();
+    }
+    assert!(compared > 100);
+}
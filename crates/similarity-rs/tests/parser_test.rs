@@ -1,6 +1,8 @@
 #![allow(clippy::uninlined_format_args)]
 
 use similarity_core::language_parser::LanguageParser;
+use similarity_core::tsed::{calculate_tsed, TSEDOptions};
+use similarity_core::TreeNode;
 use similarity_rs::rust_parser::RustParser;
 
 #[test]
@@ -40,3 +42,45 @@ fn test_parser_differentiates_function_names() {
         "Functions with different names should produce different trees"
     );
 }
+
+/// Ids of the subtree in pre-order
+fn preorder_ids(node: &TreeNode, ids: &mut Vec<usize>) {
+    ids.push(node.id);
+    for child in &node.children {
+        preorder_ids(child, ids);
+    }
+}
+
+#[test]
+fn test_node_ids_and_scores_are_stable() {
+    let sources = [
+        "fn add(a: i32, b: i32) -> i32 {\n    a + b\n}",
+        "fn sub(a: i32, b: i32) -> i32 {\n    a - b\n}",
+        "fn total(items: &[i32]) -> i32 {\n    let mut acc = 0;\n    for item in items {\n        acc += item;\n    }\n    acc\n}",
+        "fn sum(values: &[i32]) -> i32 {\n    let mut total = 0;\n    for value in values {\n        if *value > 0 {\n            total += value;\n        }\n    }\n    total\n}",
+    ];
+    let mut parser = RustParser::new().unwrap();
+    let trees: Vec<_> =
+        sources.iter().map(|source| parser.parse(source, "lib.rs").unwrap()).collect();
+
+    // APTED memoizes by node id, so every node needs its own, numbered in pre-order
+    for tree in &trees {
+        let mut ids = Vec::new();
+        preorder_ids(tree, &mut ids);
+        assert_eq!(ids, (0..tree.get_subtree_size()).collect::<Vec<_>>());
+    }
+
+    let options = TSEDOptions::default();
+    let score = |i: usize, j: usize| calculate_tsed(&trees[i], &trees[j], &options);
+    for (i, j, expected) in [
+        (0, 0, 0.912_870_929_175_276_9),
+        (0, 1, 0.811_724_830_222_656_2),
+        (0, 2, 0.699_176_143_481_973_4),
+        (0, 3, 0.497_752_604_171_294_24),
+        (2, 2, 1.0),
+        (2, 3, 0.935_714_285_714_285_7),
+    ] {
+        assert!((score(i, j) - expected).abs() < 1e-12, "({i}, {j}): {}", score(i, j));
+        assert!((score(j, i) - expected).abs() < 1e-12, "({j}, {i}): {}", score(j, i));
+    }
+}
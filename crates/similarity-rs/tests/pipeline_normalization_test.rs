@@ -0,0 +1,36 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::tsed::{calculate_tsed, TSEDOptions};
+use similarity_rs::rust_parser::RustParser;
+
+fn pipeline_similarity(code1: &str, code2: &str, normalize_pipelines: bool) -> f64 {
+    let mut parser = RustParser::new().unwrap();
+    let tree1 = parser.parse(code1, "a.rs").unwrap();
+    let tree2 = parser.parse(code2, "b.rs").unwrap();
+
+    let mut options = TSEDOptions::default();
+    options.apted_options.compare_values = true;
+    options.size_penalty = false;
+    options.normalize_pipelines = normalize_pipelines;
+    calculate_tsed(&tree1, &tree2, &options)
+}
+
+#[test]
+fn test_equivalent_iterator_chains_score_high() {
+    let code1 = r#"fn doubled_large(values: &[i32]) -> Vec<i32> {
+    values.iter().map(|v| v * 2).filter(|v| *v > 10).collect()
+}"#;
+    let code2 = r#"fn large_doubled(values: &[i32]) -> Vec<i32> {
+    values
+        .iter()
+        .map(|v| v * 2)
+        .filter(|v| *v > 10)
+        .collect::<Vec<_>>()
+}"#;
+
+    let plain = pipeline_similarity(code1, code2, false);
+    let normalized = pipeline_similarity(code1, code2, true);
+    println!("plain: {plain:.4}, normalized: {normalized:.4}");
+
+    assert!(normalized > 0.9, "Equivalent pipelines should score high: {normalized}");
+    assert!(normalized >= plain);
+}
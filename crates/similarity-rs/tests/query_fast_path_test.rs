@@ -0,0 +1,106 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::tree::TreeNode;
+use similarity_core::tsed::{calculate_tsed, TSEDOptions, TsedQuery};
+use similarity_rs::rust_parser::RustParser;
+use std::rc::Rc;
+
+/// Functions of varied size and shape, with several near-duplicates of the query
+fn candidates(parser: &mut RustParser) -> Vec<Rc<TreeNode>> {
+    let mut sources = Vec::new();
+    for i in 0..8 {
+        sources.push(format!(
+            "fn sum_{i}(values: &[i32]) -> i32 {{\n    let mut total = {i};\n    for value in values {{\n        total += value;\n    }}\n    total\n}}"
+        ));
+        let statements: String = (0..i)
+            .map(|j| format!("    let v{j} = x * {j} + {i};\n    println!(\"{{}}\", v{j});\n"))
+            .collect();
+        sources.push(format!("fn grow_{i}(x: i32) {{\n{statements}}}"));
+    }
+    sources.push("fn tiny() {}".to_string());
+    sources.push(
+        "fn filtered(values: &[i32]) -> i32 {\n    values.iter().filter(|v| **v > 0).sum()\n}"
+            .to_string(),
+    );
+
+    sources.iter().map(|source| parser.parse(source, "candidate.rs").unwrap()).collect()
+}
+
+fn assert_matches_naive(options: &TSEDOptions, threshold: f64) {
+    let mut parser = RustParser::new().unwrap();
+    let query = parser
+        .parse(
+            "fn total(items: &[i32]) -> i32 {\n    let mut acc = 0;\n    for item in items {\n        acc += item;\n    }\n    acc\n}",
+            "query.rs",
+        )
+        .unwrap();
+    let candidates = candidates(&mut parser);
+
+    let mut naive: Vec<(usize, f64)> = candidates
+        .iter()
+        .enumerate()
+        .map(|(index, candidate)| (index, calculate_tsed(&query, candidate, options)))
+        .filter(|(_, similarity)| *similarity >= threshold)
+        .collect();
+    naive.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then(a.0.cmp(&b.0)));
+
+    let fast = TsedQuery::new(&query, options).find_similar(&candidates, threshold);
+    assert_eq!(fast, naive, "threshold {threshold}");
+}
+
+#[test]
+fn test_query_results_match_naive_comparison() {
+    let mut options = TSEDOptions::default();
+    options.apted_options.compare_values = true;
+
+    for threshold in [0.0, 0.5, 0.7, 0.85, 0.95] {
+        assert_matches_naive(&options, threshold);
+    }
+}
+
+#[test]
+fn test_query_results_match_naive_comparison_with_normalization() {
+    let options = TSEDOptions {
+        size_penalty: false,
+        ignore_literals: true,
+        normalize_pipelines: true,
+        ..Default::default()
+    };
+
+    for threshold in [0.3, 0.8, 0.9] {
+        assert_matches_naive(&options, threshold);
+    }
+}
+
+#[test]
+fn test_size_bound_never_undercuts_similarity() {
+    let mut parser = RustParser::new().unwrap();
+    let candidates = candidates(&mut parser);
+    let options = TSEDOptions::default();
+
+    for query in &candidates {
+        let fast = TsedQuery::new(query, &options);
+        for candidate in &candidates {
+            let similarity = calculate_tsed(query, candidate, &options);
+            assert!(similarity <= fast.max_similarity(candidate.get_subtree_size()));
+        }
+    }
+}
+
+#[test]
+fn test_best_matches_are_the_top_of_find_similar() {
+    let mut parser = RustParser::new().unwrap();
+    let query = parser
+        .parse(
+            "fn total(items: &[i32]) -> i32 {\n    let mut acc = 0;\n    for item in items {\n        acc += item;\n    }\n    acc\n}",
+            "query.rs",
+        )
+        .unwrap();
+    let candidates = candidates(&mut parser);
+    let options = TSEDOptions::default();
+    let query = TsedQuery::new(&query, &options);
+
+    let all = query.find_similar(&candidates, 0.5);
+    assert!(all.len() > 3);
+    assert_eq!(query.best_matches(&candidates, 0.5, 3), all[..3].to_vec());
+    assert_eq!(query.best_matches(&candidates, 0.5, all.len() + 5), all);
+}
@@ -0,0 +1,132 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const CODE: &str = r#"fn sum_values(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for value in values {
+        total += value;
+    }
+    total
+}
+
+fn greet(name: &str) -> String {
+    format!("Hello, {name}!")
+}
+"#;
+
+const OTHER: &str = r#"fn sum_numbers(numbers: &[i32]) -> i32 {
+    let mut sum = 0;
+    for number in numbers {
+        sum += number;
+    }
+    sum
+}
+
+fn sum_positive(numbers: &[i32]) -> i32 {
+    let mut sum = 0;
+    for number in numbers {
+        if *number > 0 {
+            sum += number;
+        }
+    }
+    sum
+}
+"#;
+
+fn query(dir: &std::path::Path, name: &str, threshold: &str) -> assert_cmd::assert::Assert {
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .args(["query", "--function", name, "--in"])
+        .arg(dir)
+        .args(["--threshold", threshold])
+        .assert()
+}
+
+#[test]
+fn test_query_ranks_similar_functions() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), CODE).unwrap();
+    fs::write(dir.path().join("other.rs"), OTHER).unwrap();
+
+    let output = query(dir.path(), "sum_values", "0.85").success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    assert!(stdout.contains("Function: "), "{stdout}");
+    assert!(stdout.contains("lib.rs:1-7 sum_values"), "{stdout}");
+
+    // The closest copy comes first
+    let numbers = stdout.find("other.rs:1-7 sum_numbers").expect(&stdout);
+    let positive = stdout.find("other.rs:9-17 sum_positive").expect(&stdout);
+    assert!(numbers < positive, "{stdout}");
+    assert!(!stdout.contains(" greet"), "{stdout}");
+    assert!(stdout.contains("Total similar functions: 2"), "{stdout}");
+
+    // A higher threshold keeps only the close copy
+    query(dir.path(), "sum_values", "0.92")
+        .success()
+        .stdout(predicate::str::contains("sum_numbers"))
+        .stdout(predicate::str::contains("sum_positive").not());
+}
+
+#[test]
+fn test_query_applies_the_candidate_filters_of_check() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), CODE).unwrap();
+    fs::write(dir.path().join("other.rs"), OTHER).unwrap();
+
+    let query_with = |extra: &[&str]| {
+        let output = Command::cargo_bin("similarity-rs")
+            .unwrap()
+            .args(["query", "--function", "sum_values", "--in"])
+            .arg(dir.path())
+            .args(["--threshold", "0", "--min-tokens", "0"])
+            .args(extra)
+            .assert()
+            .success();
+        String::from_utf8(output.get_output().stdout.clone()).unwrap()
+    };
+
+    // The 3-line greet passes the default --min-lines only
+    assert!(query_with(&[]).contains(" greet"));
+    let stdout = query_with(&["--min-lines", "8"]);
+    assert!(!stdout.contains(" greet"), "{stdout}");
+    assert!(!stdout.contains("sum_numbers"), "{stdout}");
+    assert!(stdout.contains("sum_positive"), "{stdout}");
+}
+
+#[test]
+fn test_query_fails_for_unknown_function() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), CODE).unwrap();
+
+    query(dir.path(), "missing", "0.85")
+        .failure()
+        .stderr(predicate::str::contains("No function named missing found"));
+}
+
+#[test]
+fn test_query_uses_the_extensions_and_scoring_flags() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("lib.rs"), CODE).unwrap();
+    fs::write(dir.path().join("other.inc"), OTHER).unwrap();
+
+    query(dir.path(), "sum_values", "0.85")
+        .success()
+        .stdout(predicate::str::contains("No similar functions found!"));
+
+    let with_extension = |extra: &[&str]| {
+        Command::cargo_bin("similarity-rs")
+            .unwrap()
+            .args(["query", "--function", "sum_values", "--in"])
+            .arg(dir.path())
+            .args(["--threshold", "0.85", "-e", "rs,inc"])
+            .args(extra)
+            .assert()
+            .success()
+    };
+    with_extension(&[]).stdout(predicate::str::contains("Total similar functions: 2"));
+    // Every renamed identifier costs a full edit
+    with_extension(&["--rename-cost", "1.0"])
+        .stdout(predicate::str::contains("No similar functions found!"));
+}
@@ -0,0 +1,37 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::tsed::{calculate_tsed, TSEDOptions};
+use similarity_rs::rust_parser::RustParser;
+
+const BODY: &str = "{
+    let width = self.right - self.left;
+    let height = self.bottom - self.top;
+    width * height
+}";
+
+fn parse(signature: &str) -> std::rc::Rc<similarity_core::tree::TreeNode> {
+    let mut parser = RustParser::new().unwrap();
+    parser.parse(&format!("{signature} {BODY}"), "area.rs").unwrap()
+}
+
+#[test]
+fn test_methods_compare_identically_regardless_of_receiver_when_excluded() {
+    let by_ref = parse("fn area(&self) -> u32");
+    let by_mut_ref = parse("fn area(&mut self) -> u32");
+    let by_box = parse("fn area(self: Box<Self>) -> u32");
+
+    let options = TSEDOptions { include_receiver_in_tree: false, ..Default::default() };
+    assert_eq!(calculate_tsed(&by_ref, &by_mut_ref, &options), 1.0);
+    assert_eq!(calculate_tsed(&by_ref, &by_box, &options), 1.0);
+
+    let with_receiver = TSEDOptions::default();
+    assert!(calculate_tsed(&by_ref, &by_box, &with_receiver) < 1.0);
+}
+
+#[test]
+fn test_method_and_function_compare_on_remaining_parameters() {
+    let method = parse("fn scaled(&self, factor: u32) -> u32");
+    let function = parse("fn scaled(factor: u32) -> u32");
+
+    let options = TSEDOptions { include_receiver_in_tree: false, ..Default::default() };
+    assert_eq!(calculate_tsed(&method, &function, &options), 1.0);
+}
@@ -0,0 +1,98 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+const BASE: &str = r#"
+pub fn compute_total(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        if item.available {
+            total += item.price * item.quantity;
+        }
+    }
+    total
+}
+
+pub fn describe(item: &Item) -> String {
+    format!("{} x{}", item.name, item.quantity)
+}
+"#;
+
+#[test]
+fn test_renamed_function_is_paired_with_its_new_name() {
+    let dir = tempdir().unwrap();
+    let repo = dir.path();
+    git(repo, &["init", "-q"]);
+    fs::create_dir(repo.join("src")).unwrap();
+    fs::write(repo.join("src/lib.rs"), BASE).unwrap();
+    git(repo, &["add", "."]);
+    git(repo, &["commit", "-qm", "base"]);
+
+    // Rename the function and move it to a new module
+    let (renamed, rest) = BASE.split_at(BASE.find("pub fn describe").unwrap());
+    fs::write(repo.join("src/lib.rs"), rest).unwrap();
+    fs::write(repo.join("src/pricing.rs"), renamed.replace("compute_total", "order_total"))
+        .unwrap();
+    git(repo, &["add", "-A"]);
+    git(repo, &["commit", "-qm", "rename"]);
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .args(["renames", "HEAD~1", "HEAD", "--repo"])
+        .arg(repo)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- src/lib.rs:2-10 compute_total"))
+        .stdout(predicate::str::contains("+ src/pricing.rs:2-10 order_total"))
+        .stdout(predicate::str::is_match(r"Similarity: 9\d\.\d\d%").unwrap())
+        .stdout(predicate::str::contains("Total deleted functions: 1"));
+}
+
+#[test]
+fn test_unreadable_file_is_skipped() {
+    let dir = tempdir().unwrap();
+    let repo = dir.path();
+    git(repo, &["init", "-q"]);
+    fs::create_dir(repo.join("src")).unwrap();
+    fs::write(repo.join("src/lib.rs"), BASE).unwrap();
+    git(repo, &["add", "."]);
+    git(repo, &["commit", "-qm", "base"]);
+
+    // A submodule entry named like a Rust file has no content to show
+    let missing_commit = "1111111111111111111111111111111111111111";
+    git(
+        repo,
+        &[
+            "update-index",
+            "--add",
+            "--cacheinfo",
+            &format!("160000,{missing_commit},src/vendored.rs"),
+        ],
+    );
+    fs::write(repo.join("src/lib.rs"), BASE.replace("compute_total", "order_total")).unwrap();
+    git(repo, &["add", "src/lib.rs"]);
+    git(repo, &["commit", "-qm", "rename"]);
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .args(["renames", "HEAD~1", "HEAD", "--repo"])
+        .arg(repo)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Skipping src/vendored.rs at HEAD:"))
+        .stdout(predicate::str::contains("+ src/lib.rs:2-10 order_total"))
+        .stdout(predicate::str::contains("Total deleted functions: 1"));
+}
@@ -0,0 +1,42 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_report_empty_lists_files_without_functions() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("constants.rs"),
+        "pub const MAX_RETRIES: u32 = 3;\npub const TIMEOUT_MS: u64 = 5_000;\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("lib.rs"),
+        "pub fn retries() -> u32 {\n    let retries = 3;\n    retries\n}\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--report-empty")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files without functions (1):"))
+        .stdout(predicate::str::contains("constants.rs"))
+        .stdout(predicate::str::contains("lib.rs").not());
+}
+
+#[test]
+fn test_report_empty_is_off_by_default() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("constants.rs"), "pub const MAX_RETRIES: u32 = 3;\n").unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files without functions").not());
+}
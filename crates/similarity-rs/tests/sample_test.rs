@@ -0,0 +1,67 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+fn write_functions(dir: &std::path::Path) {
+    let mut source = String::new();
+    for i in 0..30 {
+        source.push_str(&format!(
+            r#"
+fn accumulate_{i}(values: &[i32]) -> i32 {{
+    let mut total = {i};
+    for v in values {{
+        if *v > {i} {{
+            total += v;
+        }}
+    }}
+    total
+}}
+"#
+        ));
+    }
+    fs::write(dir.join("lib.rs"), source).unwrap();
+}
+
+fn run(dir: &std::path::Path, seed: &str) -> Vec<String> {
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir)
+        .arg("--sample")
+        .arg("8")
+        .arg("--seed")
+        .arg(seed)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Sampled 8 of 30 functions"))
+        .get_output()
+        .stdout
+        .clone();
+
+    let mut lines: Vec<String> =
+        String::from_utf8_lossy(&output).lines().map(str::to_string).collect();
+    lines.sort();
+    lines
+}
+
+#[test]
+fn test_same_seed_produces_same_sample() {
+    let dir = tempdir().unwrap();
+    write_functions(dir.path());
+
+    let first = run(dir.path(), "7");
+    let second = run(dir.path(), "7");
+    assert_eq!(first, second);
+
+    // 8 sampled functions yield at most 8 * 7 / 2 pairs
+    let pairs = first.iter().filter(|line| line.trim_start().starts_with("Similarity:")).count();
+    assert!(pairs > 0 && pairs <= 28, "unexpected pair count {pairs}");
+}
+
+#[test]
+fn test_different_seeds_pick_different_functions() {
+    let dir = tempdir().unwrap();
+    write_functions(dir.path());
+
+    assert_ne!(run(dir.path(), "1"), run(dir.path(), "2"));
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::{output, run, summing_function};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_sarif_output_parses_with_one_result_per_pair() {
+    let dir = tempdir().unwrap();
+    let code: String = [("sum_prices", "price"), ("sum_weights", "weight"), ("sum_fees", "fee")]
+        .iter()
+        .map(|(name, field)| summing_function(name, field, false))
+        .collect();
+    fs::write(dir.path().join("lib.rs"), code).unwrap();
+
+    let stdout = run(dir.path(), &["--format", "sarif"]);
+    let log: serde_json::Value =
+        serde_json::from_str(&stdout).unwrap_or_else(|e| panic!("{e}: {stdout}"));
+
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "similarity-rs");
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    for result in results {
+        assert_eq!(result["ruleId"], "duplicate-function");
+        let uri = result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"].as_str();
+        assert!(uri.unwrap().ends_with("lib.rs"));
+        assert_eq!(result["relatedLocations"].as_array().unwrap().len(), 1);
+        let similarity = result["properties"]["similarity"].as_f64().unwrap();
+        let level = if similarity > 0.95 { "warning" } else { "note" };
+        assert_eq!(result["level"], level);
+    }
+}
+
+fn sarif_run(dir: &std::path::Path, extra: &[&str]) -> (bool, Vec<serde_json::Value>) {
+    let output = output(dir, &[&["--format", "sarif"], extra].concat());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let log: serde_json::Value =
+        serde_json::from_str(&stdout).unwrap_or_else(|e| panic!("{e}: {stdout}"));
+    (output.status.success(), log["runs"][0]["results"].as_array().unwrap().clone())
+}
+
+#[test]
+fn test_streamed_sarif_goes_through_the_post_filters_and_gates() {
+    let dir = tempdir().unwrap();
+    let code: String = [("sum_prices", "price"), ("sum_weights", "weight"), ("sum_fees", "fee")]
+        .iter()
+        .map(|(name, field)| summing_function(name, field, false))
+        .collect();
+    fs::write(dir.path().join("lib.rs"), code).unwrap();
+
+    // The document is complete even when a gate fails the run
+    let (success, results) = sarif_run(dir.path(), &["--max-duplication-ratio", "1"]);
+    assert!(!success);
+    assert_eq!(results.len(), 3);
+
+    // The three functions are all similar, so they are reported as the pairs
+    // of one representative
+    let (success, results) = sarif_run(dir.path(), &["--transitive-dedup"]);
+    assert!(success);
+    assert_eq!(results.len(), 2);
+
+    let (_, results) = sarif_run(dir.path(), &["--report-empty", "--explain-filter"]);
+    assert_eq!(results.len(), 3);
+}
@@ -0,0 +1,84 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+const SOURCE: &str = r#"
+fn sum_positive(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for v in values {
+        if *v > 0 {
+            total += v;
+        }
+    }
+    total
+}
+
+fn sum_positive_copy(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for v in values {
+        if *v > 0 {
+            total += v;
+        }
+    }
+    total
+}
+
+fn count_matching(items: &[String], needle: &str) -> usize {
+    let mut found = 0;
+    for item in items {
+        if item.contains(needle) {
+            found += 1;
+        } else {
+            println!("skip {}", item);
+        }
+    }
+    found
+}
+"#;
+
+fn read_pairs(path: &std::path::Path) -> Vec<serde_json::Value> {
+    let text = fs::read_to_string(path).unwrap();
+    serde_json::from_str::<Vec<serde_json::Value>>(&text).unwrap()
+}
+
+fn names(pair: &serde_json::Value) -> (String, String) {
+    (
+        pair["function1"]["name"].as_str().unwrap().to_string(),
+        pair["function2"]["name"].as_str().unwrap().to_string(),
+    )
+}
+
+#[test]
+fn test_split_output_partitions_pairs_by_severity() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, SOURCE).unwrap();
+    let out_dir = dir.path().join("report");
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--threshold")
+        .arg("0.6")
+        .arg("--split-output")
+        .arg(&out_dir)
+        .assert()
+        .success();
+
+    let exact = read_pairs(&out_dir.join("exact.json"));
+    let high = read_pairs(&out_dir.join("high.json"));
+    let moderate = read_pairs(&out_dir.join("moderate.json"));
+
+    assert_eq!(exact.len(), 1, "exact: {exact:?}");
+    assert_eq!(names(&exact[0]), ("sum_positive".to_string(), "sum_positive_copy".to_string()));
+    assert!(exact[0]["similarity"].as_f64().unwrap() >= 0.98);
+
+    assert!(!moderate.is_empty(), "moderate: {moderate:?}");
+    for pair in moderate.iter().chain(high.iter()) {
+        let (name1, name2) = names(pair);
+        assert!(name1 == "count_matching" || name2 == "count_matching");
+    }
+    for pair in &moderate {
+        assert!(pair["similarity"].as_f64().unwrap() < 0.9);
+    }
+}
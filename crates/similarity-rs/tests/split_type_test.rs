@@ -0,0 +1,69 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_rs::rust_parser::RustParser;
+
+const STACK: &str = r#"
+pub struct Stack<T> {
+    items: Vec<T>,
+}
+
+impl<T> Stack<T> {
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.items.pop()
+    }
+}
+"#;
+
+const STACK_EXTENSIONS: &str = r#"
+use crate::stack::Stack;
+
+impl<T: Clone> Stack<T> {
+    pub fn peek(&self) -> Option<T> {
+        self.items.last().cloned()
+    }
+}
+
+impl<T> Default for crate::stack::Stack<T> {
+    fn default() -> Self {
+        Stack { items: Vec::new() }
+    }
+}
+
+impl<T> Extend<T> for &mut Stack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+fn helper() {}
+"#;
+
+#[test]
+fn test_methods_of_a_type_split_across_files_share_its_name() {
+    let mut parser = RustParser::new().unwrap();
+    let stack = parser.extract_functions(STACK, "stack.rs").unwrap();
+    let extensions = parser.extract_functions(STACK_EXTENSIONS, "stack_ext.rs").unwrap();
+
+    let methods: Vec<(&str, &str, Option<&str>)> = stack
+        .iter()
+        .map(|func| ("stack.rs", func))
+        .chain(extensions.iter().map(|func| ("stack_ext.rs", func)))
+        .map(|(file, func)| (file, func.name.as_str(), func.class_name.as_deref()))
+        .collect();
+
+    // Trait impls are associated with the implementing type, not the trait
+    assert_eq!(
+        methods,
+        vec![
+            ("stack.rs", "push", Some("Stack")),
+            ("stack.rs", "pop", Some("Stack")),
+            ("stack_ext.rs", "peek", Some("Stack")),
+            ("stack_ext.rs", "default", Some("Stack")),
+            ("stack_ext.rs", "extend", Some("Stack")),
+            ("stack_ext.rs", "helper", None),
+        ]
+    );
+}
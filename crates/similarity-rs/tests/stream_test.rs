@@ -0,0 +1,94 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{BufRead, BufReader, Write as _};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tempfile::tempdir;
+
+/// Many near-identical functions, so that comparing them all takes a while
+fn generate_functions(count: usize) -> String {
+    let mut code = String::new();
+    for i in 0..count {
+        writeln!(
+            code,
+            r#"
+fn process_{i}(items: &[i32]) -> i32 {{
+    let mut total = {i};
+    for item in items {{
+        if *item > {i} {{
+            total += item * 2;
+        }} else {{
+            total -= item;
+        }}
+    }}
+    total
+}}"#
+        )
+        .unwrap();
+    }
+    code
+}
+
+#[test]
+fn test_stream_prints_pairs_before_the_scan_finishes() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("many.rs");
+    fs::write(&file_path, generate_functions(20)).unwrap();
+
+    // The hook keeps the process waiting for stdin once every pair is found,
+    // so only pairs printed from the comparison loop can be read before then
+    let mut child = Command::new(assert_cmd::cargo::cargo_bin("similarity-rs"))
+        .arg(&file_path)
+        .arg("--stream")
+        .env("SIMILARITY_RS_HOLD_BEFORE_SUMMARY", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let stdout = child.stdout.take().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    let reader = thread::spawn(move || {
+        let mut lines = BufReader::new(stdout).lines().map_while(Result::ok);
+        let first_pair = lines.by_ref().find(|line| line.trim_start().starts_with("Similarity:"));
+        sender.send(first_pair).ok();
+        lines.any(|line| line.starts_with("Total duplicate pairs found"))
+    });
+
+    let first_pair = receiver.recv_timeout(Duration::from_secs(120));
+    child.stdin.take().unwrap().write_all(b"\n").unwrap();
+    let status = child.wait().unwrap();
+
+    assert!(matches!(first_pair, Ok(Some(_))), "no pair was printed before the scan finished");
+    assert!(reader.join().unwrap(), "the summary was not printed");
+    assert!(status.success());
+}
+
+#[test]
+fn test_stream_reports_same_pairs_as_sorted_output() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("few.rs");
+    fs::write(&file_path, generate_functions(4)).unwrap();
+
+    let run = |extra: &[&str]| {
+        let output = assert_cmd::Command::cargo_bin("similarity-rs")
+            .unwrap()
+            .arg(&file_path)
+            .args(extra)
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let mut pairs: Vec<String> =
+            stdout.lines().filter(|l| l.contains(" <-> ")).map(str::to_string).collect();
+        pairs.sort();
+        (pairs, stdout)
+    };
+
+    let (sorted_pairs, _) = run(&[]);
+    let (streamed_pairs, streamed) = run(&["--stream"]);
+    assert_eq!(sorted_pairs.len(), 6);
+    assert_eq!(streamed_pairs, sorted_pairs);
+    assert!(streamed.contains("Total duplicate pairs found: 6"));
+}
@@ -0,0 +1,61 @@
+use similarity_core::language_parser::LanguageParser;
+use similarity_core::tree_normalizer::normalize_identifiers;
+use similarity_core::tsed::{calculate_tsed, TSEDOptions};
+use similarity_rs::rust_parser::RustParser;
+
+const ORIGINAL: &str = r#"
+fn total_price(items: &[Item]) -> u64 {
+    let mut total = 0;
+    for item in items {
+        total += item.price * item.quantity;
+    }
+    total
+}
+"#;
+
+const RENAMED: &str = r#"
+fn sum_weights(parcels: &[Parcel]) -> u64 {
+    let mut sum = 0;
+    for parcel in parcels {
+        sum += parcel.weight * parcel.count;
+    }
+    sum
+}
+"#;
+
+fn options(structure_weight: f64) -> TSEDOptions {
+    let mut options = TSEDOptions::default();
+    options.apted_options.compare_values = true;
+    options.size_penalty = false;
+    options.structure_weight = structure_weight;
+    options
+}
+
+#[test]
+fn test_structure_weight_spans_named_and_structural_scores() {
+    let mut parser = RustParser::new().unwrap();
+    let tree1 = parser.parse(ORIGINAL, "a.rs").unwrap();
+    let tree2 = parser.parse(RENAMED, "b.rs").unwrap();
+
+    let named = calculate_tsed(&tree1, &tree2, &options(0.0));
+    let structure = calculate_tsed(&tree1, &tree2, &options(1.0));
+    let blended = calculate_tsed(&tree1, &tree2, &options(0.5));
+
+    // 0.0 is the default, name-sensitive score
+    let mut default_options = TSEDOptions::default();
+    default_options.apted_options.compare_values = true;
+    default_options.size_penalty = false;
+    let default = calculate_tsed(&tree1, &tree2, &default_options);
+    assert!((named - default).abs() < 1e-9);
+
+    // 1.0 scores as if identifier names were ignored
+    let ignored = calculate_tsed(
+        &normalize_identifiers(&tree1),
+        &normalize_identifiers(&tree2),
+        &options(0.0),
+    );
+    assert!((structure - ignored).abs() < 1e-9, "{} vs {}", structure, ignored);
+    assert!(structure > named, "renaming should only cost in the named score");
+
+    assert!((blended - (structure + named) / 2.0).abs() < 1e-9);
+}
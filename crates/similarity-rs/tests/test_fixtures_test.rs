@@ -0,0 +1,88 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+const TESTS: &str = r#"
+#[test]
+fn creates_order() {
+    let db = Database::connect("memory://").unwrap();
+    let user = db.insert_user("alice", Role::Admin).unwrap();
+    let catalog = Catalog::load(&db, "fixtures/catalog.json").unwrap();
+    let cart = Cart::new(&user, &catalog);
+    let order = cart.checkout().unwrap();
+    assert_eq!(order.status, Status::Pending);
+}
+
+#[test]
+fn cancels_order() {
+    let db = Database::connect("memory://").unwrap();
+    let user = db.insert_user("alice", Role::Admin).unwrap();
+    let catalog = Catalog::load(&db, "fixtures/catalog.json").unwrap();
+    let cart = Cart::new(&user, &catalog);
+    let order = cart.checkout().unwrap();
+    assert!(order.cancel().is_ok());
+    assert_eq!(order.status, Status::Cancelled);
+}
+
+#[test]
+fn parses_price() {
+    assert_eq!(parse_price("1.50"), Some(150));
+}
+"#;
+
+#[test]
+fn test_shared_setup_is_reported_as_duplicated_fixture() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("orders_test.rs"), TESTS).unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--test-fixtures")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let section = stdout.split("=== Duplicated Test Setup ===").nth(1).unwrap();
+
+    // The setup spans stop before the first assertion
+    assert!(section.contains("creates_order setup"), "{section}");
+    assert!(section.contains("cancels_order setup"), "{section}");
+    assert!(section.contains(":4-8"), "{section}");
+    assert!(section.contains(":14-18"), "{section}");
+    assert!(!section.contains("parses_price"), "{section}");
+    assert!(section.contains("Total duplicated test setups: 1"), "{section}");
+}
+
+#[test]
+fn test_only_test_attributes_mark_tests() {
+    let source =
+        TESTS.replacen("#[test]\nfn cancels_order", "#[tokio::test]\nasync fn cancels_order", 1)
+            + r#"
+#[cfg(not(test))]
+fn seeds_demo_data() {
+    let db = Database::connect("memory://").unwrap();
+    let user = db.insert_user("alice", Role::Admin).unwrap();
+    let catalog = Catalog::load(&db, "fixtures/catalog.json").unwrap();
+    let cart = Cart::new(&user, &catalog);
+    let order = cart.checkout().unwrap();
+    assert!(order.is_valid());
+}
+"#;
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("orders.rs"), source).unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--test-fixtures")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let section = stdout.split("=== Duplicated Test Setup ===").nth(1).unwrap();
+
+    assert!(section.contains("cancels_order setup"), "{section}");
+    assert!(!section.contains("seeds_demo_data"), "{section}");
+    assert!(section.contains("Total duplicated test setups: 1"), "{section}");
+}
@@ -23,11 +23,13 @@ fn test_short_function_similarity() {
             delete_cost: 1.0,
             insert_cost: 1.0,
             compare_values: true,
+            ..Default::default()
         },
         min_lines: 1,
         min_tokens: None,
         size_penalty: true,
         skip_test: false,
+        ..Default::default()
     };
 
     let sim12 = calculate_tsed(&tree1, &tree2, &options);
@@ -0,0 +1,79 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+fn duplicated_source(prefix: &str) -> String {
+    format!(
+        r#"
+fn {prefix}_sum(values: &[i32]) -> i32 {{
+    let mut total = 0;
+    for v in values {{
+        if *v > 0 {{
+            total += v;
+        }}
+    }}
+    total
+}}
+
+fn {prefix}_add(items: &[i32]) -> i32 {{
+    let mut sum = 0;
+    for i in items {{
+        if *i > 0 {{
+            sum += i;
+        }}
+    }}
+    sum
+}}
+"#
+    )
+}
+
+/// Run the scan and return its output lines in a stable order
+fn sorted_output(dir: &std::path::Path, io_threads: &str) -> Vec<String> {
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir)
+        .arg("--threshold")
+        .arg("0.7")
+        .arg("--io-threads")
+        .arg(io_threads)
+        .arg("--compare-threads")
+        .arg("2")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let mut lines: Vec<String> =
+        String::from_utf8_lossy(&output).lines().map(str::to_string).collect();
+    lines.sort();
+    lines
+}
+
+#[test]
+fn test_results_are_stable_across_io_threads() {
+    let dir = tempdir().unwrap();
+    for name in ["alpha", "beta", "gamma", "delta"] {
+        fs::write(dir.path().join(format!("{name}.rs")), duplicated_source(name)).unwrap();
+    }
+
+    let single = sorted_output(dir.path(), "1");
+    let multi = sorted_output(dir.path(), "4");
+
+    assert!(single.iter().any(|line| line.contains("alpha_sum")));
+    assert!(single.iter().any(|line| line.contains("delta_add")));
+    assert_eq!(single, multi);
+}
+
+#[test]
+fn test_zero_threads_is_rejected() {
+    let dir = tempdir().unwrap();
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--io-threads")
+        .arg("0")
+        .assert()
+        .failure();
+}
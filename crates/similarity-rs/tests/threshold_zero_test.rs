@@ -0,0 +1,60 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_threshold_zero_reports_every_pair() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(
+        &file_path,
+        r#"
+fn sum_positive(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for v in values {
+        if *v > 0 {
+            total += v;
+        }
+    }
+    total
+}
+
+fn describe(code: u32) -> String {
+    match code {
+        0 => "zero".to_string(),
+        1 => "one".to_string(),
+        n => format!("many: {}", n),
+    }
+}
+
+fn parse_pair(input: &str) -> Option<(String, String)> {
+    let mut parts = input.splitn(2, '=');
+    let key = parts.next()?.trim().to_string();
+    let value = parts.next()?.trim().to_string();
+    Some((key, value))
+}
+
+fn tiny() -> i32 { 1 }
+"#,
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--threshold")
+        .arg("0")
+        .arg("--min-tokens")
+        .arg("0")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+
+    // tiny() is below --min-lines, so 3 functions remain: 3 pairs
+    let pairs = stdout.lines().filter(|l| l.trim_start().starts_with("Similarity:")).count();
+    assert_eq!(pairs, 3, "{stdout}");
+    assert!(!stdout.contains("tiny"));
+}
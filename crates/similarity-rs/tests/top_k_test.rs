@@ -0,0 +1,32 @@
+mod common;
+
+use common::{run, summing_function};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_top_k_reports_every_similar_peer() {
+    let dir = tempdir().unwrap();
+    let peers = [("sum_weights", "weight"), ("sum_taxes", "tax"), ("sum_fees", "fee")];
+    let code: String = [("sum_prices", "price")]
+        .iter()
+        .chain(&peers)
+        .map(|(name, field)| summing_function(name, field, false))
+        .collect();
+    fs::write(dir.path().join("lib.rs"), code).unwrap();
+
+    let stdout = run(dir.path(), &["--top-k", "3"]);
+    let section = stdout.split("=== Top-3 Matches ===").nth(1).unwrap();
+
+    let prices = section.split("\n\n").find(|block| block.contains("sum_prices (3 matches)"));
+    let prices = prices.unwrap_or_else(|| panic!("{section}"));
+    for (rank, peer) in ["1. ", "2. ", "3. "].iter().zip(peers.map(|(name, _)| name)) {
+        assert!(prices.contains(rank), "{prices}");
+        assert!(prices.contains(peer), "{prices}");
+    }
+    assert!(section.contains("Total functions with similar matches: 4"));
+
+    // With K = 1 only the best match is shown
+    let stdout = run(dir.path(), &["--top-k", "1"]);
+    assert!(stdout.contains("sum_prices (1 match)"));
+}
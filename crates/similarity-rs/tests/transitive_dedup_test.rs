@@ -0,0 +1,39 @@
+mod common;
+
+use common::{run, summing_function};
+use std::fs;
+use tempfile::tempdir;
+
+fn pair_lines(stdout: &str) -> Vec<&str> {
+    stdout.lines().filter(|line| line.contains(" <-> ")).collect()
+}
+
+#[test]
+fn test_three_mutually_similar_functions() {
+    let dir = tempdir().unwrap();
+    let code =
+        [("sum_prices", "price", false), ("sum_fees", "fee", true), ("sum_taxes", "tax", false)]
+            .iter()
+            .map(|(name, field, extra)| summing_function(name, field, *extra))
+            .collect::<String>();
+    fs::write(dir.path().join("lib.rs"), code).unwrap();
+
+    // Every unordered pair once, the earlier function first
+    let stdout = run(dir.path(), &["--threshold", "0.8"]);
+    let pairs = pair_lines(&stdout);
+    assert_eq!(pairs.len(), 3, "{stdout}");
+    for pair in &pairs {
+        let (first, second) = pair.split_once(" <-> ").unwrap();
+        let start = |location: &str| -> u32 {
+            let lines = location.rsplit_once(':').unwrap().1;
+            lines.split('-').next().unwrap().parse().unwrap()
+        };
+        assert!(start(first) < start(second), "{stdout}");
+    }
+
+    // The longest function against each of the others
+    let stdout = run(dir.path(), &["--threshold", "0.8", "--transitive-dedup"]);
+    let pairs = pair_lines(&stdout);
+    assert_eq!(pairs.len(), 2, "{stdout}");
+    assert!(pairs.iter().all(|pair| pair.contains("sum_fees")), "{stdout}");
+}
@@ -0,0 +1,75 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+const CODE: &str = r#"
+impl From<UserRow> for User {
+    fn from(row: UserRow) -> Self {
+        User {
+            id: row.id,
+            name: row.name,
+            email: row.email,
+        }
+    }
+}
+
+impl From<OrderRow> for Order {
+    fn from(row: OrderRow) -> Self {
+        Order {
+            id: row.id,
+            name: row.name,
+            total: row.total,
+        }
+    }
+}
+
+impl User {
+    fn score(&self, events: &[Event]) -> u64 {
+        let mut score = 0;
+        for event in events {
+            if event.user_id == self.id {
+                score += event.weight;
+            }
+        }
+        score
+    }
+}
+
+impl Order {
+    fn weight(&self, lines: &[Line]) -> u64 {
+        let mut weight = 0;
+        for line in lines {
+            if line.order_id == self.id {
+                weight += line.weight;
+            }
+        }
+        weight
+    }
+}
+"#;
+
+fn run(dir: &std::path::Path, extra: &[&str]) -> String {
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir)
+        .args(["--threshold", "0.8"])
+        .args(extra)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_trivial_from_impls_are_skipped() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("models.rs"), CODE).unwrap();
+
+    let stdout = run(dir.path(), &[]);
+    assert!(stdout.contains("method from"), "{stdout}");
+
+    let stdout = run(dir.path(), &["--skip-trivial-impls"]);
+    assert!(!stdout.contains("method from"), "{stdout}");
+    assert!(stdout.contains("method score"), "{stdout}");
+    assert!(stdout.contains("method weight"), "{stdout}");
+}
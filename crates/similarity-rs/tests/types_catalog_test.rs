@@ -0,0 +1,126 @@
+use assert_cmd::Command;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_types_json_catalog_lists_types_of_every_file() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("user.rs"),
+        "pub struct User {\n    id: u64,\n    email: Option<String>,\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("order.rs"),
+        "struct Order {\n    id: u64,\n    total: f64,\n}\n\nenum Status {\n    Open,\n    Closed { reason: String },\n}\n",
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg("types")
+        .arg("--format")
+        .arg("json")
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let catalog: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let types = catalog["types"].as_array().unwrap();
+    let find = |name: &str| types.iter().find(|t| t["name"] == name).unwrap();
+
+    let user = find("User");
+    assert_eq!(user["kind"], "struct");
+    assert!(user["file"].as_str().unwrap().ends_with("user.rs"));
+    assert_eq!(user["start_line"], 1);
+    assert_eq!(user["end_line"], 4);
+    assert_eq!(
+        user["properties"],
+        serde_json::json!([
+            { "name": "email", "type": "string", "optional": true },
+            { "name": "id", "type": "u64", "optional": false },
+        ])
+    );
+
+    let order = find("Order");
+    assert!(order["file"].as_str().unwrap().ends_with("order.rs"));
+    let names: Vec<&str> = order["properties"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["id", "total"]);
+
+    let status = find("Status");
+    assert_eq!(status["kind"], "enum");
+    assert_eq!(
+        status["variants"],
+        serde_json::json!([
+            { "name": "Open" },
+            { "name": "Closed", "payload": "{ reason: String }" },
+        ])
+    );
+}
+
+#[test]
+fn test_tuple_structs_with_same_element_types_are_duplicates() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("units.rs"),
+        "pub struct Meters(pub f64, pub String);\npub struct Distance(f64, String);\npub struct Count(u32);\n",
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .args(["types", "--duplicates", "--tuples"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Meters <->"), "{stdout}");
+    assert!(stdout.contains(":2 Distance"), "{stdout}");
+    assert!(stdout.contains("Total duplicate types: 1"), "{stdout}");
+
+    // Without --tuples, tuple structs have no shape to compare
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .args(["types", "--duplicates"])
+        .arg(dir.path())
+        .output()
+        .unwrap();
+    assert!(String::from_utf8(output.stdout).unwrap().contains("No duplicate types found!"));
+}
+
+#[test]
+fn test_duplicate_threshold_comes_from_the_cli() {
+    let dir = tempdir().unwrap();
+    // Two items per line: each keeps its own fields
+    fs::write(
+        dir.path().join("points.rs"),
+        "struct Point { x: f64, y: f64, z: f64 } struct Label(String);\n\
+         struct Vector { x: f64, y: f64, w: f64 } struct Tag(String);\n",
+    )
+    .unwrap();
+
+    let duplicates = |threshold: &str| {
+        let output = Command::cargo_bin("similarity-rs")
+            .unwrap()
+            .args(["types", "--duplicates", "--tuples", "--threshold", threshold])
+            .arg(dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let strict = duplicates("0.9");
+    assert!(strict.contains("Label <->") && strict.contains(":2 Tag"), "{strict}");
+    assert!(!strict.contains("Point <->"), "{strict}");
+
+    let loose = duplicates("0.5");
+    assert!(loose.contains("Point <->") && loose.contains(":2 Vector"), "{loose}");
+}
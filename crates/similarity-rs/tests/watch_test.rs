@@ -0,0 +1,88 @@
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tempfile::tempdir;
+
+const ORIGINAL: &str = r#"
+fn sum_values(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for value in values {
+        if *value > 0 {
+            total += value;
+        }
+    }
+    total
+}
+"#;
+
+const DUPLICATE: &str = r#"
+fn sum_numbers(numbers: &[i32]) -> i32 {
+    let mut sum = 0;
+    for number in numbers {
+        if *number > 0 {
+            sum += number;
+        }
+    }
+    sum
+}
+"#;
+
+#[test]
+fn test_watch_jsonl_emits_rescan_event() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, ORIGINAL).unwrap();
+
+    let mut child = Command::new(assert_cmd::cargo::cargo_bin("similarity-rs"))
+        .arg(dir.path())
+        .args(["--watch", "--watch-interval", "50", "--format", "jsonl"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let stdout = child.stdout.take().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let scan: Value =
+        serde_json::from_str(&receiver.recv_timeout(Duration::from_secs(30)).unwrap()).unwrap();
+    assert_eq!(scan["event"], "scan");
+    assert_eq!(scan["new_pairs"].as_array().unwrap().len(), 0);
+
+    fs::write(&file_path, format!("{ORIGINAL}{DUPLICATE}")).unwrap();
+    let line = receiver.recv_timeout(Duration::from_secs(30));
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    let rescan: Value = serde_json::from_str(&line.unwrap()).unwrap();
+    assert_eq!(rescan["event"], "rescan");
+    assert!(rescan["file"].as_str().unwrap().ends_with("lib.rs"));
+    let new_pairs = rescan["new_pairs"].as_array().unwrap();
+    assert_eq!(new_pairs.len(), 1);
+    let names =
+        [new_pairs[0]["function1"]["name"].clone(), new_pairs[0]["function2"]["name"].clone()];
+    assert!(names.contains(&Value::from("sum_values")));
+    assert!(names.contains(&Value::from("sum_numbers")));
+    assert_eq!(rescan["resolved_pairs"].as_array().unwrap().len(), 0);
+}
+
+#[test]
+fn test_format_requires_watch() {
+    let dir = tempdir().unwrap();
+    assert_cmd::Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(dir.path())
+        .args(["--format", "jsonl"])
+        .assert()
+        .failure();
+}
@@ -0,0 +1,107 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+const DUPLICATES: &str = r#"
+fn sum_positive(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for v in values {
+        if *v > 0 {
+            total += v;
+        }
+    }
+    total
+}
+
+fn add_positive(items: &[i32]) -> i32 {
+    let mut sum = 0;
+    for i in items {
+        if *i > 0 {
+            sum += i;
+        }
+    }
+    sum
+}
+"#;
+
+#[test]
+fn test_with_metrics_reports_both_sides() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, DUPLICATES).unwrap();
+
+    let output = Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--threshold")
+        .arg("0.7")
+        .arg("--with-metrics")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("sum_positive"))
+        .stdout(predicate::str::contains("add_positive"))
+        .get_output()
+        .stdout
+        .clone();
+    let stdout = String::from_utf8_lossy(&output);
+
+    let metrics_line =
+        stdout.lines().find(|line| line.contains("Metrics:")).expect("metrics line missing");
+    let sides: Vec<&str> = metrics_line.split("<->").collect();
+    assert_eq!(sides.len(), 2);
+    for side in sides {
+        assert!(side.contains("lines: 9"), "unexpected metrics: {side}");
+        // one `for` and one `if`
+        assert!(side.contains("complexity: 3"), "unexpected metrics: {side}");
+        assert!(side.contains("params: 1"), "unexpected metrics: {side}");
+    }
+}
+
+#[test]
+fn test_metrics_hidden_by_default() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, DUPLICATES).unwrap();
+
+    Command::cargo_bin("similarity-rs")
+        .unwrap()
+        .arg(&file_path)
+        .arg("--threshold")
+        .arg("0.7")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Metrics:").not());
+}
+
+#[test]
+fn test_with_metrics_in_json_records() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("lib.rs");
+    fs::write(&file_path, DUPLICATES).unwrap();
+
+    let json = |extra: &[&str]| {
+        let output = Command::cargo_bin("similarity-rs")
+            .unwrap()
+            .arg(&file_path)
+            .args(["--threshold", "0.7", "--format", "json"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    let report = json(&["--with-metrics"]);
+    let pair = &report["pairs"][0];
+    for side in ["metrics1", "metrics2"] {
+        let metrics = &pair[side];
+        assert_eq!(metrics["lines"], 9, "{pair}");
+        assert_eq!(metrics["complexity"], 3, "{pair}");
+        assert_eq!(metrics["params"], 1, "{pair}");
+        assert!(metrics["tokens"].as_u64().unwrap() > 0, "{pair}");
+    }
+
+    let report = json(&[]);
+    assert!(report["pairs"][0].get("metrics1").is_none(), "{report}");
+}
@@ -3,6 +3,7 @@
 use clap::Parser;
 
 mod check;
+mod overrides;
 pub mod parallel;
 
 #[derive(Parser)]
@@ -18,7 +19,7 @@ struct Cli {
     #[arg(short, long)]
     print: bool,
 
-    /// Similarity threshold (0.0-1.0)
+    /// Similarity threshold (0.0-1.0); 0 reports every compared pair with its score
     #[arg(short, long, default_value = "0.87")]
     threshold: f64,
 
@@ -109,6 +110,10 @@ struct Cli {
     /// Size tolerance for overlap detection (0.0-1.0)
     #[arg(long, default_value = "0.25")]
     overlap_size_tolerance: f64,
+
+    /// Report methods nearly identical to the superclass method they override
+    #[arg(long)]
+    overrides: bool,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -119,7 +124,7 @@ fn main() -> anyhow::Result<()> {
     let overlap_enabled = cli.overlap;
 
     // Validate that at least one analyzer is enabled
-    if !functions_enabled && !types_enabled && !overlap_enabled {
+    if !functions_enabled && !types_enabled && !overlap_enabled && !cli.overrides {
         eprintln!("Error: At least one analyzer must be enabled. Use --types to enable type checking, --overlap for overlap detection, or remove --no-functions.");
         return Err(anyhow::anyhow!("No analyzer enabled"));
     }
@@ -190,7 +195,7 @@ fn main() -> anyhow::Result<()> {
     if overlap_enabled {
         println!("=== Overlap Detection ===");
         check_overlaps(
-            cli.paths,
+            cli.paths.clone(),
             cli.threshold,
             cli.extensions.as_ref(),
             cli.print,
@@ -201,6 +206,15 @@ fn main() -> anyhow::Result<()> {
         )?;
     }
 
+    if cli.overrides {
+        println!("\n{}\n", separator);
+        println!("=== Redundant Overrides ===");
+        let mut options = similarity_core::TSEDOptions::default();
+        options.apted_options.rename_cost = cli.rename_cost;
+        options.size_penalty = !cli.no_size_penalty;
+        overrides::check_overrides(&cli.paths, cli.extensions.as_ref(), cli.threshold, &options)?;
+    }
+
     Ok(())
 }
 
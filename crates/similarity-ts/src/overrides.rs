@@ -0,0 +1,175 @@
+#![allow(clippy::uninlined_format_args)]
+
+use similarity_core::{
+    calculate_tsed, cli_file_utils::collect_files, cli_output::format_function_output,
+    extract_classes, extract_functions, parse_and_convert_to_tree, ClassDefinition,
+    FunctionDefinition, FunctionType, TSEDOptions, TreeNode,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+/// A class of the scanned code with its methods and the source they come from
+struct ParsedClass {
+    file: PathBuf,
+    class: ClassDefinition,
+    methods: Vec<FunctionDefinition>,
+    content: Rc<str>,
+}
+
+impl ParsedClass {
+    fn method(&self, name: &str) -> Option<&FunctionDefinition> {
+        self.methods.iter().find(|method| method.name == name)
+    }
+}
+
+/// Report methods whose body is nearly identical to the implementation they
+/// override in an ancestor class
+pub fn check_overrides(
+    paths: &[String],
+    extensions: Option<&Vec<String>>,
+    threshold: f64,
+    options: &TSEDOptions,
+) -> anyhow::Result<()> {
+    let default_extensions = vec!["ts", "tsx", "js", "jsx", "mjs", "cjs", "mts", "cts"];
+    let exts: Vec<&str> =
+        extensions.map_or(default_extensions, |v| v.iter().map(String::as_str).collect());
+    let files = collect_files(paths, &exts)?;
+
+    let mut classes = Vec::new();
+    for file in &files {
+        let content: Rc<str> = match fs::read_to_string(file) {
+            Ok(content) => content.into(),
+            Err(e) => {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                continue;
+            }
+        };
+        let filename = file.to_string_lossy();
+        let (file_classes, functions) =
+            match (extract_classes(&filename, &content), extract_functions(&filename, &content)) {
+                (Ok(classes), Ok(functions)) => (classes, functions),
+                (Err(e), _) | (_, Err(e)) => {
+                    eprintln!("Error parsing {}: {}", file.display(), e);
+                    continue;
+                }
+            };
+
+        for class in file_classes {
+            // Constructors chain to the parent with `super(...)` rather than override it
+            let methods = functions
+                .iter()
+                .filter(|func| func.function_type == FunctionType::Method)
+                .filter(|func| func.class_name.as_deref() == Some(class.name.as_str()))
+                .filter(|func| {
+                    func.start_line >= class.start_line && func.end_line <= class.end_line
+                })
+                .cloned()
+                .collect();
+            classes.push(ParsedClass {
+                file: file.clone(),
+                class,
+                methods,
+                content: content.clone(),
+            });
+        }
+    }
+
+    let mut by_name: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, parsed) in classes.iter().enumerate() {
+        by_name.entry(parsed.class.name.as_str()).or_default().push(index);
+    }
+
+    let mut redundant = Vec::new();
+    for (child_index, child) in classes.iter().enumerate() {
+        for method in &child.methods {
+            let Some(parent_index) = find_overridden(&classes, &by_name, child_index, &method.name)
+            else {
+                continue;
+            };
+            let parent = &classes[parent_index];
+            let parent_method = parent.method(&method.name).expect("ancestor defines the method");
+            let (Some(tree), Some(parent_tree)) =
+                (method_tree(method, &child.content), method_tree(parent_method, &parent.content))
+            else {
+                continue;
+            };
+            let similarity = calculate_tsed(&tree, &parent_tree, options);
+            if similarity >= threshold {
+                redundant.push((child_index, method, parent_index, parent_method, similarity));
+            }
+        }
+    }
+    redundant.sort_by(|a, b| b.4.partial_cmp(&a.4).unwrap_or(std::cmp::Ordering::Equal));
+
+    if redundant.is_empty() {
+        println!("\nNo redundant overrides found!");
+        return Ok(());
+    }
+
+    for (child_index, method, parent_index, parent_method, similarity) in &redundant {
+        println!(
+            "\n  {} overrides {}",
+            describe(&classes[*child_index], method),
+            describe(&classes[*parent_index], parent_method)
+        );
+        println!("  Similarity: {:.2}%", similarity * 100.0);
+    }
+
+    println!("\nTotal redundant overrides: {}", redundant.len());
+
+    Ok(())
+}
+
+/// Parse a method on its own, inside an empty class so that its modifiers and
+/// type annotations stay valid syntax
+fn method_tree(method: &FunctionDefinition, content: &str) -> Option<Rc<TreeNode>> {
+    let text = content.get(method.body_span.start as usize..method.body_span.end as usize)?;
+    parse_and_convert_to_tree("method.ts", &format!("class C {{\n{}\n}}", text)).ok()
+}
+
+fn describe(parsed: &ParsedClass, method: &FunctionDefinition) -> String {
+    format_function_output(
+        &parsed.file.to_string_lossy(),
+        &format!("{}.{}", parsed.class.name, method.name),
+        method.start_line,
+        method.end_line,
+    )
+}
+
+/// Find the nearest ancestor of a class that defines the given method.
+///
+/// Superclasses are resolved by name, preferring a class defined in the same
+/// file; superclasses that are not part of the scanned code are ignored.
+fn find_overridden(
+    classes: &[ParsedClass],
+    by_name: &HashMap<&str, Vec<usize>>,
+    class_index: usize,
+    method_name: &str,
+) -> Option<usize> {
+    let mut visited = HashSet::from([class_index]);
+    let mut queue = VecDeque::from([class_index]);
+
+    while let Some(index) = queue.pop_front() {
+        let class = &classes[index];
+        let Some(candidates) = class.class.extends.as_deref().and_then(|base| by_name.get(base))
+        else {
+            continue;
+        };
+        let base_index = candidates
+            .iter()
+            .copied()
+            .find(|&candidate| classes[candidate].file == class.file)
+            .unwrap_or(candidates[0]);
+        if !visited.insert(base_index) {
+            continue;
+        }
+        if classes[base_index].method(method_name).is_some() {
+            return Some(base_index);
+        }
+        queue.push_back(base_index);
+    }
+
+    None
+}
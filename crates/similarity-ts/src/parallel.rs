@@ -50,7 +50,8 @@ pub fn check_within_file_duplicates_parallel(
             Ok(code) => {
                 let file_str = file.to_string_lossy();
 
-                let similar_pairs = if fast_mode {
+                // A zero threshold asks for every pair, which the fingerprint prefilter would hide
+                let similar_pairs = if fast_mode && threshold > 0.0 {
                     let fast_options = FastSimilarityOptions {
                         fingerprint_threshold: 0.3,
                         similarity_threshold: threshold,
@@ -105,28 +106,33 @@ pub fn check_cross_file_duplicates_parallel(
         }
     }
 
+    // Key each function once, so that exact duplicates skip APTED
+    let keys: Vec<Option<u64>> = all_functions
+        .par_iter()
+        .map(|(_, content, func)| similarity_core::function_match_key(func, content, options).ok())
+        .collect();
+
     // Process pairs in parallel
     pairs_to_check
         .into_par_iter()
         .filter_map(|(i, j)| {
             let (file1, content1, func1) = &all_functions[i];
             let (file2, content2, func2) = &all_functions[j];
+            // Functions whose body does not parse are not compared
+            let (key1, key2) = (keys[i]?, keys[j]?);
 
-            // Use core's compare_functions
-            match similarity_core::compare_functions(func1, func2, content1, content2, options) {
-                Ok(similarity) => {
-                    if similarity >= threshold {
-                        Some((
-                            file1.clone(),
-                            SimilarityResult::new(func1.clone(), func2.clone(), similarity),
-                            file2.clone(),
-                        ))
-                    } else {
-                        None
-                    }
-                }
-                Err(_) => None,
-            }
+            let similarity = similarity_core::compare_functions_keyed(
+                (func1, content1, key1),
+                (func2, content2, key2),
+                options,
+                threshold,
+            )
+            .ok()??;
+            Some((
+                file1.clone(),
+                SimilarityResult::new(func1.clone(), func2.clone(), similarity),
+                file2.clone(),
+            ))
         })
         .collect()
 }
@@ -57,6 +57,7 @@ impl LanguageParser for TypeScriptParser {
                     f.function_type,
                     similarity_core::function_extractor::FunctionType::Method
                 ),
+                kind: f.function_type,
                 class_name: f.class_name,
                 is_async: false,        // TODO: Extract async information from AST
                 is_generator: false, // TypeScript/JavaScript doesn't have generators in our current model
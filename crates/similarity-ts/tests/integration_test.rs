@@ -330,3 +330,48 @@ export function found() {
         .success()
         .stdout(predicate::str::contains("No duplicate functions found"));
 }
+
+#[test]
+fn test_deeply_nested_file_does_not_crash() {
+    let dir = tempdir().unwrap();
+    let nesting = 10_000;
+    fs::write(
+        dir.path().join("deep.ts"),
+        format!(
+            "export function deep() {{ return {}1{}; }}\n",
+            "(".repeat(nesting),
+            ")".repeat(nesting)
+        ),
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("sample.ts"),
+        r#"
+export function calculateSum(numbers: number[]): number {
+    let total = 0;
+    for (const num of numbers) {
+        total += num;
+    }
+    return total;
+}
+
+export function computeTotal(values: number[]): number {
+    let sum = 0;
+    for (const val of values) {
+        sum += val;
+    }
+    return sum;
+}
+"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("similarity-ts")
+        .unwrap()
+        .arg(dir.path())
+        .arg("--no-size-penalty")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("calculateSum"))
+        .stdout(predicate::str::contains("computeTotal"));
+}
@@ -0,0 +1,86 @@
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_overrides_flags_method_copied_from_superclass() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("shapes.ts");
+
+    let content = r#"
+export class Shape {
+  describe(): string {
+    const parts: string[] = [];
+    for (const [key, value] of Object.entries(this)) {
+      parts.push(`${key}=${value}`);
+    }
+    return parts.join(", ");
+  }
+
+  area(): number {
+    return 0;
+  }
+}
+
+class Square extends Shape {
+  constructor(private side: number) {
+    super();
+  }
+
+  describe(): string {
+    const items: string[] = [];
+    for (const [key, value] of Object.entries(this)) {
+      items.push(`${key}=${value}`);
+    }
+    return items.join(", ");
+  }
+
+  area(): number {
+    const total = this.side * this.side;
+    if (total < 0) {
+      throw new Error("negative area");
+    }
+    return total;
+  }
+}
+"#;
+
+    fs::write(&file_path, content).unwrap();
+
+    Command::cargo_bin("similarity-ts")
+        .unwrap()
+        .arg(&file_path)
+        .args(["--overrides", "--no-functions", "--no-size-penalty", "--threshold", "0.9"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("=== Redundant Overrides ==="))
+        .stdout(predicate::str::contains("Square.describe overrides"))
+        .stdout(predicate::str::contains("Shape.describe"))
+        .stdout(predicate::str::contains("Square.area overrides").not())
+        .stdout(predicate::str::contains("Total redundant overrides: 1"));
+}
+
+#[test]
+fn test_overrides_resolve_superclasses_across_files() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("base.ts"),
+        "export class Base {\n  load(id: string): string {\n    const key = `item:${id}`;\n    return cache.get(key) ?? fetch(key);\n  }\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("derived.ts"),
+        "import { Base } from './base';\n\nexport class Derived extends Base {\n  load(id: string): string {\n    const key = `item:${id}`;\n    return cache.get(key) ?? fetch(key);\n  }\n}\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("similarity-ts")
+        .unwrap()
+        .arg(dir.path())
+        .args(["--overrides", "--no-functions", "--no-size-penalty"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Derived.load overrides"))
+        .stdout(predicate::str::contains("base.ts"));
+}